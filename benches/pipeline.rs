@@ -0,0 +1,87 @@
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use common::{SyntheticTree, TreeSpec};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use pgrep::{detect_projects, FolderScan, ProjectKind, Query};
+
+/// The tree shapes benchmarked, from a small workspace to a large monorepo-like hierarchy
+const SPECS: [TreeSpec; 3] = [
+  TreeSpec::new(4, 8, 2),
+  TreeSpec::new(8, 16, 3),
+  TreeSpec::new(10, 20, 4),
+];
+
+fn folder_scan(c: &mut Criterion) {
+  let mut group = c.benchmark_group("folder_scan");
+  group.sample_size(10);
+  for spec in SPECS {
+    let tree = SyntheticTree::generate(spec).unwrap();
+    group.bench_with_input(
+      BenchmarkId::from_parameter(tree.file_count()),
+      &tree,
+      |b, tree| b.iter(|| FolderScan::new(tree.root()).unwrap()),
+    );
+  }
+  group.finish();
+}
+
+fn detection(c: &mut Criterion) {
+  let mut group = c.benchmark_group("detect_projects");
+  group.sample_size(10);
+  for spec in SPECS {
+    let tree = SyntheticTree::generate(spec).unwrap();
+    let scan = FolderScan::new(tree.root()).unwrap();
+    group.bench_with_input(
+      BenchmarkId::from_parameter(tree.file_count()),
+      &scan,
+      |b, scan| b.iter(|| detect_projects(scan, vec![])),
+    );
+  }
+  group.finish();
+}
+
+fn detection_with_custom_kinds(c: &mut Criterion) {
+  let kinds = (0..8)
+    .map(|i| ProjectKind::Custom {
+      name: format!("custom-{}", i),
+      language_exts: vec![format!("ext{}", i)],
+      project_files: vec![format!("custom-{}.toml", i)],
+    })
+    .collect::<Vec<_>>();
+  let mut group = c.benchmark_group("detect_projects_custom_kinds");
+  group.sample_size(10);
+  for spec in SPECS {
+    let tree = SyntheticTree::generate(spec).unwrap();
+    let scan = FolderScan::new(tree.root()).unwrap();
+    group.bench_with_input(
+      BenchmarkId::from_parameter(tree.file_count()),
+      &scan,
+      |b, scan| b.iter(|| detect_projects(scan, kinds.clone())),
+    );
+  }
+  group.finish();
+}
+
+fn query_matching(c: &mut Criterion) {
+  let subjects = (0..1000)
+    .map(|i| format!("my-project-{:04}-api", i))
+    .collect::<Vec<_>>();
+  let mut group = c.benchmark_group("query_matches");
+  for expr in ["my-project-0042-api", "*0042*", "my-*-#-api", "*-api?"] {
+    let query = expr.parse::<Query>().unwrap();
+    group.bench_with_input(BenchmarkId::from_parameter(expr), &query, |b, query| {
+      b.iter(|| subjects.iter().filter(|s| query.matches(s)).count())
+    });
+  }
+  group.finish();
+}
+
+criterion_group!(
+  benches,
+  folder_scan,
+  detection,
+  detection_with_custom_kinds,
+  query_matching
+);
+criterion_main!(benches);
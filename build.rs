@@ -0,0 +1,37 @@
+use std::{path::Path, process::Command};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+/// Export the build information read by `pgrep --build-info`
+fn main() {
+  let commit = Command::new("git")
+    .args(["rev-parse", "--short=12", "HEAD"])
+    .output()
+    .ok()
+    .filter(|output| output.status.success())
+    .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    .unwrap_or_else(|| "unknown".to_string());
+  println!("cargo:rustc-env=PGREP_GIT_COMMIT={}", commit);
+
+  // honor reproducible builds, see https://reproducible-builds.org/specs/source-date-epoch/
+  let date = std::env::var("SOURCE_DATE_EPOCH")
+    .ok()
+    .and_then(|epoch| epoch.parse::<i64>().ok())
+    .and_then(|epoch| DateTime::from_timestamp(epoch, 0))
+    .unwrap_or_else(Utc::now);
+  println!(
+    "cargo:rustc-env=PGREP_BUILD_DATE={}",
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+  );
+  println!(
+    "cargo:rustc-env=PGREP_TARGET={}",
+    std::env::var("TARGET").unwrap_or_default()
+  );
+
+  println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+  for path in [".git/HEAD", ".git/refs/heads"] {
+    if Path::new(path).exists() {
+      println!("cargo:rerun-if-changed={}", path);
+    }
+  }
+}
@@ -0,0 +1,277 @@
+use std::{
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+use chrono::{DateTime, Local};
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::{i18n::tr_fmt, scaffold::copy_dir, Error, Project, Table};
+
+/// How [`archive`] stores a project folder in the archive folder
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum ArchiveMode {
+  /// Move the folder as is
+  #[default]
+  Move,
+  /// Compress the folder to a `.tar.gz` file with the `tar` command, then remove it
+  Tar,
+}
+
+/// A project stored in the archive folder, recorded in its [`ArchiveManifest`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedProject {
+  /// The project folder before archiving
+  pub from: PathBuf,
+  /// The moved folder or the created archive
+  pub to: PathBuf,
+  /// The kind names of the project
+  pub kinds: Vec<String>,
+  /// When the project was archived
+  pub archived_at: DateTime<Local>,
+}
+
+impl ArchivedProject {
+  /// Compute where `project` is stored in the `dir` archive folder
+  pub fn new(project: &Project, dir: &Path, mode: ArchiveMode) -> Self {
+    let name = project
+      .path()
+      .file_name()
+      .map(|name| name.to_string_lossy().into_owned())
+      .unwrap_or_default();
+    let to = match mode {
+      ArchiveMode::Move => dir.join(name),
+      ArchiveMode::Tar => dir.join(format!("{}.tar.gz", name)),
+    };
+    Self {
+      from: project.path().clone(),
+      to,
+      kinds: project.kinds().iter().map(|k| k.name()).collect(),
+      archived_at: Local::now(),
+    }
+  }
+
+  /// Describe the archived projects as a table, `done` telling whether they were actually
+  /// archived or only planned by a dry run
+  pub fn table(archived: &[ArchivedProject], done: bool) -> Table {
+    let mut table = Table::new("Archive", &["Action", "From", "To", "Kinds"]);
+    let action = match done {
+      true => "archived",
+      false => "would archive",
+    };
+    for project in archived {
+      table.push_row(vec![
+        action.into(),
+        project.from.display().to_string().into(),
+        project.to.display().to_string().into(),
+        project.kinds.clone().into(),
+      ]);
+    }
+    table
+  }
+}
+
+/// The record of every project stored in an archive folder, kept in it as
+/// [`ArchiveManifest::FILE_NAME`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+  /// The archived projects, oldest first
+  #[serde(default)]
+  pub archived: Vec<ArchivedProject>,
+}
+
+impl ArchiveManifest {
+  /// The manifest file name
+  pub const FILE_NAME: &'static str = "pgrep-archive.toml";
+
+  /// Load the manifest of the `dir` archive folder, empty if it doesn't exist yet
+  pub fn load<P: AsRef<Path>>(dir: P) -> crate::Result<Self> {
+    let file = dir.as_ref().join(Self::FILE_NAME);
+    if !file.exists() {
+      return Ok(Self::default());
+    }
+    let content = std::fs::read_to_string(&file)
+      .map_err(|e| Error::io(format!("cannot read '{}'", file.display()), e))?;
+    Ok(toml::from_str(&content)?)
+  }
+
+  /// Write the manifest to the `dir` archive folder
+  pub fn save<P: AsRef<Path>>(&self, dir: P) -> crate::Result<()> {
+    let file = dir.as_ref().join(Self::FILE_NAME);
+    std::fs::write(&file, toml::to_string_pretty(self)?)
+      .map_err(|e| Error::io(format!("cannot write '{}'", file.display()), e))
+  }
+}
+
+/// Store the projects in the `dir` archive folder, then record them in its manifest.
+///
+/// Nothing is archived when any target already exists, or when several projects share a folder
+/// name and would be stored at the same place. A project failing to be archived stops
+/// the others, the manifest still recording the ones archived so far.
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::{archive, ArchiveManifest, ArchiveMode, Project, ProjectKind};
+///
+/// let root = std::env::temp_dir().join(format!("pgrep-archive-doc-{}", std::process::id()));
+/// let path = root.join("code").join("old");
+/// std::fs::create_dir_all(&path).unwrap();
+/// let project = Project::new(&path, vec![ProjectKind::Rust], vec![], vec![]);
+///
+/// let archived = archive(&[project], &root.join("archive"), ArchiveMode::Move).unwrap();
+/// assert!(archived[0].to.is_dir());
+/// assert!(!path.exists());
+/// assert_eq!(ArchiveManifest::load(root.join("archive")).unwrap().archived, archived);
+/// # std::fs::remove_dir_all(&root).unwrap();
+/// ```
+pub fn archive(
+  projects: &[Project],
+  dir: &Path,
+  mode: ArchiveMode,
+) -> crate::Result<Vec<ArchivedProject>> {
+  let planned = projects
+    .iter()
+    .map(|project| ArchivedProject::new(project, dir, mode))
+    .collect::<Vec<_>>();
+  if let Some(taken) = planned.iter().find(|project| project.to.exists()) {
+    return Err(Error::config(tr_fmt(
+      "archive.exists",
+      &[&taken.to.display()],
+    )));
+  }
+  for (i, project) in planned.iter().enumerate() {
+    if let Some(other) = planned[..i].iter().find(|other| other.to == project.to) {
+      let message = tr_fmt(
+        "archive.same-target",
+        &[
+          &other.from.display(),
+          &project.from.display(),
+          &project.to.display(),
+        ],
+      );
+      return Err(Error::config(message));
+    }
+  }
+  std::fs::create_dir_all(dir)
+    .map_err(|e| Error::io(format!("cannot create '{}'", dir.display()), e))?;
+  let mut manifest = ArchiveManifest::load(dir)?;
+  let mut ret = vec![];
+  let mut failure = None;
+  for project in planned {
+    debug!(
+      "archiving '{}' to '{}'",
+      project.from.display(),
+      project.to.display()
+    );
+    match store(&project.from, &project.to, mode) {
+      Ok(()) => ret.push(project),
+      Err(e) => {
+        failure = Some(e);
+        break;
+      }
+    }
+  }
+  manifest.archived.extend(ret.iter().cloned());
+  manifest.save(dir)?;
+  match failure {
+    Some(e) => Err(e),
+    None => Ok(ret),
+  }
+}
+
+/// Move or compress a single project folder
+fn store(from: &Path, to: &Path, mode: ArchiveMode) -> crate::Result<()> {
+  match mode {
+    ArchiveMode::Move => {
+      // renaming fails across file systems, copy then
+      if std::fs::rename(from, to).is_err() {
+        copy_dir(from, to)?;
+        std::fs::remove_dir_all(from)?;
+      }
+    }
+    ArchiveMode::Tar => {
+      let (Some(parent), Some(name)) = (from.parent(), from.file_name()) else {
+        return Err(Error::config(tr_fmt(
+          "archive.not-a-folder",
+          &[&from.display()],
+        )));
+      };
+      let output = Command::new("tar")
+        .arg("-czf")
+        .arg(to)
+        .arg("-C")
+        .arg(parent)
+        .arg(name)
+        .output()
+        .map_err(|e| Error::io("failed to run tar", e))?;
+      if !output.status.success() {
+        let _ = std::fs::remove_file(to);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = tr_fmt("archive.tar-failed", &[&from.display(), &stderr.trim()]);
+        return Err(Error::Exec(message));
+      }
+      std::fs::remove_dir_all(from)?;
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use super::{archive, ArchiveMode};
+  use crate::{test_support::temp_tree, Project, ProjectKind};
+
+  #[test]
+  fn projects_sharing_a_name_are_not_archived() {
+    let root = temp_tree(
+      "archive-same-name",
+      &[("a/api/main.rs", "first"), ("b/api/main.rs", "second")],
+    );
+    let projects = ["a/api", "b/api"]
+      .map(|path| Project::new(root.join(path), vec![ProjectKind::Rust], vec![], vec![]));
+    for mode in [ArchiveMode::Move, ArchiveMode::Tar] {
+      assert!(archive(&projects, &root.join("archive"), mode).is_err());
+      assert_eq!(
+        std::fs::read_to_string(root.join("a/api/main.rs")).unwrap(),
+        "first"
+      );
+      assert_eq!(
+        std::fs::read_to_string(root.join("b/api/main.rs")).unwrap(),
+        "second"
+      );
+    }
+    assert!(!root.join("archive").exists());
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn copied_projects_keep_their_links() {
+    let root = temp_tree("archive-links", &[("api/main.rs", "fn main() {}")]);
+    let from = root.join("api");
+    std::os::unix::fs::symlink("main.rs", from.join("entry.rs")).unwrap();
+    std::os::unix::fs::symlink("../missing", from.join("dangling")).unwrap();
+    std::os::unix::fs::symlink("/", from.join("system")).unwrap();
+    let to = root.join("copy");
+    crate::scaffold::copy_dir(&from, &to).unwrap();
+    assert_eq!(
+      std::fs::read_link(to.join("entry.rs")).unwrap(),
+      PathBuf::from("main.rs")
+    );
+    assert_eq!(
+      std::fs::read_link(to.join("dangling")).unwrap(),
+      PathBuf::from("../missing")
+    );
+    assert_eq!(
+      std::fs::read_link(to.join("system")).unwrap(),
+      PathBuf::from("/")
+    );
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+}
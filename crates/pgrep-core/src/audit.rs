@@ -0,0 +1,387 @@
+use std::{
+  collections::BTreeMap,
+  io::Read,
+  path::{Path, PathBuf},
+  process::{Command, Output, Stdio},
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Mutex,
+  },
+  time::{Duration, Instant},
+};
+
+use log::debug;
+use serde::Deserialize;
+
+use crate::{i18n::tr_fmt, CancelToken, Error, Project, ProjectKind, Table};
+
+/// A direct dependency having a newer version than the one used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutdatedDependency {
+  /// The dependency name
+  pub name: String,
+  /// The version used by the project, `-` when it isn't installed
+  pub current: String,
+  /// The latest version published
+  pub latest: String,
+}
+
+/// Lists the outdated direct dependencies of one project kind through the kind's own tool, see
+/// [`outdated_plugins`]
+pub trait OutdatedPlugin: Send + Sync {
+  /// The project kind audited
+  fn kind(&self) -> ProjectKind;
+
+  /// The program then arguments listing the outdated dependencies, run in the project folder
+  fn command(&self) -> &[&str];
+
+  /// Extract the outdated direct dependencies from the standard output of [`Self::command`],
+  /// none when it's empty
+  fn parse(&self, output: &str) -> crate::Result<Vec<OutdatedDependency>>;
+}
+
+/// Audits Rust projects with `cargo outdated`, installed by `cargo install cargo-outdated`
+pub struct CargoOutdated;
+
+/// A line of `cargo outdated --format json`, one per workspace member
+#[derive(Deserialize)]
+struct CargoOutdatedCrate {
+  dependencies: Vec<CargoOutdatedDependency>,
+}
+
+#[derive(Deserialize)]
+struct CargoOutdatedDependency {
+  name: String,
+  project: String,
+  latest: String,
+}
+
+impl OutdatedPlugin for CargoOutdated {
+  fn kind(&self) -> ProjectKind {
+    ProjectKind::Rust
+  }
+
+  fn command(&self) -> &[&str] {
+    &["cargo", "outdated", "--root-deps-only", "--format", "json"]
+  }
+
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::{CargoOutdated, OutdatedPlugin};
+  ///
+  /// let output = [
+  ///   r#"{"crate_name":"app","dependencies":["#,
+  ///   r#"{"name":"clap","project":"4.0.0","latest":"4.5.20","kind":"Normal"},"#,
+  ///   r#"{"name":"gone","project":"1.0.0","latest":"Removed","kind":"Normal"}]}"#,
+  /// ]
+  /// .concat();
+  /// let outdated = CargoOutdated.parse(&output).unwrap();
+  /// assert_eq!(outdated.len(), 1);
+  /// assert_eq!((outdated[0].current.as_str(), outdated[0].latest.as_str()), ("4.0.0", "4.5.20"));
+  /// ```
+  fn parse(&self, output: &str) -> crate::Result<Vec<OutdatedDependency>> {
+    let mut ret = BTreeMap::new();
+    for line in output.lines().filter(|line| !line.trim().is_empty()) {
+      let krate: CargoOutdatedCrate = serde_json::from_str(line)
+        .map_err(|e| Error::Exec(tr_fmt("audit.invalid-output", &[&"cargo outdated", &e])))?;
+      for dep in krate.dependencies {
+        // members of a workspace may share dependencies
+        if dep.latest != dep.project && !matches!(dep.latest.as_str(), "---" | "Removed") {
+          ret.insert(
+            dep.name.clone(),
+            OutdatedDependency {
+              name: dep.name,
+              current: dep.project,
+              latest: dep.latest,
+            },
+          );
+        }
+      }
+    }
+    Ok(ret.into_values().collect())
+  }
+}
+
+/// Audits Node projects with `npm outdated`
+pub struct NpmOutdated;
+
+#[derive(Deserialize)]
+struct NpmOutdatedDependency {
+  current: Option<String>,
+  latest: String,
+}
+
+impl OutdatedPlugin for NpmOutdated {
+  fn kind(&self) -> ProjectKind {
+    ProjectKind::Node
+  }
+
+  fn command(&self) -> &[&str] {
+    &["npm", "outdated", "--json"]
+  }
+
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::{NpmOutdated, OutdatedPlugin};
+  ///
+  /// let output = r#"{"react": {"current": "17.0.2", "wanted": "17.0.2", "latest": "18.3.1"}}"#;
+  /// let outdated = NpmOutdated.parse(output).unwrap();
+  /// assert_eq!(outdated[0].name, "react");
+  /// assert_eq!(outdated[0].latest, "18.3.1");
+  /// ```
+  fn parse(&self, output: &str) -> crate::Result<Vec<OutdatedDependency>> {
+    if output.trim().is_empty() {
+      return Ok(vec![]);
+    }
+    let deps: BTreeMap<String, NpmOutdatedDependency> = serde_json::from_str(output)
+      .map_err(|e| Error::Exec(tr_fmt("audit.invalid-output", &[&"npm outdated", &e])))?;
+    Ok(
+      deps
+        .into_iter()
+        .map(|(name, dep)| OutdatedDependency {
+          name,
+          current: dep.current.unwrap_or_else(|| "-".to_string()),
+          latest: dep.latest,
+        })
+        .collect(),
+    )
+  }
+}
+
+/// Audits Go projects with `go list -m -u`
+pub struct GoOutdated;
+
+/// An object of `go list -m -u -json`, which writes them one after the other
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GoModule {
+  path: String,
+  #[serde(default)]
+  version: String,
+  update: Option<GoModuleUpdate>,
+  #[serde(default)]
+  main: bool,
+  #[serde(default)]
+  indirect: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GoModuleUpdate {
+  version: String,
+}
+
+impl OutdatedPlugin for GoOutdated {
+  fn kind(&self) -> ProjectKind {
+    ProjectKind::Go
+  }
+
+  fn command(&self) -> &[&str] {
+    &["go", "list", "-m", "-u", "-json", "all"]
+  }
+
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::{GoOutdated, OutdatedPlugin};
+  ///
+  /// let output = r#"
+  ///   {"Path": "example.com/app", "Main": true}
+  ///   {"Path": "golang.org/x/text", "Version": "v0.3.0", "Update": {"Version": "v0.16.0"}}
+  ///   {"Path": "golang.org/x/sys", "Version": "v0.1.0", "Update": {"Version": "v0.2.0"},
+  ///    "Indirect": true}
+  /// "#;
+  /// let outdated = GoOutdated.parse(output).unwrap();
+  /// assert_eq!(outdated.len(), 1);
+  /// assert_eq!(outdated[0].name, "golang.org/x/text");
+  /// ```
+  fn parse(&self, output: &str) -> crate::Result<Vec<OutdatedDependency>> {
+    let mut ret = vec![];
+    for module in serde_json::Deserializer::from_str(output).into_iter::<GoModule>() {
+      let module =
+        module.map_err(|e| Error::Exec(tr_fmt("audit.invalid-output", &[&"go list", &e])))?;
+      if let (false, false, Some(update)) = (module.main, module.indirect, module.update) {
+        ret.push(OutdatedDependency {
+          name: module.path,
+          current: module.version,
+          latest: update.version,
+        });
+      }
+    }
+    Ok(ret)
+  }
+}
+
+/// The plugins auditing the outdated dependencies, one per supported project kind
+pub fn outdated_plugins() -> Vec<Box<dyn OutdatedPlugin>> {
+  vec![
+    Box::new(CargoOutdated),
+    Box::new(NpmOutdated),
+    Box::new(GoOutdated),
+  ]
+}
+
+/// The outcome of auditing a project kind by [`audit_outdated`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditResult {
+  /// The project folder
+  pub path: PathBuf,
+  /// The kind audited
+  pub kind: ProjectKind,
+  /// The outdated direct dependencies
+  pub outdated: Vec<OutdatedDependency>,
+  /// Why the audit failed or timed out
+  pub error: Option<String>,
+}
+
+impl AuditResult {
+  /// Summarize the outcomes as a table, one row per audited project kind
+  pub fn table(results: &[AuditResult]) -> Table {
+    let mut table = Table::new(
+      "Outdated",
+      &["Status", "Path", "Kind", "Outdated", "Dependencies"],
+    );
+    for result in results {
+      let status = match (&result.error, result.outdated.is_empty()) {
+        (Some(_), _) => "failed",
+        (None, true) => "up to date",
+        (None, false) => "outdated",
+      };
+      let deps = result
+        .outdated
+        .iter()
+        .map(|dep| format!("{} {} -> {}", dep.name, dep.current, dep.latest))
+        .collect::<Vec<_>>();
+      table.push_row(vec![
+        status.into(),
+        result.path.display().to_string().into(),
+        result.kind.name().into(),
+        result.outdated.len().to_string().into(),
+        deps.into(),
+      ]);
+    }
+    table
+  }
+}
+
+/// Audit the outdated direct dependencies of every project, running the [`outdated_plugins`]
+/// of their kinds `parallel` at a time.
+///
+/// A tool still running after `timeout` is killed. Projects of kinds without a plugin are
+/// skipped, as are audits not started yet once `cancel` is triggered. The results keep the
+/// order of `projects`.
+pub fn audit_outdated(
+  projects: &[Project],
+  parallel: usize,
+  timeout: Duration,
+  cancel: &CancelToken,
+) -> Vec<AuditResult> {
+  let plugins = outdated_plugins();
+  let jobs = projects
+    .iter()
+    .flat_map(|project| {
+      let plugins = &plugins;
+      project.kinds().iter().filter_map(move |kind| {
+        let plugin = plugins.iter().find(|plugin| plugin.kind() == *kind)?;
+        Some((project, plugin.as_ref()))
+      })
+    })
+    .collect::<Vec<_>>();
+  let next = AtomicUsize::new(0);
+  let results = Mutex::new(vec![None; jobs.len()]);
+  std::thread::scope(|scope| {
+    for _ in 0..parallel.clamp(1, jobs.len().max(1)) {
+      scope.spawn(|| loop {
+        let id = next.fetch_add(1, Ordering::SeqCst);
+        if id >= jobs.len() || cancel.is_cancelled() {
+          return;
+        }
+        let (project, plugin) = jobs[id];
+        let result = audit(project.path(), plugin, timeout);
+        results.lock().unwrap()[id] = Some(AuditResult {
+          path: project.path().clone(),
+          kind: plugin.kind(),
+          outdated: result.as_ref().cloned().unwrap_or_default(),
+          error: result.err().map(|e| e.to_string()),
+        });
+      });
+    }
+  });
+  results
+    .into_inner()
+    .unwrap()
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Run a plugin in the `path` project folder
+fn audit(
+  path: &Path,
+  plugin: &dyn OutdatedPlugin,
+  timeout: Duration,
+) -> crate::Result<Vec<OutdatedDependency>> {
+  let command = plugin.command();
+  debug!("running `{}` in '{}'", command.join(" "), path.display());
+  let output = run(command, path, timeout)?;
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  // some tools, like npm, exit with an error when dependencies are outdated
+  match plugin.parse(&stdout) {
+    Ok(outdated) if output.status.success() || !stdout.trim().is_empty() => Ok(outdated),
+    Err(e) if output.status.success() => Err(e),
+    _ => {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      let last_line = stderr.lines().rev().find(|line| !line.trim().is_empty());
+      let last_line = last_line.unwrap_or_default().trim();
+      Err(Error::Exec(tr_fmt(
+        "audit.failed",
+        &[&command.join(" "), &last_line],
+      )))
+    }
+  }
+}
+
+/// Run a command, killing it once `timeout` elapsed
+fn run(command: &[&str], dir: &Path, timeout: Duration) -> crate::Result<Output> {
+  let mut child = Command::new(command[0])
+    .args(&command[1..])
+    .current_dir(dir)
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .map_err(|e| Error::io(format!("failed to run {}", command[0]), e))?;
+  // drain the pipes while waiting, the child would block once they are full
+  let drain = |pipe: Option<Box<dyn Read + Send>>| {
+    std::thread::spawn(move || {
+      let mut ret = vec![];
+      if let Some(mut pipe) = pipe {
+        let _ = pipe.read_to_end(&mut ret);
+      }
+      ret
+    })
+  };
+  let stdout = drain(child.stdout.take().map(|pipe| Box::new(pipe) as _));
+  let stderr = drain(child.stderr.take().map(|pipe| Box::new(pipe) as _));
+  let deadline = Instant::now() + timeout;
+  let status = loop {
+    if let Some(status) = child.try_wait()? {
+      break status;
+    }
+    if Instant::now() >= deadline {
+      let _ = child.kill();
+      let _ = child.wait();
+      // the readers are left behind, grandchildren may still hold the pipes
+      let message = tr_fmt("audit.timed-out", &[&command.join(" "), &timeout.as_secs()]);
+      return Err(Error::Exec(message));
+    }
+    std::thread::sleep(Duration::from_millis(50));
+  };
+  Ok(Output {
+    status,
+    stdout: stdout.join().unwrap_or_default(),
+    stderr: stderr.join().unwrap_or_default(),
+  })
+}
@@ -0,0 +1,739 @@
+use std::{
+  collections::{hash_map::DefaultHasher, HashMap, HashSet},
+  hash::{Hash, Hasher},
+  path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Duration, Local};
+use log::{debug, warn};
+use rmp_serde::{Deserializer, Serializer};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  get_project_dirs,
+  i18n::{tr, tr_fmt},
+  Error, APP_APPLICATION,
+};
+
+/// An index for cache files which allows storing last write times and paths.
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
+pub struct Index {
+  #[serde(with = "crate::path_serde::vec")]
+  paths: Vec<PathBuf>,
+  #[serde(with = "crate::path_serde::map")]
+  write_times: HashMap<PathBuf, DateTime<Local>>,
+  written_at: Option<DateTime<Local>>,
+  /// Bumped whenever an entry is stored, invalidated or dropped
+  #[serde(default)]
+  generation: u64,
+  /// The fingerprints of the settings the entries were made with, see
+  /// [`Cache::check_fingerprint`]
+  #[serde(default, with = "crate::path_serde::map")]
+  fingerprints: HashMap<PathBuf, String>,
+  /// The samples of the file system state the entries were made from, see
+  /// [`Cache::check_sample`]
+  #[serde(default, with = "crate::path_serde::map")]
+  samples: HashMap<PathBuf, String>,
+}
+
+/// The cache store holding the caching state of the whole app.
+///
+/// It will write the index on shutdown to persist state.
+pub struct Cache {
+  base_dir: PathBuf,
+  index: Index,
+  enabled: bool,
+  /// Whether entries are only loaded, see [`Cache::set_read_only`]
+  read_only: bool,
+  /// A read-only cache folder shared by every user, and its index
+  shared: Option<(PathBuf, Index)>,
+  /// The bucket the cache is synchronized with, and its index when it was last read
+  #[cfg(feature = "s3")]
+  remote: Option<(crate::S3Store, Index)>,
+}
+
+impl Cache {
+  /// The threshold after which to bust the cache, effectively rescanning project roots
+  pub const CACHE_BUST_THRESHOLD: Duration = Duration::minutes(5);
+  /// The threshold after which to bust the entries checked by [`Cache::check_sample`], catching
+  /// the changes their sample misses
+  pub const SAMPLED_BUST_THRESHOLD: Duration = Duration::hours(1);
+  /// The stored files extension
+  pub const CACHE_EXT: &'static str = ".bin";
+  /// The key under which to find the index
+  pub const CACHE_INDEX_KEY: &'static str = "index";
+  /// The shared cache folder used when none is configured, if it exists
+  #[cfg(unix)]
+  pub const DEFAULT_SHARED_DIR: Option<&'static str> = Some("/var/lib/pgrep/index");
+  /// The shared cache folder used when none is configured, if it exists
+  #[cfg(not(unix))]
+  pub const DEFAULT_SHARED_DIR: Option<&'static str> = None;
+
+  /// Create a new cache store in the user cache folder.
+  ///
+  /// On locked-down systems where it cannot be created or written to, such as sandboxes and
+  /// containers, a temporary folder is used instead, and finally nothing is cached at all.
+  pub fn new() -> Self {
+    let mut dirs = vec![];
+    if let Some(proj_dir) = get_project_dirs() {
+      dirs.push(proj_dir.cache_dir().to_path_buf());
+    }
+    let temp_dir = std::env::temp_dir().join(format!("{}-{}", APP_APPLICATION, whoami::username()));
+    dirs.push(temp_dir.clone());
+    for dir in dirs {
+      let opened = Self::check_writable(&dir)
+        .map_err(Error::from)
+        .and_then(|_| Self::open(dir.clone()));
+      match opened {
+        Ok(cache) => return cache,
+        Err(e) => warn!("{}", tr_fmt("cache.unusable", &[&dir.display(), &e])),
+      }
+    }
+    warn!("{}", tr("cache.in-memory"));
+    let mut ret = Self::empty(temp_dir);
+    ret.disable();
+    ret
+  }
+
+  /// Create a cache store without entries in `cache_dir`, without touching the file system
+  fn empty(cache_dir: PathBuf) -> Self {
+    Self {
+      index: Index::default(),
+      base_dir: cache_dir,
+      enabled: true,
+      read_only: false,
+      shared: None,
+      #[cfg(feature = "s3")]
+      remote: None,
+    }
+  }
+
+  /// Check that files can be written to a cache folder, creating it if needed.
+  /// It may exist but be read-only.
+  fn check_writable(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let probe = dir.join(".write-test");
+    std::fs::write(&probe, [])?;
+    std::fs::remove_file(&probe)
+  }
+
+  /// Write a cache file through a temporary file renamed over it, so that an interrupted
+  /// write never leaves a truncated file behind
+  fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let mut temp = path.as_os_str().to_os_string();
+    temp.push(".tmp");
+    std::fs::write(&temp, content)?;
+    std::fs::rename(&temp, path)
+  }
+
+  /// Open the cache store held by `cache_dir`, creating the folder if needed
+  pub fn open<P: AsRef<Path>>(cache_dir: P) -> crate::Result<Self> {
+    let cache_dir = cache_dir.as_ref().to_path_buf();
+    if !cache_dir.exists() {
+      std::fs::create_dir_all(&cache_dir)?;
+    }
+    let mut ret = Self::empty(cache_dir);
+    let index_path = ret.path(Self::CACHE_INDEX_KEY);
+    if index_path.exists() {
+      if let Err(e) = ret.load_index() {
+        debug!("{}", e);
+      }
+    }
+    for (key, write_time) in &ret.index.write_times {
+      let expires_at: DateTime<Local> = *write_time + ret.max_age(key);
+      let now = Local::now();
+      let is_expired = now > expires_at;
+      debug!(
+        "{} created at {}{}",
+        key.display(),
+        write_time.format("%Y-%m-%d %H:%M:%S"),
+        match is_expired {
+          true => format!(
+            " \x1b[0;31mexpired at\x1b[0m {} ({} minutes ago)",
+            expires_at.format("%Y-%m-%d %H:%M:%S"),
+            (now - expires_at).num_minutes()
+          ),
+          false => format!(
+            " \x1b[0;32mwill expire at\x1b[0m {}",
+            expires_at.format("%Y-%m-%d %H:%M:%S")
+          ),
+        }
+      );
+    }
+    Ok(ret)
+  }
+
+  /// Move the cache store to another folder, loading its index. The previous folder is left as is
+  pub fn set_base_dir<P: AsRef<Path>>(&mut self, dir: P) -> crate::Result<()> {
+    let mut moved = Self::open(dir.as_ref())?;
+    moved.enabled = self.enabled;
+    moved.read_only = self.read_only;
+    *self = moved;
+    Ok(())
+  }
+
+  /// Retrieve the folder holding the cache files
+  pub fn base_dir(&self) -> &PathBuf {
+    &self.base_dir
+  }
+
+  /// Use the read-only cache folder `dir`, usually maintained by an administrator with
+  /// `--cache-dir`, for the entries missing or stale in this one. Its entries are used whatever
+  /// their age. Returns whether `dir` holds an index.
+  pub fn attach_shared<P: AsRef<Path>>(&mut self, dir: P) -> crate::Result<bool> {
+    let dir = dir.as_ref();
+    let index_path = dir.join(Self::CACHE_INDEX_KEY.to_string() + Self::CACHE_EXT);
+    if !index_path.exists() {
+      return Ok(false);
+    }
+    let index = Self::read_index(&index_path)?;
+    debug!(
+      "Loaded shared index '{}': {} entries",
+      index_path.display(),
+      index.paths.len()
+    );
+    self.shared = Some((dir.to_path_buf(), index));
+    Ok(true)
+  }
+
+  /// If disabled, caching will never occur
+  pub fn set_enabled(&mut self, state: bool) {
+    self.enabled = state
+  }
+
+  /// Enable caching
+  pub fn enable(&mut self) {
+    self.set_enabled(true)
+  }
+
+  /// Disable caching
+  pub fn disable(&mut self) {
+    self.set_enabled(false)
+  }
+
+  /// If read-only, entries are loaded whatever their age but nothing is ever written:
+  /// neither new entries, nor the index, nor remote entries
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::Cache;
+  ///
+  /// let mut cache = Cache::open(std::env::temp_dir().join("pgrep-doc-read-only")).unwrap();
+  /// cache.set_read_only(true);
+  /// let path = cache.store(&"/dev/read-only/project", &42u32).unwrap();
+  /// assert!(!path.exists());
+  /// assert_eq!(cache.load::<_, u32>("/dev/read-only/project").unwrap(), None);
+  /// ```
+  pub fn set_read_only(&mut self, state: bool) {
+    self.read_only = state
+  }
+
+  /// Check whether nothing is written to the cache folder
+  pub fn is_read_only(&self) -> bool {
+    self.read_only
+  }
+
+  /// Save the index
+  pub fn save_index(&mut self) -> crate::Result<()> {
+    if !self.enabled || self.read_only {
+      return Ok(());
+    }
+    let mut buf = vec![];
+    self.index.written_at = Some(Local::now());
+    self
+      .index
+      .serialize(&mut Serializer::new(&mut buf))
+      .map_err(|e| Error::cache(Self::CACHE_INDEX_KEY, "failed to serialize", e))?;
+    let path = self.path(Self::CACHE_INDEX_KEY);
+    Self::write_atomic(&path, &buf)
+      .map_err(|e| Error::cache(&path, "failed to save index to", e))?;
+    debug!(
+      "Saved '{}': {} entries",
+      path.display(),
+      self.index.paths.len()
+    );
+    Ok(())
+  }
+
+  /// Load the index
+  pub fn load_index(&mut self) -> crate::Result<()> {
+    if !self.enabled {
+      return Ok(());
+    }
+    let path = self.path(Self::CACHE_INDEX_KEY);
+    self.index = Self::read_index(&path)?;
+    debug!(
+      "Loaded '{}': {} entries",
+      path.display(),
+      self.index.paths.len()
+    );
+    Ok(())
+  }
+
+  /// Read the index stored at `path`
+  fn read_index(path: &Path) -> crate::Result<Index> {
+    let buf =
+      std::fs::read(path).map_err(|e| Error::cache(path, "failed to load index from", e))?;
+    let mut de = Deserializer::new(buf.as_slice());
+    Deserialize::deserialize(&mut de)
+      .map_err(|e| Error::cache(path, "failed to deserialize index", e))
+  }
+
+  /// Shutdown the cache store, saving its index and uploading the new entries to the remote
+  /// store if any
+  pub fn shutdown(&mut self) -> crate::Result<()> {
+    self.save_index()?;
+    #[cfg(feature = "s3")]
+    if let Err(e) = self.push() {
+      log::warn!("{}", crate::i18n::tr_fmt("cache.remote-failed", &[&e]));
+    }
+    Ok(())
+  }
+
+  /// Retrieve the name of the file storing `key`, also naming its remote object
+  #[cfg(feature = "s3")]
+  fn object_name<K: AsRef<Path>>(&self, key: K) -> String {
+    self
+      .path(key)
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_default()
+  }
+
+  /// Read the index of the remote store, empty if it doesn't exist yet
+  #[cfg(feature = "s3")]
+  fn remote_index(&self, store: &crate::S3Store) -> crate::Result<Index> {
+    let name = self.object_name(Self::CACHE_INDEX_KEY);
+    match store.get(&name)? {
+      Some(content) => Index::deserialize(&mut Deserializer::new(content.as_slice()))
+        .map_err(|e| Error::cache(&name, "failed to deserialize remote index", e)),
+      None => Ok(Index::default()),
+    }
+  }
+
+  /// Synchronize the cache with a remote store shared by several machines: the remote entries
+  /// newer than the local ones are downloaded now, and the local entries written since are
+  /// uploaded by [`Cache::shutdown`]. Returns the number of downloaded entries.
+  #[cfg(feature = "s3")]
+  pub fn attach_remote(&mut self, store: crate::S3Store) -> crate::Result<usize> {
+    if !self.enabled || self.read_only {
+      return Ok(0);
+    }
+    let remote_index = self.remote_index(&store)?;
+    let mut pulled = 0;
+    for (key, remote_time) in &remote_index.write_times {
+      if self
+        .index
+        .write_times
+        .get(key)
+        .is_some_and(|time| time >= remote_time)
+      {
+        continue;
+      }
+      let Some(content) = store.get(&self.object_name(key))? else {
+        continue;
+      };
+      std::fs::write(self.path(key), content)
+        .map_err(|e| Error::cache(key, "cannot save remote cache entry", e))?;
+      if !self.index.paths.contains(key) {
+        self.index.paths.push(key.clone());
+      }
+      self.index.write_times.insert(key.clone(), *remote_time);
+      pulled += 1;
+    }
+    debug!("downloaded {} remote cache entries", pulled);
+    self.remote = Some((store, remote_index));
+    Ok(pulled)
+  }
+
+  /// Upload the entries written since the remote store was attached, then merge the indexes
+  #[cfg(feature = "s3")]
+  fn push(&mut self) -> crate::Result<()> {
+    let Some((store, pulled)) = &self.remote else {
+      return Ok(());
+    };
+    // another machine may have pushed meanwhile, keep its entries
+    let mut merged = self.remote_index(store)?;
+    let mut pushed = 0;
+    for (key, time) in &self.index.write_times {
+      let is_newer = |index: &Index| match index.write_times.get(key) {
+        Some(written_at) => written_at < time,
+        None => true,
+      };
+      if !is_newer(pulled) || !is_newer(&merged) {
+        continue;
+      }
+      let path = self.path(key);
+      let content =
+        std::fs::read(&path).map_err(|e| Error::cache(key, "cannot load cache entry", e))?;
+      store.put(&self.object_name(key), &content)?;
+      if !merged.paths.contains(key) {
+        merged.paths.push(key.clone());
+      }
+      merged.write_times.insert(key.clone(), *time);
+      pushed += 1;
+    }
+    if pushed == 0 {
+      return Ok(());
+    }
+    merged.written_at = Some(Local::now());
+    let mut buf = vec![];
+    merged
+      .serialize(&mut Serializer::new(&mut buf))
+      .map_err(|e| Error::cache(Self::CACHE_INDEX_KEY, "failed to serialize", e))?;
+    store.put(&self.object_name(Self::CACHE_INDEX_KEY), &buf)?;
+    debug!("uploaded {} cache entries", pushed);
+    Ok(())
+  }
+
+  /// Forget when the entry stored under `key` was written, so that the next
+  /// [`Cache::load_store`] refreshes it
+  pub fn invalidate<K: AsRef<Path>>(&mut self, key: &K) {
+    if self.index.write_times.remove(key.as_ref()).is_some() {
+      self.index.generation += 1;
+    }
+  }
+
+  /// Record the `fingerprint` of the settings the entry stored under `key` depends on, such as
+  /// [`crate::ScanOptions::fingerprint`], invalidating the entry when it was made with other
+  /// settings. Returns whether it was invalidated.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::Cache;
+  ///
+  /// let mut cache = Cache::open(std::env::temp_dir().join("pgrep-doc-fingerprint")).unwrap();
+  /// cache.check_fingerprint(&"/dev", "a");
+  /// cache.store(&"/dev", &vec!["api"]).unwrap();
+  /// assert!(!cache.check_fingerprint(&"/dev", "a"));
+  /// assert!(cache.check_fingerprint(&"/dev", "b"));
+  /// assert!(!cache.contains(&"/dev"));
+  /// ```
+  pub fn check_fingerprint<K: AsRef<Path>>(&mut self, key: &K, fingerprint: &str) -> bool {
+    let key = key.as_ref();
+    if !Self::record(&mut self.index.fingerprints, key, fingerprint) {
+      return false;
+    }
+    let stored = self.index.write_times.contains_key(key);
+    if stored {
+      debug!("settings changed for '{}', invalidating it", key.display());
+      self.invalidate(&key);
+    }
+    stored
+  }
+
+  /// Record the `sample` of the file system state the entry stored under `key` was made from,
+  /// such as [`crate::FolderScan::mtime_sample`], invalidating the entry when it changed.
+  /// Returns whether it was invalidated.
+  ///
+  /// Checked entries are trusted for [`Self::SAMPLED_BUST_THRESHOLD`] instead of
+  /// [`Self::CACHE_BUST_THRESHOLD`], the sample telling when they are outdated sooner.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::Cache;
+  ///
+  /// let mut cache = Cache::open(std::env::temp_dir().join("pgrep-doc-sample")).unwrap();
+  /// cache.check_sample(&"/dev", "a");
+  /// cache.store(&"/dev", &vec!["api"]).unwrap();
+  /// assert!(!cache.check_sample(&"/dev", "a"));
+  /// assert!(cache.check_sample(&"/dev", "b"));
+  /// assert!(!cache.contains(&"/dev"));
+  /// ```
+  pub fn check_sample<K: AsRef<Path>>(&mut self, key: &K, sample: &str) -> bool {
+    let key = key.as_ref();
+    if !Self::record(&mut self.index.samples, key, sample) {
+      return false;
+    }
+    let stored = self.index.write_times.contains_key(key);
+    if stored {
+      debug!("'{}' changed on disk, invalidating it", key.display());
+      self.invalidate(&key);
+    }
+    stored
+  }
+
+  /// Record `value` for `key` in `values`, returning whether it differs from the known one
+  fn record(values: &mut HashMap<PathBuf, String>, key: &Path, value: &str) -> bool {
+    if values.get(key).is_some_and(|known| known == value) {
+      return false;
+    }
+    values.insert(key.to_path_buf(), value.to_string());
+    true
+  }
+
+  /// Retrieve how long the entry stored under `key` is trusted once written, see
+  /// [`Self::check_sample`]
+  pub fn max_age<K: AsRef<Path>>(&self, key: &K) -> Duration {
+    match self.index.samples.contains_key(key.as_ref()) {
+      true => Self::SAMPLED_BUST_THRESHOLD,
+      false => Self::CACHE_BUST_THRESHOLD,
+    }
+  }
+
+  /// Retrieve the generation of the cache index, which changes whenever an entry is stored,
+  /// invalidated or dropped, so that values derived from the entries can tell they are outdated
+  pub fn generation(&self) -> u64 {
+    let shared = self
+      .shared
+      .as_ref()
+      .map_or(0, |(_, index)| index.generation);
+    self.index.generation + shared
+  }
+
+  /// Check whether the entry stored under `key` would be loaded rather than refreshed by
+  /// [`Cache::load_store`], see [`Self::max_age`]
+  pub fn contains<K: AsRef<Path>>(&self, key: &K) -> bool {
+    self.enabled && self.entry_path(key.as_ref()).is_some()
+  }
+
+  /// Drop the entries whose key isn't kept by `keep`, and the files no entry refers to.
+  ///
+  /// Returns the keys of the dropped entries, followed by the paths of the orphan files.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::Cache;
+  ///
+  /// // keep every indexed entry, only dropping orphan files
+  /// let mut cache = Cache::open(std::env::temp_dir().join("pgrep-doc-gc")).unwrap();
+  /// let removed = cache.gc(|_| true).unwrap();
+  /// assert!(removed.iter().all(|path| !path.exists()));
+  /// ```
+  pub fn gc<F: Fn(&Path) -> bool>(&mut self, keep: F) -> crate::Result<Vec<PathBuf>> {
+    if !self.enabled || self.read_only {
+      return Ok(vec![]);
+    }
+    let (kept, mut removed): (Vec<_>, Vec<_>) =
+      self.index.paths.drain(..).partition(|key| keep(key));
+    self.index.paths = kept;
+    let paths = &self.index.paths;
+    self.index.write_times.retain(|key, _| paths.contains(key));
+    self.index.fingerprints.retain(|key, _| paths.contains(key));
+    self.index.samples.retain(|key, _| paths.contains(key));
+    if !removed.is_empty() {
+      self.index.generation += 1;
+    }
+    for key in &removed {
+      let path = self.path(key);
+      if path.exists() {
+        std::fs::remove_file(&path)
+          .map_err(|e| Error::cache(key, "cannot remove cache entry", e))?;
+      }
+    }
+    let known = self
+      .index
+      .paths
+      .iter()
+      .map(|key| self.path(key))
+      .chain([self.path(Self::CACHE_INDEX_KEY)])
+      // stored next to the entries, but outside of the index
+      .chain([self.base_dir.join(crate::FacetCache::FILE_NAME)])
+      .chain([self.base_dir.join(crate::ResultCache::FILE_NAME)])
+      .collect::<HashSet<_>>();
+    for entry in std::fs::read_dir(&self.base_dir)? {
+      let path = entry?.path();
+      let is_entry = path.to_string_lossy().ends_with(Self::CACHE_EXT);
+      if is_entry && !known.contains(&path) {
+        debug!("removing orphan cache file '{}'", path.display());
+        std::fs::remove_file(&path)
+          .map_err(|e| Error::cache(&path, "cannot remove orphan cache file", e))?;
+        removed.push(path);
+      }
+    }
+    debug!("dropped {} stale cache entries", removed.len());
+    Ok(removed)
+  }
+
+  /// Will delete everything in the cache folder!
+  pub fn clean(&self) -> crate::Result<PathBuf> {
+    if self.base_dir.exists() {
+      std::fs::remove_dir_all(&self.base_dir)?;
+    }
+    Ok(self.base_dir.clone())
+  }
+
+  /// Retrieve the on-disk path for a given key.
+  /// This will replace non-alnum characters with '_', and append a hash of the key when it
+  /// isn't valid UTF-8 so that keys differing by their invalid bytes keep their own file
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use std::path::PathBuf;
+  /// use pgrep_core::Cache;
+  ///
+  /// let cache = Cache::open(std::env::temp_dir().join("pgrep-doc")).unwrap();
+  /// assert_eq!(cache.path("/home/user/myfile.txt").file_name().unwrap(), PathBuf::from("_home_user_myfile_txt.bin"));
+  /// assert_eq!(cache.path("myfile.txt").file_name().unwrap(), PathBuf::from("myfile_txt.bin"));
+  /// ```
+  pub fn path<K: AsRef<Path>>(&self, key: K) -> PathBuf {
+    let sub = format!("{}", key.as_ref().display())
+      .replace("\\", "/")
+      .chars()
+      .map(|ch| {
+        if ch.is_ascii_alphanumeric() {
+          return ch;
+        }
+        '_'
+      })
+      .collect::<String>();
+    let sub = match key.as_ref().to_str() {
+      Some(_) => sub,
+      None => {
+        let mut hasher = DefaultHasher::new();
+        key.as_ref().hash(&mut hasher);
+        format!("{}_{:016x}", sub, hasher.finish())
+      }
+    } + Self::CACHE_EXT;
+    self.base_dir.join(&sub)
+  }
+
+  /// Load a cached entity from the store
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use std::path::PathBuf;
+  /// use pgrep_core::{Cache, Project, Result};
+  ///
+  /// let mut cache = Cache::open(std::env::temp_dir().join("pgrep-doc")).unwrap();
+  /// let res: Result<Option<Project>> = cache.load("C:/dev/project/my_project");
+  /// ```
+  pub fn load<'a, K: AsRef<Path>, E: Deserialize<'a>>(&self, key: K) -> crate::Result<Option<E>> {
+    if !self.enabled {
+      return Ok(None);
+    }
+    debug!("loading '{}' from cache", key.as_ref().display());
+    let Some(path) = self.entry_path(key.as_ref()) else {
+      return Ok(None);
+    };
+    let content =
+      std::fs::read(path).map_err(|e| Error::cache(key.as_ref(), "cannot load cache entry", e))?;
+    let mut de = Deserializer::new(content.as_slice());
+    let ret: E = Deserialize::deserialize(&mut de)
+      .map_err(|e| Error::cache(key.as_ref(), "cannot deserialize cache entry", e))?;
+    Ok(Some(ret))
+  }
+
+  /// Retrieve when the entry stored under `key` was last written, if it is in the index
+  pub fn written_at<K: AsRef<Path>>(&self, key: K) -> Option<DateTime<Local>> {
+    self.index.write_times.get(key.as_ref()).copied()
+  }
+
+  /// Find the file holding the entry stored under `key`: the user one unless stale, else the
+  /// shared one
+  fn entry_path(&self, key: &Path) -> Option<PathBuf> {
+    match self.index.write_times.get(key) {
+      Some(write_time) if self.read_only || Local::now() < *write_time + self.max_age(&key) => {
+        let path = self.path(key);
+        if path.exists() {
+          return Some(path);
+        }
+      }
+      Some(_) => debug!("cache is stale for '{}'", key.display()),
+      None => debug!("cache entry '{}' not in index", key.display()),
+    }
+    let (dir, index) = self.shared.as_ref()?;
+    if !index.write_times.contains_key(key) {
+      return None;
+    }
+    let path = dir.join(self.path(key).file_name()?);
+    debug!("using shared cache entry '{}'", path.display());
+    path.exists().then_some(path)
+  }
+
+  /// Save an entity to the cache store
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use std::path::PathBuf;
+  /// use pgrep_core::{Cache, Project, Result, ProjectKind};
+  ///
+  /// let mut cache = Cache::open(std::env::temp_dir().join("pgrep-doc")).unwrap();
+  /// let project = Project::new(
+  ///   "C:/dev/project/my_project",
+  ///   vec![ProjectKind::Rust],
+  ///   vec![PathBuf::from("C:/dev/project/my_project/src/main.rs")],
+  ///   vec![PathBuf::from("C:/dev/project/my_project/Cargo.toml")]
+  /// );
+  /// let res: Result<PathBuf> = cache.store(project.path(), &project);
+  /// ```
+  pub fn store<K: AsRef<Path>, E: Serialize>(
+    &mut self,
+    key: &K,
+    value: &E,
+  ) -> crate::Result<PathBuf> {
+    let path = self.path(key);
+    if !self.enabled || self.read_only {
+      return Ok(path);
+    }
+    debug!("saving '{}' to cache", key.as_ref().display());
+    let mut buf = vec![];
+    value
+      .serialize(&mut Serializer::new(&mut buf))
+      .map_err(|e| Error::cache(key.as_ref(), "cannot serialize cache entry", e))?;
+    Self::write_atomic(&path, &buf)
+      .map_err(|e| Error::cache(key.as_ref(), "cannot save cache entry", e))?;
+    let key_path = key.as_ref().to_path_buf();
+    if !self.index.paths.contains(&key_path) {
+      self.index.paths.push(key_path.clone());
+    }
+    self.index.write_times.insert(key_path, Local::now());
+    self.index.generation += 1;
+    Ok(path)
+  }
+
+  /// Load the entity from cache if it was found in the store and its [`Self::max_age`] has not
+  /// been reached yet.
+  ///
+  /// Otherwise store the entity provided by the `action` parameter. Entries that cannot be
+  /// deserialized anymore, such as those written by an older version, are refreshed the same way.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use std::path::PathBuf;
+  /// use pgrep_core::{Cache, Project, Result, ProjectKind};
+  ///
+  /// let mut cache = Cache::open(std::env::temp_dir().join("pgrep-doc")).unwrap();
+  /// let path = PathBuf::from("C:/dev/project/my_project");
+  /// let project: Result<Project> = cache.load_store(&path, || Ok(Project::new(
+  ///   path.clone(),
+  ///   vec![ProjectKind::Rust],
+  ///   vec![PathBuf::from("C:/dev/project/my_project/src/main.rs")],
+  ///   vec![PathBuf::from("C:/dev/project/my_project/Cargo.toml")]
+  /// )));
+  /// ```
+  pub fn load_store<
+    'a,
+    K: AsRef<Path>,
+    E: Deserialize<'a> + Serialize,
+    F: Fn() -> crate::Result<E>,
+  >(
+    &mut self,
+    key: &K,
+    action: F,
+  ) -> crate::Result<E> {
+    // entries written by an older version may not deserialize anymore, refresh them
+    match self.load::<_, E>(key.as_ref()) {
+      Ok(Some(entity)) => return Ok(entity),
+      Ok(None) => {}
+      Err(e) => debug!("{}, refreshing it", e),
+    }
+    let entity = action()?;
+    self.store(key, &entity)?;
+    Ok(entity)
+  }
+}
+
+impl Default for Cache {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -0,0 +1,79 @@
+use std::{fmt::Display, path::Path};
+
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, IntoEnumIterator};
+
+/// A continuous integration system configured by a project, see [`CiSystem::detect`]
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum CiSystem {
+  /// GitHub Actions, configured by `.github/workflows/*.yml`
+  Github,
+  /// GitLab CI, configured by `.gitlab-ci.yml`
+  Gitlab,
+  /// Jenkins, configured by a `Jenkinsfile`
+  Jenkins,
+}
+
+impl CiSystem {
+  /// Retrieve the name used by the `ci:` query selector, such as `github`
+  pub fn name(&self) -> &'static str {
+    match self {
+      Self::Github => "github",
+      Self::Gitlab => "gitlab",
+      Self::Jenkins => "jenkins",
+    }
+  }
+
+  /// Find the system with the given name, ignoring case
+  pub fn from_name(name: &str) -> Option<Self> {
+    Self::iter().find(|ci| ci.name().eq_ignore_ascii_case(name))
+  }
+
+  /// Check whether the project in the `path` folder is configured for this system
+  pub fn is_configured(&self, path: &Path) -> bool {
+    match self {
+      Self::Github => std::fs::read_dir(path.join(".github").join("workflows"))
+        .map(|entries| {
+          entries.flatten().any(|entry| {
+            let path = entry.path();
+            let ext = path
+              .extension()
+              .and_then(|ext| ext.to_str())
+              .unwrap_or_default();
+            matches!(ext, "yml" | "yaml")
+          })
+        })
+        .unwrap_or(false),
+      Self::Gitlab => path.join(".gitlab-ci.yml").is_file(),
+      Self::Jenkins => path.join("Jenkinsfile").is_file(),
+    }
+  }
+
+  /// Detect the systems configured by the project in the `path` folder
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::CiSystem;
+  ///
+  /// let root = std::env::temp_dir().join(format!("pgrep-ci-{}", std::process::id()));
+  /// std::fs::create_dir_all(root.join(".github").join("workflows")).unwrap();
+  /// assert_eq!(CiSystem::detect(&root), vec![]);
+  /// std::fs::write(root.join(".github").join("workflows").join("rust.yml"), "on: push").unwrap();
+  /// std::fs::write(root.join("Jenkinsfile"), "pipeline {}").unwrap();
+  /// assert_eq!(CiSystem::detect(&root), vec![CiSystem::Github, CiSystem::Jenkins]);
+  /// std::fs::remove_dir_all(&root).unwrap();
+  /// ```
+  pub fn detect(path: &Path) -> Vec<Self> {
+    Self::iter().filter(|ci| ci.is_configured(path)).collect()
+  }
+}
+
+impl Display for CiSystem {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
@@ -0,0 +1,227 @@
+use std::{
+  fmt::Display,
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+use log::debug;
+
+use crate::{human_size, i18n::tr_fmt, Error, Project, ProjectKind, Table};
+
+/// A way of removing build artifacts, chosen per project kind by [`clean_actions`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CleanAction {
+  /// Run the clean command of the kind's build tool, such as `cargo clean`
+  Command { program: String, args: Vec<String> },
+  /// Remove an artifact folder, such as `node_modules`
+  Remove(PathBuf),
+}
+
+impl CleanAction {
+  /// Run the action in the `project` folder
+  fn run(&self, project: &Path) -> crate::Result<()> {
+    debug!("cleaning '{}': {}", project.display(), self);
+    match self {
+      Self::Command { program, args } => {
+        let output = Command::new(program)
+          .args(args)
+          .current_dir(project)
+          .output()
+          .map_err(|e| Error::io(format!("failed to run {}", program), e))?;
+        if !output.status.success() {
+          let stderr = String::from_utf8_lossy(&output.stderr);
+          return Err(Error::Exec(tr_fmt("clean.failed", &[self, &stderr.trim()])));
+        }
+      }
+      Self::Remove(dir) => std::fs::remove_dir_all(dir)
+        .map_err(|e| Error::io(format!("cannot remove '{}'", dir.display()), e))?,
+    }
+    Ok(())
+  }
+}
+
+impl Display for CleanAction {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Command { program, args } => write!(f, "{} {}", program, args.join(" ")),
+      Self::Remove(dir) => {
+        let name = dir.file_name().unwrap_or(dir.as_os_str());
+        write!(f, "rm -rf {}", name.to_string_lossy())
+      }
+    }
+  }
+}
+
+/// List the actions removing the existing build artifacts of `project`: `cargo clean` for Rust
+/// projects, removing the [`Project::artifact_dirs`] of the other kinds.
+///
+/// The `build` folder of C projects is only removed when clearly generated, see
+/// [`is_generated_build_dir`], `make clean` runs instead when the project has a `Makefile`.
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::{clean_actions, CleanAction, Project, ProjectKind};
+///
+/// let root = std::env::temp_dir().join(format!("pgrep-clean-doc-{}", std::process::id()));
+/// std::fs::create_dir_all(root.join("node_modules")).unwrap();
+/// let project = Project::new(&root, vec![ProjectKind::Node], vec![], vec![]);
+/// assert_eq!(clean_actions(&project), vec![CleanAction::Remove(root.join("node_modules"))]);
+/// std::fs::remove_dir_all(root.join("node_modules")).unwrap();
+/// assert_eq!(clean_actions(&project), vec![]);
+/// # std::fs::remove_dir_all(&root).unwrap();
+/// ```
+pub fn clean_actions(project: &Project) -> Vec<CleanAction> {
+  let mut ret = vec![];
+  let mut handled = vec![];
+  if project.kinds().contains(&ProjectKind::Rust) && project.path().join("target").is_dir() {
+    // also cleans a target folder configured elsewhere
+    ret.push(CleanAction::Command {
+      program: "cargo".to_string(),
+      args: vec!["clean".to_string()],
+    });
+    handled.push(project.path().join("target"));
+  }
+  let build = project.path().join("build");
+  if project.kinds().contains(&ProjectKind::C) && !is_generated_build_dir(project, &build) {
+    if project.path().join("Makefile").is_file() {
+      ret.push(CleanAction::Command {
+        program: "make".to_string(),
+        args: vec!["clean".to_string()],
+      });
+    }
+    handled.push(build);
+  }
+  for dir in project.artifact_dirs() {
+    if dir.is_dir() && !handled.contains(&dir) {
+      handled.push(dir.clone());
+      ret.push(CleanAction::Remove(dir));
+    }
+  }
+  ret
+}
+
+/// Check whether the `build` folder of a C `project` was generated by CMake: it holds a
+/// `CMakeCache.txt`, or the project has a `CMakeLists.txt` and no other kind
+fn is_generated_build_dir(project: &Project, build: &Path) -> bool {
+  let cmake_only = project.path().join("CMakeLists.txt").is_file()
+    && project.kinds().iter().all(|kind| *kind == ProjectKind::C);
+  build.join("CMakeCache.txt").is_file() || cmake_only
+}
+
+/// The outcome of [`clean`] for a single project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleanResult {
+  /// The project folder
+  pub path: PathBuf,
+  /// The actions run, in order
+  pub actions: Vec<CleanAction>,
+  /// The number of bytes freed
+  pub freed: u64,
+  /// Why an action failed, stopping the following ones
+  pub error: Option<String>,
+}
+
+impl CleanResult {
+  /// Describe the outcomes as a table, one row per project
+  pub fn table(results: &[CleanResult]) -> Table {
+    let mut table = Table::new("Cleaned", &["Status", "Path", "Actions", "Freed"]);
+    for result in results {
+      let status = match result.error {
+        Some(_) => "failed",
+        None => "ok",
+      };
+      let actions = result
+        .actions
+        .iter()
+        .map(|a| a.to_string())
+        .collect::<Vec<_>>();
+      table.push_row(vec![
+        status.into(),
+        result.path.display().to_string().into(),
+        actions.into(),
+        human_size(result.freed).into(),
+      ]);
+    }
+    table
+  }
+}
+
+/// Run the [`clean_actions`] of `project`, measuring the space they freed
+pub fn clean(project: &Project) -> CleanResult {
+  let actions = clean_actions(project);
+  let before = project.compute_disk_usage(true);
+  let error = actions
+    .iter()
+    .try_for_each(|action| action.run(project.path()))
+    .err()
+    .map(|e| e.to_string());
+  let after = project.compute_disk_usage(true);
+  CleanResult {
+    path: project.path().clone(),
+    actions,
+    freed: before.saturating_sub(after),
+    error,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use super::{clean_actions, CleanAction};
+  use crate::{test_support::temp_tree, Project, ProjectKind};
+
+  /// Create a project folder named after `test` holding the `files`, `build/` included
+  fn project(test: &str, kinds: Vec<ProjectKind>, files: &[&str]) -> (PathBuf, Project) {
+    let files = files.iter().map(|file| (*file, "")).collect::<Vec<_>>();
+    let root = temp_tree(&format!("clean-{}", test), &files);
+    std::fs::create_dir_all(root.join("build")).unwrap();
+    let project = Project::new(&root, kinds, vec![], vec![]);
+    (root, project)
+  }
+
+  fn make_clean() -> CleanAction {
+    CleanAction::Command {
+      program: "make".to_string(),
+      args: vec!["clean".to_string()],
+    }
+  }
+
+  #[test]
+  fn build_folders_of_other_kinds_are_kept() {
+    let (_, go) = project(
+      "go",
+      vec![ProjectKind::Go, ProjectKind::C],
+      &["go.mod", "Makefile"],
+    );
+    assert_eq!(clean_actions(&go), vec![make_clean()]);
+    let (root, node) = project(
+      "node",
+      vec![ProjectKind::Node, ProjectKind::C],
+      &["package.json", "Makefile", "node_modules/left-pad/index.js"],
+    );
+    let expected = vec![make_clean(), CleanAction::Remove(root.join("node_modules"))];
+    assert_eq!(clean_actions(&node), expected);
+  }
+
+  #[test]
+  fn generated_build_folders_are_removed() {
+    let (root, cmake) = project("cmake", vec![ProjectKind::C], &["CMakeLists.txt"]);
+    assert_eq!(
+      clean_actions(&cmake),
+      vec![CleanAction::Remove(root.join("build"))]
+    );
+    let (root, go) = project(
+      "cache",
+      vec![ProjectKind::Go, ProjectKind::C],
+      &["go.mod", "Makefile", "build/CMakeCache.txt"],
+    );
+    assert_eq!(
+      clean_actions(&go),
+      vec![CleanAction::Remove(root.join("build"))]
+    );
+    let (_, make) = project("make", vec![ProjectKind::C], &["Makefile"]);
+    assert_eq!(clean_actions(&make), vec![make_clean()]);
+  }
+}
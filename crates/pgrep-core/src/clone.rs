@@ -0,0 +1,146 @@
+//! Local clones of the projects found in remote folders, the `ssh://` and `container://` ones,
+//! made when they are opened since editors only open local folders.
+//!
+//! A project is cloned from its `origin` git remote, read on the machine or in the container
+//! holding it, so only the projects in a git work tree with such a remote can be cloned.
+
+use std::{
+  path::{Path, PathBuf},
+  process::{Command, Output},
+};
+
+use log::debug;
+
+use crate::{i18n::tr_fmt, ssh::shell_quote, ContainerFolder, Error, SshFolder};
+
+/// Read the URL of the `origin` git remote of the project at `path`, in a remote folder
+pub fn remote_origin<P: AsRef<Path>>(path: P) -> crate::Result<String> {
+  let path = path.as_ref();
+  let Some(output) = read_origin(path) else {
+    return Err(Error::config(tr_fmt("clone.local", &[&path.display()])));
+  };
+  let output = output.map_err(|e| {
+    Error::io(
+      format!("cannot read the git remote of '{}'", path.display()),
+      e,
+    )
+  })?;
+  let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+  match output.status.success() && !url.is_empty() {
+    true => Ok(url),
+    false => Err(Error::config(tr_fmt("clone.no-remote", &[&path.display()]))),
+  }
+}
+
+/// Run `git remote get-url origin` where the project at `path` is, `None` when it isn't in a
+/// remote folder
+fn read_origin(path: &Path) -> Option<std::io::Result<Output>> {
+  if let Some(remote) = SshFolder::parse(path) {
+    return Some(remote.run(&format!(
+      "git -C {} remote get-url origin",
+      remote.quoted_path()
+    )));
+  }
+  if let Some(container) = ContainerFolder::parse(path) {
+    let command = format!(
+      "git -C {} remote get-url origin",
+      shell_quote(&container.path)
+    );
+    return Some(container.exec(&command));
+  }
+  debug!("no remote folder holds '{}'", path.display());
+  None
+}
+
+/// Clone the project at `path`, in a remote folder, from its `origin` git remote into the
+/// `dir` folder, under its own folder name. Returns the clone, reused when it already exists.
+///
+/// `git` writes its progress to the standard error.
+pub fn clone_remote<P: AsRef<Path>>(path: P, dir: &Path) -> crate::Result<PathBuf> {
+  let path = path.as_ref();
+  let url = remote_origin(path)?;
+  let Some(name) = path.file_name() else {
+    return Err(Error::config(tr_fmt("clone.no-remote", &[&path.display()])));
+  };
+  let to = dir.join(name);
+  clone_url(&url, &to)?;
+  Ok(to)
+}
+
+/// Clone `url` to the `to` folder, unless it is already a clone of it
+fn clone_url(url: &str, to: &Path) -> crate::Result<()> {
+  if to.exists() {
+    let origin = Command::new("git")
+      .arg("-C")
+      .arg(to)
+      .args(["remote", "get-url", "origin"])
+      .output()
+      .map_err(|e| Error::io("failed to run git", e))?;
+    return match String::from_utf8_lossy(&origin.stdout).trim() == url {
+      true => Ok(()),
+      false => Err(Error::config(tr_fmt(
+        "clone.exists",
+        &[&to.display(), &url],
+      ))),
+    };
+  }
+  if let Some(parent) = to.parent() {
+    std::fs::create_dir_all(parent)
+      .map_err(|e| Error::io(format!("cannot create '{}'", parent.display()), e))?;
+  }
+  debug!("cloning '{}' to '{}'", url, to.display());
+  let status = Command::new("git")
+    .args(["clone", "--progress", "--"])
+    .arg(url)
+    .arg(to)
+    .status()
+    .map_err(|e| Error::io("failed to run git", e))?;
+  if !status.success() {
+    // leave no half-cloned project behind
+    let _ = std::fs::remove_dir_all(to);
+    return Err(Error::Exec(tr_fmt("clone.failed", &[&url, &status])));
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{path::Path, process::Command};
+
+  use super::{clone_url, remote_origin};
+
+  fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+      .arg("-C")
+      .arg(dir)
+      .args(args)
+      .status()
+      .unwrap();
+    assert!(status.success());
+  }
+
+  #[test]
+  fn local_projects_are_not_cloned() {
+    assert!(remote_origin("/home/me/src/api").is_err());
+  }
+
+  #[test]
+  fn clones_are_reused() {
+    let root = std::env::temp_dir().join(format!("pgrep-clone-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let origin = root.join("origin");
+    std::fs::create_dir_all(&origin).unwrap();
+    git(&origin, &["init", "--quiet"]);
+    let url = origin.to_str().unwrap();
+    let to = root.join("clones").join("api");
+    clone_url(url, &to).unwrap();
+    assert!(to.join(".git").is_dir());
+    clone_url(url, &to).unwrap();
+    // another project of the same name isn't mistaken for the clone
+    let other = root.join("other");
+    std::fs::create_dir_all(&other).unwrap();
+    git(&other, &["init", "--quiet"]);
+    assert!(clone_url(other.to_str().unwrap(), &to).is_err());
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+}
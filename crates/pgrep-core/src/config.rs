@@ -0,0 +1,1284 @@
+use std::{
+  collections::BTreeMap,
+  path::{Path, PathBuf},
+};
+
+use directories::{ProjectDirs, UserDirs};
+use log::{debug, trace, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::{
+  i18n::tr_fmt, is_secret_command, parse_size, split_wsl_path, wsl_share_path, Discovery, Error,
+  HooksConfig, MatchMode, OutputFormat, Project, ProjectKind, Query, ScanDetail, SortOrder,
+  WSL_SCHEME,
+};
+
+/// The qualifier for windows and macOS config folders
+pub const APP_QUALIFIER: &'static str = "com";
+/// The organization for windows and macOS config folders
+pub const APP_ORGANIZATION: &'static str = "darksofts";
+/// The application for windows and macOS config folders
+pub const APP_APPLICATION: &'static str = "pgrep";
+
+/// Retrieve the platform-dependent project directories.
+pub fn get_project_dirs() -> Option<ProjectDirs> {
+  ProjectDirs::from(APP_QUALIFIER, APP_ORGANIZATION, APP_APPLICATION)
+}
+
+/// Retrieve the user data folder, holding the data kept apart from the cache such as pins and
+/// notes, or a temporary folder when the platform has none.
+pub fn get_data_dir() -> PathBuf {
+  match get_project_dirs() {
+    Some(dirs) => dirs.data_dir().to_path_buf(),
+    None => std::env::temp_dir().join(APP_APPLICATION),
+  }
+}
+
+/// Expand a path containing symbolic dirs into an absolute one.
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::config::expand_path;
+/// use std::path::PathBuf;
+///
+/// let user = |path: &str| PathBuf::from(path.replace("<user>", &whoami::username()));
+/// let expanded = expand_path("~/my_folder").unwrap();
+///
+/// #[cfg(target_os = "windows")]
+/// assert_eq!(expanded, user("C:/Users/<user>/my_folder"));
+///
+/// #[cfg(target_os = "linux")]
+/// assert_eq!(expanded, user("/home/<user>/my_folder"));
+///
+/// #[cfg(target_os = "macos")]
+/// assert_eq!(expanded, user("/Users/<user>/my_folder"));
+/// ```
+///
+/// ```
+/// use pgrep_core::config::expand_path;
+/// use std::path::PathBuf;
+///
+/// std::env::set_var("MY_VAR", "a_value");
+///
+/// let expanded = expand_path("/var/${MY_VAR}/my_folder").unwrap();
+/// assert_eq!(expanded, PathBuf::from("/var/a_value/my_folder"));
+/// ```
+///
+pub fn expand_path<P: AsRef<Path>>(path: P) -> crate::Result<PathBuf> {
+  let home = if let Some(user_dirs) = UserDirs::new() {
+    user_dirs.home_dir().to_path_buf()
+  } else if let Ok(val) = std::env::var("HOME") {
+    PathBuf::from(val)
+  } else {
+    PathBuf::from(".")
+  };
+  let mut ret = format!("{}", path.as_ref().display());
+  if ret.starts_with("~") {
+    let mut sub = ret[1..].trim();
+    if sub.starts_with("/") {
+      sub = sub[1..].trim();
+    }
+    ret = format!("{}/{}", home.display(), sub);
+  }
+  Ok(expand_env(ret)?.into())
+}
+
+/// Check whether `path` designates a folder on another machine, such as `ssh://devbox:~/src`,
+/// which cannot be checked on the local file system.
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::config::is_remote_path;
+///
+/// assert!(is_remote_path("ssh://devbox:~/src"));
+/// assert!(!is_remote_path("/home/user/dev"));
+/// assert!(!is_remote_path(r"C:\dev"));
+/// ```
+pub fn is_remote_path<P: AsRef<Path>>(path: P) -> bool {
+  path
+    .as_ref()
+    .to_str()
+    .and_then(|path| path.split_once("://"))
+    .is_some_and(|(scheme, _)| {
+      scheme.len() > 1
+        && scheme
+          .chars()
+          .all(|c| c.is_ascii_alphanumeric() || c == '+')
+    })
+}
+
+/// Write a path the same way on every platform, for matching and reports, so that patterns
+/// written with `/` match Windows paths too.
+///
+/// Windows paths, which start with a drive letter or a `\\?\` verbatim prefix, or any path
+/// on Windows, get `/` separators, their verbatim prefix dropped and their drive letter
+/// uppercased. The paths into a WSL distro are written `wsl://<distro>/...`, see
+/// [`crate::split_wsl_path`]. Paths that aren't valid UTF-8 are converted lossily.
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::config::normalize_path;
+///
+/// assert_eq!(normalize_path(r"\\?\c:\dev\app"), "C:/dev/app");
+/// assert_eq!(normalize_path(r"\\?\UNC\server\share\app"), "//server/share/app");
+/// assert_eq!(normalize_path(r"\\?\UNC\wsl$\Ubuntu\home\app"), "wsl://Ubuntu/home/app");
+/// assert_eq!(normalize_path("/home/user/dev/app"), "/home/user/dev/app");
+/// ```
+pub fn normalize_path<P: AsRef<Path>>(path: P) -> String {
+  let path = path.as_ref().to_string_lossy();
+  let verbatim = path.starts_with(r"\\?\");
+  let drive = |path: &str| {
+    let bytes = path.as_bytes();
+    bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+  };
+  if !cfg!(windows) && !verbatim && !drive(&path) {
+    return path.into_owned();
+  }
+  if let Some((distro, path)) = split_wsl_path(path.as_ref()) {
+    return format!("{}{}{}", WSL_SCHEME, distro, path);
+  }
+  let path = path.replace('\\', "/");
+  let mut ret = match path.strip_prefix("//?/") {
+    Some(rest) => match rest.strip_prefix("UNC/") {
+      Some(share) => format!("//{}", share),
+      None => rest.to_string(),
+    },
+    None => path,
+  };
+  if drive(&ret) {
+    ret[..1].make_ascii_uppercase();
+  }
+  ret
+}
+
+/// Replace the `${VAR}` references of a configuration value by the environment variables values.
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::config::expand_env;
+///
+/// std::env::set_var("MY_TOKEN", "secret");
+///
+/// assert_eq!(expand_env("Bearer ${MY_TOKEN}").unwrap(), "Bearer secret");
+/// assert!(expand_env("${MY_UNDEFINED_TOKEN}").is_err());
+/// ```
+pub fn expand_env<S: AsRef<str>>(value: S) -> crate::Result<String> {
+  let mut ret = value.as_ref().to_string();
+  let markers = ("${", "}");
+  while let Some(start) = ret.find(markers.0) {
+    if let Some(mut end) = ret[start + 2..].find(markers.1) {
+      end += start + 2;
+      let env_key = &ret[start + 2..end];
+      if let Ok(env_val) = std::env::var(env_key) {
+        ret = format!("{}{}{}", &ret[0..start], env_val, &ret[end + 1..]);
+      } else {
+        return Err(Error::config(format!(
+          "{}: invalid configuration value, environment variable '{}' is undefined",
+          value.as_ref(),
+          env_key
+        )));
+      }
+    }
+  }
+  Ok(ret)
+}
+
+/// A code folder of [`GeneralConfig::folders`], along with the options overriding the general
+/// ones for this folder. It is written as a plain path, or as a table when it has options:
+/// `{ path = "~/dev", max_depth = 4, exclude = ["sandbox"], kinds = ["Rust"] }`
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FolderConfig {
+  pub path: PathBuf,
+  /// The number of directory levels scanned below the folder, overridden by `--max-depth`
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub max_depth: Option<usize>,
+  /// Additional directory names never scanned in the folder
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub exclude: Vec<String>,
+  /// The kinds of the projects reported in the folder, such as `Rust`, every kind when empty
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub kinds: Vec<String>,
+}
+
+impl FolderConfig {
+  /// Check whether the folder has options, rather than being a plain path
+  pub fn has_options(&self) -> bool {
+    self.max_depth.is_some() || !self.exclude.is_empty() || !self.kinds.is_empty()
+  }
+}
+
+impl From<PathBuf> for FolderConfig {
+  fn from(path: PathBuf) -> Self {
+    Self {
+      path,
+      ..Default::default()
+    }
+  }
+}
+
+/// Serialize the folders as plain paths, unless they have options, and read both forms, for
+/// `#[serde(with = "...")]` attributes
+mod folder_entries {
+  use std::fmt::Formatter;
+
+  use serde::{
+    de::{value::MapAccessDeserializer, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+  };
+
+  use super::FolderConfig;
+
+  /// A folder read from a plain path or a table
+  struct Entry(FolderConfig);
+
+  /// A folder written as a plain path, unless it has options
+  struct EntryRef<'a>(&'a FolderConfig);
+
+  impl Serialize for EntryRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+      match self.0.has_options() {
+        true => self.0.serialize(serializer),
+        false => self.0.path.serialize(serializer),
+      }
+    }
+  }
+
+  impl<'de> Deserialize<'de> for Entry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+      deserializer.deserialize_any(EntryVisitor)
+    }
+  }
+
+  struct EntryVisitor;
+
+  impl<'de> Visitor<'de> for EntryVisitor {
+    type Value = Entry;
+
+    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+      f.write_str("a folder path, or a table with a `path`")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+      Ok(Entry(FolderConfig::from(std::path::PathBuf::from(v))))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+      FolderConfig::deserialize(MapAccessDeserializer::new(map)).map(Entry)
+    }
+  }
+
+  pub fn serialize<S: Serializer>(
+    folders: &[FolderConfig],
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(folders.iter().map(EntryRef))
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Vec<FolderConfig>, D::Error> {
+    let entries = Vec::<Entry>::deserialize(deserializer)?;
+    Ok(entries.into_iter().map(|entry| entry.0).collect())
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GeneralConfig {
+  /// The code folders scanned for projects, see [`GeneralConfig::folder`]
+  #[serde(with = "folder_entries")]
+  pub folders: Vec<FolderConfig>,
+  pub project_kinds: Vec<ProjectKind>,
+  /// Additional marker file names preventing a directory from being scanned,
+  /// on top of [`crate::ScanOptions::DEFAULT_STOP_MARKER`]
+  #[serde(default)]
+  pub stop_markers: Vec<String>,
+  /// How queries are applied to project names, overridden by `--partial`
+  #[serde(default)]
+  pub match_mode: MatchMode,
+  /// Directories holding more entries are skipped with a warning, `0` disables the limit.
+  /// Defaults to [`crate::ScanOptions::DEFAULT_MAX_FILES_PER_DIR`]
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub max_files_per_dir: Option<usize>,
+  /// A folder scan stops with a warning after finding this many files, `0` disables the limit.
+  /// Defaults to [`crate::ScanOptions::DEFAULT_MAX_SCAN_FILES`]
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub max_scan_files: Option<usize>,
+  /// Which files are recorded by scans, and cached. `light` only keeps project and source files
+  #[serde(default)]
+  pub scan_detail: ScanDetail,
+  /// How scans find the files, `spotlight` and `locate` asking the macOS index or the plocate
+  /// database for the project files
+  #[serde(default)]
+  pub discovery: Discovery,
+  /// The query searched when none is given on the command-line
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub query: Option<Query>,
+  /// The number of directory levels scanned below each folder, overridden by `--max-depth`
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub max_depth: Option<usize>,
+  /// Whether scans follow symbolic links to directories, also enabled by `--follow-symlinks`
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  pub follow_symlinks: bool,
+  /// Whether scans enter the directories whose name starts with a `.`, also enabled by
+  /// `--hidden`
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  pub include_hidden: bool,
+  /// Additional directory names never scanned, on top of [`crate::FolderScan::DIR_EXCLUSIONS`]
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub exclusions: Vec<String>,
+  /// Patterns of the entries skipped in every folder, with the syntax of a
+  /// [`crate::IgnoreFile`]
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub ignore_patterns: Vec<String>,
+  /// Whether searches record their number, the scan durations and the cache hits in the local
+  /// [`crate::UsageStats`], shown by `pgrep stats`
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  pub usage_stats: bool,
+  /// The folder the projects of remote folders are cloned into when opened, see
+  /// [`crate::clone_remote`]
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub clone_dir: Option<PathBuf>,
+}
+
+impl Default for GeneralConfig {
+  fn default() -> Self {
+    return Self {
+      folders: vec![],
+      project_kinds: vec![],
+      stop_markers: vec![],
+      match_mode: MatchMode::default(),
+      max_files_per_dir: None,
+      max_scan_files: None,
+      scan_detail: ScanDetail::default(),
+      discovery: Discovery::default(),
+      query: None,
+      max_depth: None,
+      follow_symlinks: false,
+      include_hidden: false,
+      exclusions: vec![],
+      ignore_patterns: vec![],
+      usage_stats: false,
+      clone_dir: None,
+    };
+  }
+}
+
+impl GeneralConfig {
+  /// Retrieve the entry of the code folder at `path`
+  pub fn folder(&self, path: &Path) -> Option<&FolderConfig> {
+    self.folders.iter().find(|folder| folder.path == path)
+  }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OutputConfig {
+  /// The default output format, overridden by `--format` and the `--output` extension
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub format: Option<OutputFormat>,
+  /// The default order of the matching projects, overridden by `--sort`
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub sort: Option<SortOrder>,
+  /// The facets added to reports by default, overridden by `--facets`
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub facets: Vec<String>,
+}
+
+/// The settings of an S3-compatible bucket sharing the cache, see [`CacheConfig`].
+/// Values may hold `${VAR}` references, expanded from the environment, or reference a secret,
+/// see [`crate::secrets`]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct S3Config {
+  /// The server URL, such as `https://s3.eu-west-1.amazonaws.com`
+  pub endpoint: String,
+  /// The bucket holding the cache
+  pub bucket: String,
+  /// The folder of the bucket holding the cache, the bucket root by default
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub prefix: Option<String>,
+  /// The bucket region, defaults to `AWS_REGION` or `us-east-1`
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub region: Option<String>,
+  /// The access key, defaults to `AWS_ACCESS_KEY_ID`
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub access_key_id: Option<String>,
+  /// The secret key, defaults to `AWS_SECRET_ACCESS_KEY`
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub secret_access_key: Option<String>,
+}
+
+impl S3Config {
+  /// Check whether connecting to the bucket runs secret commands, see
+  /// [`crate::is_secret_command`]
+  pub fn runs_commands(&self) -> bool {
+    [&self.region, &self.access_key_id, &self.secret_access_key]
+      .into_iter()
+      .flatten()
+      .chain([&self.endpoint, &self.bucket])
+      .chain(&self.prefix)
+      .any(is_secret_command)
+  }
+}
+
+/// The cache settings
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CacheConfig {
+  /// A read-only cache folder shared by every user, such as one maintained by an administrator
+  /// with `--cache-dir`. Defaults to [`crate::Cache::DEFAULT_SHARED_DIR`] if it exists
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub shared: Option<PathBuf>,
+  /// A bucket the cache is synchronized with, requires the `s3` feature
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub s3: Option<S3Config>,
+}
+
+/// The settings of reports sent with `--post`
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PostConfig {
+  /// The `Authorization` header value, `${VAR}` references are expanded. It may reference a
+  /// secret instead, see [`crate::secrets`]
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub authorization: Option<String>,
+  /// The number of retries after a failed request, defaults to 3
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub retries: Option<u32>,
+  /// The request timeout in seconds, defaults to 30
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<u64>,
+}
+
+impl PostConfig {
+  /// Check whether sending a report runs a secret command, see [`crate::is_secret_command`]
+  pub fn runs_commands(&self) -> bool {
+    self.authorization.as_ref().is_some_and(is_secret_command)
+  }
+}
+
+/// The settings of `pgrep watch`
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WatchConfig {
+  /// The memory the projects kept between two scans may use, such as `256MiB`, see
+  /// [`WatchConfig::memory_cap`]
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub memory_cap: Option<String>,
+}
+
+impl WatchConfig {
+  /// The default [`WatchConfig::memory_cap`]
+  pub const DEFAULT_MEMORY_CAP: u64 = 256 << 20;
+
+  /// Retrieve the memory the projects kept between two scans may use, in bytes, beyond which
+  /// they are kept without their source files, see [`crate::Project::drop_source_files`]
+  pub fn memory_cap(&self) -> crate::Result<u64> {
+    match &self.memory_cap {
+      Some(cap) => parse_size(cap).map_err(Error::config),
+      None => Ok(Self::DEFAULT_MEMORY_CAP),
+    }
+  }
+}
+
+/// A named group of related projects, such as the repositories of a multi-repo system
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WorkspaceConfig {
+  /// The queries matching the member projects, such as `*api*`, or their folders, such as
+  /// `~/dev/shared-lib`, see [`WorkspaceConfig::member_folder`]
+  #[serde(default)]
+  pub members: Vec<String>,
+}
+
+impl WorkspaceConfig {
+  /// Retrieve the folder of a member given as a path: starting with `~`, `.` or absolute, and
+  /// without glob characters. Other members are queries.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::WorkspaceConfig;
+  ///
+  /// assert!(WorkspaceConfig::member_folder("./shared-lib").unwrap().is_some());
+  /// assert!(WorkspaceConfig::member_folder("*api*").unwrap().is_none());
+  /// assert!(WorkspaceConfig::member_folder("/dev/*/api").unwrap().is_none());
+  /// ```
+  pub fn member_folder(member: &str) -> crate::Result<Option<PathBuf>> {
+    let is_glob = member.contains(['*', '?', '[']);
+    let is_path = member.starts_with(['~', '.']) || Path::new(member).is_absolute();
+    match (is_path, is_glob) {
+      (true, false) => Ok(Some(expand_path(member)?)),
+      _ => Ok(None),
+    }
+  }
+}
+
+/// The directories reported as projects of the [`ProjectKind::Bookmark`] kind, although they hold
+/// no project file, such as a scripts folder or a notes vault
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BookmarksConfig {
+  /// The bookmarked directories, `~` and `${VAR}` references are expanded
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub paths: Vec<PathBuf>,
+}
+
+impl BookmarksConfig {
+  /// Build the projects of the bookmarked directories, skipping the missing ones with a warning
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::{BookmarksConfig, ProjectKind};
+  ///
+  /// let bookmarks = BookmarksConfig {
+  ///   paths: vec![std::env::temp_dir(), "/no/such/bookmark".into()],
+  /// };
+  /// let projects = bookmarks.projects();
+  /// assert_eq!(projects.len(), 1);
+  /// assert_eq!(projects[0].kinds(), &[ProjectKind::Bookmark]);
+  /// ```
+  pub fn projects(&self) -> Vec<Project> {
+    self
+      .paths
+      .iter()
+      .filter(|path| match path.is_dir() {
+        true => true,
+        false => {
+          warn!("{}", tr_fmt("config.missing-bookmark", &[&path.display()]));
+          false
+        }
+      })
+      .map(|path| Project::new(path, vec![ProjectKind::Bookmark], vec![], vec![]))
+      .collect()
+  }
+}
+
+/// A configuration fragment listed in the `include` key of a configuration, holding the values
+/// it shares with a team, such as from a dotfiles repository. Unlike the including file, it may
+/// not define commands, so that it never needs to be trusted
+#[derive(Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+struct IncludedConfig {
+  #[serde(default)]
+  general: IncludedGeneralConfig,
+  #[serde(default)]
+  queries: BTreeMap<String, String>,
+  #[serde(default)]
+  aliases: BTreeMap<String, String>,
+}
+
+/// The `[general]` values of an [`IncludedConfig`]
+#[derive(Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+struct IncludedGeneralConfig {
+  #[serde(default, with = "folder_entries")]
+  folders: Vec<FolderConfig>,
+  #[serde(default)]
+  project_kinds: Vec<ProjectKind>,
+}
+
+/// A key of a configuration file that pgrep doesn't know, see [`Config::unknown_keys`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownKey {
+  /// The dotted path of the key, such as `general.folers`
+  pub key: String,
+  /// The known key it is closest to, such as `folders`
+  pub suggestion: Option<String>,
+}
+
+/// Collect the keys of `value` which `schema` doesn't describe, `root` holding the definitions
+/// it refers to
+fn unknown_keys(
+  root: &JsonValue,
+  schema: &JsonValue,
+  value: &toml::Value,
+  path: &str,
+) -> Vec<UnknownKey> {
+  if let Some(name) = schema["$ref"].as_str() {
+    let def = &root["$defs"][name.trim_start_matches("#/$defs/")];
+    return unknown_keys(root, def, value, path);
+  }
+  if let Some(variants) = schema["anyOf"].as_array() {
+    // report against the closest variant of the same type
+    let same_type = |variant: &&JsonValue| match variant["type"].as_str() {
+      Some(name) => name == value.type_str() || (name == "object" && value.is_table()),
+      None => true,
+    };
+    return variants
+      .iter()
+      .filter(same_type)
+      .map(|variant| unknown_keys(root, variant, value, path))
+      .min_by_key(Vec::len)
+      .unwrap_or_default();
+  }
+  match value {
+    toml::Value::Table(table) => table
+      .iter()
+      .flat_map(|(key, value)| {
+        let path = match path {
+          "" => key.clone(),
+          path => format!("{}.{}", path, key),
+        };
+        match (&schema["properties"][key], &schema["additionalProperties"]) {
+          (property @ JsonValue::Object(_), _) => unknown_keys(root, property, value, &path),
+          (_, JsonValue::Bool(false)) => {
+            let known = schema["properties"]
+              .as_object()
+              .into_iter()
+              .flat_map(|p| p.keys());
+            vec![UnknownKey {
+              key: path,
+              suggestion: closest(key, known.map(String::as_str)),
+            }]
+          }
+          (_, additional) => unknown_keys(root, additional, value, &path),
+        }
+      })
+      .collect(),
+    toml::Value::Array(items) => items
+      .iter()
+      .flat_map(|item| unknown_keys(root, &schema["items"], item, path))
+      .collect(),
+    _ => vec![],
+  }
+}
+
+/// Find the candidate closest to `key`, when one is close enough to be a typo, as clap does
+pub(crate) fn closest<'a>(key: &str, candidates: impl Iterator<Item = &'a str>) -> Option<String> {
+  candidates
+    .map(|candidate| (strsim::jaro(key, candidate), candidate))
+    .filter(|(similarity, _)| *similarity > 0.7)
+    .max_by(|a, b| a.0.total_cmp(&b.0))
+    .map(|(_, candidate)| candidate.to_string())
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Config {
+  /// The configuration fragments merged into this one, see [`Config::merge_includes`]
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub include: Vec<PathBuf>,
+  pub general: GeneralConfig,
+  #[serde(default)]
+  pub output: OutputConfig,
+  #[serde(default)]
+  pub hooks: HooksConfig,
+  #[serde(default)]
+  pub post: PostConfig,
+  #[serde(default)]
+  pub cache: CacheConfig,
+  #[serde(default)]
+  pub watch: WatchConfig,
+  #[serde(default)]
+  pub bookmarks: BookmarksConfig,
+  /// The workspaces by name, listed by `pgrep ws <name>`
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub workspace: BTreeMap<String, WorkspaceConfig>,
+  /// The folders copied by `pgrep new <kind>`, by kind name, see [`crate::scaffold`]
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub templates: BTreeMap<String, PathBuf>,
+  /// The saved query expressions by name, run with `pgrep @<name>`, see
+  /// [`crate::Query::expand_saved`]
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub queries: BTreeMap<String, String>,
+  /// The query words replaced by an expression before parsing, see
+  /// [`crate::Query::expand_aliases`]
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub aliases: BTreeMap<String, String>,
+  /// The file the configuration was loaded from
+  #[serde(skip)]
+  pub source: Option<PathBuf>,
+}
+
+impl Config {
+  /// The default configuration file name to search in [`common_directories`]
+  ///
+  /// [`common_directories`]: Config::common_config_dirs
+  pub const DEFAULT_CONFIG_NAME: &'static str = "pgrep.toml";
+
+  /// The JSON Schema of the configuration file, written by `pgrep config schema` for editors
+  /// to complete and validate it
+  pub const SCHEMA: &str = include_str!("../pgrep.schema.json");
+
+  /// Retrieve the list of common config directories.
+  /// This is used to sequentially check for a config file in each folder.
+  ///
+  /// On linux, this will give:
+  /// ```json
+  /// [
+  ///   "~/.config/pgrep",
+  ///   "~/.local/share/pgrep",
+  ///   "."
+  /// ]
+  /// ```
+  pub fn common_config_dirs() -> Vec<PathBuf> {
+    let mut ret = vec![];
+    if let Some(proj_dirs) = get_project_dirs() {
+      ret.push(proj_dirs.preference_dir().to_path_buf());
+      ret.push(proj_dirs.config_dir().to_path_buf());
+      ret.push(proj_dirs.config_local_dir().to_path_buf());
+      ret.push(proj_dirs.data_dir().to_path_buf());
+      ret.push(proj_dirs.data_local_dir().to_path_buf());
+    }
+    ret.push(PathBuf::from("."));
+    ret.dedup();
+    ret
+  }
+
+  /// Retrieve the final configuration path.
+  /// It will search through [`common directories`] if `path` is not given.
+  pub fn path(path: Option<&PathBuf>) -> PathBuf {
+    let common_dirs = Self::common_config_dirs();
+    path
+      .cloned()
+      .or_else(|| {
+        common_dirs
+          .iter()
+          .map(|config_dir| config_dir.join(Self::DEFAULT_CONFIG_NAME))
+          .find(|config_file| config_file.exists())
+      })
+      .or_else(|| Some(common_dirs[0].join(Self::DEFAULT_CONFIG_NAME)))
+      .unwrap()
+  }
+
+  /// Load the configuration values.
+  ///
+  /// If no config file path is specified, it will search the list of [`common directories`] for the [`Config::DEFAULT_CONFIG_NAME`] file
+  /// and if found load it.
+  ///
+  /// If a config file path is specified, it doesn't even try to find the common config dir.
+  ///
+  /// If the config file doesn't exist, it write the default config to it. When it cannot be
+  /// written, such as in a sandbox, the default config is used with a warning.
+  ///
+  /// [`common directories`]: Config::common_config_dirs()
+  pub fn load(user_path: Option<&PathBuf>, folders: Vec<PathBuf>) -> crate::Result<Self> {
+    let dflt_config = Config::default();
+
+    let path = Self::path(user_path);
+    let mut config = match path.exists() {
+      true => {
+        debug!("Loading user configuration from '{}'", path.display());
+        let mut config = Config::parse(&path)?;
+        config.source = Some(path.clone());
+        config
+      }
+      false => {
+        debug!("Creating default configuration at '{}'", path.display());
+        // Create the config dir and write the default config file
+        match dflt_config.save(Some(&path)) {
+          Ok(()) => Config {
+            source: Some(path.clone()),
+            ..dflt_config
+          },
+          Err(e) => {
+            warn!("{}", tr_fmt("config.unwritable", &[&path.display(), &e]));
+            dflt_config
+          }
+        }
+      }
+    };
+    if let Some(format) = config.output.format {
+      format
+        .ensure_available()
+        .map_err(|e| Error::config(format!("{}: {}", path.display(), e)))?;
+    }
+    config
+      .general
+      .discovery
+      .ensure_available()
+      .map_err(|e| Error::config(format!("{}: {}", path.display(), e)))?;
+    let len_before = config.general.folders.len();
+    config
+      .general
+      .folders
+      .extend(folders.into_iter().map(FolderConfig::from));
+    // the configured entries come first, keeping their options
+    config.general.folders.sort_by(|a, b| a.path.cmp(&b.path));
+    config.general.folders.dedup_by(|a, b| a.path == b.path);
+    if config.general.folders.len() != len_before && config.source.is_some() {
+      if let Err(e) = config.save(Some(&path)) {
+        warn!("{}", tr_fmt("config.unwritable", &[&path.display(), &e]));
+      }
+    }
+
+    config.merge_includes(&path)?;
+
+    // expand folders, the WSL ones may be written as in reports
+    for folder in &mut config.general.folders {
+      folder.path = expand_path(&folder.path)?;
+      if cfg!(windows) {
+        folder.path = wsl_share_path(&folder.path).unwrap_or_else(|| folder.path.clone());
+      }
+    }
+    for path in &mut config.bookmarks.paths {
+      *path = expand_path(&path)?;
+    }
+    config.general.folders.sort_by(|a, b| a.path.cmp(&b.path));
+    config.general.folders.dedup_by(|a, b| a.path == b.path);
+    trace!("Config: {:#?}", config);
+    Ok(config)
+  }
+
+  /// Merge the fragments listed in `include` into this configuration, `path` being the file it
+  /// was loaded from, which relative fragment paths start from. Their folders and project kinds
+  /// are added, along with the saved queries and aliases this configuration doesn't define.
+  /// A missing fragment, such as a work one on a personal computer, is skipped with a warning
+  pub fn merge_includes(&mut self, path: &Path) -> crate::Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    for include in &self.include {
+      let include = dir.join(expand_path(include)?);
+      let content = match std::fs::read_to_string(&include) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+          warn!(
+            "{}",
+            tr_fmt("config.missing-include", &[&include.display()])
+          );
+          continue;
+        }
+        Err(e) => return Err(e.into()),
+      };
+      debug!("Including configuration from '{}'", include.display());
+      let included = toml::from_str::<IncludedConfig>(&content).map_err(|e| Error::Config {
+        message: format!("{}: invalid included configuration", include.display()),
+        source: Some(Box::new(e)),
+      })?;
+      self.general.folders.extend(included.general.folders);
+      for kind in included.general.project_kinds {
+        if !self.general.project_kinds.contains(&kind) {
+          self.general.project_kinds.push(kind);
+        }
+      }
+      for (name, query) in included.queries {
+        self.queries.entry(name).or_insert(query);
+      }
+      for (name, alias) in included.aliases {
+        self.aliases.entry(name).or_insert(alias);
+      }
+    }
+    Ok(())
+  }
+
+  /// Check whether the commands defined by this configuration, such as hooks, may run: it must
+  /// be loaded from a user configuration folder and, on unix, owned by the current user.
+  /// This prevents a repository-local `pgrep.toml` from running arbitrary commands.
+  pub fn is_trusted(&self) -> bool {
+    let Some(path) = self
+      .source
+      .as_ref()
+      .and_then(|path| path.canonicalize().ok())
+    else {
+      return false;
+    };
+    let in_user_dir = Self::common_config_dirs()
+      .iter()
+      .filter(|dir| dir.is_absolute())
+      .filter_map(|dir| dir.canonicalize().ok())
+      .any(|dir| path.starts_with(dir));
+    #[cfg(unix)]
+    let owned = {
+      use std::os::unix::fs::MetadataExt;
+      // SAFETY: geteuid cannot fail and has no side effect
+      let uid = unsafe { libc::geteuid() };
+      path.metadata().is_ok_and(|meta| meta.uid() == uid)
+    };
+    #[cfg(not(unix))]
+    let owned = true;
+    in_user_dir && owned
+  }
+
+  /// Fail with an explanation unless the commands of this configuration may run, see
+  /// [`Config::is_trusted`]. `trust` overrides the check, as given by `--trust`.
+  pub fn ensure_trusted(&self, trust: bool) -> crate::Result<()> {
+    if trust || self.is_trusted() {
+      return Ok(());
+    }
+    let path = self
+      .source
+      .as_ref()
+      .map(|path| path.display().to_string())
+      .unwrap_or_default();
+    let dirs = Self::common_config_dirs()
+      .iter()
+      .filter(|dir| dir.is_absolute())
+      .map(|dir| dir.display().to_string())
+      .collect::<Vec<_>>();
+    Err(Error::config(tr_fmt(
+      "config.untrusted",
+      &[&path, &dirs.join(", ")],
+    )))
+  }
+
+  /// Save the current configuration to disk.
+  /// If path is supplied it will use this instead of guessing the correct path.
+  pub fn save(&self, path: Option<&PathBuf>) -> crate::Result<()> {
+    let path = Self::path(path);
+    if let Some(parent) = path.parent() {
+      if !parent.exists() {
+        std::fs::create_dir_all(parent)?;
+      }
+    }
+    let mut f = std::fs::File::create(&path).map_err(|e| Error::Config {
+      message: format!("failed to create config file '{}'", path.display()),
+      source: Some(Box::new(e)),
+    })?;
+    self.write(&mut f)
+  }
+
+  /// Parse the configuration from a file path, warning about the keys it ignores, see
+  /// [`Config::unknown_keys`]. When it cannot be parsed, these keys are reported instead, as a
+  /// misspelled key is the likely cause of a missing one.
+  pub fn parse<P: AsRef<Path>>(path: P) -> crate::Result<Config> {
+    let content = std::fs::read_to_string(&path)?;
+    let path = path.as_ref().display();
+    let unknown = Self::unknown_keys(&content)?
+      .into_iter()
+      .map(|unknown| match unknown.suggestion {
+        Some(known) => tr_fmt("config.unknown-key-typo", &[&path, &unknown.key, &known]),
+        None => tr_fmt("config.unknown-key", &[&path, &unknown.key]),
+      })
+      .collect::<Vec<_>>();
+    match toml::from_str(&content) {
+      Ok(config) => {
+        unknown.iter().for_each(|message| warn!("{}", message));
+        Ok(config)
+      }
+      Err(e) if !unknown.is_empty() => Err(Error::Config {
+        message: unknown.join("\n"),
+        source: Some(Box::new(e)),
+      }),
+      Err(e) => Err(e.into()),
+    }
+  }
+
+  /// Find the keys of a configuration that pgrep doesn't know and would ignore, such as
+  /// misspelled ones, along with the known key closest to each. See [`Config::SCHEMA`]
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::Config;
+  ///
+  /// let unknown = Config::unknown_keys("[general]\nfolers = []\nproject_kinds = []\n").unwrap();
+  /// assert_eq!(unknown[0].key, "general.folers");
+  /// assert_eq!(unknown[0].suggestion.as_deref(), Some("folders"));
+  /// ```
+  pub fn unknown_keys(content: &str) -> crate::Result<Vec<UnknownKey>> {
+    let value = toml::from_str::<toml::Value>(content)?;
+    let schema = serde_json::from_str::<JsonValue>(Self::SCHEMA).expect("invalid schema");
+    Ok(unknown_keys(&schema, &schema, &value, ""))
+  }
+
+  /// Write the configuration to a [`std::io::Write`]
+  pub fn write<W: std::io::Write>(&self, mut w: W) -> crate::Result<()> {
+    let data = toml::to_string_pretty(self)?;
+    w.write_all(data.as_bytes())?;
+    Ok(())
+  }
+
+  /// Read the configuration from a [`std::io::Write`]
+  pub fn read<R: std::io::Read>(&mut self, mut r: R) -> crate::Result<()> {
+    let mut buf = String::new();
+    r.read_to_string(&mut buf)?;
+    *self = toml::from_str(&buf)?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use crate::{Config, FolderConfig, OutputFormat, ProjectKind, SortOrder};
+
+  #[test]
+  fn common_dirs() {
+    println!("{:#?}", Config::common_config_dirs());
+  }
+
+  #[test]
+  fn foreign_configs_are_untrusted() {
+    let dir = std::env::temp_dir().join(format!("pgrep-trust-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(Config::DEFAULT_CONFIG_NAME);
+    std::fs::write(&path, "").unwrap();
+    let config = Config {
+      source: Some(path),
+      ..Default::default()
+    };
+    assert!(!config.is_trusted());
+    assert!(config.ensure_trusted(false).is_err());
+    assert!(config.ensure_trusted(true).is_ok());
+    assert!(!Config::default().is_trusted());
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn output_format() {
+    let mut config = Config::default();
+    config
+      .read("[general]\nfolders = []\nproject_kinds = []\n".as_bytes())
+      .unwrap();
+    assert_eq!(config.output.format, None);
+    config
+      .read("[general]\nfolders = []\nproject_kinds = []\n[output]\nformat = \"json\"\n".as_bytes())
+      .unwrap();
+    assert_eq!(config.output.format, Some(OutputFormat::Json));
+    let mut out = vec![];
+    config.write(&mut out).unwrap();
+    assert!(String::from_utf8(out)
+      .unwrap()
+      .contains("[output]\nformat = \"json\""));
+  }
+
+  #[test]
+  fn defaults_round_trip() {
+    let mut config = Config::default();
+    let toml = "[general]\nfolders = []\nproject_kinds = []\nquery = \"api* ci:github\"\n\n\
+      [output]\nsort = \"name\"\nfacets = [\"ci\"]\n";
+    config.read(toml.as_bytes()).unwrap();
+    assert_eq!(
+      config.general.query,
+      Some("api* ci:github".parse().unwrap())
+    );
+    assert_eq!(config.output.sort, Some(SortOrder::Name));
+    let mut out = vec![];
+    config.write(&mut out).unwrap();
+    let mut written = Config::default();
+    written.read(out.as_slice()).unwrap();
+    assert_eq!(written, config);
+    let invalid = "[general]\nfolders = []\nproject_kinds = []\nquery = \"a[\"\n";
+    assert!(config.read(invalid.as_bytes()).is_err());
+  }
+
+  #[test]
+  fn folder_entries_round_trip() {
+    let mut config = Config::default();
+    let toml = "[general]\nfolders = [\"~/dev\", { path = \"~/work\", max_depth = 2, \
+      kinds = [\"Rust\"] }, { path = \"~/tmp\" }]\nproject_kinds = []\n";
+    config.read(toml.as_bytes()).unwrap();
+    let folders = &config.general.folders;
+    assert_eq!(folders[0], FolderConfig::from(PathBuf::from("~/dev")));
+    assert_eq!(folders[1].max_depth, Some(2));
+    assert_eq!(folders[1].kinds, ["Rust"]);
+    assert!(!folders[2].has_options());
+    let mut out = vec![];
+    config.write(&mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    // the folders without options are written as plain paths, as before they could have some
+    assert!(out.contains("\"~/dev\",\n"), "{}", out);
+    assert!(out.contains("\"~/tmp\",\n"), "{}", out);
+    let mut written = Config::default();
+    written.read(out.as_bytes()).unwrap();
+    assert_eq!(written, config);
+    let invalid = "[general]\nfolders = [{ max_depth = 2 }]\nproject_kinds = []\n";
+    assert!(config.read(invalid.as_bytes()).is_err());
+  }
+
+  #[test]
+  fn includes_are_merged() {
+    let dir = std::env::temp_dir().join(format!("pgrep-include-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("team")).unwrap();
+    let main = dir.join("pgrep.toml");
+    std::fs::write(
+      &main,
+      "include = [\"team/work.toml\", \"missing.toml\"]\n\n[general]\nfolders = [\"/dev\"]\n\
+        project_kinds = []\n\n[aliases]\nweb = \"kind:node\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+      dir.join("team/work.toml"),
+      "[general]\nfolders = [\"/dev\", { path = \"/work\", max_depth = 2 }]\n\
+        project_kinds = [{ type = \"Go\" }]\n\n[aliases]\nweb = \"kind:go\"\napi = \"*api*\"\n",
+    )
+    .unwrap();
+    let config = Config::load(Some(&main), vec![]);
+    let invalid = "[general]\nfolders = []\n\n[hooks]\non_new_project = \"rm -rf {path}\"\n";
+    std::fs::write(dir.join("team/work.toml"), invalid).unwrap();
+    let rejected = Config::load(Some(&main), vec![]);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let config = config.unwrap();
+    let folders = &config.general.folders;
+    assert_eq!(folders.len(), 2);
+    assert_eq!(folders[1].max_depth, Some(2));
+    assert_eq!(config.general.project_kinds, [ProjectKind::Go]);
+    // the including file wins
+    assert_eq!(config.aliases["web"], "kind:node");
+    assert_eq!(config.aliases["api"], "*api*");
+    assert!(rejected.is_err());
+  }
+
+  #[test]
+  fn unknown_keys_are_reported() {
+    let toml = "[general]\nfolders = [{ path = \"~/dev\", max_dept = 2 }]\nfolers = []\n\
+      project_kinds = [{ type = \"Custom\", name = \"Zig\", language_ext = [], \
+      project_files = [] }]\n\n[output]\nformat = \"json\"\n\n\
+      [workspace.shop]\nmember = []\n\n[queries]\nanything = \"*\"\n\n[telemetry]\n";
+    let unknown = Config::unknown_keys(toml).unwrap();
+    let found = unknown
+      .iter()
+      .map(|unknown| (unknown.key.as_str(), unknown.suggestion.as_deref()))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      found,
+      [
+        ("general.folders.max_dept", Some("max_depth")),
+        ("general.folers", Some("folders")),
+        ("general.project_kinds.language_ext", Some("language_exts")),
+        ("telemetry", None),
+        ("workspace.shop.member", Some("members")),
+      ]
+    );
+    assert!(
+      Config::unknown_keys("[general]\nfolders = []\nproject_kinds = []\n")
+        .unwrap()
+        .is_empty()
+    );
+  }
+
+  /// Check that the schema describes every key of a configuration, and the values of its
+  /// enumerations. The structures are written without defaults, so that a new field fails to
+  /// build until added here, then fails this test until added to the schema.
+  #[test]
+  fn schema_describes_every_key() {
+    use serde_json::Value;
+    use std::collections::BTreeMap;
+
+    use crate::{
+      BookmarksConfig, CacheConfig, Discovery, GeneralConfig, HooksConfig, MatchMode, OutputConfig,
+      PostConfig, ProjectKind, S3Config, ScanDetail, WatchConfig, WorkspaceConfig,
+    };
+
+    /// List the keys of `value` that `schema` doesn't describe, or the values it doesn't allow
+    fn undescribed(root: &Value, schema: &Value, value: &toml::Value, path: &str) -> Vec<String> {
+      if let Some(name) = schema["$ref"].as_str() {
+        let def = &root["$defs"][name.trim_start_matches("#/$defs/")];
+        return undescribed(root, def, value, path);
+      }
+      if let Some(variants) = schema["anyOf"].as_array() {
+        let mut checks = variants
+          .iter()
+          .map(|variant| undescribed(root, variant, value, path));
+        return checks
+          .find(Vec::is_empty)
+          .unwrap_or_else(|| vec![path.to_string()]);
+      }
+      match value {
+        toml::Value::Table(table) => table
+          .iter()
+          .flat_map(|(key, value)| {
+            let path = format!("{}.{}", path, key);
+            let property = match &schema["properties"][key] {
+              Value::Null => &schema["additionalProperties"],
+              property => property,
+            };
+            match property.is_object() {
+              true => undescribed(root, property, value, &path),
+              false => vec![path],
+            }
+          })
+          .collect(),
+        toml::Value::Array(items) => items
+          .iter()
+          .flat_map(|item| undescribed(root, &schema["items"], item, path))
+          .collect(),
+        value => {
+          let allowed = match (&schema["enum"], &schema["const"]) {
+            (Value::Array(values), _) => values.iter().any(|v| v.as_str() == value.as_str()),
+            (_, Value::Null) => true,
+            (_, allowed) => allowed.as_str() == value.as_str(),
+          };
+          match allowed {
+            true => vec![],
+            false => vec![format!("{} = {}", path, value)],
+          }
+        }
+      }
+    }
+
+    let schema = serde_json::from_str::<Value>(Config::SCHEMA).unwrap();
+    let config = Config {
+      include: vec!["~/work/pgrep-work.toml".into()],
+      general: GeneralConfig {
+        folders: vec![
+          FolderConfig::from(PathBuf::from("~/dev")),
+          FolderConfig {
+            path: "~/work".into(),
+            max_depth: Some(4),
+            exclude: vec!["sandbox".to_string()],
+            kinds: vec!["Rust".to_string()],
+          },
+        ],
+        project_kinds: vec![
+          ProjectKind::Rust,
+          ProjectKind::Custom {
+            name: "Zig".to_string(),
+            language_exts: vec!["zig".to_string()],
+            project_files: vec!["build.zig".to_string()],
+          },
+        ],
+        stop_markers: vec![".nopgrep".to_string()],
+        match_mode: MatchMode::Partial,
+        max_files_per_dir: Some(1000),
+        max_scan_files: Some(100000),
+        scan_detail: ScanDetail::Light,
+        discovery: Discovery::Spotlight,
+        query: Some("api* ci:github".parse().unwrap()),
+        max_depth: Some(4),
+        follow_symlinks: true,
+        include_hidden: true,
+        exclusions: vec!["vendor".to_string()],
+        ignore_patterns: vec!["*.bak".to_string()],
+        usage_stats: true,
+        clone_dir: Some("~/clones".into()),
+      },
+      output: OutputConfig {
+        format: Some(OutputFormat::Markdown),
+        sort: Some(SortOrder::Size),
+        facets: vec!["ci".to_string()],
+      },
+      hooks: HooksConfig {
+        on_new_project: Some("echo {name}".to_string()),
+        on_removed_project: Some("echo {path}".to_string()),
+      },
+      post: PostConfig {
+        authorization: Some("Bearer ${TOKEN}".to_string()),
+        retries: Some(1),
+        timeout: Some(10),
+      },
+      cache: CacheConfig {
+        shared: Some("/var/cache/pgrep".into()),
+        s3: Some(S3Config {
+          endpoint: "https://s3.eu-west-1.amazonaws.com".to_string(),
+          bucket: "pgrep".to_string(),
+          prefix: Some("cache".to_string()),
+          region: Some("eu-west-1".to_string()),
+          access_key_id: Some("${KEY_ID}".to_string()),
+          secret_access_key: Some("${SECRET}".to_string()),
+        }),
+      },
+      watch: WatchConfig {
+        memory_cap: Some("64MiB".to_string()),
+      },
+      bookmarks: BookmarksConfig {
+        paths: vec!["~/scripts".into()],
+      },
+      workspace: BTreeMap::from([(
+        "shop".to_string(),
+        WorkspaceConfig {
+          members: vec!["*api*".to_string()],
+        },
+      )]),
+      templates: BTreeMap::from([("rust".to_string(), "~/templates/rust".into())]),
+      queries: BTreeMap::from([("apis".to_string(), "*api*".to_string())]),
+      aliases: BTreeMap::from([("web".to_string(), "kind:node".to_string())]),
+      source: None,
+    };
+    let value = toml::Value::try_from(&config).unwrap();
+    assert_eq!(
+      undescribed(&schema, &schema, &value, ""),
+      Vec::<String>::new()
+    );
+  }
+}
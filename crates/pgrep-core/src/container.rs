@@ -0,0 +1,124 @@
+//! Code folders inside running containers, configured as `container://<name>:<path>` such as
+//! `container://devcontainer:/workspace`, or scanned for a run with `--in-container`.
+//!
+//! The files are listed by `find` through `docker exec`, or `podman exec` when docker isn't
+//! installed, and scanned by [`FolderScan::from_files`]. The projects keep the paths they have
+//! in the container, prefixed with its name.
+
+use std::{
+  io::ErrorKind,
+  path::{Path, PathBuf},
+  process::{Command, Output},
+};
+
+use log::debug;
+
+use crate::{
+  i18n::tr_fmt,
+  ssh::{find_command, shell_quote},
+  Error, FolderScan, ScanOptions,
+};
+
+/// A code folder inside a running container
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainerFolder {
+  /// The container name or id
+  pub name: String,
+  /// The absolute folder path in the container
+  pub path: String,
+}
+
+impl ContainerFolder {
+  /// The prefix of the container folders
+  pub const SCHEME: &'static str = "container://";
+  /// The container engines tried in turn
+  pub const ENGINES: [&'static str; 2] = ["docker", "podman"];
+
+  /// Parse a `<name>:<path>` folder, as given to `--in-container`, `None` when the path isn't
+  /// absolute
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::ContainerFolder;
+  ///
+  /// let folder = ContainerFolder::from_spec("app:/workspace/").unwrap();
+  /// assert_eq!(folder.name, "app");
+  /// assert_eq!(folder.path, "/workspace");
+  /// assert_eq!(folder.to_path().to_str(), Some("container://app:/workspace"));
+  /// assert_eq!(ContainerFolder::parse(folder.to_path()), Some(folder));
+  /// assert_eq!(ContainerFolder::from_spec("app:workspace"), None);
+  /// ```
+  pub fn from_spec(spec: &str) -> Option<Self> {
+    let (name, path) = spec.split_once(':')?;
+    if name.is_empty() || !path.starts_with('/') {
+      return None;
+    }
+    Some(Self {
+      name: name.to_string(),
+      path: match path.trim_end_matches('/') {
+        "" => "/".to_string(),
+        path => path.to_string(),
+      },
+    })
+  }
+
+  /// Parse a `container://<name>:<path>` folder, `None` if `path` is another kind of folder
+  pub fn parse<P: AsRef<Path>>(path: P) -> Option<Self> {
+    Self::from_spec(path.as_ref().to_str()?.strip_prefix(Self::SCHEME)?)
+  }
+
+  /// The path of the folder as a code folder, `container://<name>:<path>`
+  pub fn to_path(&self) -> PathBuf {
+    PathBuf::from(format!("{}{}:{}", Self::SCHEME, self.name, self.path))
+  }
+
+  /// Run `command` in the container with the first installed engine
+  pub(crate) fn exec(&self, command: &str) -> std::io::Result<Output> {
+    let mut engines = Self::ENGINES.iter().peekable();
+    loop {
+      let engine = engines.next().expect("at least one engine");
+      let output = Command::new(engine)
+        .args(["exec", &self.name, "sh", "-c", command])
+        .output();
+      match output {
+        Err(e) if e.kind() == ErrorKind::NotFound && engines.peek().is_some() => {
+          debug!(
+            "{} is not installed, trying the next container engine",
+            engine
+          );
+        }
+        output => return output,
+      }
+    }
+  }
+
+  /// Scan the folder, reported as `path`
+  pub fn scan<P: AsRef<Path>>(&self, path: P, options: &ScanOptions) -> crate::Result<FolderScan> {
+    let command = find_command(&shell_quote(&self.path), options);
+    debug!(
+      "listing the files of '{}' with `{}`",
+      path.as_ref().display(),
+      command
+    );
+    let output = self.exec(&command).map_err(|e| Error::Scan {
+      path: path.as_ref().to_path_buf(),
+      message: tr_fmt("container.unavailable", &[&Self::ENGINES.join(", ")]),
+      source: Some(Box::new(e)),
+    })?;
+    // find fails on unreadable directories, the files it could list are still scanned
+    if !output.status.success() && output.stdout.is_empty() {
+      let reason = String::from_utf8_lossy(&output.stderr).trim().to_string();
+      return Err(Error::Scan {
+        path: path.as_ref().to_path_buf(),
+        message: tr_fmt("container.failed", &[&self.name, &reason]),
+        source: None,
+      });
+    }
+    let files = String::from_utf8_lossy(&output.stdout)
+      .lines()
+      .map(PathBuf::from)
+      .collect::<Vec<_>>();
+    Ok(FolderScan::from_files(path, files, options))
+  }
+}
@@ -0,0 +1,110 @@
+use std::path::{Path, PathBuf};
+
+use directories::UserDirs;
+
+use crate::FolderConfig;
+
+/// Find the folders where package managers extract the source code of dependencies, scanned
+/// with `--include-deps-sources`. Only the existing ones are returned, each limited to the kind
+/// of its ecosystem:
+///
+/// - the crates of the cargo registry and git checkouts, in `$CARGO_HOME` or `~/.cargo`;
+/// - the Go module cache, in `$GOMODCACHE` or the `pkg/mod` folder of the first `$GOPATH`
+///   entry, `~/go` by default;
+/// - the global Node modules, in the `lib/node_modules` folder of `$NPM_CONFIG_PREFIX`, or the
+///   usual npm prefixes.
+///
+/// Their projects are given the [`crate::ProjectKind::Dependency`] kind by the caller.
+pub fn dependency_sources() -> Vec<FolderConfig> {
+  let Some(home) = UserDirs::new().map(|dirs| dirs.home_dir().to_path_buf()) else {
+    return vec![];
+  };
+  dependency_sources_in(&home, |name| std::env::var_os(name).map(PathBuf::from))
+    .into_iter()
+    .filter(|folder| folder.path.is_dir())
+    .collect()
+}
+
+/// List the candidate folders of [`dependency_sources`], reading the variables with `env`
+fn dependency_sources_in<E: Fn(&str) -> Option<PathBuf>>(home: &Path, env: E) -> Vec<FolderConfig> {
+  let folder = |path: PathBuf, kind: &str, exclude: &[&str]| FolderConfig {
+    path,
+    max_depth: None,
+    exclude: exclude.iter().map(|name| name.to_string()).collect(),
+    kinds: vec![kind.to_string()],
+  };
+  let mut ret = vec![];
+  let cargo_home = env("CARGO_HOME").unwrap_or_else(|| home.join(".cargo"));
+  ret.push(folder(cargo_home.join("registry").join("src"), "Rust", &[]));
+  ret.push(folder(
+    cargo_home.join("git").join("checkouts"),
+    "Rust",
+    &[],
+  ));
+  let go_mod_cache = env("GOMODCACHE").unwrap_or_else(|| {
+    let gopath = env("GOPATH").and_then(|paths| std::env::split_paths(&paths).next());
+    gopath
+      .unwrap_or_else(|| home.join("go"))
+      .join("pkg")
+      .join("mod")
+  });
+  // the cache holds the downloaded archives, the modules are extracted next to it
+  ret.push(folder(go_mod_cache, "Go", &["cache"]));
+  let npm_prefixes = match env("NPM_CONFIG_PREFIX") {
+    Some(prefix) => vec![prefix],
+    None => vec![
+      home.join(".npm-global"),
+      PathBuf::from("/usr/local"),
+      PathBuf::from("/usr"),
+    ],
+  };
+  for prefix in npm_prefixes {
+    #[cfg(windows)]
+    let modules = prefix.join("node_modules");
+    #[cfg(not(windows))]
+    let modules = prefix.join("lib").join("node_modules");
+    ret.push(folder(modules, "Node", &[]));
+  }
+  #[cfg(windows)]
+  if let Some(app_data) = env("APPDATA") {
+    ret.push(folder(
+      app_data.join("npm").join("node_modules"),
+      "Node",
+      &[],
+    ));
+  }
+  ret
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::{Path, PathBuf};
+
+  use super::dependency_sources_in;
+
+  #[test]
+  fn sources_follow_the_environment() {
+    let home = Path::new("/home/me");
+    let paths = |vars: &[(&str, &str)]| {
+      dependency_sources_in(home, |name| {
+        vars
+          .iter()
+          .find(|(var, _)| *var == name)
+          .map(|(_, value)| PathBuf::from(value))
+      })
+      .into_iter()
+      .map(|folder| (folder.path, folder.kinds[0].clone()))
+      .collect::<Vec<_>>()
+    };
+    let defaults = paths(&[]);
+    assert!(defaults.contains(&("/home/me/.cargo/registry/src".into(), "Rust".into())));
+    assert!(defaults.contains(&("/home/me/go/pkg/mod".into(), "Go".into())));
+    let custom = paths(&[("CARGO_HOME", "/opt/cargo"), ("GOPATH", "/opt/go")]);
+    assert!(custom.contains(&("/opt/cargo/git/checkouts".into(), "Rust".into())));
+    assert!(custom.contains(&("/opt/go/pkg/mod".into(), "Go".into())));
+    let mod_cache = paths(&[("GOMODCACHE", "/cache/mod"), ("GOPATH", "/opt/go")]);
+    assert!(mod_cache.contains(&("/cache/mod".into(), "Go".into())));
+    let npm = paths(&[("NPM_CONFIG_PREFIX", "/opt/npm")]);
+    assert_eq!(npm.iter().filter(|(_, kind)| kind == "Node").count(), 1);
+  }
+}
@@ -0,0 +1,247 @@
+//! Discovery backends finding the project files of a folder in a file index rather than by
+//! walking it, chosen with the `discovery` setting, see [`crate::Discovery`]: the Spotlight
+//! index of macOS, or the plocate database with the `locate` feature.
+//!
+//! Only the project files are recorded, so that the projects are detected as usual but hold
+//! no source file. A backend returns `None` when its index is unavailable, and the folder is
+//! walked instead.
+
+use std::{
+  collections::BTreeSet,
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+use log::debug;
+use strum::IntoEnumIterator;
+
+use crate::{FolderScan, ProjectKind, ScanOptions};
+
+/// The names of the project files of the built-in kinds and of the custom ones of `options`
+fn project_file_names(options: &ScanOptions) -> BTreeSet<String> {
+  ProjectKind::iter()
+    .filter(|kind| !matches!(kind, ProjectKind::Custom { .. }))
+    .chain(options.custom_kinds.iter().cloned())
+    .flat_map(|kind| kind.project_files())
+    .collect()
+}
+
+/// Read the paths written one per line by an index command, keeping the bytes of the paths
+/// which aren't valid UTF-8 on unix
+fn output_paths(stdout: &[u8]) -> Vec<PathBuf> {
+  stdout
+    .split(|byte| *byte == b'\n')
+    .filter(|line| !line.is_empty())
+    .map(|line| {
+      #[cfg(unix)]
+      {
+        use std::os::unix::ffi::OsStrExt;
+        PathBuf::from(std::ffi::OsStr::from_bytes(line))
+      }
+      #[cfg(not(unix))]
+      PathBuf::from(String::from_utf8_lossy(line).trim_end_matches('\r'))
+    })
+    .collect()
+}
+
+/// Build the scan of `path` from the absolute paths of the project files found in it, honoring
+/// the ignore files and stop markers of the folders leading to them
+fn indexed_scan(path: &Path, files: Vec<PathBuf>, options: &ScanOptions) -> FolderScan {
+  let files = files
+    .into_iter()
+    .filter_map(|file| file.strip_prefix(path).ok().map(Path::to_path_buf));
+  FolderScan::from_files(path, files, options)
+}
+
+/// Build the Spotlight query matching the files named `names`
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::spotlight_query;
+///
+/// assert_eq!(
+///   spotlight_query(["Cargo.toml", "go.mod"]),
+///   r#"kMDItemFSName == "Cargo.toml" || kMDItemFSName == "go.mod""#
+/// );
+/// ```
+pub fn spotlight_query<I: IntoIterator<Item = S>, S: AsRef<str>>(names: I) -> String {
+  names
+    .into_iter()
+    .map(|name| {
+      format!(
+        "kMDItemFSName == \"{}\"",
+        name.as_ref().replace('"', "\\\"")
+      )
+    })
+    .collect::<Vec<_>>()
+    .join(" || ")
+}
+
+/// Scan `path` with the project files `mdfind` finds in the Spotlight index, `None` when not
+/// on macOS or when the folder isn't indexed
+pub fn spotlight_scan(path: &Path, options: &ScanOptions) -> Option<FolderScan> {
+  if !cfg!(target_os = "macos") {
+    debug!(
+      "Spotlight is only available on macOS, walking '{}'",
+      path.display()
+    );
+    return None;
+  }
+  let output = Command::new("mdfind")
+    .arg("-onlyin")
+    .arg(path)
+    .arg(spotlight_query(project_file_names(options)))
+    .output();
+  let files = match output {
+    Ok(output) if output.status.success() => output_paths(&output.stdout),
+    Ok(output) => {
+      debug!(
+        "mdfind failed with {}, walking '{}'",
+        output.status,
+        path.display()
+      );
+      return None;
+    }
+    Err(e) => {
+      debug!("cannot run mdfind, {}, walking '{}'", e, path.display());
+      return None;
+    }
+  };
+  // volumes left out of the index, or whose indexing is disabled, return nothing
+  if files.is_empty() {
+    debug!(
+      "no project file indexed by Spotlight, walking '{}'",
+      path.display()
+    );
+    return None;
+  }
+  Some(indexed_scan(path, files, options))
+}
+
+/// Build the regular expression matching the paths of the files named `names` under `folder`,
+/// as given to `plocate --regex`
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::locate_regex;
+///
+/// assert_eq!(
+///   locate_regex("/home/me/dev.d", ["Cargo.toml", "go.mod"]),
+///   r"^/home/me/dev\.d/(.*/)?(Cargo\.toml|go\.mod)$"
+/// );
+/// ```
+pub fn locate_regex<P: AsRef<Path>, I: IntoIterator<Item = S>, S: AsRef<str>>(
+  folder: P,
+  names: I,
+) -> String {
+  let escape = |value: &str| {
+    value.chars().fold(String::new(), |mut ret, c| {
+      if "\\.^$|?*+()[]{}".contains(c) {
+        ret.push('\\');
+      }
+      ret.push(c);
+      ret
+    })
+  };
+  let folder = folder.as_ref().to_string_lossy();
+  let names = names
+    .into_iter()
+    .map(|name| escape(name.as_ref()))
+    .collect::<Vec<_>>();
+  format!(
+    "^{}/(.*/)?({})$",
+    escape(folder.trim_end_matches('/')),
+    names.join("|")
+  )
+}
+
+/// Scan `path` with the project files `plocate` finds in its database, `None` when plocate
+/// isn't installed or the folder isn't in its database. As the database is only as recent as
+/// the latest `updatedb` run, the files which no longer exist are left out.
+#[cfg(feature = "locate")]
+pub fn locate_scan(path: &Path, options: &ScanOptions) -> Option<FolderScan> {
+  let output = Command::new("plocate")
+    .arg("--regex")
+    .arg(locate_regex(path, project_file_names(options)))
+    .output();
+  let files = match output {
+    Ok(output) if output.status.success() => output_paths(&output.stdout),
+    // plocate also fails when nothing matches, such as a folder created after `updatedb` ran
+    Ok(output) => {
+      debug!(
+        "plocate failed with {}, walking '{}'",
+        output.status,
+        path.display()
+      );
+      return None;
+    }
+    Err(e) => {
+      debug!("cannot run plocate, {}, walking '{}'", e, path.display());
+      return None;
+    }
+  };
+  let located = files.len();
+  let files = files
+    .into_iter()
+    .filter(|file| file.is_file())
+    .collect::<Vec<_>>();
+  if files.is_empty() {
+    debug!(
+      "no project file in the plocate database, walking '{}'",
+      path.display()
+    );
+    return None;
+  }
+  debug!(
+    "{} of {} located project files exist in '{}'",
+    files.len(),
+    located,
+    path.display()
+  );
+  Some(indexed_scan(path, files, options))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{indexed_scan, output_paths};
+  use crate::ScanOptions;
+
+  #[test]
+  #[cfg(unix)]
+  fn located_paths_keep_their_bytes() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::PathBuf};
+
+    let paths = output_paths(b"/dev/caf\xe9/Cargo.toml\n/dev/api/go.mod\n");
+    assert_eq!(
+      paths[0].as_os_str(),
+      OsStr::from_bytes(b"/dev/caf\xe9/Cargo.toml")
+    );
+    assert_eq!(paths[1], PathBuf::from("/dev/api/go.mod"));
+    assert_eq!(paths.len(), 2);
+  }
+
+  #[test]
+  fn located_files_honor_ignore_files_and_stop_markers() {
+    let root = std::env::temp_dir().join(format!("pgrep-located-{}", std::process::id()));
+    for dir in ["api", "fixtures/demo", "templates/web"] {
+      std::fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    std::fs::write(root.join(".pgrepignore"), "fixtures/").unwrap();
+    std::fs::write(root.join("templates/.pgrep-stop"), "").unwrap();
+    let files = [
+      "api/Cargo.toml",
+      "fixtures/demo/Cargo.toml",
+      "templates/web/package.json",
+    ]
+    .map(|file| root.join(file))
+    .to_vec();
+    let scan = indexed_scan(&root, files, &ScanOptions::default());
+    assert_eq!(
+      scan.files().collect::<Vec<_>>(),
+      [root.join("api/Cargo.toml")]
+    );
+    std::fs::remove_dir_all(root).unwrap();
+  }
+}
@@ -0,0 +1,310 @@
+use std::{path::PathBuf, string::FromUtf8Error};
+
+use crate::{i18n::tr, QueryParseError};
+
+/// A boxed error, used as the `caused by` field of [`Error`]
+pub type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Debug, thiserror::Error)]
+/// This crate's error type
+///
+/// Every variant has a stable numeric [`Error::code`], also used as the process exit status:
+///
+/// | Code | Variant                  |
+/// | ---: | ------------------------ |
+/// |    1 | [`Error::NoMatch`]       |
+/// |    2 | [`Error::Config`]        |
+/// |    3 | [`Error::QueryParse`]    |
+/// |    4 | [`Error::Scan`]          |
+/// |    5 | [`Error::Cache`]         |
+/// |    6 | [`Error::Format`]        |
+/// |    7 | [`Error::IO`]            |
+/// |    8 | [`Error::Unknown`]       |
+/// |    9 | [`Error::Exec`]          |
+/// |  130 | [`Error::Interrupted`]   |
+pub enum Error {
+  /// Nothing matched the user request
+  #[error("{0}")]
+  NoMatch(String),
+  /// Invalid command-line options or configuration values
+  #[error("{message}")]
+  Config {
+    message: String,
+    #[source]
+    source: Option<BoxedError>,
+  },
+  /// The query expression could not be parsed
+  #[error(transparent)]
+  QueryParse(#[from] QueryParseError),
+  /// A folder could not be scanned
+  #[error("{message} '{}'", path.display())]
+  Scan {
+    path: PathBuf,
+    message: String,
+    #[source]
+    source: Option<BoxedError>,
+  },
+  /// A cache entry could not be loaded or stored
+  #[error("{message} '{}'", key.display())]
+  Cache {
+    key: PathBuf,
+    message: String,
+    #[source]
+    source: Option<BoxedError>,
+  },
+  /// The report could not be rendered
+  #[error("{message}")]
+  Format {
+    message: String,
+    #[source]
+    source: Option<BoxedError>,
+  },
+  /// Generic input/output failure
+  #[error("{message}")]
+  IO {
+    message: String,
+    #[source]
+    source: Option<BoxedError>,
+  },
+  /// Anything else
+  #[error("{0}")]
+  Unknown(String),
+  /// Some of the commands run by `pgrep exec` failed
+  #[error("{0}")]
+  Exec(String),
+  /// The work was cancelled, usually by Ctrl-C
+  #[error("{0}")]
+  Interrupted(String),
+}
+
+impl Error {
+  /// Create a [`Error::Config`] error without cause
+  pub fn config<M: Into<String>>(message: M) -> Self {
+    Self::Config {
+      message: message.into(),
+      source: None,
+    }
+  }
+
+  /// Create a [`Error::IO`] error caused by `source`
+  pub fn io<M: Into<String>, E: std::error::Error + Send + Sync + 'static>(
+    message: M,
+    source: E,
+  ) -> Self {
+    Self::IO {
+      message: message.into(),
+      source: Some(Box::new(source)),
+    }
+  }
+
+  /// Create a [`Error::Cache`] error for `key`, caused by `source`
+  pub fn cache<K: Into<PathBuf>, M: Into<String>, E: std::error::Error + Send + Sync + 'static>(
+    key: K,
+    message: M,
+    source: E,
+  ) -> Self {
+    Self::Cache {
+      key: key.into(),
+      message: message.into(),
+      source: Some(Box::new(source)),
+    }
+  }
+
+  /// Modify the message, prepending `prefix` to the current message
+  pub fn with_context(mut self, prefix: String) -> Self {
+    match &mut self {
+      Self::NoMatch(m) | Self::Unknown(m) | Self::Exec(m) | Self::Interrupted(m) => {
+        *m = format!("{}, {}", prefix, m)
+      }
+      Self::QueryParse(e) => {
+        *e = QueryParseError::new(
+          e.expr(),
+          e.position(),
+          format!("{}, {}", prefix, e.message()),
+        )
+      }
+      Self::Config { message, .. }
+      | Self::Scan { message, .. }
+      | Self::Cache { message, .. }
+      | Self::Format { message, .. }
+      | Self::IO { message, .. } => *message = format!("{}, {}", prefix, message),
+    };
+    self
+  }
+
+  /// Retrieve the stable error code, used as the process exit status
+  pub fn code(&self) -> u8 {
+    match self {
+      Self::NoMatch(..) => 1,
+      Self::Config { .. } => 2,
+      Self::QueryParse(..) => 3,
+      Self::Scan { .. } => 4,
+      Self::Cache { .. } => 5,
+      Self::Format { .. } => 6,
+      Self::IO { .. } => 7,
+      Self::Unknown(..) => 8,
+      Self::Exec(..) => 9,
+      // the conventional status of processes killed by SIGINT
+      Self::Interrupted(..) => 130,
+    }
+  }
+
+  /// Retrieve the error kind
+  pub fn kind<'a>(&self) -> &'a str {
+    match self {
+      Self::NoMatch(..) => "No match",
+      Self::Config { .. } => "Configuration",
+      Self::QueryParse(..) => "Query",
+      Self::Scan { .. } => "Scan",
+      Self::Cache { .. } => "Cache",
+      Self::Format { .. } => "Format",
+      Self::IO { .. } => "I/O",
+      Self::Unknown(..) => "Unknown",
+      Self::Exec(..) => "Exec",
+      Self::Interrupted(..) => "Interrupted",
+    }
+  }
+
+  /// Retrieve the error kind, translated in the selected [`crate::i18n::Lang`]
+  pub fn localized_kind(&self) -> &'static str {
+    tr(match self {
+      Self::NoMatch(..) => "error.kind.no-match",
+      Self::Config { .. } => "error.kind.config",
+      Self::QueryParse(..) => "error.kind.query",
+      Self::Scan { .. } => "error.kind.scan",
+      Self::Cache { .. } => "error.kind.cache",
+      Self::Format { .. } => "error.kind.format",
+      Self::IO { .. } => "error.kind.io",
+      Self::Unknown(..) => "error.kind.unknown",
+      Self::Exec(..) => "error.kind.exec",
+      Self::Interrupted(..) => "error.kind.interrupted",
+    })
+  }
+
+  /// Retrieve the stored message
+  pub fn message(&self) -> &String {
+    match self {
+      Self::NoMatch(m) | Self::Unknown(m) | Self::Exec(m) | Self::Interrupted(m) => m,
+      Self::QueryParse(e) => e.message(),
+      Self::Config { message, .. }
+      | Self::Scan { message, .. }
+      | Self::Cache { message, .. }
+      | Self::Format { message, .. }
+      | Self::IO { message, .. } => message,
+    }
+  }
+
+  /// Retrieve the `caused by` field
+  pub fn cause(&self) -> Option<&BoxedError> {
+    match self {
+      Self::Config { source, .. }
+      | Self::Scan { source, .. }
+      | Self::Cache { source, .. }
+      | Self::Format { source, .. }
+      | Self::IO { source, .. } => source.as_ref(),
+      Self::NoMatch(..)
+      | Self::QueryParse(..)
+      | Self::Unknown(..)
+      | Self::Exec(..)
+      | Self::Interrupted(..) => None,
+    }
+  }
+
+  /// Render the error as a json object, used when the `json` output format is selected
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::Error;
+  ///
+  /// let json = Error::config("no folders").to_json();
+  /// assert_eq!(json, r#"{"error":{"cause":null,"code":2,"kind":"Configuration","message":"no folders"}}"#);
+  /// ```
+  #[cfg(feature = "json")]
+  pub fn to_json(&self) -> String {
+    serde_json::json!({
+      "error": {
+        "code": self.code(),
+        "kind": self.kind(),
+        "message": self.to_string(),
+        "cause": self.cause().map(|c| c.to_string()),
+      }
+    })
+    .to_string()
+  }
+}
+
+/// This crate's result type
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(feature = "clap")]
+impl From<clap::error::Error> for Error {
+  fn from(value: clap::error::Error) -> Self {
+    Error::Config {
+      message: "invalid command-line".to_string(),
+      source: Some(Box::new(value)),
+    }
+  }
+}
+
+impl From<log::SetLoggerError> for Error {
+  fn from(value: log::SetLoggerError) -> Self {
+    Error::Unknown(format!("failed to initialize logger, {}", value))
+  }
+}
+
+impl From<std::io::Error> for Error {
+  fn from(value: std::io::Error) -> Self {
+    Error::IO {
+      message: value.to_string(),
+      source: None,
+    }
+  }
+}
+
+impl From<toml::de::Error> for Error {
+  fn from(value: toml::de::Error) -> Self {
+    Error::Config {
+      message: "failed to deserialize configuration".to_string(),
+      source: Some(Box::new(value)),
+    }
+  }
+}
+
+impl From<toml::ser::Error> for Error {
+  fn from(value: toml::ser::Error) -> Self {
+    Error::Config {
+      message: "failed to serialize configuration".to_string(),
+      source: Some(Box::new(value)),
+    }
+  }
+}
+
+impl From<chrono::OutOfRangeError> for Error {
+  fn from(value: chrono::OutOfRangeError) -> Self {
+    Error::Unknown(value.to_string())
+  }
+}
+
+#[cfg(feature = "json")]
+impl From<serde_json::Error> for Error {
+  fn from(value: serde_json::Error) -> Self {
+    Error::Format {
+      message: "failed to serialize entity".to_string(),
+      source: Some(Box::new(value)),
+    }
+  }
+}
+
+#[cfg(feature = "tokio")]
+impl From<tokio::task::JoinError> for Error {
+  fn from(value: tokio::task::JoinError) -> Self {
+    Error::Unknown(format!("background task failed, {}", value))
+  }
+}
+
+impl From<FromUtf8Error> for Error {
+  fn from(value: FromUtf8Error) -> Self {
+    Error::io("utf-8 conversion failed", value)
+  }
+}
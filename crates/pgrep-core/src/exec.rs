@@ -0,0 +1,208 @@
+use std::{
+  path::PathBuf,
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Mutex,
+  },
+};
+
+use log::debug;
+
+use crate::{hooks::shell_command, render_hook, CancelToken, Project, Table};
+
+/// The outcome of a command run against a project by [`exec_all`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecResult {
+  /// The project folder the command ran in
+  pub path: PathBuf,
+  /// The command, its placeholders replaced
+  pub command: String,
+  /// The exit code, `None` when killed by a signal or when the command couldn't start
+  pub code: Option<i32>,
+  /// Whether the command exited successfully
+  pub success: bool,
+  /// The standard output then error of the command, or why it couldn't start
+  pub output: String,
+}
+
+impl ExecResult {
+  /// Summarize the outcomes as a table, one row per project
+  pub fn table(results: &[ExecResult]) -> Table {
+    let mut table = Table::new("Commands", &["Status", "Path", "Code", "Output"]);
+    for result in results {
+      let status = match result.success {
+        true => "ok",
+        false => "failed",
+      };
+      // the last line usually tells what went wrong
+      let last_line = result
+        .output
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty());
+      table.push_row(vec![
+        status.into(),
+        result.path.display().to_string().into(),
+        result
+          .code
+          .map(|code| code.to_string())
+          .unwrap_or_default()
+          .into(),
+        last_line.unwrap_or_default().trim().into(),
+      ]);
+    }
+    table
+  }
+
+  /// Count the commands which failed, or couldn't start
+  pub fn failures(results: &[ExecResult]) -> usize {
+    results.iter().filter(|result| !result.success).count()
+  }
+}
+
+/// Run `template` through the platform shell in every project folder, `parallel` at a time.
+///
+/// The `{name}`, `{path}` and `{kinds}` placeholders are replaced as for hooks, see
+/// [`render_hook`]. Projects not started yet are skipped once `cancel` is triggered, the
+/// results keep the order of `projects`.
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::{exec_all, CancelToken, Project, ProjectKind};
+///
+/// let dir = std::env::temp_dir();
+/// let projects = vec![Project::new(&dir, vec![ProjectKind::Rust], vec![], vec![])];
+/// let results = exec_all("echo {kinds}", &projects, 2, &CancelToken::default());
+/// assert!(results[0].success);
+/// assert_eq!(results[0].output.trim(), "Rust");
+/// ```
+pub fn exec_all(
+  template: &str,
+  projects: &[Project],
+  parallel: usize,
+  cancel: &CancelToken,
+) -> Vec<ExecResult> {
+  let next = AtomicUsize::new(0);
+  let results = Mutex::new(vec![None; projects.len()]);
+  std::thread::scope(|scope| {
+    for _ in 0..parallel.clamp(1, projects.len().max(1)) {
+      scope.spawn(|| loop {
+        let id = next.fetch_add(1, Ordering::SeqCst);
+        if id >= projects.len() || cancel.is_cancelled() {
+          return;
+        }
+        let result = exec(template, &projects[id]);
+        results.lock().unwrap()[id] = Some(result);
+      });
+    }
+  });
+  results
+    .into_inner()
+    .unwrap()
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Run `template` against a single project, waiting for it to exit
+fn exec(template: &str, project: &Project) -> ExecResult {
+  let kinds = project.kinds().iter().map(|k| k.name()).collect::<Vec<_>>();
+  let command = render_hook(template, project.path(), &kinds);
+  debug!("running `{}` in '{}'", command, project.path().display());
+  let output = shell_command(&command, project.path(), &kinds)
+    .current_dir(project.path())
+    .output();
+  let (code, success, output) = match output {
+    Ok(output) => {
+      let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+      text.push_str(&String::from_utf8_lossy(&output.stderr));
+      (output.status.code(), output.status.success(), text)
+    }
+    Err(e) => (None, false, e.to_string()),
+  };
+  ExecResult {
+    path: project.path().clone(),
+    command,
+    code,
+    success,
+    output,
+  }
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+  use std::time::Duration;
+
+  use super::{exec_all, ExecResult};
+  use crate::{test_support::temp_tree, CancelToken, Project};
+
+  /// Create the project folders named `names` in a temporary folder named after `test`
+  fn projects(test: &str, names: &[&str]) -> Vec<Project> {
+    let root = temp_tree(&format!("exec-{}", test), &[]);
+    names
+      .iter()
+      .map(|name| {
+        let path = root.join(name);
+        std::fs::create_dir_all(&path).unwrap();
+        Project::new(path, vec![], vec![], vec![])
+      })
+      .collect()
+  }
+
+  #[test]
+  fn results_keep_the_project_order() {
+    let projects = projects("order", &["a", "b", "c"]);
+    let template = "case {name} in a) sleep 0.3;; b) sleep 0.1;; esac; echo {name}";
+    let results = exec_all(template, &projects, 3, &CancelToken::default());
+    let outputs = results
+      .iter()
+      .map(|result| result.output.trim())
+      .collect::<Vec<_>>();
+    assert_eq!(outputs, ["a", "b", "c"]);
+    assert!(results
+      .iter()
+      .zip(&projects)
+      .all(|(result, p)| &result.path == p.path()));
+  }
+
+  #[test]
+  fn cancelled_runs_skip_the_remaining_projects() {
+    let projects = projects("cancel", &["a", "b", "c"]);
+    let cancelled = CancelToken::default();
+    cancelled.cancel();
+    assert!(exec_all("true", &projects, 2, &cancelled).is_empty());
+    let cancel = CancelToken::default();
+    let results = std::thread::scope(|scope| {
+      let run = scope.spawn(|| exec_all("sleep 0.3", &projects, 1, &cancel));
+      std::thread::sleep(Duration::from_millis(100));
+      cancel.cancel();
+      run.join().unwrap()
+    });
+    assert_eq!(results.len(), 1);
+    assert!(results[0].success);
+  }
+
+  #[test]
+  fn failures_are_counted() {
+    let mut projects = projects("failures", &["a", "b", "c"]);
+    projects.push(Project::new(
+      "/nonexistent/pgrep-exec",
+      vec![],
+      vec![],
+      vec![],
+    ));
+    let results = exec_all("test {name} != b", &projects, 2, &CancelToken::default());
+    assert_eq!(ExecResult::failures(&results), 2);
+    assert_eq!(results[1].code, Some(1));
+    assert_eq!(results[3].code, None);
+  }
+
+  #[test]
+  fn fields_are_never_run_as_shell_syntax() {
+    let projects = projects("quoting", &["it's $(echo pwned) & `id`"]);
+    let results = exec_all("printf %s {name}", &projects, 1, &CancelToken::default());
+    assert_eq!(results[0].output, "it's $(echo pwned) & `id`");
+  }
+}
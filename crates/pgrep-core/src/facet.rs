@@ -0,0 +1,634 @@
+//! Project metadata extracted on demand, such as the pinned toolchains or the lines of code.
+
+use std::{
+  collections::BTreeMap,
+  fmt::Display,
+  path::{Path, PathBuf},
+  time::SystemTime,
+};
+
+use log::debug;
+use rmp_serde::{Deserializer, Serializer};
+use serde::{Deserialize, Serialize};
+
+use crate::{CiSystem, Error, Project, ProjectId};
+
+/// The value a [`ProjectFacet`] extracts from a project
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum FacetValue {
+  /// Whether the project has some property, such as being containerized
+  Bool(bool),
+  /// A quantity, such as the lines of code
+  Count(u64),
+  /// A free-form description, such as the git status
+  Text(String),
+  /// A set of names, such as the CI systems
+  List(Vec<String>),
+  /// Values by name, such as the toolchain versions by tool
+  Map(BTreeMap<String, String>),
+}
+
+impl FacetValue {
+  /// Check whether the value marks the project as having the facet: `true`, non-zero or
+  /// non-empty
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::FacetValue;
+  ///
+  /// assert!(FacetValue::Count(12).is_set());
+  /// assert!(!FacetValue::Bool(false).is_set());
+  /// assert!(!FacetValue::List(vec![]).is_set());
+  /// ```
+  pub fn is_set(&self) -> bool {
+    match self {
+      Self::Bool(value) => *value,
+      Self::Count(count) => *count > 0,
+      Self::Text(text) => !text.is_empty(),
+      Self::List(names) => !names.is_empty(),
+      Self::Map(values) => !values.is_empty(),
+    }
+  }
+}
+
+impl Display for FacetValue {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Bool(true) => write!(f, "yes"),
+      Self::Bool(false) => write!(f, "no"),
+      Self::Count(count) => write!(f, "{}", count),
+      Self::Text(text) => write!(f, "{}", text),
+      Self::List(names) if names.is_empty() => write!(f, "none"),
+      Self::List(names) => write!(f, "{}", names.join(", ")),
+      Self::Map(values) => {
+        let values = values
+          .iter()
+          .map(|(name, value)| format!("{} {}", name, value));
+        write!(f, "{}", values.collect::<Vec<_>>().join(", "))
+      }
+    }
+  }
+}
+
+/// An extractor of project metadata, registered in a [`FacetRegistry`]
+pub trait ProjectFacet: Send + Sync {
+  /// The name selecting the facet, such as `loc`
+  fn name(&self) -> &'static str;
+
+  /// The files whose modification changes the value, besides the project folder itself
+  fn watched_files(&self, _project: &Project) -> Vec<PathBuf> {
+    vec![]
+  }
+
+  /// Whether the value may be cached until the watched files change
+  fn cacheable(&self) -> bool {
+    true
+  }
+
+  /// Whether extracting the value reads every source file or runs commands, so that
+  /// interactive interfaces only extract it for the projects the user looks at
+  fn expensive(&self) -> bool {
+    false
+  }
+
+  /// Extract the value from `project`, `None` when the facet doesn't apply to it
+  fn extract(&self, project: &Project) -> Option<FacetValue>;
+}
+
+/// The toolchain versions pinned by a project, by tool: the `rust` channel of
+/// `rust-toolchain.toml`, the `node` version of `.nvmrc`, the `python` version of
+/// `.python-version` and the `go` directive of `go.mod`.
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::{FacetValue, Project, ProjectFacet, ToolchainFacet};
+///
+/// let root = std::env::temp_dir().join(format!("pgrep-toolchains-{}", std::process::id()));
+/// std::fs::create_dir_all(&root).unwrap();
+/// let project = Project::new(&root, vec![], vec![], vec![]);
+/// assert_eq!(ToolchainFacet.extract(&project), None);
+/// std::fs::write(root.join("rust-toolchain.toml"), "[toolchain]\nchannel = \"1.78.0\"\n")
+///   .unwrap();
+/// std::fs::write(root.join(".nvmrc"), "v18.17.0\n").unwrap();
+/// let value = ToolchainFacet.extract(&project).unwrap();
+/// assert_eq!(value.to_string(), "node v18.17.0, rust 1.78.0");
+/// std::fs::remove_dir_all(&root).unwrap();
+/// ```
+pub struct ToolchainFacet;
+
+impl ToolchainFacet {
+  /// The files pinning toolchain versions, relative to the project folder
+  pub const FILES: &'static [&'static str] = &[
+    "rust-toolchain.toml",
+    "rust-toolchain",
+    ".nvmrc",
+    ".python-version",
+    "go.mod",
+  ];
+}
+
+impl ProjectFacet for ToolchainFacet {
+  fn name(&self) -> &'static str {
+    "toolchain"
+  }
+
+  fn watched_files(&self, project: &Project) -> Vec<PathBuf> {
+    Self::FILES
+      .iter()
+      .map(|file| project.path().join(file))
+      .collect()
+  }
+
+  fn extract(&self, project: &Project) -> Option<FacetValue> {
+    let read = |name: &str| std::fs::read_to_string(project.path().join(name)).ok();
+    let first_line = |content: String| {
+      let mut lines = content.lines().map(str::trim);
+      let line = lines.find(|line| !line.is_empty() && !line.starts_with('#'));
+      line.map(str::to_string)
+    };
+    let mut ret = BTreeMap::new();
+    // the legacy rust-toolchain file holds either the toml table or the bare channel
+    let rust = read("rust-toolchain.toml")
+      .or_else(|| read("rust-toolchain"))
+      .and_then(|content| match toml::from_str::<toml::Table>(&content) {
+        Ok(table) => table
+          .get("toolchain")?
+          .get("channel")?
+          .as_str()
+          .map(str::to_string),
+        Err(_) => first_line(content),
+      });
+    let node = read(".nvmrc").and_then(first_line);
+    let python = read(".python-version").and_then(first_line);
+    let go = read("go.mod").and_then(|content| {
+      content.lines().find_map(|line| {
+        line
+          .trim()
+          .strip_prefix("go ")
+          .map(|v| v.trim().to_string())
+      })
+    });
+    for (tool, version) in [
+      ("rust", rust),
+      ("node", node),
+      ("python", python),
+      ("go", go),
+    ] {
+      if let Some(version) = version {
+        ret.insert(tool.to_string(), version);
+      }
+    }
+    (!ret.is_empty()).then_some(FacetValue::Map(ret))
+  }
+}
+
+/// The continuous integration systems configured by a project, see [`CiSystem::detect`]
+pub struct CiFacet;
+
+impl ProjectFacet for CiFacet {
+  fn name(&self) -> &'static str {
+    "ci"
+  }
+
+  fn watched_files(&self, project: &Project) -> Vec<PathBuf> {
+    vec![
+      project.path().join(".github").join("workflows"),
+      project.path().join(".gitlab-ci.yml"),
+      project.path().join("Jenkinsfile"),
+    ]
+  }
+
+  fn extract(&self, project: &Project) -> Option<FacetValue> {
+    let systems = CiSystem::detect(project.path());
+    Some(FacetValue::List(
+      systems.iter().map(|ci| ci.name().to_string()).collect(),
+    ))
+  }
+}
+
+/// Whether a project builds or runs in containers: its folder holds one of the
+/// [`ContainerFacet::FILES`], such as a `Dockerfile`
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::{ContainerFacet, FacetValue, Project, ProjectFacet};
+///
+/// let root = std::env::temp_dir().join(format!("pgrep-container-{}", std::process::id()));
+/// std::fs::create_dir_all(root.join(".devcontainer")).unwrap();
+/// let project = Project::new(&root, vec![], vec![], vec![]);
+/// assert_eq!(ContainerFacet.extract(&project), Some(FacetValue::Bool(false)));
+/// std::fs::write(root.join(".devcontainer").join("devcontainer.json"), "{}").unwrap();
+/// assert_eq!(ContainerFacet.extract(&project), Some(FacetValue::Bool(true)));
+/// std::fs::remove_dir_all(&root).unwrap();
+/// ```
+pub struct ContainerFacet;
+
+impl ContainerFacet {
+  /// The files revealing a containerized project, relative to its folder
+  pub const FILES: &'static [&'static str] = &[
+    "Dockerfile",
+    "docker-compose.yml",
+    "docker-compose.yaml",
+    "compose.yml",
+    "compose.yaml",
+    ".devcontainer/devcontainer.json",
+    ".devcontainer.json",
+  ];
+}
+
+impl ProjectFacet for ContainerFacet {
+  fn name(&self) -> &'static str {
+    "container"
+  }
+
+  fn watched_files(&self, project: &Project) -> Vec<PathBuf> {
+    let mut ret = vec![project.path().join(".devcontainer")];
+    ret.extend(Self::FILES.iter().map(|file| project.path().join(file)));
+    ret
+  }
+
+  fn extract(&self, project: &Project) -> Option<FacetValue> {
+    let containerized = Self::FILES
+      .iter()
+      .any(|file| project.path().join(file).is_file());
+    Some(FacetValue::Bool(containerized))
+  }
+}
+
+/// The lines of the source files of a project, recognized by the
+/// [`crate::ProjectKind::language_extensions`] of its kinds. Hidden folders and build artifacts
+/// are skipped.
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::{FacetValue, LocFacet, Project, ProjectFacet, ProjectKind};
+///
+/// let root = std::env::temp_dir().join(format!("pgrep-loc-{}", std::process::id()));
+/// std::fs::create_dir_all(root.join("src")).unwrap();
+/// std::fs::create_dir_all(root.join("target")).unwrap();
+/// std::fs::write(root.join("src").join("main.rs"), "fn main() {\n}\n").unwrap();
+/// std::fs::write(root.join("target").join("gen.rs"), "// generated\n").unwrap();
+/// let project = Project::new(&root, vec![ProjectKind::Rust], vec![], vec![]);
+/// assert_eq!(LocFacet.extract(&project), Some(FacetValue::Count(2)));
+/// std::fs::remove_dir_all(&root).unwrap();
+/// ```
+pub struct LocFacet;
+
+impl LocFacet {
+  /// List the source files below `dir` along with the folders holding them
+  fn walk(dir: &Path, exts: &[String], skipped: &[PathBuf], ret: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+      return;
+    };
+    ret.push(dir.to_path_buf());
+    for entry in entries.flatten() {
+      let path = entry.path();
+      let Ok(file_type) = entry.file_type() else {
+        continue;
+      };
+      if file_type.is_dir() {
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if !hidden && !skipped.contains(&path) {
+          Self::walk(&path, exts, skipped, ret);
+        }
+      } else if file_type.is_file() {
+        let ext = path
+          .extension()
+          .map(|ext| ext.to_string_lossy())
+          .unwrap_or_default();
+        if exts.iter().any(|known| known.eq_ignore_ascii_case(&ext)) {
+          ret.push(path);
+        }
+      }
+    }
+  }
+
+  /// List the source files of `project` along with the folders holding them, so that
+  /// removing a file changes the modification time of a watched folder
+  fn sources(project: &Project) -> Vec<PathBuf> {
+    let exts = project
+      .kinds()
+      .iter()
+      .flat_map(|kind| kind.language_extensions())
+      .collect::<Vec<_>>();
+    let mut ret = vec![];
+    if !exts.is_empty() {
+      Self::walk(project.path(), &exts, &project.artifact_dirs(), &mut ret);
+    }
+    ret
+  }
+}
+
+impl ProjectFacet for LocFacet {
+  fn name(&self) -> &'static str {
+    "loc"
+  }
+
+  fn expensive(&self) -> bool {
+    true
+  }
+
+  fn watched_files(&self, project: &Project) -> Vec<PathBuf> {
+    Self::sources(project)
+  }
+
+  fn extract(&self, project: &Project) -> Option<FacetValue> {
+    let sources = Self::sources(project);
+    if sources.is_empty() {
+      return None;
+    }
+    let mut lines = 0;
+    for file in sources.iter().filter(|path| path.is_file()) {
+      let Ok(content) = std::fs::read(file) else {
+        continue;
+      };
+      lines += content.iter().filter(|byte| **byte == b'\n').count() as u64;
+      if content.last().is_some_and(|byte| *byte != b'\n') {
+        lines += 1;
+      }
+    }
+    Some(FacetValue::Count(lines))
+  }
+}
+
+/// The state of the git work tree holding a project, such as `main, 2 ahead, 3 dirty`. It is
+/// never cached as editing any file changes it.
+#[cfg(feature = "git")]
+pub struct GitFacet;
+
+#[cfg(feature = "git")]
+impl ProjectFacet for GitFacet {
+  fn name(&self) -> &'static str {
+    "git"
+  }
+
+  fn cacheable(&self) -> bool {
+    false
+  }
+
+  fn expensive(&self) -> bool {
+    true
+  }
+
+  fn extract(&self, project: &Project) -> Option<FacetValue> {
+    let status = match crate::GitStatus::read(project.path()) {
+      Ok(status) => status?,
+      Err(e) => {
+        debug!(
+          "cannot read the git status of '{}': {}",
+          project.path().display(),
+          e
+        );
+        return None;
+      }
+    };
+    let mut parts = vec![status.branch.unwrap_or_else(|| "detached".to_string())];
+    for (count, what) in [
+      (status.ahead, "ahead"),
+      (status.behind, "behind"),
+      (status.dirty, "dirty"),
+      (status.stashes, "stashed"),
+    ] {
+      if count > 0 {
+        parts.push(format!("{} {}", count, what));
+      }
+    }
+    Some(FacetValue::Text(parts.join(", ")))
+  }
+}
+
+/// The known [`ProjectFacet`]s, by name
+pub struct FacetRegistry {
+  facets: Vec<Box<dyn ProjectFacet>>,
+}
+
+impl Default for FacetRegistry {
+  /// Register the built-in facets: `toolchain`, `ci`, `container`, `loc`, and `git` with the
+  /// `git` feature
+  fn default() -> Self {
+    let mut ret = Self { facets: vec![] };
+    ret.register(Box::new(ToolchainFacet));
+    ret.register(Box::new(CiFacet));
+    ret.register(Box::new(ContainerFacet));
+    ret.register(Box::new(LocFacet));
+    #[cfg(feature = "git")]
+    ret.register(Box::new(GitFacet));
+    ret
+  }
+}
+
+impl FacetRegistry {
+  /// Register a facet, replacing the one with the same name
+  pub fn register(&mut self, facet: Box<dyn ProjectFacet>) {
+    self.facets.retain(|known| known.name() != facet.name());
+    self.facets.push(facet);
+  }
+
+  /// List the names of the registered facets, in registration order
+  pub fn names(&self) -> Vec<&'static str> {
+    self.facets.iter().map(|facet| facet.name()).collect()
+  }
+
+  /// Find the facet named `name`
+  pub fn get(&self, name: &str) -> Option<&dyn ProjectFacet> {
+    self
+      .facets
+      .iter()
+      .find(|facet| facet.name() == name)
+      .map(|facet| facet.as_ref())
+  }
+
+  /// Extract the facets named `names` from every project in parallel, reusing the values
+  /// cached since their watched files last changed. Unknown names are ignored.
+  pub fn extract(&self, names: &[String], projects: &mut [Project], cache: &mut FacetCache) {
+    let facets = names
+      .iter()
+      .filter_map(|name| self.get(name))
+      .collect::<Vec<_>>();
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk = projects.len().div_ceil(threads).max(1);
+    let cached: &FacetCache = cache;
+    let extracted = std::thread::scope(|scope| {
+      let handles = projects
+        .chunks_mut(chunk)
+        .map(|chunk| {
+          let facets = &facets;
+          scope.spawn(move || {
+            let mut extracted = vec![];
+            for project in chunk {
+              for facet in facets {
+                let modified = facet
+                  .cacheable()
+                  .then(|| modified_at(project, *facet))
+                  .flatten();
+                let value = match modified.and_then(|at| cached.get(facet.name(), project, at)) {
+                  Some(value) => value.clone(),
+                  None => {
+                    let value = facet.extract(project);
+                    if let Some(at) = modified {
+                      extracted.push((facet.name(), project.id(), at, value.clone()));
+                    }
+                    value
+                  }
+                };
+                project.set_facet(facet.name(), value);
+              }
+            }
+            extracted
+          })
+        })
+        .collect::<Vec<_>>();
+      handles
+        .into_iter()
+        .flat_map(|handle| handle.join().unwrap_or_default())
+        .collect::<Vec<_>>()
+    });
+    for (name, id, modified, value) in extracted {
+      cache.insert(name, id, modified, value);
+    }
+  }
+}
+
+/// Find the latest modification time of the project folder and of the files watched by
+/// `facet`, `None` when the folder cannot be read
+fn modified_at(project: &Project, facet: &dyn ProjectFacet) -> Option<SystemTime> {
+  let mut ret = std::fs::metadata(project.path())
+    .and_then(|m| m.modified())
+    .ok()?;
+  for file in facet.watched_files(project) {
+    if let Ok(modified) = std::fs::metadata(file).and_then(|m| m.modified()) {
+      ret = ret.max(modified);
+    }
+  }
+  Some(ret)
+}
+
+/// A facet value, along with the modification time it was extracted at
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct CachedFacet {
+  modified: SystemTime,
+  value: Option<FacetValue>,
+}
+
+/// The facet values extracted by previous runs, by facet name and [`Project::id`].
+///
+/// A value is reused as long as the latest modification time of the project folder and of the
+/// [`ProjectFacet::watched_files`] is the one it was extracted at.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FacetCache {
+  /// The file the values are stored in
+  file: PathBuf,
+  /// The cached values
+  entries: BTreeMap<String, BTreeMap<ProjectId, CachedFacet>>,
+}
+
+impl FacetCache {
+  /// The facet cache file name
+  pub const FILE_NAME: &'static str = "facets.bin";
+
+  /// Load the values stored in `file`, none if it doesn't exist yet or was written by an
+  /// incompatible version
+  pub fn load<P: AsRef<Path>>(file: P) -> Self {
+    let file = file.as_ref().to_path_buf();
+    let entries = match std::fs::read(&file) {
+      Ok(content) => Deserialize::deserialize(&mut Deserializer::new(content.as_slice()))
+        .unwrap_or_else(|e| {
+          debug!("ignoring the facet cache '{}': {}", file.display(), e);
+          BTreeMap::new()
+        }),
+      Err(_) => BTreeMap::new(),
+    };
+    Self { file, entries }
+  }
+
+  /// Write the values to their file, creating its folder if needed
+  pub fn save(&self) -> crate::Result<()> {
+    if let Some(dir) = self.file.parent() {
+      std::fs::create_dir_all(dir)?;
+    }
+    let mut buf = vec![];
+    self
+      .entries
+      .serialize(&mut Serializer::new(&mut buf))
+      .map_err(|e| Error::IO {
+        message: "cannot serialize facet cache".to_string(),
+        source: Some(Box::new(e)),
+      })?;
+    std::fs::write(&self.file, buf).map_err(|e| {
+      Error::io(
+        format!("cannot write facet cache '{}'", self.file.display()),
+        e,
+      )
+    })?;
+    debug!("saved facet cache '{}'", self.file.display());
+    Ok(())
+  }
+
+  /// Retrieve the value of the facet named `name` for `project`, if it was extracted when the
+  /// watched files were last `modified`
+  pub fn get(
+    &self,
+    name: &str,
+    project: &Project,
+    modified: SystemTime,
+  ) -> Option<&Option<FacetValue>> {
+    let cached = self.entries.get(name)?.get(&project.id())?;
+    (cached.modified == modified).then_some(&cached.value)
+  }
+
+  /// Cache the value of the facet named `name` for the project `id`, extracted when the
+  /// watched files were last `modified`
+  pub fn insert(
+    &mut self,
+    name: &str,
+    id: ProjectId,
+    modified: SystemTime,
+    value: Option<FacetValue>,
+  ) {
+    let entries = self.entries.entry(name.to_string()).or_default();
+    entries.insert(id, CachedFacet { modified, value });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{collections::BTreeMap, time::SystemTime};
+
+  use super::{FacetCache, FacetValue};
+  use crate::{Project, ProjectId};
+
+  #[test]
+  fn facet_cache_is_persisted() {
+    let file = std::env::temp_dir()
+      .join(format!("pgrep-facets-{}", std::process::id()))
+      .join(FacetCache::FILE_NAME);
+    let modified = SystemTime::now();
+    let toolchains = BTreeMap::from([("rust".to_string(), "1.78.0".to_string())]);
+    let values = [
+      ("container", Some(FacetValue::Bool(true))),
+      ("loc", Some(FacetValue::Count(1234))),
+      ("git", Some(FacetValue::Text("main, 2 ahead".to_string()))),
+      ("ci", Some(FacetValue::List(vec!["github".to_string()]))),
+      ("toolchain", Some(FacetValue::Map(toolchains))),
+      ("missing", None),
+    ];
+    let mut cache = FacetCache::load(&file);
+    for (name, value) in values.clone() {
+      cache.insert(name, ProjectId::of("/dev/alpha"), modified, value);
+    }
+    cache.save().unwrap();
+    let cache = FacetCache::load(&file);
+    let project = Project::new("/dev/alpha", vec![], vec![], vec![]);
+    for (name, value) in &values {
+      assert_eq!(cache.get(name, &project, modified), Some(value));
+    }
+    // values extracted before the latest modification are stale
+    assert_eq!(cache.get("loc", &project, SystemTime::now()), None);
+    std::fs::remove_dir_all(file.parent().unwrap()).unwrap();
+  }
+}
@@ -0,0 +1,176 @@
+use crate::{escape_markup, normalize_path, Project, ProjectMatchesFormatter, Table};
+
+/// The most basic project writer: a human readable list on stdout
+pub struct HtmlProjectMatchesWriter {}
+
+pub const HTML_TEMPLATE: &'static str = "
+<!DOCTYPE html>
+<html lang=\"en\">
+  <head>
+    <meta charset=\"UTF-8\">
+    <meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">
+    <meta http-equiv=\"X-UA-Compatible\" content=\"ie=edge\">
+    <title>Discovered projects</title>
+    <!--<link rel=\"stylesheet\" href=\"style.css\">-->
+  </head>
+  <body>
+    <form onsubmit=\"filter(); return false\">
+      <input type=\"text\" id=\"query\" oninput=\"filter()\" autofocus> <button type='button' onclick='clearFilter()'>Clear</button>
+    </form>
+    <table>
+      <thead>
+{{HEADER}}
+      </thead>
+      <tbody>
+{{BODY}}
+      </tbody>
+    </table>
+  <script type=\"text/javascript\">
+    async function filter() {
+      let qelem = document.getElementById('query');
+      let elems = document.querySelectorAll('tr[path]');
+      let res = [];
+      let q = qelem.value.toLowerCase();
+      // when served by `pgrep watch --serve`, the index is queried with the pgrep query syntax
+      let served = null;
+      const api = document.body.dataset.api;
+      if (api && q) {
+        const resp = await fetch(api + '?q=' + encodeURIComponent(qelem.value));
+        if (resp.ok) {
+          served = new Set(await resp.json());
+        }
+      }
+      for (const elem of elems) {
+        const path = elem.getAttribute('path');
+        const kinds = elem.getAttribute('kinds');
+        const name = elem.getAttribute('name');
+        const found = served
+          ? served.has(path)
+          : path && path.toLowerCase().includes(q) || kinds && kinds.toLowerCase().includes(q) || name && name.toLowerCase().includes(q);
+        if (found) {
+          res.push(elem);
+          elem.style.display = 'table-row';
+        } else {
+          elem.style.display = 'none';
+        }
+      }
+    }
+
+    function clearFilter() {
+      let qelem = document.getElementById('query');
+      let elems = document.getElementsByTagName('TR');
+      let res = [];
+      let q = qelem.value;
+      for (const elem of elems) {
+        elem.style.display = 'table-row';
+      }
+    }
+  </script>
+  </body>
+</html>";
+
+impl ProjectMatchesFormatter for HtmlProjectMatchesWriter {
+  fn write(
+    &self,
+    to: &mut dyn std::io::Write,
+    matches: &mut dyn Iterator<Item = &Project>,
+  ) -> crate::Result<()> {
+    let header = vec!["Language", "Name", "Path"]
+      .iter()
+      .map(|val| format!("<th>{}</th>", val))
+      .fold(String::new(), |mut prev, cur| {
+        if !prev.is_empty() {
+          prev.push('\n');
+        }
+        prev.push_str(&cur);
+        prev
+      });
+    let body = matches
+      .map(|proj| {
+        let kinds = escape_markup(
+          proj
+            .kinds()
+            .iter()
+            .map(|k| k.name())
+            .collect::<Vec<_>>()
+            .join(","),
+        );
+        let name = escape_markup(proj.name().unwrap_or_default());
+        let path = escape_markup(normalize_path(proj.path()));
+        let hash = match proj.manifest_hash() {
+          Some(hash) => format!(" manifest-hash=\"{}\"", escape_markup(hash)),
+          None => String::new(),
+        };
+        let size = match proj.disk_usage() {
+          Some(size) => format!(" disk-usage=\"{}\"", size),
+          None => String::new(),
+        };
+        let facets = proj
+          .facets()
+          .iter()
+          .map(|(name, value)| format!(" {}=\"{}\"", name, escape_markup(value.to_string())))
+          .collect::<String>();
+        format!(
+          "<tr path=\"{path}\" name=\"{name}\" kinds=\"{kinds}\"{hash}{size}{facets}><td>{}</td><td>{}</td><td>{}</td></tr>",
+          kinds, name, path
+        )
+      })
+      .fold(String::new(), |mut prev, cur| {
+        if !prev.is_empty() {
+          prev.push('\n');
+        }
+        prev.push_str(&cur);
+        prev
+      });
+    writeln!(
+      to,
+      "{}",
+      HTML_TEMPLATE
+        .replace("{{BODY}}", &body)
+        .replace("{{HEADER}}", &header)
+    )?;
+    Ok(())
+  }
+
+  fn write_table(&self, to: &mut dyn std::io::Write, table: &Table) -> crate::Result<()> {
+    let header = table
+      .columns()
+      .iter()
+      .map(|val| format!("<th>{}</th>", escape_markup(val)))
+      .collect::<Vec<_>>()
+      .join("\n");
+    let body = table
+      .rows()
+      .iter()
+      .map(|row| {
+        let cells = row
+          .iter()
+          .map(|cell| escape_markup(cell.to_string()))
+          .collect::<Vec<_>>();
+        // the filter script searches the `name` and `kinds` attributes of `tr[path]` rows
+        format!(
+          "<tr path=\"\" name=\"{}\" kinds=\"{}\">{}</tr>",
+          cells.first().cloned().unwrap_or_default(),
+          cells.join(" "),
+          cells
+            .iter()
+            .map(|cell| format!("<td>{}</td>", cell))
+            .collect::<String>()
+        )
+      })
+      .collect::<Vec<_>>()
+      .join("\n");
+    writeln!(
+      to,
+      "{}",
+      HTML_TEMPLATE
+        .replace(
+          "<title>Discovered projects</title>",
+          &format!("<title>{}</title>", escape_markup(table.title()))
+        )
+        .replace("{{BODY}}", &body)
+        .replace("{{HEADER}}", &header)
+    )?;
+    Ok(())
+  }
+}
@@ -0,0 +1,38 @@
+use serde_json::{Map, Value};
+
+use crate::{Cell, Project, ProjectMatchesFormatter, Table};
+
+/// The most basic project writer: a human readable list on stdout
+pub struct JsonProjectMatchesWriter {}
+
+impl ProjectMatchesFormatter for JsonProjectMatchesWriter {
+  fn write(
+    &self,
+    to: &mut dyn std::io::Write,
+    matches: &mut dyn Iterator<Item = &Project>,
+  ) -> crate::Result<()> {
+    let matches = matches.collect::<Vec<_>>();
+    write!(to, "{}", serde_json::to_string_pretty(&matches)?)?;
+    Ok(())
+  }
+
+  fn write_table(&self, to: &mut dyn std::io::Write, table: &Table) -> crate::Result<()> {
+    let keys = table.keys();
+    let rows = table
+      .rows()
+      .iter()
+      .map(|row| {
+        let fields = keys.iter().zip(row).map(|(key, cell)| {
+          let value = match cell {
+            Cell::Text(text) => Value::from(text.as_str()),
+            Cell::List(values) => Value::from(values.clone()),
+          };
+          (key.clone(), value)
+        });
+        Value::Object(fields.collect::<Map<_, _>>())
+      })
+      .collect::<Vec<_>>();
+    write!(to, "{}", serde_json::to_string_pretty(&rows)?)?;
+    Ok(())
+  }
+}
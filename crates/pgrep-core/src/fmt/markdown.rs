@@ -0,0 +1,98 @@
+use crate::{facet_names, human_size, normalize_path, Project, ProjectMatchesFormatter, Table};
+
+/// The most basic project writer: a human readable list on stdout
+pub struct MarkdownProjectMatchesWriter {}
+
+impl ProjectMatchesFormatter for MarkdownProjectMatchesWriter {
+  fn write(
+    &self,
+    to: &mut dyn std::io::Write,
+    matches: &mut dyn Iterator<Item = &Project>,
+  ) -> crate::Result<()> {
+    let matches = matches.collect::<Vec<_>>();
+    let hashed = matches.iter().any(|prj| prj.manifest_hash().is_some());
+    let sized = matches.iter().any(|prj| prj.disk_usage().is_some());
+    let facets = facet_names(&matches);
+    let mut columns = vec!["Language", "Name", "Path"];
+    if hashed {
+      columns.push("Manifest hash");
+    }
+    if sized {
+      columns.push("Disk usage");
+    }
+    columns.extend(&facets);
+    let mut table = Table::new("Projects", &columns);
+    for prj in matches {
+      let mut row = vec![
+        prj
+          .kinds()
+          .iter()
+          .map(|k| k.name())
+          .collect::<Vec<_>>()
+          .join(",")
+          .into(),
+        prj.name().unwrap_or_default().into(),
+        normalize_path(prj.path()).into(),
+      ];
+      if hashed {
+        row.push(prj.manifest_hash().cloned().unwrap_or_default().into());
+      }
+      if sized {
+        row.push(prj.disk_usage().map(human_size).unwrap_or_default().into());
+      }
+      for name in &facets {
+        row.push(
+          prj
+            .facet(name)
+            .map(|value| value.to_string())
+            .unwrap_or_default()
+            .into(),
+        );
+      }
+      table.push_row(row);
+    }
+    self.write_table(to, &table)
+  }
+
+  fn write_table(&self, to: &mut dyn std::io::Write, table: &Table) -> crate::Result<()> {
+    writeln!(to, "# {}", table.title())?;
+    writeln!(to, "")?;
+    // pipes would otherwise be interpreted as column separators
+    let escape = |cell: String| cell.replace('|', "\\|");
+    let mut rows = vec![table
+      .columns()
+      .iter()
+      .cloned()
+      .map(escape)
+      .collect::<Vec<_>>()];
+    for row in table.rows() {
+      rows.push(row.iter().map(|cell| escape(cell.to_string())).collect());
+    }
+    let mut widths = vec![0; table.columns().len()];
+    for row in &rows {
+      for (width, cell) in widths.iter_mut().zip(row) {
+        *width = (*width).max(cell.chars().count());
+      }
+    }
+    for (row_id, row) in rows.iter().enumerate() {
+      let cells = row
+        .iter()
+        .enumerate()
+        .map(|(cell_id, cell)| format!("{:0width$}", cell, width = widths[cell_id]))
+        .collect::<Vec<_>>();
+      writeln!(to, "| {} |", cells.join(" | "))?;
+      if row_id == 0 {
+        writeln!(
+          to,
+          "| {} |",
+          cells
+            .iter()
+            .map(|c| "-".repeat(c.chars().count()))
+            .collect::<Vec<_>>()
+            .join(" | ")
+        )?;
+      }
+    }
+    Ok(())
+  }
+}
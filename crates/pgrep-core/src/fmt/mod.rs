@@ -0,0 +1,380 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator, VariantNames};
+
+use crate::{i18n::tr_fmt, Error, Project};
+
+pub mod table;
+pub use table::{Cell, Table};
+
+#[cfg(feature = "csv")]
+pub mod csv;
+#[cfg(feature = "html")]
+pub mod html;
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "markdown")]
+pub mod markdown;
+#[cfg(feature = "text")]
+pub mod text;
+#[cfg(feature = "xml")]
+pub mod xml;
+
+#[cfg(not(any(
+  feature = "text",
+  feature = "json",
+  feature = "csv",
+  feature = "xml",
+  feature = "html",
+  feature = "markdown"
+)))]
+compile_error!(
+  "no output format enabled, enable at least one of the text, json, csv, xml, html or markdown \
+   features"
+);
+
+/// A project writer to support multiple output formats, shared by the threads of an app
+pub trait ProjectMatchesFormatter: Send + Sync {
+  /// Write the given projects to the output stream
+  ///
+  /// # Arguments
+  ///
+  /// * `to` - The output stream to write to
+  /// * `matches` - The projects to be written
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::{default_format, Project, ProjectKind};
+  ///
+  /// let projects = vec![Project::new("/dev/my_project", vec![ProjectKind::Rust], vec![], vec![])];
+  /// let mut out = vec![];
+  /// default_format().unwrap().write(&mut out, &mut projects.iter()).unwrap();
+  /// ```
+  fn write(
+    &self,
+    to: &mut dyn std::io::Write,
+    matches: &mut dyn Iterator<Item = &Project>,
+  ) -> crate::Result<()>;
+
+  /// Write generic tabular data to the output stream, such as the supported kinds
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::{default_format, Table};
+  ///
+  /// let mut table = Table::new("Formats", &["Name"]);
+  /// table.push_row(vec!["text".into()]);
+  /// let mut out = vec![];
+  /// default_format().unwrap().write_table(&mut out, &table).unwrap();
+  /// ```
+  fn write_table(&self, to: &mut dyn std::io::Write, table: &Table) -> crate::Result<()>;
+}
+
+/// A boxed [`ProjectMatchesFormatter`]
+pub type BoxedProjectMatchesFormatter = Box<dyn ProjectMatchesFormatter>;
+
+/// The output formats known to pgrep, whether or not their cargo feature is enabled.
+///
+/// Every variant is always declared so that a format left out of the build can be named, and
+/// reported with a helpful error by [`OutputFormat::formatter`] rather than rejected by clap.
+#[derive(
+  EnumIter,
+  EnumString,
+  VariantNames,
+  PartialEq,
+  Eq,
+  PartialOrd,
+  Ord,
+  Hash,
+  Debug,
+  Display,
+  Copy,
+  Clone,
+  Serialize,
+  Deserialize,
+)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+#[strum(ascii_case_insensitive)]
+pub enum OutputFormat {
+  #[strum(serialize = "text")]
+  #[cfg_attr(all(feature = "clap", not(feature = "text")), value(hide = true))]
+  Text,
+  #[strum(serialize = "json")]
+  #[cfg_attr(all(feature = "clap", not(feature = "json")), value(hide = true))]
+  Json,
+  #[strum(serialize = "csv")]
+  #[cfg_attr(all(feature = "clap", not(feature = "csv")), value(hide = true))]
+  Csv,
+  #[strum(serialize = "xml")]
+  #[cfg_attr(all(feature = "clap", not(feature = "xml")), value(hide = true))]
+  Xml,
+  #[strum(serialize = "html")]
+  #[cfg_attr(all(feature = "clap", not(feature = "html")), value(hide = true))]
+  Html,
+  #[strum(serialize = "markdown")]
+  #[cfg_attr(all(feature = "clap", not(feature = "markdown")), value(hide = true))]
+  Markdown,
+}
+
+impl OutputFormat {
+  /// Retrieve the cargo feature enabling this format
+  pub fn feature(&self) -> &'static str {
+    match self {
+      Self::Text => "text",
+      Self::Json => "json",
+      Self::Csv => "csv",
+      Self::Xml => "xml",
+      Self::Html => "html",
+      Self::Markdown => "markdown",
+    }
+  }
+
+  /// Retrieve the file extensions this format is inferred from, the first being the canonical one
+  pub fn extensions(&self) -> &'static [&'static str] {
+    match self {
+      Self::Text => &["txt", "text"],
+      Self::Json => &["json"],
+      Self::Csv => &["csv"],
+      Self::Xml => &["xml"],
+      Self::Html => &["html", "htm"],
+      Self::Markdown => &["md", "markdown"],
+    }
+  }
+
+  /// Find the format written to files with the given extension, ignoring case.
+  /// The format may not be compiled in, see [`OutputFormat::is_available`].
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::OutputFormat;
+  ///
+  /// assert_eq!(OutputFormat::from_extension("HTM"), Some(OutputFormat::Html));
+  /// assert_eq!(OutputFormat::from_extension("md"), Some(OutputFormat::Markdown));
+  /// assert_eq!(OutputFormat::from_extension("exe"), None);
+  /// ```
+  pub fn from_extension(ext: &str) -> Option<Self> {
+    Self::iter().find(|f| f.extensions().iter().any(|e| e.eq_ignore_ascii_case(ext)))
+  }
+
+  /// Find the format of the given file from its extension, see [`OutputFormat::from_extension`]
+  pub fn from_path<P: AsRef<std::path::Path>>(path: P) -> Option<Self> {
+    Self::from_extension(path.as_ref().extension()?.to_str()?)
+  }
+
+  /// Check whether this format was compiled in
+  pub fn is_available(&self) -> bool {
+    match self {
+      Self::Text => cfg!(feature = "text"),
+      Self::Json => cfg!(feature = "json"),
+      Self::Csv => cfg!(feature = "csv"),
+      Self::Xml => cfg!(feature = "xml"),
+      Self::Html => cfg!(feature = "html"),
+      Self::Markdown => cfg!(feature = "markdown"),
+    }
+  }
+
+  /// Fail with the list of compiled-in formats and the feature to enable,
+  /// if this format was left out of the build
+  pub fn ensure_available(&self) -> crate::Result<()> {
+    match self.is_available() {
+      true => Ok(()),
+      false => Err(self.unavailable()),
+    }
+  }
+
+  /// Build the error reported when this format was left out of the build
+  fn unavailable(&self) -> Error {
+    let available = Self::available()
+      .iter()
+      .map(|f| f.to_string())
+      .collect::<Vec<_>>();
+    let available = match available.is_empty() {
+      true => "-".to_string(),
+      false => available.join(", "),
+    };
+    Error::Format {
+      message: tr_fmt("fmt.unavailable", &[self, &available, &self.feature()]),
+      source: None,
+    }
+  }
+
+  /// Retrieve the compiled-in formats, in declaration order
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::OutputFormat;
+  ///
+  /// assert!(OutputFormat::available().iter().all(|f| f.is_available()));
+  /// ```
+  pub fn available() -> Vec<Self> {
+    Self::iter().filter(|f| f.is_available()).collect()
+  }
+
+  /// Retrieve the format used when none is selected: the first compiled-in one.
+  /// At least one format is always compiled in, the build fails otherwise.
+  pub fn preferred() -> Self {
+    Self::available().first().copied().unwrap_or(Self::Text)
+  }
+
+  /// Build the formatter of this format, failing with the list of compiled-in formats
+  /// and the feature to enable when it was left out of the build.
+  pub fn formatter(&self) -> crate::Result<BoxedProjectMatchesFormatter> {
+    match self {
+      #[cfg(feature = "text")]
+      Self::Text => Ok(Box::new(text::TextProjectMatchesWriter {})),
+      #[cfg(feature = "json")]
+      Self::Json => Ok(Box::new(json::JsonProjectMatchesWriter {})),
+      #[cfg(feature = "csv")]
+      Self::Csv => Ok(Box::new(csv::CsvProjectMatchesWriter {})),
+      #[cfg(feature = "xml")]
+      Self::Xml => Ok(Box::new(xml::XmlProjectMatchesWriter {})),
+      #[cfg(feature = "html")]
+      Self::Html => Ok(Box::new(html::HtmlProjectMatchesWriter {})),
+      #[cfg(feature = "markdown")]
+      Self::Markdown => Ok(Box::new(markdown::MarkdownProjectMatchesWriter {})),
+      #[allow(unreachable_patterns)]
+      _ => Err(self.unavailable()),
+    }
+  }
+}
+
+/// Retrieve the compiled-in formats and their [`ProjectMatchesFormatter`]
+pub fn supported_formats() -> Vec<(String, BoxedProjectMatchesFormatter)> {
+  OutputFormat::available()
+    .into_iter()
+    .filter_map(|fmt| Some((format!("{:?}", fmt), fmt.formatter().ok()?)))
+    .collect::<Vec<_>>()
+}
+
+/// Retrieve the [`ProjectMatchesFormatter`] of the [`OutputFormat::preferred`] format
+pub fn default_format() -> crate::Result<BoxedProjectMatchesFormatter> {
+  OutputFormat::preferred().formatter()
+}
+
+/// Retrieve the formatter of the format named `name`, ignoring case, or the default one if no
+/// such format exists. Fails if the format exists but wasn't compiled in.
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::get_format_or_default;
+///
+/// assert!(get_format_or_default("JSON").is_ok());
+/// assert!(get_format_or_default("unknown").is_ok());
+/// ```
+pub fn get_format_or_default<N: AsRef<str>>(
+  name: N,
+) -> crate::Result<BoxedProjectMatchesFormatter> {
+  match name.as_ref().parse::<OutputFormat>() {
+    Ok(format) => format.formatter(),
+    Err(_) => default_format(),
+  }
+}
+
+/// Escape the characters having a special meaning in XML and HTML documents,
+/// for use in both text nodes and attribute values.
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::escape_markup;
+///
+/// assert_eq!(escape_markup("<a href=\"x\">Tom & Jerry's</a>"), "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&apos;s&lt;/a&gt;");
+/// ```
+pub fn escape_markup<S: AsRef<str>>(s: S) -> String {
+  let mut ret = String::with_capacity(s.as_ref().len());
+  for ch in s.as_ref().chars() {
+    match ch {
+      '&' => ret.push_str("&amp;"),
+      '<' => ret.push_str("&lt;"),
+      '>' => ret.push_str("&gt;"),
+      '"' => ret.push_str("&quot;"),
+      '\'' => ret.push_str("&apos;"),
+      ch => ret.push(ch),
+    }
+  }
+  ret
+}
+
+/// Render a number of bytes with binary units, such as `1.5 MiB`
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::human_size;
+///
+/// assert_eq!(human_size(512), "512 B");
+/// assert_eq!(human_size(1536), "1.5 KiB");
+/// assert_eq!(human_size(3 * 1024 * 1024 * 1024), "3.0 GiB");
+/// ```
+pub fn human_size(bytes: u64) -> String {
+  const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+  if bytes < 1024 {
+    return format!("{} B", bytes);
+  }
+  let mut size = bytes as f64 / 1024.0;
+  let mut unit = 0;
+  while size >= 1024.0 && unit + 1 < UNITS.len() {
+    size /= 1024.0;
+    unit += 1;
+  }
+  format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Parse a number of bytes written with an optional binary unit, such as `512`, `64K`,
+/// `1.5 MiB` or `2G`, the reverse of [`human_size`]
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::parse_size;
+///
+/// assert_eq!(parse_size("512"), Ok(512));
+/// assert_eq!(parse_size("1.5 KiB"), Ok(1536));
+/// assert_eq!(parse_size("256M"), Ok(256 * 1024 * 1024));
+/// assert!(parse_size("big").is_err());
+/// ```
+pub fn parse_size(s: &str) -> Result<u64, String> {
+  let s = s.trim();
+  let split = s
+    .find(|ch: char| ch.is_ascii_alphabetic())
+    .unwrap_or(s.len());
+  let (count, unit) = s.split_at(split);
+  let shift = match unit.to_ascii_lowercase().as_str() {
+    "" | "b" => Some(0),
+    "k" | "kb" | "kib" => Some(10),
+    "m" | "mb" | "mib" => Some(20),
+    "g" | "gb" | "gib" => Some(30),
+    "t" | "tb" | "tib" => Some(40),
+    _ => None,
+  };
+  match (count.trim().parse::<f64>(), shift) {
+    (Ok(count), Some(shift)) if count >= 0.0 => Ok((count * (1u64 << shift) as f64) as u64),
+    _ => Err(format!(
+      "`{}` isn't a valid size, use a number of bytes with an optional unit such as 256MiB",
+      s
+    )),
+  }
+}
+
+/// Collect the names of the facets extracted from any of the `projects`, in name order, as
+/// the optional columns of tabular reports
+pub fn facet_names<'a>(projects: &[&'a Project]) -> Vec<&'a str> {
+  let names = projects.iter().flat_map(|prj| prj.facets().keys());
+  let names = names
+    .map(String::as_str)
+    .collect::<std::collections::BTreeSet<_>>();
+  names.into_iter().collect()
+}
+
+/// Retrieve all supported format names
+pub fn supported_format_names() -> Vec<String> {
+  supported_formats()
+    .iter()
+    .map(|(name, _)| name.clone())
+    .collect::<Vec<_>>()
+}
@@ -0,0 +1,97 @@
+use std::fmt::Display;
+
+/// A cell of a [`Table`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Cell {
+  /// A single value
+  Text(String),
+  /// Multiple values, rendered as arrays when the format supports them
+  List(Vec<String>),
+}
+
+impl Display for Cell {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Text(text) => write!(f, "{}", text),
+      Self::List(values) => write!(f, "{}", values.join(", ")),
+    }
+  }
+}
+
+impl From<String> for Cell {
+  fn from(value: String) -> Self {
+    Self::Text(value)
+  }
+}
+
+impl From<&str> for Cell {
+  fn from(value: &str) -> Self {
+    Self::Text(value.to_string())
+  }
+}
+
+impl From<Vec<String>> for Cell {
+  fn from(value: Vec<String>) -> Self {
+    Self::List(value)
+  }
+}
+
+/// Generic tabular data, written by [`crate::ProjectMatchesFormatter::write_table`]
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::Table;
+///
+/// let mut table = Table::new("Kinds", &["Name", "Project files"]);
+/// table.push_row(vec!["Rust".into(), vec!["Cargo.toml".to_string()].into()]);
+/// assert_eq!(table.keys(), vec!["name", "project_files"]);
+/// assert_eq!(table.rows()[0][1].to_string(), "Cargo.toml");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Table {
+  title: String,
+  columns: Vec<String>,
+  rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+  /// Create a new empty table
+  pub fn new<T: Into<String>>(title: T, columns: &[&str]) -> Self {
+    Self {
+      title: title.into(),
+      columns: columns.iter().map(|c| c.to_string()).collect(),
+      rows: vec![],
+    }
+  }
+
+  /// Append a row, holding one cell per column
+  pub fn push_row(&mut self, row: Vec<Cell>) {
+    debug_assert_eq!(row.len(), self.columns.len());
+    self.rows.push(row);
+  }
+
+  /// Retrieve the table title
+  pub fn title(&self) -> &String {
+    &self.title
+  }
+
+  /// Retrieve the human readable column names
+  pub fn columns(&self) -> &Vec<String> {
+    &self.columns
+  }
+
+  /// Retrieve the column identifiers, used as keys by structured formats
+  pub fn keys(&self) -> Vec<String> {
+    self
+      .columns
+      .iter()
+      .map(|c| c.to_lowercase().replace(' ', "_"))
+      .collect()
+  }
+
+  /// Retrieve the rows
+  pub fn rows(&self) -> &Vec<Vec<Cell>> {
+    &self.rows
+  }
+}
@@ -0,0 +1,73 @@
+use crate::{
+  human_size,
+  i18n::tr_fmt,
+  normalize_path,
+  style::{paint_styled, Style},
+  FacetValue, Project, ProjectMatchesFormatter, Table,
+};
+
+/// The most basic project writer: a human readable list on stdout.
+///
+/// Kinds and names are styled unless colors are disabled, the styles being removed by the
+/// [`crate::style::styled_writer`] written through when its destination has no colors.
+/// Projects moved since the previous scan are annotated with their previous path, and
+/// manifest hashes, disk usages and facets follow the paths when computed.
+pub struct TextProjectMatchesWriter {}
+
+impl ProjectMatchesFormatter for TextProjectMatchesWriter {
+  fn write(
+    &self,
+    to: &mut dyn std::io::Write,
+    matches: &mut dyn Iterator<Item = &Project>,
+  ) -> crate::Result<()> {
+    for prj in matches {
+      write!(
+        to,
+        "[{}] {} - {}",
+        paint_styled(
+          Style::Cyan,
+          prj
+            .kinds()
+            .iter()
+            .map(|k| k.name())
+            .collect::<Vec<_>>()
+            .join(", ")
+        ),
+        paint_styled(Style::Bold, prj.name().unwrap_or_default()),
+        normalize_path(prj.path())
+      )?;
+      if let Some(hash) = prj.manifest_hash() {
+        write!(to, " {}", paint_styled(Style::Dim, format!("#{}", hash)))?;
+      }
+      if let Some(size) = prj.disk_usage() {
+        write!(to, " {}", paint_styled(Style::Dim, human_size(size)))?;
+      }
+      for (name, value) in prj.facets() {
+        let facet = match value {
+          FacetValue::Bool(true) => name.clone(),
+          FacetValue::Bool(false) => continue,
+          value => format!("{}:{}", name, value.to_string().replace(", ", ",")),
+        };
+        write!(to, " {}", paint_styled(Style::Dim, facet))?;
+      }
+      if let Some(from) = prj.moved_from() {
+        let moved = tr_fmt("fmt.moved-from", &[&normalize_path(from)]);
+        write!(to, " {}", paint_styled(Style::Dim, format!("({})", moved)))?;
+      }
+      writeln!(to)?;
+    }
+    return Ok(());
+  }
+
+  fn write_table(&self, to: &mut dyn std::io::Write, table: &Table) -> crate::Result<()> {
+    for row in table.rows() {
+      let cells = row
+        .iter()
+        .map(|cell| cell.to_string())
+        .filter(|cell| !cell.is_empty())
+        .collect::<Vec<_>>();
+      writeln!(to, "{}", cells.join(" - "))?;
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,71 @@
+use crate::{escape_markup, normalize_path, Project, ProjectMatchesFormatter, Table};
+
+/// The most basic project writer: a human readable list on stdout
+pub struct XmlProjectMatchesWriter {}
+
+impl ProjectMatchesFormatter for XmlProjectMatchesWriter {
+  fn write(
+    &self,
+    to: &mut dyn std::io::Write,
+    matches: &mut dyn Iterator<Item = &Project>,
+  ) -> crate::Result<()> {
+    writeln!(
+      to,
+      "<?xml version = \"1.0\" encoding = \"UTF-8\" standalone = \"yes\" ?>"
+    )?;
+    writeln!(to, "<projects>")?;
+    for prj in matches {
+      let name = escape_markup(prj.name().unwrap_or_default());
+      let path = escape_markup(normalize_path(prj.path()));
+      let mut attrs = format!("name=\"{}\" path=\"{}\"", name, path);
+      if let Some(hash) = prj.manifest_hash() {
+        attrs.push_str(&format!(" manifest_hash=\"{}\"", escape_markup(hash)));
+      }
+      if let Some(size) = prj.disk_usage() {
+        attrs.push_str(&format!(" disk_usage=\"{}\"", size));
+      }
+      for (name, value) in prj.facets() {
+        attrs.push_str(&format!(
+          " {}=\"{}\"",
+          name,
+          escape_markup(value.to_string())
+        ));
+      }
+      if prj.kinds().len() == 1 {
+        writeln!(
+          to,
+          "\t<project {} kind=\"{}\"/>",
+          attrs,
+          escape_markup(prj.kinds()[0].name())
+        )?;
+      } else {
+        writeln!(to, "\t<project {}>", attrs)?;
+        for k in prj.kinds() {
+          writeln!(to, "\t\t<kind>{}</kind>", escape_markup(k.name()))?;
+        }
+        writeln!(to, "\t</project>")?;
+      }
+    }
+    writeln!(to, "</projects>")?;
+    Ok(())
+  }
+
+  fn write_table(&self, to: &mut dyn std::io::Write, table: &Table) -> crate::Result<()> {
+    writeln!(
+      to,
+      "<?xml version = \"1.0\" encoding = \"UTF-8\" standalone = \"yes\" ?>"
+    )?;
+    writeln!(to, "<table name=\"{}\">", escape_markup(table.title()))?;
+    let keys = table.keys();
+    for row in table.rows() {
+      let attrs = keys
+        .iter()
+        .zip(row)
+        .map(|(key, cell)| format!("{}=\"{}\"", key, escape_markup(cell.to_string())))
+        .collect::<Vec<_>>();
+      writeln!(to, "\t<row {}/>", attrs.join(" "))?;
+    }
+    writeln!(to, "</table>")?;
+    Ok(())
+  }
+}
@@ -0,0 +1,129 @@
+//! Git metadata of project folders, read through the `git` command-line.
+
+use std::{
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+use crate::{Error, Table};
+
+/// The state of the git work tree holding a project
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitStatus {
+  /// The project folder
+  pub path: PathBuf,
+  /// The checked out branch, `None` when detached
+  pub branch: Option<String>,
+  /// The branch tracked by the checked out one
+  pub upstream: Option<String>,
+  /// The number of local commits missing from the upstream branch
+  pub ahead: usize,
+  /// The number of upstream commits missing from the local branch
+  pub behind: usize,
+  /// The number of modified, staged, conflicting or untracked files
+  pub dirty: usize,
+  /// The number of stashed changes
+  pub stashes: usize,
+}
+
+impl GitStatus {
+  /// Read the status of the work tree holding `path`, `None` when it isn't in one
+  pub fn read<P: AsRef<Path>>(path: P) -> crate::Result<Option<Self>> {
+    let path = path.as_ref();
+    let Some(status) = git(path, &["status", "--porcelain=v2", "--branch"])? else {
+      return Ok(None);
+    };
+    let stashes = git(path, &["stash", "list"])?.unwrap_or_default();
+    let mut ret = Self::parse(&status);
+    ret.path = path.to_path_buf();
+    ret.stashes = stashes.lines().count();
+    Ok(Some(ret))
+  }
+
+  /// Parse the output of `git status --porcelain=v2 --branch`
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::GitStatus;
+  ///
+  /// let output = [
+  ///   "# branch.oid 1234",
+  ///   "# branch.head main",
+  ///   "# branch.upstream origin/main",
+  ///   "# branch.ab +2 -1",
+  ///   "1 .M N... 100644 100644 100644 12 34 src/main.rs",
+  ///   "? notes.txt",
+  /// ];
+  /// let status = GitStatus::parse(&output.join("\n"));
+  /// assert_eq!(status.branch.as_deref(), Some("main"));
+  /// assert_eq!((status.ahead, status.behind, status.dirty), (2, 1, 2));
+  /// ```
+  pub fn parse(status: &str) -> Self {
+    let mut ret = Self::default();
+    for line in status.lines() {
+      match line
+        .strip_prefix("# ")
+        .and_then(|header| header.split_once(' '))
+      {
+        Some(("branch.head", "(detached)")) => {}
+        Some(("branch.head", head)) => ret.branch = Some(head.to_string()),
+        Some(("branch.upstream", upstream)) => ret.upstream = Some(upstream.to_string()),
+        Some(("branch.ab", counts)) => {
+          for count in counts.split_whitespace() {
+            if let Some(ahead) = count.strip_prefix('+') {
+              ret.ahead = ahead.parse().unwrap_or_default();
+            } else if let Some(behind) = count.strip_prefix('-') {
+              ret.behind = behind.parse().unwrap_or_default();
+            }
+          }
+        }
+        Some(_) => {}
+        None if !line.starts_with('!') && !line.is_empty() => ret.dirty += 1,
+        None => {}
+      }
+    }
+    ret
+  }
+
+  /// Summarize the statuses as a table, one row per work tree
+  pub fn table(statuses: &[GitStatus]) -> Table {
+    let mut table = Table::new(
+      "Git status",
+      &[
+        "Path", "Branch", "Upstream", "Ahead", "Behind", "Dirty", "Stashes",
+      ],
+    );
+    for status in statuses {
+      table.push_row(vec![
+        status.path.display().to_string().into(),
+        status.branch.clone().unwrap_or_default().into(),
+        status.upstream.clone().unwrap_or_default().into(),
+        status.ahead.to_string().into(),
+        status.behind.to_string().into(),
+        status.dirty.to_string().into(),
+        status.stashes.to_string().into(),
+      ]);
+    }
+    table
+  }
+
+  /// Check whether the work tree has anything not pushed or not committed
+  pub fn needs_attention(&self) -> bool {
+    self.ahead > 0 || self.behind > 0 || self.dirty > 0 || self.stashes > 0
+  }
+}
+
+/// Run a git command in `path`, returning its output or `None` outside of a work tree
+fn git(path: &Path, args: &[&str]) -> crate::Result<Option<String>> {
+  let output = Command::new("git")
+    .arg("-C")
+    .arg(path)
+    .args(args)
+    .output()
+    .map_err(|e| Error::io("failed to run git", e))?;
+  match output.status.success() {
+    true => Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned())),
+    false => Ok(None),
+  }
+}
@@ -0,0 +1,352 @@
+use std::{
+  collections::BTreeMap,
+  path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone};
+use log::debug;
+use rmp_serde::{Deserializer, Serializer};
+use serde::{Deserialize, Serialize};
+
+use crate::{get_project_dirs, Error, Project, Table};
+
+/// The projects detected by a scan, and when it happened
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+  /// When the projects were detected
+  pub taken_at: DateTime<Local>,
+  /// The kind names of every project, by path
+  #[serde(with = "crate::path_serde::map")]
+  pub projects: BTreeMap<PathBuf, Vec<String>>,
+}
+
+impl Snapshot {
+  /// Take a snapshot of the given projects
+  pub fn new<'a, I: IntoIterator<Item = &'a Project>>(projects: I) -> Self {
+    Self {
+      taken_at: Local::now(),
+      projects: projects
+        .into_iter()
+        .map(|p| {
+          (
+            p.path().clone(),
+            p.kinds().iter().map(|k| k.name()).collect(),
+          )
+        })
+        .collect(),
+    }
+  }
+
+  /// Estimate the bytes this snapshot holds in memory, see [`Project::memory_usage`]
+  pub fn memory_usage(&self) -> usize {
+    let entry = |(path, kinds): (&PathBuf, &Vec<String>)| {
+      std::mem::size_of::<(PathBuf, Vec<String>)>()
+        + path.capacity()
+        + kinds
+          .iter()
+          .map(|kind| std::mem::size_of::<String>() + kind.capacity())
+          .sum::<usize>()
+    };
+    std::mem::size_of::<Self>() + self.projects.iter().map(entry).sum::<usize>()
+  }
+
+  /// Compute the changes from this snapshot to the `newer` one
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::{Change, Project, ProjectKind, Snapshot};
+  ///
+  /// let old = Snapshot::new(&[
+  ///   Project::new("/dev/alpha", vec![ProjectKind::Rust], vec![], vec![]),
+  ///   Project::new("/dev/beta", vec![ProjectKind::Go], vec![], vec![]),
+  /// ]);
+  /// let new = Snapshot::new(&[
+  ///   Project::new("/dev/beta", vec![ProjectKind::Go, ProjectKind::Node], vec![], vec![]),
+  ///   Project::new("/dev/gamma", vec![ProjectKind::C], vec![], vec![]),
+  /// ]);
+  /// let changes = old.diff(&new);
+  /// assert_eq!(changes.len(), 3);
+  /// assert!(matches!(changes[0], Change::Removed { .. }));
+  /// assert!(matches!(changes[1], Change::KindsChanged { .. }));
+  /// assert!(matches!(changes[2], Change::Added { .. }));
+  /// ```
+  pub fn diff(&self, newer: &Snapshot) -> Vec<Change> {
+    let mut ret = vec![];
+    for (path, kinds) in &self.projects {
+      match newer.projects.get(path) {
+        None => ret.push(Change::Removed {
+          path: path.clone(),
+          kinds: kinds.clone(),
+        }),
+        Some(new_kinds) if new_kinds != kinds => ret.push(Change::KindsChanged {
+          path: path.clone(),
+          old: kinds.clone(),
+          new: new_kinds.clone(),
+        }),
+        Some(_) => {}
+      }
+    }
+    for (path, kinds) in &newer.projects {
+      if !self.projects.contains_key(path) {
+        ret.push(Change::Added {
+          path: path.clone(),
+          kinds: kinds.clone(),
+        });
+      }
+    }
+    ret.sort_by(|a, b| a.path().cmp(b.path()));
+    ret
+  }
+}
+
+/// A difference between two [`Snapshot`]s
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+  /// A project appeared
+  Added { path: PathBuf, kinds: Vec<String> },
+  /// A project disappeared
+  Removed { path: PathBuf, kinds: Vec<String> },
+  /// A project kinds changed
+  KindsChanged {
+    path: PathBuf,
+    old: Vec<String>,
+    new: Vec<String>,
+  },
+}
+
+impl Change {
+  /// Retrieve the path of the changed project
+  pub fn path(&self) -> &PathBuf {
+    match self {
+      Self::Added { path, .. } | Self::Removed { path, .. } | Self::KindsChanged { path, .. } => {
+        path
+      }
+    }
+  }
+
+  /// Describe the changes as a table, with the previous and current kinds of every project
+  pub fn table(changes: &[Change]) -> Table {
+    let mut table = Table::new("Changes", &["Change", "Path", "Old kinds", "New kinds"]);
+    for change in changes {
+      let (name, old, new) = match change {
+        Self::Added { kinds, .. } => ("added", vec![], kinds.clone()),
+        Self::Removed { kinds, .. } => ("removed", kinds.clone(), vec![]),
+        Self::KindsChanged { old, new, .. } => ("changed", old.clone(), new.clone()),
+      };
+      table.push_row(vec![
+        name.into(),
+        change.path().display().to_string().into(),
+        old.into(),
+        new.into(),
+      ]);
+    }
+    table
+  }
+}
+
+/// The [`Snapshot`]s of the latest scans, kept as a ring buffer of files in a folder
+pub struct History {
+  dir: PathBuf,
+  capacity: usize,
+}
+
+impl History {
+  /// The number of snapshots kept by default
+  pub const DEFAULT_CAPACITY: usize = 100;
+  /// The snapshot files extension
+  pub const SNAPSHOT_EXT: &'static str = "bin";
+
+  /// Open the history stored in `dir`, keeping at most `capacity` snapshots
+  pub fn new<P: AsRef<Path>>(dir: P, capacity: usize) -> Self {
+    Self {
+      dir: dir.as_ref().to_path_buf(),
+      capacity,
+    }
+  }
+
+  /// Open the history stored in the user data folder, or in a temporary folder when the
+  /// platform has none
+  pub fn open() -> Self {
+    let dir = match get_project_dirs() {
+      Some(dirs) => dirs.data_dir().join("history"),
+      None => std::env::temp_dir().join(format!("{}-history", crate::APP_APPLICATION)),
+    };
+    Self::new(dir, Self::DEFAULT_CAPACITY)
+  }
+
+  /// List the snapshot files, oldest first
+  fn files(&self) -> crate::Result<Vec<PathBuf>> {
+    if !self.dir.exists() {
+      return Ok(vec![]);
+    }
+    let mut ret = vec![];
+    for entry in std::fs::read_dir(&self.dir)? {
+      let path = entry?.path();
+      if path
+        .extension()
+        .is_some_and(|ext| ext == Self::SNAPSHOT_EXT)
+      {
+        ret.push(path);
+      }
+    }
+    // names are zero-padded timestamps
+    ret.sort();
+    Ok(ret)
+  }
+
+  /// Read a snapshot file
+  fn read(path: &Path) -> crate::Result<Snapshot> {
+    let content = std::fs::read(path)
+      .map_err(|e| Error::io(format!("cannot read snapshot '{}'", path.display()), e))?;
+    Snapshot::deserialize(&mut Deserializer::new(content.as_slice())).map_err(|e| Error::IO {
+      message: format!("cannot deserialize snapshot '{}'", path.display()),
+      source: Some(Box::new(e)),
+    })
+  }
+
+  /// Retrieve the latest snapshot
+  pub fn latest(&self) -> crate::Result<Option<Snapshot>> {
+    match self.files()?.last() {
+      Some(path) => Ok(Some(Self::read(path)?)),
+      None => Ok(None),
+    }
+  }
+
+  /// Retrieve the latest snapshot taken at or before `time`, or the oldest one if they were all
+  /// taken after it
+  pub fn at(&self, time: DateTime<Local>) -> crate::Result<Option<Snapshot>> {
+    let mut ret = None;
+    for path in self.files()? {
+      let snapshot = Self::read(&path)?;
+      if ret.is_some() && snapshot.taken_at > time {
+        break;
+      }
+      ret = Some(snapshot);
+    }
+    Ok(ret)
+  }
+
+  /// Record a snapshot unless the projects didn't change since the latest one, dropping the
+  /// oldest snapshots beyond the capacity. Returns whether the snapshot was recorded.
+  pub fn record(&self, snapshot: &Snapshot) -> crate::Result<bool> {
+    if self
+      .latest()?
+      .is_some_and(|latest| latest.projects == snapshot.projects)
+    {
+      return Ok(false);
+    }
+    std::fs::create_dir_all(&self.dir)?;
+    let mut buf = vec![];
+    snapshot
+      .serialize(&mut Serializer::new(&mut buf))
+      .map_err(|e| Error::IO {
+        message: "cannot serialize snapshot".to_string(),
+        source: Some(Box::new(e)),
+      })?;
+    let name = format!(
+      "{:020}.{}",
+      snapshot.taken_at.timestamp_micros(),
+      Self::SNAPSHOT_EXT
+    );
+    let path = self.dir.join(name);
+    std::fs::write(&path, buf)
+      .map_err(|e| Error::io(format!("cannot write snapshot '{}'", path.display()), e))?;
+    debug!("recorded snapshot '{}'", path.display());
+    let files = self.files()?;
+    for old in files.iter().take(files.len().saturating_sub(self.capacity)) {
+      debug!("dropping snapshot '{}'", old.display());
+      std::fs::remove_file(old)?;
+    }
+    Ok(true)
+  }
+}
+
+/// Parse a point in time relative to `now`: `now`, `today`, `yesterday`, a duration such as
+/// `30m`, `12h`, `2d` or `1w`, a `YYYY-MM-DD` date or an RFC 3339 date and time.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{Duration, Local};
+/// use pgrep_core::parse_since;
+///
+/// let now = Local::now();
+/// assert_eq!(parse_since("2d", now), Ok(now - Duration::days(2)));
+/// assert!(parse_since("yesterday", now).unwrap() < now - Duration::days(1));
+/// assert!(parse_since("2024-01-31", now).is_ok());
+/// assert!(parse_since("someday", now).is_err());
+/// ```
+pub fn parse_since(s: &str, now: DateTime<Local>) -> Result<DateTime<Local>, String> {
+  let midnight = |date: NaiveDate| {
+    Local
+      .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+      .earliest()
+      .ok_or_else(|| format!("'{}' doesn't exist in the local timezone", date))
+  };
+  match s.trim() {
+    "now" => return Ok(now),
+    "today" => return midnight(now.date_naive()),
+    "yesterday" => return midnight(now.date_naive() - Duration::days(1)),
+    _ => {}
+  }
+  if let Some((unit_id, _)) = s.char_indices().last() {
+    let (count, unit) = s.split_at(unit_id);
+    if let Ok(count) = count.parse::<i64>() {
+      let duration = match unit {
+        "m" => Some(Duration::minutes(count)),
+        "h" => Some(Duration::hours(count)),
+        "d" => Some(Duration::days(count)),
+        "w" => Some(Duration::weeks(count)),
+        _ => None,
+      };
+      if let Some(duration) = duration {
+        return Ok(now - duration);
+      }
+    }
+  }
+  if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+    return midnight(date);
+  }
+  DateTime::parse_from_rfc3339(s)
+    .map(|time| time.with_timezone(&Local))
+    .map_err(|_| {
+      format!(
+        "`{}` isn't a valid time, use now, today, yesterday, a duration such as 2d, \
+         or a date such as 2024-01-31",
+        s
+      )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::{Duration, Local};
+
+  use super::{History, Snapshot};
+  use crate::{Project, ProjectKind};
+
+  #[test]
+  fn history_is_a_ring_buffer() {
+    let dir = std::env::temp_dir().join(format!("pgrep-history-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let history = History::new(&dir, 2);
+    let start = Local::now();
+    for id in 0..4 {
+      let path = format!("/dev/project-{}", id);
+      let mut snapshot =
+        Snapshot::new(&[Project::new(path, vec![ProjectKind::Rust], vec![], vec![])]);
+      snapshot.taken_at = start + Duration::minutes(id);
+      assert!(history.record(&snapshot).unwrap());
+      // unchanged projects aren't recorded twice
+      assert!(!history.record(&snapshot).unwrap());
+    }
+    assert_eq!(history.files().unwrap().len(), 2);
+    let latest = history.latest().unwrap().unwrap();
+    assert_eq!(latest.taken_at, start + Duration::minutes(3));
+    // the oldest kept snapshot is returned for earlier times
+    let oldest = history.at(start).unwrap().unwrap();
+    assert_eq!(oldest.taken_at, start + Duration::minutes(2));
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
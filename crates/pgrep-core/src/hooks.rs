@@ -0,0 +1,211 @@
+use std::{path::Path, process::Command};
+
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(windows))]
+use crate::ssh::shell_quote;
+use crate::{i18n::tr_fmt, Change};
+
+/// The shell commands run by `pgrep watch` when projects appear or disappear
+///
+/// Commands are templates: `{name}`, `{path}` and `{kinds}` are replaced by the project fields,
+/// quoted for the shell, see [`render_hook`]. The fields are also given to the command as the
+/// `PGREP_PROJECT_NAME`, `PGREP_PROJECT_PATH` and `PGREP_PROJECT_KINDS` environment variables.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HooksConfig {
+  /// The command run for every new project
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub on_new_project: Option<String>,
+  /// The command run for every removed project
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub on_removed_project: Option<String>,
+}
+
+impl HooksConfig {
+  /// Check whether no hook is configured
+  pub fn is_empty(&self) -> bool {
+    self.on_new_project.is_none() && self.on_removed_project.is_none()
+  }
+
+  /// Run the hooks matching each change, in order. Failing hooks are reported as warnings
+  pub fn run(&self, changes: &[Change]) {
+    for change in changes {
+      let (template, kinds) = match change {
+        Change::Added { kinds, .. } => (&self.on_new_project, kinds),
+        Change::Removed { kinds, .. } => (&self.on_removed_project, kinds),
+        Change::KindsChanged { .. } => continue,
+      };
+      if let Some(template) = template {
+        if let Err(e) = run_hook(template, change.path(), kinds) {
+          warn!("{}", tr_fmt("app.hook-failed", &[template, &e]));
+        }
+      }
+    }
+  }
+}
+
+/// Replace the `{name}`, `{path}` and `{kinds}` placeholders of a hook command.
+///
+/// The fields are quoted according to where they appear, so that they are never run as shell
+/// syntax: unquoted placeholders are single-quoted, and the quotes and escapes of placeholders
+/// inside quotes are escaped. On Windows, where `cmd` cannot escape every character, they are
+/// replaced by references to the `PGREP_PROJECT_*` environment variables instead.
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::render_hook;
+///
+/// let kinds = vec!["Rust".to_string(), "Node".to_string()];
+/// # #[cfg(unix)]
+/// assert_eq!(
+///   render_hook("notify-send 'New project {name}' {kinds}", "/dev/alpha", &kinds),
+///   "notify-send 'New project alpha' 'Rust, Node'"
+/// );
+/// # #[cfg(unix)]
+/// assert_eq!(render_hook("cd {path}", "/dev/it's", &kinds), r"cd '/dev/it'\''s'");
+/// ```
+pub fn render_hook<P: AsRef<Path>>(template: &str, path: P, kinds: &[String]) -> String {
+  let (name, path, kinds) = hook_fields(path.as_ref(), kinds);
+  let fields = [
+    ("{name}", "PGREP_PROJECT_NAME", name),
+    ("{path}", "PGREP_PROJECT_PATH", path),
+    ("{kinds}", "PGREP_PROJECT_KINDS", kinds),
+  ];
+  let mut ret = String::new();
+  let mut quote = None;
+  let mut chars = template.char_indices();
+  while let Some((pos, c)) = chars.next() {
+    let field = fields
+      .iter()
+      .find(|(placeholder, ..)| template[pos..].starts_with(placeholder));
+    if let Some((placeholder, var, value)) = field {
+      ret.push_str(&quote_field(value, var, quote));
+      chars.nth(placeholder.len() - 2);
+      continue;
+    }
+    ret.push(c);
+    match (quote, c) {
+      (None, c) if SHELL_QUOTES.contains(c) => quote = Some(c),
+      (Some(open), c) if open == c => quote = None,
+      // the escaped character is copied as is, cmd has no escapes between quotes
+      (None, SHELL_ESCAPE) => ret.extend(chars.next().map(|(_, c)| c)),
+      (Some('"'), SHELL_ESCAPE) if cfg!(not(windows)) => ret.extend(chars.next().map(|(_, c)| c)),
+      _ => {}
+    }
+  }
+  ret
+}
+
+/// The quotes of the platform shell
+#[cfg(not(windows))]
+const SHELL_QUOTES: &str = "'\"";
+#[cfg(windows)]
+const SHELL_QUOTES: &str = "\"";
+
+/// The escape character of the platform shell
+#[cfg(not(windows))]
+const SHELL_ESCAPE: char = '\\';
+#[cfg(windows)]
+const SHELL_ESCAPE: char = '^';
+
+/// Quote the `value` of the field given as the `var` environment variable, for a placeholder
+/// inside the `quote` of the command or outside of any
+#[cfg(not(windows))]
+fn quote_field(value: &str, _var: &str, quote: Option<char>) -> String {
+  match quote {
+    None => shell_quote(value),
+    Some('\'') => value.replace('\'', "'\\''"),
+    Some(_) => value.chars().fold(String::new(), |mut ret, c| {
+      if "\"\\$`".contains(c) {
+        ret.push('\\');
+      }
+      ret.push(c);
+      ret
+    }),
+  }
+}
+
+/// Quote the `value` of the field given as the `var` environment variable, for a placeholder
+/// inside the `quote` of the command or outside of any
+#[cfg(windows)]
+fn quote_field(_value: &str, var: &str, quote: Option<char>) -> String {
+  // the variables are expanded once, their values are not parsed again
+  match quote {
+    None => format!("\"%{}%\"", var),
+    Some(_) => format!("%{}%", var),
+  }
+}
+
+/// Retrieve the name, path and kinds of a project as given to hooks
+fn hook_fields(path: &Path, kinds: &[String]) -> (String, String, String) {
+  let name = path
+    .file_name()
+    .map(|name| name.to_string_lossy().to_string())
+    .unwrap_or_default();
+  (name, path.display().to_string(), kinds.join(", "))
+}
+
+/// Run a hook command through the platform shell, waiting for it to exit
+fn run_hook(template: &str, path: &Path, kinds: &[String]) -> std::io::Result<()> {
+  let command = render_hook(template, path, kinds);
+  debug!("running hook `{}`", command);
+  let status = shell_command(&command, path, kinds).status()?;
+  match status.success() {
+    true => Ok(()),
+    false => Err(std::io::Error::other(status.to_string())),
+  }
+}
+
+/// Prepare a command run through the platform shell, given the project fields as environment
+/// variables
+pub(crate) fn shell_command(command: &str, path: &Path, kinds: &[String]) -> Command {
+  let (name, path, kinds) = hook_fields(path, kinds);
+  let mut cmd = platform_shell(command);
+  cmd
+    .env("PGREP_PROJECT_NAME", name)
+    .env("PGREP_PROJECT_PATH", path)
+    .env("PGREP_PROJECT_KINDS", kinds);
+  cmd
+}
+
+/// Prepare a command run through the platform shell
+pub(crate) fn platform_shell(command: &str) -> Command {
+  #[cfg(windows)]
+  let (shell, flag) = ("cmd", "/C");
+  #[cfg(not(windows))]
+  let (shell, flag) = ("sh", "-c");
+  let mut cmd = Command::new(shell);
+  cmd.arg(flag).arg(command);
+  cmd
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::Path;
+
+  use super::{render_hook, shell_command};
+
+  #[test]
+  #[cfg(unix)]
+  fn fields_are_never_run_as_shell_syntax() {
+    let path = Path::new("/dev/it's $(touch pwned); `id` \\\"x\" & y");
+    let name = path.file_name().unwrap().to_str().unwrap();
+    for template in [
+      "printf %s {name}",
+      "printf %s '{name}'",
+      "printf %s \"{name}\"",
+    ] {
+      let command = render_hook(template, path, &[]);
+      let output = shell_command(&command, path, &[]).output().unwrap();
+      assert_eq!(String::from_utf8_lossy(&output.stdout), name, "{}", command);
+    }
+    let command = render_hook("printf %s \\'{path}\\'", path, &[]);
+    let output = shell_command(&command, path, &[]).output().unwrap();
+    assert_eq!(
+      String::from_utf8_lossy(&output.stdout),
+      format!("'{}'", path.display())
+    );
+  }
+}
@@ -0,0 +1,1025 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use strum::EnumString;
+
+/// The languages user-facing strings are translated to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumString)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum Lang {
+  #[default]
+  En,
+  Fr,
+}
+
+impl Lang {
+  /// Detect the language from the `LC_ALL`, `LC_MESSAGES` and `LANG` environment variables,
+  /// in that order, defaulting to [`Lang::En`].
+  pub fn from_env() -> Self {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+      .iter()
+      .filter_map(|var| std::env::var(var).ok())
+      .find(|value| !value.is_empty())
+      .and_then(|value| Self::from_locale(&value))
+      .unwrap_or_default()
+  }
+
+  /// Detect the language from a `--lang` command-line argument, before the command-line is
+  /// parsed so that its help can be translated, then from the environment.
+  pub fn detect<I: IntoIterator<Item = String>>(args: I) -> Self {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+      let value = match arg.strip_prefix("--lang") {
+        Some("") => args.next(),
+        Some(value) => value.strip_prefix('=').map(|v| v.to_string()),
+        None => continue,
+      };
+      if let Some(lang) = value.and_then(|v| v.parse().ok()) {
+        return lang;
+      }
+    }
+    Self::from_env()
+  }
+
+  /// Parse a POSIX locale name, such as `fr_FR.UTF-8`
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::i18n::Lang;
+  ///
+  /// assert_eq!(Lang::from_locale("fr_FR.UTF-8"), Some(Lang::Fr));
+  /// assert_eq!(Lang::from_locale("C"), Some(Lang::En));
+  /// assert_eq!(Lang::from_locale("tlh"), None);
+  /// ```
+  pub fn from_locale(locale: &str) -> Option<Self> {
+    let code = locale.split(['_', '.', '@', '-']).next()?.to_lowercase();
+    match code.as_str() {
+      "c" | "posix" => Some(Self::En),
+      code => code.parse().ok(),
+    }
+  }
+
+  /// Retrieve the message catalog of this language
+  fn catalog(&self) -> &'static [(&'static str, &'static str)] {
+    match self {
+      Self::En => EN,
+      Self::Fr => FR,
+    }
+  }
+}
+
+/// The selected [`Lang`], stored as its discriminant
+static LANG: AtomicU8 = AtomicU8::new(Lang::En as u8);
+
+/// Select the language of user-facing strings, for the whole process
+pub fn set_lang(lang: Lang) {
+  LANG.store(lang as u8, Ordering::Relaxed);
+}
+
+/// Retrieve the selected [`Lang`]
+pub fn lang() -> Lang {
+  match LANG.load(Ordering::Relaxed) {
+    x if x == Lang::Fr as u8 => Lang::Fr,
+    _ => Lang::En,
+  }
+}
+
+/// Retrieve the translation of `key` in the selected language, if there is one
+pub fn lookup(key: &str) -> Option<&'static str> {
+  lookup_in(lang(), key)
+}
+
+/// Retrieve the translation of `key` in `lang`, if there is one
+pub fn lookup_in(lang: Lang, key: &str) -> Option<&'static str> {
+  lang
+    .catalog()
+    .iter()
+    .find(|(k, _)| *k == key)
+    .map(|(_, msg)| *msg)
+}
+
+/// Translate `key` in the selected language, falling back to english then to the key itself
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::i18n::{set_lang, tr, Lang};
+///
+/// set_lang(Lang::Fr);
+/// assert_eq!(tr("tui.details"), "Détails");
+/// assert_eq!(tr("missing.key"), "missing.key");
+/// ```
+pub fn tr(key: &'static str) -> &'static str {
+  lookup(key)
+    .or_else(|| EN.iter().find(|(k, _)| *k == key).map(|(_, msg)| *msg))
+    .unwrap_or(key)
+}
+
+/// Translate `key` then replace its `{}` placeholders with `args`, in order
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::i18n::{set_lang, tr_fmt, Lang};
+///
+/// set_lang(Lang::En);
+/// assert_eq!(tr_fmt("tui.projects", &[&3]), "Projects (3)");
+/// ```
+pub fn tr_fmt(key: &'static str, args: &[&dyn std::fmt::Display]) -> String {
+  let mut parts = tr(key).split("{}");
+  let mut ret = parts.next().unwrap_or_default().to_string();
+  for (id, part) in parts.enumerate() {
+    if let Some(arg) = args.get(id) {
+      ret.push_str(&arg.to_string());
+    }
+    ret.push_str(part);
+  }
+  ret
+}
+
+/// The english catalog, holding every runtime message
+const EN: &[(&str, &str)] = &[
+  ("error.fatal", "fatal"),
+  ("error.caused-by", "Caused by"),
+  ("error.kind.no-match", "No match"),
+  ("error.kind.config", "Configuration"),
+  ("error.kind.query", "Query"),
+  ("error.kind.scan", "Scan"),
+  ("error.kind.cache", "Cache"),
+  ("error.kind.format", "Format"),
+  ("error.kind.io", "I/O"),
+  ("error.kind.unknown", "Unknown"),
+  ("error.kind.interrupted", "Interrupted"),
+  ("error.kind.exec", "Exec"),
+  (
+    "app.exec-no-match",
+    "no project matched, no command was run",
+  ),
+  ("app.exec-failed", "{} of {} commands failed"),
+  (
+    "app.git-no-repository",
+    "no matching project is in a git repository",
+  ),
+  (
+    "app.archive-no-match",
+    "no project matches '{}', nothing to archive",
+  ),
+  (
+    "app.archive-dry-run",
+    "dry run, add --apply to archive these {} projects",
+  ),
+  (
+    "app.audit-no-match",
+    "no matching project is of a kind which can be audited",
+  ),
+  (
+    "app.audit-summary",
+    "{} of {} audited projects have outdated dependencies",
+  ),
+  ("app.audit-failed", "{} of {} audits failed"),
+  (
+    "app.clean-no-match",
+    "no project matching '{}' has build artifacts to clean",
+  ),
+  (
+    "app.clean-confirm",
+    "remove the build artifacts of these {} projects? [y/N]",
+  ),
+  ("app.clean-cancelled", "cancelled, nothing was cleaned"),
+  ("app.clean-freed", "freed {} in {} projects"),
+  ("app.clean-failed", "{} of {} projects failed to be cleaned"),
+  (
+    "app.unknown-kind",
+    "unknown project kind '{}', see `pgrep list-kinds`",
+  ),
+  ("app.project-created", "created {} project '{}'"),
+  (
+    "app.project-outside-folders",
+    "'{}' isn't under a code folder, add one with -F to find it",
+  ),
+  (
+    "app.no-editor",
+    "no editor to open the project, set --editor or EDITOR",
+  ),
+  (
+    "app.no-clone-dir",
+    "'{}' is in a remote folder, set `clone_dir` in the [general] configuration to clone it \
+     when opened",
+  ),
+  ("app.cloning", "cloning '{}' into '{}'"),
+  (
+    "app.unknown-workspace",
+    "no workspace is named '{}', configured workspaces: {}",
+  ),
+  (
+    "app.workspace-member",
+    "invalid member '{}' of workspace '{}'",
+  ),
+  (
+    "app.workspace-no-member",
+    "member '{}' of workspace '{}' matches no project",
+  ),
+  (
+    "app.workspace-empty",
+    "workspace '{}' has no member project",
+  ),
+  (
+    "app.rescan-unknown",
+    "'{}' isn't in a configured code folder, it cannot be rescanned",
+  ),
+  (
+    "app.invalid-container",
+    "invalid container folder '{}', expected NAME:/PATH",
+  ),
+  (
+    "app.subcommand-query",
+    "'{}' is a subcommand, run `pgrep {} --help` for its usage or `pgrep -- {}` to search the \
+     projects named '{}'",
+  ),
+  ("error.interrupted", "interrupted while scanning '{}'"),
+  ("error.unreadable", "cannot read"),
+  (
+    "app.no-folders",
+    "No source code folders configured. use -F/--folder to specify one or more.",
+  ),
+  (
+    "app.list-with-query",
+    "Query given with --list but the two options are mutually exclusive!",
+  ),
+  (
+    "app.missing-query",
+    "no query given, and no default one configured as `query` in the [general] section",
+  ),
+  (
+    "app.no-project",
+    "no project root discovered for {} dirs:\n{}",
+  ),
+  (
+    "app.no-match",
+    "no match found for query '{}' in {} projects",
+  ),
+  ("app.no-match-any", "no match found for query '{}'"),
+  (
+    "app.scan-limit",
+    "'{}' was not fully scanned, it exceeds the `{}` configuration limit",
+  ),
+  ("app.cache-gc", "removed {} stale cache entries"),
+  ("app.scanning", "scanning, {} files found..."),
+  (
+    "app.memory-cap",
+    "the projects use {} of memory even without their source files, above the {} memory cap",
+  ),
+  (
+    "app.no-history",
+    "no previous scan recorded, run a search first",
+  ),
+  ("app.hook-failed", "hook `{}` failed: {}"),
+  ("scaffold.exists", "'{}' already exists"),
+  (
+    "archive.exists",
+    "'{}' already exists in the archive folder, nothing was archived",
+  ),
+  (
+    "archive.same-target",
+    "'{}' and '{}' would both be archived to '{}', nothing was archived",
+  ),
+  (
+    "archive.not-a-folder",
+    "cannot archive '{}', it isn't a project folder",
+  ),
+  ("archive.tar-failed", "failed to compress '{}': {}"),
+  ("clean.failed", "`{}` failed: {}"),
+  ("audit.failed", "`{}` failed: {}"),
+  (
+    "audit.timed-out",
+    "`{}` was killed after running for {} seconds",
+  ),
+  ("audit.invalid-output", "unexpected output of `{}`: {}"),
+  (
+    "scaffold.no-template-dir",
+    "the template folder '{}' doesn't exist",
+  ),
+  (
+    "scaffold.no-template",
+    "no template is configured for {} projects, see `[templates]`",
+  ),
+  ("scaffold.failed", "`{}` failed: {}"),
+  ("app.interrupted", "interrupted"),
+  (
+    "app.no-interrupt-handler",
+    "Ctrl-C won't keep the cache entries stored so far: {}",
+  ),
+  ("app.no-duplicates", "no projects share the same manifest"),
+  (
+    "app.no-pinned-match",
+    "none of the {} matching projects is pinned",
+  ),
+  (
+    "app.no-facet-match",
+    "none of the {} matching projects has the {} facet",
+  ),
+  ("app.recalled-query", "recalled query '{}'"),
+  ("app.pinned", "pinned '{}'"),
+  ("app.unpinned", "unpinned '{}'"),
+  ("app.not-pinned", "'{}' isn't pinned"),
+  ("app.no-note", "'{}' has no note"),
+  ("app.note-removed", "removed the note of '{}'"),
+  (
+    "app.note-not-a-folder",
+    "cannot attach a note to '{}', it isn't a folder",
+  ),
+  (
+    "app.post-retry",
+    "request to '{}' failed ({}), retrying in {}s",
+  ),
+  (
+    "app.unknown-extension",
+    "cannot infer the format of '{}' from its extension, writing {} instead",
+  ),
+  (
+    "fmt.unavailable",
+    "the '{}' format is not available in this build (available formats: {}), rebuild with \
+     `--features {}` to enable it",
+  ),
+  ("fmt.moved-from", "moved from {}"),
+  ("cache.unusable", "cannot use the cache folder '{}', {}"),
+  (
+    "cache.in-memory",
+    "no usable cache folder, nothing will be cached",
+  ),
+  (
+    "config.unwritable",
+    "cannot write the configuration to '{}', {}",
+  ),
+  (
+    "config.missing-bookmark",
+    "bookmarked directory '{}' not found, skipped",
+  ),
+  (
+    "config.missing-include",
+    "included configuration '{}' not found, skipped",
+  ),
+  (
+    "config.discovery-unavailable",
+    "the '{}' discovery is not available in this build, rebuild with `--features {}` to enable it",
+  ),
+  (
+    "clone.local",
+    "'{}' is a local project, there is nothing to clone",
+  ),
+  (
+    "clone.no-remote",
+    "'{}' has no `origin` git remote to clone it from",
+  ),
+  (
+    "clone.exists",
+    "'{}' already exists and isn't a clone of '{}'",
+  ),
+  ("clone.failed", "failed to clone '{}', git exited with {}"),
+  ("ssh.unavailable", "cannot run ssh to scan"),
+  (
+    "container.unavailable",
+    "cannot run a container engine ({}) to scan",
+  ),
+  (
+    "container.failed",
+    "cannot list the files of container '{}', {}, cannot scan",
+  ),
+  ("ssh.failed", "ssh failed with {}, {}, cannot scan"),
+  ("ssh.unknown-host", "{} (did you mean the '{}' host?)"),
+  ("config.unknown-key", "{}: unknown key '{}' ignored"),
+  (
+    "config.unknown-key-typo",
+    "{}: unknown key '{}' ignored, did you mean '{}'?",
+  ),
+  (
+    "config.untrusted",
+    "refusing to run the commands defined by '{}': it isn't owned by you or lies outside your \
+     configuration folders ({}), run with --trust if you trust it",
+  ),
+  ("cache.remote-failed", "remote cache unavailable, {}"),
+  (
+    "cache.s3-unavailable",
+    "the '{}' bucket is ignored, rebuild with `--features s3` to share the cache",
+  ),
+  ("update.up-to-date", "pgrep {} is up to date"),
+  (
+    "update.available",
+    "pgrep {} is available, currently running {}",
+  ),
+  ("update.done", "replaced '{}' by pgrep {}"),
+  (
+    "update.unsigned",
+    "this build has no release public key (PGREP_RELEASE_PUBKEY), refusing to install an \
+     unverified binary",
+  ),
+  (
+    "update.unsigned-release",
+    "the release binary has no signature, refusing to install it",
+  ),
+  ("tui.details", "Details"),
+  ("tui.details-text", "Languages: {}\nName: {}\nPath: {}"),
+  ("tui.projects", "Projects ({})"),
+  ("tui.all", "1:All ({})"),
+  ("tui.status-query", "query '{}'"),
+  ("tui.status-matches", "{}/{} projects"),
+  ("tui.status-sort", "sorted by {}"),
+  ("tui.status-filter", "kind {}"),
+  ("tui.cache-fresh", "{} scanned {}s ago"),
+  ("tui.cache-stale", "{} stale"),
+  ("tui.cache-missing", "{} not cached"),
+  ("tui.files", "Files"),
+  ("tui.details-note", "Note: {}"),
+  ("tui.details-facet", "{}: {}"),
+  ("tui.extracting", "extracting…"),
+  (
+    "tui.keys",
+    "Return details, / search, o open, p pin, f files, Tab filter, q quit",
+  ),
+  (
+    "tui.search-keys",
+    "Return apply, Up/Down history, Esc cancel",
+  ),
+  ("tui.status-search", "search '{}'"),
+  ("gui.title", "pgrep"),
+  ("gui.failed", "failed to run the window: {}"),
+  ("gui.search", "Search"),
+  ("gui.projects", "{}/{} projects"),
+  ("gui.name", "Name"),
+  ("gui.kinds-column", "Languages"),
+  ("gui.path-column", "Path"),
+  ("gui.kinds", "Languages: {}"),
+  ("gui.path", "Path: {}"),
+  ("gui.open", "Open in editor"),
+  (
+    "gui.no-editor",
+    "no editor to open the project with, define EDITOR, VISUAL or --editor",
+  ),
+  ("gui.open-failed", "failed to run the editor '{}': {}"),
+  ("web.bind-failed", "cannot serve the web interface on {}"),
+  ("web.serving", "web interface served on http://{}"),
+];
+
+/// The french catalog, also translating the command-line help
+const FR: &[(&str, &str)] = &[
+  ("error.fatal", "erreur fatale"),
+  ("error.caused-by", "Causée par"),
+  ("error.kind.no-match", "Aucun résultat"),
+  ("error.kind.config", "Configuration"),
+  ("error.kind.query", "Requête"),
+  ("error.kind.scan", "Analyse"),
+  ("error.kind.cache", "Cache"),
+  ("error.kind.format", "Format"),
+  ("error.kind.io", "E/S"),
+  ("error.kind.unknown", "Inconnue"),
+  ("error.kind.interrupted", "Interruption"),
+  ("error.kind.exec", "Exécution"),
+  ("app.exec-no-match", "aucun projet ne correspond, aucune commande n'a été lancée"),
+  ("app.exec-failed", "{} commandes sur {} ont échoué"),
+  ("app.git-no-repository", "aucun projet correspondant n'est dans un dépôt git"),
+  ("app.archive-no-match", "aucun projet ne correspond à '{}', rien à archiver"),
+  ("app.archive-dry-run", "simulation, ajoutez --apply pour archiver ces {} projets"),
+  ("app.audit-no-match", "aucun projet correspondant n'est d'un type pouvant être audité"),
+  ("app.audit-summary", "{} des {} projets audités ont des dépendances obsolètes"),
+  ("app.audit-failed", "{} audits sur {} ont échoué"),
+  (
+    "app.clean-no-match",
+    "aucun projet correspondant à '{}' n'a d'artefacts de compilation à nettoyer",
+  ),
+  ("app.clean-confirm", "supprimer les artefacts de compilation de ces {} projets ? [o/N]"),
+  ("app.clean-cancelled", "annulé, rien n'a été nettoyé"),
+  ("app.clean-freed", "{} libérés dans {} projets"),
+  ("app.clean-failed", "le nettoyage de {} projets sur {} a échoué"),
+  ("app.unknown-kind", "type de projet '{}' inconnu, voir `pgrep list-kinds`"),
+  ("app.project-created", "projet {} '{}' créé"),
+  ("app.project-outside-folders", "'{}' n'est dans aucun dossier de code, ajoutez-en un avec -F pour le trouver"),
+  ("app.no-editor", "aucun éditeur pour ouvrir le projet, définir --editor ou EDITOR"),
+  (
+    "app.no-clone-dir",
+    "'{}' est dans un dossier distant, définissez `clone_dir` dans la section [general] de la \
+     configuration pour le cloner à son ouverture",
+  ),
+  ("app.cloning", "clonage de '{}' dans '{}'"),
+  ("app.unknown-workspace", "aucun espace de travail ne s'appelle '{}', espaces configurés : {}"),
+  ("app.workspace-member", "membre '{}' de l'espace de travail '{}' invalide"),
+  ("app.workspace-no-member", "le membre '{}' de l'espace de travail '{}' ne correspond à aucun projet"),
+  ("app.workspace-empty", "l'espace de travail '{}' n'a aucun projet membre"),
+  (
+    "app.rescan-unknown",
+    "'{}' n'est dans aucun dossier de code configuré, impossible de l'analyser à nouveau",
+  ),
+  ("app.invalid-container", "dossier de conteneur '{}' invalide, NOM:/CHEMIN attendu"),
+  (
+    "app.subcommand-query",
+    "'{}' est une sous-commande, lancez `pgrep {} --help` pour son usage ou `pgrep -- {}` pour \
+     chercher les projets nommés '{}'",
+  ),
+  ("error.interrupted", "interrompu pendant l'analyse de '{}'"),
+  ("error.unreadable", "lecture impossible de"),
+  (
+    "app.no-folders",
+    "Aucun dossier de code source configuré. utilisez -F/--folder pour en ajouter.",
+  ),
+  (
+    "app.list-with-query",
+    "Requête donnée avec --list alors que les deux options sont exclusives !",
+  ),
+  (
+    "app.missing-query",
+    "aucune requête donnée, ni configurée par défaut en `query` dans la section [general]",
+  ),
+  ("app.no-project", "aucun projet découvert dans {} dossiers :\n{}"),
+  ("app.no-match", "aucun résultat pour la requête '{}' parmi {} projets"),
+  ("app.no-match-any", "aucun résultat pour la requête '{}'"),
+  (
+    "app.scan-limit",
+    "'{}' n'a pas été entièrement analysé, il dépasse la limite de configuration `{}`",
+  ),
+  ("app.cache-gc", "{} entrées de cache obsolètes supprimées"),
+  ("app.scanning", "analyse en cours, {} fichiers trouvés..."),
+  (
+    "app.memory-cap",
+    "les projets occupent {} de mémoire même sans leurs fichiers sources, au-delà de la \
+     limite de {}",
+  ),
+  ("app.no-history", "aucune analyse précédente enregistrée, lancez d'abord une recherche"),
+  ("app.hook-failed", "la commande `{}` a échoué : {}"),
+  ("scaffold.exists", "'{}' existe déjà"),
+  ("archive.exists", "'{}' existe déjà dans le dossier d'archive, rien n'a été archivé"),
+  (
+    "archive.same-target",
+    "'{}' et '{}' seraient tous deux archivés dans '{}', rien n'a été archivé",
+  ),
+  ("archive.not-a-folder", "impossible d'archiver '{}', ce n'est pas un dossier de projet"),
+  ("archive.tar-failed", "échec de la compression de '{}' : {}"),
+  ("clean.failed", "échec de `{}` : {}"),
+  ("audit.failed", "échec de `{}` : {}"),
+  ("audit.timed-out", "`{}` a été interrompu après {} secondes"),
+  ("audit.invalid-output", "sortie inattendue de `{}` : {}"),
+  ("scaffold.no-template-dir", "le dossier modèle '{}' n'existe pas"),
+  ("scaffold.no-template", "aucun modèle n'est configuré pour les projets {}, voir `[templates]`"),
+  ("scaffold.failed", "`{}` a échoué : {}"),
+  ("app.interrupted", "interrompu"),
+  ("app.no-interrupt-handler", "Ctrl-C ne conservera pas les entrées de cache déjà enregistrées : {}"),
+  ("app.no-duplicates", "aucun projet ne partage le même manifeste"),
+  ("app.no-pinned-match", "aucun des {} projets correspondants n'est épinglé"),
+  ("app.no-facet-match", "aucun des {} projets correspondants n'a la facette {}"),
+  ("app.recalled-query", "requête rappelée '{}'"),
+  ("app.pinned", "'{}' épinglé"),
+  ("app.unpinned", "'{}' désépinglé"),
+  ("app.not-pinned", "'{}' n'est pas épinglé"),
+  ("app.no-note", "'{}' n'a pas de note"),
+  ("app.note-removed", "note de '{}' supprimée"),
+  ("app.note-not-a-folder", "impossible d'attacher une note à '{}', ce n'est pas un dossier"),
+  ("app.post-retry", "la requête vers '{}' a échoué ({}), nouvel essai dans {}s"),
+  (
+    "app.unknown-extension",
+    "impossible de déduire le format de '{}' depuis son extension, écriture en {}",
+  ),
+  (
+    "fmt.unavailable",
+    "le format '{}' n'est pas disponible dans cette version (formats disponibles : {}), \
+     recompilez avec `--features {}` pour l'activer",
+  ),
+  ("fmt.moved-from", "déplacé depuis {}"),
+  ("cache.unusable", "impossible d'utiliser le dossier de cache '{}', {}"),
+  ("cache.in-memory", "aucun dossier de cache utilisable, rien ne sera mis en cache"),
+  ("config.unwritable", "impossible d'écrire la configuration dans '{}', {}"),
+  ("config.missing-bookmark", "répertoire favori '{}' introuvable, ignoré"),
+  ("config.missing-include", "configuration incluse '{}' introuvable, ignorée"),
+  (
+    "config.discovery-unavailable",
+    "la découverte '{}' n'est pas disponible dans cette version, recompilez avec `--features {}`",
+  ),
+  ("clone.local", "'{}' est un projet local, il n'y a rien à cloner"),
+  ("clone.no-remote", "'{}' n'a pas de dépôt distant git `origin` depuis lequel le cloner"),
+  ("clone.exists", "'{}' existe déjà et n'est pas un clone de '{}'"),
+  ("clone.failed", "échec du clonage de '{}', git a terminé avec {}"),
+  ("ssh.unavailable", "impossible de lancer ssh pour analyser"),
+  ("container.unavailable", "impossible de lancer un moteur de conteneurs ({}) pour analyser"),
+  (
+    "container.failed",
+    "impossible de lister les fichiers du conteneur '{}', {}, impossible d'analyser",
+  ),
+  ("ssh.failed", "ssh a échoué avec {}, {}, impossible d'analyser"),
+  ("ssh.unknown-host", "{} (vouliez-vous dire l'hôte '{}' ?)"),
+  ("config.unknown-key", "{} : clé inconnue '{}' ignorée"),
+  ("config.unknown-key-typo", "{} : clé inconnue '{}' ignorée, vouliez-vous dire '{}' ?"),
+  (
+    "config.untrusted",
+    "refus d'exécuter les commandes définies par '{}' : il ne vous appartient pas ou se trouve \
+     hors de vos dossiers de configuration ({}), utilisez --trust si vous lui faites confiance",
+  ),
+  ("cache.remote-failed", "cache distant indisponible, {}"),
+  (
+    "cache.s3-unavailable",
+    "le bucket '{}' est ignoré, recompilez avec `--features s3` pour partager le cache",
+  ),
+  ("update.up-to-date", "pgrep {} est à jour"),
+  ("update.available", "pgrep {} est disponible, version actuelle {}"),
+  ("update.done", "'{}' remplacé par pgrep {}"),
+  (
+    "update.unsigned",
+    "cette version n'a pas de clé publique de publication (PGREP_RELEASE_PUBKEY), refus \
+     d'installer un exécutable non vérifié",
+  ),
+  (
+    "update.unsigned-release",
+    "l'exécutable publié n'a pas de signature, refus de l'installer",
+  ),
+  ("tui.details", "Détails"),
+  ("tui.details-text", "Langages : {}\nNom : {}\nChemin : {}"),
+  ("tui.projects", "Projets ({})"),
+  ("tui.all", "1:Tous ({})"),
+  ("tui.status-query", "requête '{}'"),
+  ("tui.status-matches", "{}/{} projets"),
+  ("tui.status-sort", "tri par {}"),
+  ("tui.status-filter", "type {}"),
+  ("tui.cache-fresh", "{} analysé il y a {}s"),
+  ("tui.cache-stale", "{} périmé"),
+  ("tui.cache-missing", "{} hors cache"),
+  ("tui.files", "Fichiers"),
+  ("tui.details-note", "Note : {}"),
+  ("tui.details-facet", "{} : {}"),
+  ("tui.extracting", "extraction…"),
+  (
+    "tui.keys",
+    "Entrée détails, / rechercher, o ouvrir, p épingler, f fichiers, Tab filtrer, q quitter",
+  ),
+  ("tui.search-keys", "Entrée appliquer, Haut/Bas historique, Échap annuler"),
+  ("tui.status-search", "recherche '{}'"),
+  ("gui.title", "pgrep"),
+  ("gui.failed", "échec de l'ouverture de la fenêtre : {}"),
+  ("gui.search", "Rechercher"),
+  ("gui.projects", "{}/{} projets"),
+  ("gui.name", "Nom"),
+  ("gui.kinds-column", "Langages"),
+  ("gui.path-column", "Chemin"),
+  ("gui.kinds", "Langages : {}"),
+  ("gui.path", "Chemin : {}"),
+  ("gui.open", "Ouvrir dans l'éditeur"),
+  ("gui.no-editor", "aucun éditeur pour ouvrir le projet, définissez EDITOR, VISUAL ou --editor"),
+  ("gui.open-failed", "échec du lancement de l'éditeur '{}' : {}"),
+  ("web.bind-failed", "impossible de servir l'interface web sur {}"),
+  ("web.serving", "interface web servie sur http://{}"),
+  ("help.about", "Trouver rapidement vos projets parmi vos dossiers de code"),
+  (
+    "help.query",
+    "La requête utilisée pour trouver le projet. Jokers disponibles :\n\
+     \t- '?' : un caractère optionnel\n\
+     \t- '_' : un caractère requis\n\
+     \t- '#' : un chiffre requis\n\
+     \t- '*' : n'importe quelle chaîne\n\
+     \t- '[abc]', '[a-z]' : un caractère de l'ensemble, '[!abc]' l'inverse\n\
+     \t- '\\' : échappe le caractère suivant, ex. '\\_' pour un tiret bas littéral\n\
+     Le nom entier doit correspondre, sauf avec --partial : la requête peut alors correspondre\n\
+     n'importe où dans le nom, et un '^' initial ou un '$' final l'ancre au début ou à la fin.\n\
+     Une requête contenant '/' est comparée à la fin des chemins complets, voir --match-path.\n\
+     Un mot 'ci:<système>' ne garde que les projets configurés pour github, gitlab ou jenkins,\n\
+     'ci:none' ceux sans intégration continue. Les mots 'kind:<type>', 'name:<motif>' et\n\
+     'path:<motif>' gardent les projets d'un type, ou dont le nom ou le chemin correspond.\n\
+     Un mot '@<nom>' est remplacé par la requête enregistrée sous <nom> dans la configuration\n\
+     [queries], et un mot nommé d'après un alias de la configuration [aliases] par son\n\
+     expression. Sans requête, celle configurée en `query` dans la section [general] est\n\
+     cherchée. Une requête nommée comme une sous-commande, telle que 'config', est donnée\n\
+     après '--' : `pgrep -- config`.\n",
+  ),
+  ("help.config", "Charger un fichier de configuration spécifique."),
+  ("help.dump_config", "Afficher la configuration puis quitter"),
+  (
+    "help.build_info",
+    "Afficher la version, le commit git, la date de compilation, les fonctionnalités, formats\n\
+     et types de projets en json, puis quitter.",
+  ),
+  ("help.clean_cache", "Vider le dossier de cache puis quitter."),
+  (
+    "help.history",
+    "Lister les dernières requêtes, la plus récente en premier, puis quitter. `--recall <n>`\n\
+     recherche à nouveau la n-ième.",
+  ),
+  (
+    "help.recall",
+    "Rechercher à nouveau la n-ième dernière requête de --history au lieu d'une requête, la\n\
+     dernière par défaut",
+  ),
+  ("help.no_cache", "Désactiver le cache."),
+  (
+    "help.rescan",
+    "Analyser à nouveau le dossier de code configuré contenant le chemin donné, sans son\n\
+     analyse en cache mais avec le cache des autres dossiers, par exemple juste après avoir\n\
+     cloné un projet",
+  ),
+  (
+    "help.max_depth",
+    "N'analyser que ce nombre de niveaux de dossiers sous chaque dossier de code, par défaut le\n\
+     `max_depth` de la section [general] de la configuration",
+  ),
+  (
+    "help.follow_symlinks",
+    "Analyser les dossiers vers lesquels pointent les liens symboliques, chacun une seule fois",
+  ),
+  (
+    "help.hidden",
+    "Analyser les dossiers dont le nom commence par un '.'",
+  ),
+  (
+    "help.wsl",
+    "Analyser aussi les dossiers personnels des distributions WSL installées, sous Windows",
+  ),
+  (
+    "help.in_container",
+    "Analyser aussi un dossier d'un conteneur docker ou podman en cours d'exécution, tel que\n\
+     'app:/workspace', en signalant ses projets avec leurs chemins dans le conteneur",
+  ),
+  (
+    "help.include_deps_sources",
+    "Analyser aussi le code source des dépendances extrait par cargo, go et npm, en le signalant\n\
+     avec le type Dependency",
+  ),
+  (
+    "help.jobs",
+    "Le nombre de dossiers de code analysés en même temps, par défaut le nombre de cœurs",
+  ),
+  (
+    "help.trace_file",
+    "Écrire la durée des phases d'analyse, de détection, de correspondance et de formatage\n\
+     dans ce fichier, au format de trace Chrome",
+  ),
+  (
+    "help.cache_dir",
+    "Stocker le cache dans le dossier donné plutôt que celui de l'utilisateur, par exemple pour\n\
+     construire un index partagé",
+  ),
+  (
+    "help.cache_readonly",
+    "Utiliser les entrées du cache existantes quel que soit leur âge, sans jamais écrire dans le\n\
+     cache",
+  ),
+  (
+    "help.partial",
+    "Chercher la requête n'importe où dans les noms, '^' et '$' l'ancrent.",
+  ),
+  (
+    "help.match_path",
+    "Comparer la requête aux chemins complets des projets plutôt qu'à leurs noms.\n\
+     Implicite quand la requête contient un '/'.",
+  ),
+  (
+    "help.emit_candidates",
+    "Écrire les résultats en lignes nom, chemin, types et score séparés par des tabulations,\n\
+     les meilleurs en premier, pour alimenter des outils de recherche floue comme fzf ou skim",
+  ),
+  (
+    "help.any",
+    "Vérifier seulement qu'un projet correspond, sans l'afficher.\n\
+     L'analyse s'arrête au premier résultat, le code de sortie indique l'issue.",
+  ),
+  (
+    "help.stats",
+    "Afficher sur la sortie d'erreur le nombre de projets trouvés par type et dossier, ainsi\n\
+     que les fichiers de projet qui n'ont pas donné de racine de projet",
+  ),
+  (
+    "help.hash",
+    "Afficher l'empreinte du manifeste principal de chaque projet, comme son Cargo.toml",
+  ),
+  (
+    "help.duplicates",
+    "N'afficher que les projets dont le manifeste principal est identique à celui d'un autre,\n\
+     groupés par empreinte, pour trouver les copies en double. Implique --hash",
+  ),
+  (
+    "help.trust",
+    "Exécuter les commandes définies par un fichier de configuration qui n'appartient pas à\n\
+     l'utilisateur courant ou se trouve hors de ses dossiers de configuration, comme les hooks",
+  ),
+  (
+    "help.du",
+    "Afficher la taille sur disque de chaque projet, artefacts de compilation comme `target` ou\n\
+     `node_modules` compris",
+  ),
+  ("help.du_skip_artifacts", "Exclure les artefacts de compilation de la taille sur disque, voir --du"),
+  ("help.pinned", "Ne lister que les projets épinglés"),
+  (
+    "help.facet",
+    "Ne lister que les projets ayant toutes les facettes données, séparées par des virgules,\n\
+     comme `container` pour ceux ayant un Dockerfile, un fichier compose ou un devcontainer.json.\n\
+     Un projet a une facette quand sa valeur est vraie, non nulle ou non vide, voir --facets.",
+  ),
+  (
+    "help.facets",
+    "Extraire des facettes des projets et les ajouter au rapport, séparées par des virgules :\n\
+     `toolchain` pour les versions fixées par rust-toolchain.toml, .nvmrc, .python-version ou la\n\
+     directive go de go.mod, `ci` pour les systèmes d'intégration continue configurés,\n\
+     `container` pour savoir si le projet est conteneurisé, `loc` pour les lignes de code source\n\
+     et `git` pour l'état de l'arbre de travail avec la fonctionnalité git. Par défaut, celles\n\
+     configurées en `facets` dans la section [output]",
+  ),
+  (
+    "help.sort",
+    "Choisir l'ordre d'affichage des projets trouvés, configuré en `sort` dans la section\n\
+     [output] par défaut",
+  ),
+  ("help.folders", "Ajouter un dossier à la liste des dossiers de recherche"),
+  (
+    "help.format",
+    "Choisir le format de sortie, déduit de l'extension de --output s'il est omis,\n\
+     le premier format disponible par défaut",
+  ),
+  ("help.output", "Écrire le rapport dans le fichier donné plutôt que sur la sortie standard"),
+  (
+    "help.post",
+    "Envoyer le rapport à l'URL donnée par une requête POST plutôt que de l'écrire,\n\
+     voir la section de configuration `[post]`",
+  ),
+  (
+    "help.color",
+    "Choisir quand utiliser les couleurs, `auto` les désactive pour les tubes, les fichiers\n\
+     et quand NO_COLOR est défini",
+  ),
+  ("help.lang", "Choisir la langue des messages, détectée depuis LANG par défaut"),
+  ("help.tui", "Activer l'interface en terminal"),
+  ("help.gui", "Afficher les résultats dans une fenêtre plutôt que dans le terminal"),
+  ("help.tui_inline", "Activer l'interface en terminal sous l'invite plutôt que sur l'écran alternatif, pour qu'elle reste dans l'historique après la sortie"),
+  ("help.editor", "Définir le chemin de l'éditeur à ouvrir"),
+  ("help.list", "Lister les projets sans les filtrer"),
+  ("help.help", "Afficher l'aide"),
+  ("help.gen", "Générer la documentation à partir de la définition de la ligne de commande"),
+  ("help.gen.man", "Écrire la page de manuel sur la sortie standard"),
+  (
+    "help.exec",
+    "Lancer une commande shell dans le dossier de chaque projet correspondant à la requête,\n\
+     puis résumer lesquelles ont échoué",
+  ),
+  (
+    "help.exec.command",
+    "La commande, où `{name}`, `{path}` et `{kinds}` sont remplacés par les champs du projet,\n\
+     protégés pour le shell",
+  ),
+  ("help.exec.query", "La requête choisissant les projets, tous quand elle est omise"),
+  (
+    "help.exec.workspace",
+    "Choisir les membres d'un espace de travail configuré plutôt qu'une requête, voir la\n\
+     sous-commande `ws`",
+  ),
+  ("help.exec.parallel", "Le nombre de commandes lancées en même temps"),
+  (
+    "help.status",
+    "Résumer l'état git de chaque projet correspondant à la requête : les commits en avance et\n\
+     en retard sur la branche amont, les fichiers modifiés et les remises",
+  ),
+  (
+    "help.archive",
+    "Déplacer les projets correspondant à la requête dans un dossier d'archive, en consignant\n\
+     leur ancien emplacement dans son manifeste. Ne fait qu'une simulation sans --apply",
+  ),
+  ("help.archive.query", "La requête choisissant les projets"),
+  ("help.archive.to", "Le dossier d'archive"),
+  ("help.archive.mode", "Déplacer les dossiers tels quels, ou les compresser en .tar.gz"),
+  ("help.archive.apply", "Archiver réellement les projets plutôt que de les lister"),
+  (
+    "help.clean-artifacts",
+    "Supprimer les artefacts de compilation des projets correspondant à la requête, avec\n\
+     `cargo clean` pour Rust et en effaçant les dossiers comme node_modules pour les autres\n\
+     types, puis indiquer l'espace libéré",
+  ),
+  ("help.clean-artifacts.query", "La requête choisissant les projets"),
+  ("help.clean-artifacts.yes", "Nettoyer sans demander de confirmation"),
+  ("help.audit", "Auditer les projets correspondant à la requête"),
+  (
+    "help.audit.outdated",
+    "Lister les dépendances directes obsolètes de chaque projet correspondant à la requête, avec\n\
+     l'outil de son type : `cargo outdated`, `npm outdated` ou `go list -m -u`",
+  ),
+  ("help.audit.outdated.query", "La requête choisissant les projets, tous quand elle est omise"),
+  ("help.audit.outdated.parallel", "Le nombre d'outils lancés en même temps"),
+  ("help.audit.outdated.timeout", "Le nombre de secondes après lequel un outil est interrompu"),
+  (
+    "help.new",
+    "Créer le squelette d'un nouveau projet, à partir du dossier `[templates]` configuré pour son\n\
+     type ou avec son propre outil, puis analyser à nouveau le dossier de code qui le contient",
+  ),
+  ("help.new.kind", "Le type du projet, comme `rust`, `node`, `go` ou un type personnalisé"),
+  ("help.new.name", "Le nom du projet, qui est aussi le nom de son dossier"),
+  ("help.new.parent", "Le dossier contenant le projet, le dossier courant par défaut"),
+  ("help.new.open", "Ouvrir le projet dans l'éditeur une fois créé"),
+  (
+    "help.ws",
+    "Lister les projets membres d'un espace de travail configuré en `[workspace.<nom>]`, ou\n\
+     lister les espaces de travail quand le nom est omis",
+  ),
+  ("help.ws.name", "Le nom de l'espace de travail"),
+  (
+    "help.pin",
+    "Épingler un dossier de projet, pour le lister avant les autres résultats, ou lister les\n\
+     projets épinglés",
+  ),
+  ("help.pin.path", "Le dossier du projet, les projets épinglés sont listés quand il est omis"),
+  ("help.unpin", "Désépingler un dossier de projet"),
+  ("help.unpin.path", "Le dossier du projet"),
+  (
+    "help.note",
+    "Attacher une note à un dossier de projet, l'afficher quand le texte est omis, ou lister\n\
+     toutes les notes quand le dossier est omis aussi",
+  ),
+  ("help.note.path", "Le dossier du projet"),
+  ("help.note.text", "La note, remplaçant la précédente"),
+  ("help.note.delete", "Supprimer la note du dossier du projet"),
+  ("help.status.query", "La requête choisissant les projets, tous quand elle est omise"),
+  ("help.gen.fzf-widget", "Écrire un extrait de shell associant Ctrl-G au choix d'un projet avec fzf, puis à un cd vers son dossier"),
+  ("help.gen.fzf-widget.shell", "Le shell pour lequel écrire l'extrait"),
+  (
+    "help.list-formats",
+    "Lister les formats de sortie disponibles, dans le format choisi",
+  ),
+  (
+    "help.list-kinds",
+    "Lister les types de projets connus et leur détection, dans le format choisi",
+  ),
+  (
+    "help.self-update",
+    "Télécharger la dernière version depuis GitHub et remplacer l'exécutable actuel",
+  ),
+  (
+    "help.self-update.check",
+    "Vérifier seulement si une version plus récente est disponible",
+  ),
+  (
+    "help.self-update.force",
+    "Réinstaller la dernière version, même si elle n'est pas plus récente",
+  ),
+  (
+    "help.gen.markdown-help",
+    "Écrire la référence de la ligne de commande sur la sortie standard, en markdown",
+  ),
+  ("help.queries", "Gérer les requêtes enregistrées"),
+  (
+    "help.queries.list",
+    "Lister les requêtes enregistrées dans la table de configuration `[queries]`, lancées avec\n\
+     `pgrep @<nom>`",
+  ),
+  ("help.config.about", "Inspecter le format du fichier de configuration"),
+  (
+    "help.config.schema",
+    "Écrire le schéma JSON de pgrep.toml sur la sortie standard, pour que les éditeurs le\n\
+     complètent et le valident",
+  ),
+  ("help.cache", "Entretenir le cache"),
+  (
+    "help.cache.gc",
+    "Supprimer les entrées de cache des dossiers supprimés ou qui ne sont plus configurés",
+  ),
+  (
+    "help.stats.about",
+    "Afficher les statistiques d'utilisation enregistrées quand `usage_stats` est activé dans la\n\
+     section [general] de la configuration : les recherches, durées d'analyse et taux de succès\n\
+     du cache au fil du temps",
+  ),
+  ("help.stats.period", "La période de chaque ligne"),
+  (
+    "help.diff",
+    "Afficher les projets ajoutés, supprimés ou dont les types ont changé depuis une analyse\n\
+     précédente",
+  ),
+  (
+    "help.diff.since",
+    "La date de l'analyse à comparer : now, today, yesterday, une durée comme 2d, 12h ou 1w,\n\
+     ou une date comme 2024-01-31",
+  ),
+  (
+    "help.watch",
+    "Analyser périodiquement les dossiers de code, en signalant les projets ajoutés, supprimés\n\
+     ou dont les types ont changé, et lancer les commandes configurées",
+  ),
+  ("help.watch.interval", "Le nombre de secondes entre deux analyses"),
+  ("help.watch.serve", "Servir une interface web parcourant les projets analysés sur ce port"),
+  (
+    "help.watch.bind",
+    "L'adresse sur laquelle écoute l'interface web, seulement joignable depuis cette machine par\n\
+     défaut",
+  ),
+  (
+    "help.watch.memory_cap",
+    "La mémoire que peuvent occuper les projets conservés entre deux analyses, comme 256MiB,\n\
+     au-delà de laquelle ils sont conservés sans leurs fichiers sources. Par défaut le\n\
+     `memory_cap` de la section [watch] de la configuration",
+  ),
+  ("help.version", "Afficher la version"),
+];
+
+#[cfg(test)]
+mod tests {
+  use super::{EN, FR};
+
+  #[test]
+  fn catalogs_are_complete() {
+    for (key, _) in EN {
+      assert!(
+        FR.iter().any(|(k, _)| k == key),
+        "missing french message '{}'",
+        key
+      );
+    }
+  }
+}
@@ -0,0 +1,159 @@
+use std::path::{Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+use log::warn;
+
+/// A single pattern read from an [`IgnoreFile`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct IgnoreRule {
+  /// The compiled glob pattern
+  pattern: Pattern,
+  /// Whether the pattern contains a `/` and must match the path relative to the ignore file
+  anchored: bool,
+  /// Whether the pattern ends with a `/` and only applies to directories
+  dir_only: bool,
+  /// Whether the pattern starts with a `!` and includes back the entries it matches
+  negated: bool,
+}
+
+/// The per-directory ignore rules honored by [`crate::FolderScan`] while descending.
+///
+/// The file holds one glob pattern per line and applies to the whole subtree of the
+/// directory containing it:
+///   - blank lines and lines starting with `#` are skipped
+///   - a pattern without `/` matches an entry name at any depth (`fixtures`, `*.gen.rs`)
+///   - a pattern containing `/` matches the path relative to the ignore file (`tests/data/*`)
+///   - a trailing `/` restricts the pattern to directories (`generated/`)
+///   - a leading `!` includes back the entries a previous pattern, or the ignore file of a
+///     parent directory, skips (`!fixtures/keep`); the last matching pattern wins
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::IgnoreFile;
+///
+/// let content = "# fixtures\nfixtures/\n!sub/fixtures/\ntests/data/*";
+/// let ignore = IgnoreFile::parse("/dev/my_project", content);
+/// assert!(ignore.is_ignored("/dev/my_project/fixtures", true));
+/// assert!(!ignore.is_ignored("/dev/my_project/sub/fixtures", true));
+/// assert!(ignore.is_ignored("/dev/my_project/tests/data/Cargo.toml", false));
+/// assert!(!ignore.is_ignored("/dev/my_project/Cargo.toml", false));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IgnoreFile {
+  root: PathBuf,
+  rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreFile {
+  /// The ignore file name looked up in every scanned directory
+  pub const FILE_NAME: &'static str = ".pgrepignore";
+
+  /// Load the ignore file of the given directory, if there is one
+  pub fn load<P: AsRef<Path>>(dir: P) -> crate::Result<Option<Self>> {
+    let path = dir.as_ref().join(Self::FILE_NAME);
+    if !path.is_file() {
+      return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)?;
+    Ok(Some(Self::parse(dir, &content)))
+  }
+
+  /// Parse the ignore rules for the directory `root`.
+  ///
+  /// Invalid patterns are skipped with a warning.
+  pub fn parse<P: AsRef<Path>>(root: P, content: &str) -> Self {
+    let mut rules = vec![];
+    for line in content.lines() {
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      let negated = line.starts_with('!');
+      let line = line.strip_prefix('!').unwrap_or(line);
+      let dir_only = line.ends_with('/');
+      let line = line.trim_end_matches('/');
+      let anchored = line.contains('/');
+      match Pattern::new(line.trim_start_matches('/')) {
+        Ok(pattern) => rules.push(IgnoreRule {
+          pattern,
+          anchored,
+          dir_only,
+          negated,
+        }),
+        Err(e) => warn!(
+          "{}: invalid pattern '{}', {}",
+          root.as_ref().join(Self::FILE_NAME).display(),
+          line,
+          e
+        ),
+      }
+    }
+    Self {
+      root: root.as_ref().to_path_buf(),
+      rules,
+    }
+  }
+
+  /// Retrieve the directory this ignore file applies to
+  pub fn root(&self) -> &PathBuf {
+    &self.root
+  }
+
+  /// Check if the given path, located under [`Self::root`], must be skipped
+  pub fn is_ignored<P: AsRef<Path>>(&self, path: P, is_dir: bool) -> bool {
+    self.decide(path, is_dir).unwrap_or(false)
+  }
+
+  /// Check if the given path must be skipped according to the ignore files of its parent
+  /// directories, ordered from the outermost: the innermost one deciding about it wins
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::IgnoreFile;
+  ///
+  /// let ignores = [
+  ///   IgnoreFile::parse("/dev", "*.gen.rs"),
+  ///   IgnoreFile::parse("/dev/api", "!schema.gen.rs"),
+  /// ];
+  /// assert!(IgnoreFile::is_ignored_by(&ignores, "/dev/web/schema.gen.rs", false));
+  /// assert!(!IgnoreFile::is_ignored_by(&ignores, "/dev/api/schema.gen.rs", false));
+  /// ```
+  pub fn is_ignored_by<P: AsRef<Path>>(ignores: &[IgnoreFile], path: P, is_dir: bool) -> bool {
+    ignores
+      .iter()
+      .rev()
+      .find_map(|ignore| ignore.decide(path.as_ref(), is_dir))
+      .unwrap_or(false)
+  }
+
+  /// Decide whether the given path is skipped, `Some(false)` when a negated pattern includes
+  /// it back, or `None` when no pattern matches it
+  fn decide<P: AsRef<Path>>(&self, path: P, is_dir: bool) -> Option<bool> {
+    let rel = path
+      .as_ref()
+      .strip_prefix(&self.root)
+      .ok()?
+      .to_string_lossy()
+      .replace('\\', "/");
+    let name = path.as_ref().file_name()?.to_string_lossy();
+    let options = MatchOptions {
+      case_sensitive: true,
+      require_literal_separator: true,
+      require_literal_leading_dot: false,
+    };
+    self
+      .rules
+      .iter()
+      .rev()
+      .find(|rule| {
+        let matched = match rule.anchored {
+          true => rule.pattern.matches_with(&rel, options),
+          false => rule.pattern.matches_with(&name, options),
+        };
+        matched && (is_dir || !rule.dir_only)
+      })
+      .map(|rule| !rule.negated)
+  }
+}
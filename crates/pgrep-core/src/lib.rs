@@ -0,0 +1,133 @@
+//! The pgrep core discovers source code project roots, matches them against queries, caches
+//! scans and formats the results.
+//!
+//! It has neither a command-line nor a logger, and holds no global instance: the caller
+//! creates its [`Cache`] and [`Config`], and installs a logger of its choice if it wants to
+//! see the warnings emitted through the `log` crate. The `pgrep` crate is the command-line
+//! built on top of it.
+//!
+//! # Supported project kinds
+//!
+//! For now only [`crate::ProjectKind`] are supported but over time, this list will grow.
+//!
+//! # Queries
+//!
+//! A [`Query`] is parsed into the [`Part`]s of a name pattern, optional anchors and
+//! [`Selector`]s. A [`QueryVisitor`] walks them to inspect or validate a query, and
+//! [`Query::with_parts`] or [`Query::with_selectors`] rewrite it.
+//!
+//! # Caching
+//!
+//! Scanned folders and discovered projects are cached, and rescanned as soon as
+//! [`FolderScan::mtime_sample`] tells their top-level directories changed, or at the latest
+//! after [`Cache::SAMPLED_BUST_THRESHOLD`].
+//! The matches of the latest queries are cached by [`ResultCache`] for a shorter while.
+//!
+//! # Ignoring sub-trees
+//!
+//! Any scanned directory may contain a [`IgnoreFile::FILE_NAME`] file listing glob patterns
+//! of entries to skip while scanning it and its children (fixtures, generated code, ...).
+//!
+//! # Observing scans
+//!
+//! A [`ScanObserver`] set with [`ScanOptionsBuilder::observer`] is told about the directories
+//! entered, the files found and the directories skipped because of errors, so that progress
+//! is reported by the caller rather than the scanner. [`LogObserver`] logs them.
+//!
+//! # Facets
+//!
+//! Metadata such as the pinned toolchains or the lines of code is extracted on demand by the
+//! [`ProjectFacet`]s of a [`FacetRegistry`], and cached by [`FacetCache`] until the files it
+//! depends on change. Metadata refers to a project by its [`Project::id`], a [`ProjectId`]
+//! derived from its path which never changes across versions.
+//!
+//! # Cargo features
+//!
+//! Output formats are enabled by the `text`, `json`, `csv`, `xml`, `html` and `markdown`
+//! features, `clap` derives `clap::ValueEnum` for the enums used on command-lines, `s3`
+//! synchronizes the cache with an S3-compatible bucket, `tokio` adds the [`nonblocking`]
+//! API for async applications, `git` reads the git status of projects through the `git`
+//! command-line and `audit` lists their outdated dependencies through the tools of their kinds.
+//! `tracing` wraps folder scans and project detection in `tracing` spans, for the subscriber
+//! of the caller's choice to profile them. `locate` adds the [`Discovery::Locate`] backend,
+//! finding project files in the plocate database.
+
+pub mod archive;
+#[cfg(feature = "audit")]
+pub mod audit;
+pub mod cache;
+pub mod ci;
+pub mod clean;
+pub mod clone;
+pub mod config;
+pub mod container;
+pub mod deps;
+pub mod discovery;
+pub mod error;
+pub mod exec;
+pub mod facet;
+pub mod fmt;
+#[cfg(feature = "git")]
+pub mod git;
+pub mod history;
+pub mod hooks;
+pub mod i18n;
+pub mod ignore;
+#[cfg(feature = "tokio")]
+pub mod nonblocking;
+pub mod notes;
+pub mod observer;
+mod path_serde;
+pub mod pins;
+pub mod project;
+pub mod query;
+pub mod query_history;
+pub mod results;
+#[cfg(feature = "s3")]
+pub mod s3;
+pub mod scaffold;
+pub mod secrets;
+pub mod ssh;
+pub mod stats;
+pub mod style;
+#[cfg(test)]
+mod test_support;
+pub mod usage;
+pub mod wsl;
+
+pub use archive::*;
+#[cfg(feature = "audit")]
+pub use audit::*;
+pub use cache::*;
+pub use ci::*;
+pub use clean::*;
+pub use clone::*;
+pub use config::*;
+pub use container::*;
+pub use deps::*;
+pub use discovery::*;
+pub use error::*;
+pub use exec::*;
+pub use facet::*;
+pub use fmt::*;
+#[cfg(feature = "git")]
+pub use git::*;
+pub use history::*;
+pub use hooks::*;
+pub use ignore::*;
+pub use notes::*;
+pub use observer::*;
+pub use pins::*;
+pub use project::*;
+pub use query::*;
+pub use query_history::*;
+pub use results::*;
+#[cfg(feature = "s3")]
+pub use s3::*;
+pub use scaffold::*;
+pub use secrets::*;
+pub use ssh::*;
+pub use stats::*;
+pub use style::ColorChoice;
+pub use usage::*;
+pub use wsl::*;
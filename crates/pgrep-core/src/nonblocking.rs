@@ -0,0 +1,133 @@
+//! Async versions of the scanning, detection and cache APIs, for applications running a tokio
+//! runtime such as an async terminal interface or a daemon.
+//!
+//! File system work runs on the blocking thread pool through [`tokio::task::spawn_blocking`],
+//! so that it never stalls the runtime workers.
+
+use std::{
+  path::{Path, PathBuf},
+  sync::{Arc, Mutex},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::{sync::mpsc, task::spawn_blocking};
+
+use crate::{Cache, FolderScan, Project, ProjectKind, ScanOptions};
+
+/// The number of projects buffered by [`stream_projects`] before the scans wait for the receiver
+pub const STREAM_CAPACITY: usize = 64;
+
+/// Scan a folder, see [`FolderScan::with_options`]
+pub async fn scan_folder<P: AsRef<Path>>(
+  path: P,
+  options: &ScanOptions,
+) -> crate::Result<FolderScan> {
+  let path = path.as_ref().to_path_buf();
+  let options = options.clone();
+  spawn_blocking(move || FolderScan::with_options(path, &options)).await?
+}
+
+/// Detect the projects of a folder scan, see [`crate::detect_projects`]
+pub async fn detect_projects(
+  scan: Arc<FolderScan>,
+  custom_kinds: Vec<ProjectKind>,
+) -> crate::Result<Vec<Project>> {
+  Ok(spawn_blocking(move || crate::detect_projects(&scan, custom_kinds)).await?)
+}
+
+/// Scan every folder concurrently, sending their projects as soon as each folder is detected.
+///
+/// A folder that fails to be scanned sends its error, the other folders carry on. The channel
+/// is closed once every folder was handled. Must be called from within a tokio runtime.
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::{nonblocking::stream_projects, ScanOptions};
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let folder = std::env::temp_dir().join("pgrep-doc-stream/my_project");
+/// std::fs::create_dir_all(&folder).unwrap();
+/// std::fs::write(folder.join("Cargo.toml"), "").unwrap();
+/// let mut projects = stream_projects(vec![folder.clone()], ScanOptions::default(), vec![]);
+/// while let Some(project) = projects.recv().await {
+///   assert_eq!(project.unwrap().path(), &folder);
+/// }
+/// # });
+/// ```
+pub fn stream_projects(
+  folders: Vec<PathBuf>,
+  options: ScanOptions,
+  custom_kinds: Vec<ProjectKind>,
+) -> mpsc::Receiver<crate::Result<Project>> {
+  let (tx, rx) = mpsc::channel(STREAM_CAPACITY);
+  for folder in folders {
+    let tx = tx.clone();
+    let options = options.clone();
+    let custom_kinds = custom_kinds.clone();
+    spawn_blocking(move || {
+      let projects = match FolderScan::with_options(&folder, &options) {
+        Ok(scan) => crate::detect_projects(&scan, custom_kinds),
+        Err(e) => {
+          let _ = tx.blocking_send(Err(e));
+          return;
+        }
+      };
+      for project in projects {
+        // the receiver was dropped, nobody wants the remaining projects
+        if tx.blocking_send(Ok(project)).is_err() {
+          return;
+        }
+      }
+    });
+  }
+  rx
+}
+
+/// Load an entry from a shared cache store, see [`Cache::load`]
+pub async fn cache_load<K, E>(cache: Arc<Mutex<Cache>>, key: K) -> crate::Result<Option<E>>
+where
+  K: AsRef<Path> + Send + 'static,
+  E: DeserializeOwned + Send + 'static,
+{
+  spawn_blocking(move || cache.lock().unwrap().load(key)).await?
+}
+
+/// Save an entry to a shared cache store, see [`Cache::store`]
+pub async fn cache_store<K, E>(cache: Arc<Mutex<Cache>>, key: K, value: E) -> crate::Result<PathBuf>
+where
+  K: AsRef<Path> + Send + 'static,
+  E: Serialize + Send + 'static,
+{
+  spawn_blocking(move || cache.lock().unwrap().store(&key, &value)).await?
+}
+
+/// Write the index of a shared cache store and synchronize it, see [`Cache::shutdown`]
+pub async fn cache_shutdown(cache: Arc<Mutex<Cache>>) -> crate::Result<()> {
+  spawn_blocking(move || cache.lock().unwrap().shutdown()).await?
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use super::{cache_load, cache_store};
+  use crate::Cache;
+
+  #[test]
+  fn cache_entries_round_trip() {
+    let dir = std::env::temp_dir().join(format!("pgrep-nonblocking-{}", std::process::id()));
+    let cache = Arc::new(Mutex::new(Cache::open(&dir).unwrap()));
+    let runtime = tokio::runtime::Builder::new_current_thread()
+      .build()
+      .unwrap();
+    runtime.block_on(async {
+      cache_store(cache.clone(), "/dev/async", 42u32)
+        .await
+        .unwrap();
+      let loaded: Option<u32> = cache_load(cache.clone(), "/dev/async").await.unwrap();
+      assert_eq!(loaded, Some(42));
+    });
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
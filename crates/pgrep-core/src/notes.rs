@@ -0,0 +1,135 @@
+use std::{
+  collections::BTreeMap,
+  path::{Path, PathBuf},
+};
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::{get_data_dir, Error, Project};
+
+/// The free-text notes attached to project folders, such as "migrating to v2".
+///
+/// They are stored as a TOML file in the user data folder, apart from the cache so that
+/// cleaning it keeps them.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Notes {
+  /// The file the notes are stored in
+  #[serde(skip)]
+  file: PathBuf,
+  /// The note of every project folder having one
+  notes: BTreeMap<PathBuf, String>,
+}
+
+impl Notes {
+  /// The notes file name
+  pub const FILE_NAME: &'static str = "notes.toml";
+
+  /// Load the notes stored in `file`, none if it doesn't exist yet
+  pub fn load<P: AsRef<Path>>(file: P) -> crate::Result<Self> {
+    let file = file.as_ref().to_path_buf();
+    let mut ret = match file.exists() {
+      true => {
+        let content = std::fs::read_to_string(&file)
+          .map_err(|e| Error::io(format!("cannot read notes '{}'", file.display()), e))?;
+        toml::from_str(&content)?
+      }
+      false => Self::default(),
+    };
+    ret.file = file;
+    Ok(ret)
+  }
+
+  /// Load the notes stored in the user data folder
+  pub fn open() -> crate::Result<Self> {
+    Self::load(get_data_dir().join(Self::FILE_NAME))
+  }
+
+  /// Write the notes to their file, creating its folder if needed
+  pub fn save(&self) -> crate::Result<()> {
+    if let Some(dir) = self.file.parent() {
+      std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&self.file, toml::to_string_pretty(self)?)
+      .map_err(|e| Error::io(format!("cannot write notes '{}'", self.file.display()), e))?;
+    debug!(
+      "saved {} notes to '{}'",
+      self.notes.len(),
+      self.file.display()
+    );
+    Ok(())
+  }
+
+  /// Attach `text` to a project folder, replacing its previous note if any
+  pub fn set<P: AsRef<Path>, T: Into<String>>(&mut self, path: P, text: T) -> Option<String> {
+    self.notes.insert(path.as_ref().to_path_buf(), text.into())
+  }
+
+  /// Retrieve the note of a project folder
+  pub fn get<P: AsRef<Path>>(&self, path: P) -> Option<&String> {
+    self.notes.get(path.as_ref())
+  }
+
+  /// Remove the note of a project folder, returning it
+  pub fn remove<P: AsRef<Path>>(&mut self, path: P) -> Option<String> {
+    self.notes.remove(path.as_ref())
+  }
+
+  /// Iterate over the project folders and their notes, in path order
+  pub fn iter(&self) -> impl Iterator<Item = (&PathBuf, &String)> {
+    self.notes.iter()
+  }
+
+  /// Attach their note to the given projects, see [`Project::note`]
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::{Notes, Project};
+  ///
+  /// let mut notes = Notes::default();
+  /// notes.set("/dev/beta", "migrating to v2");
+  /// let mut projects =
+  ///   ["/dev/alpha", "/dev/beta"].map(|path| Project::new(path, vec![], vec![], vec![]));
+  /// notes.attach(&mut projects);
+  /// assert_eq!(projects[0].note(), None);
+  /// assert_eq!(projects[1].note().map(String::as_str), Some("migrating to v2"));
+  /// ```
+  pub fn attach(&self, projects: &mut [Project]) {
+    for project in projects {
+      project.set_note(self.get(project.path()).cloned());
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Notes;
+
+  #[test]
+  fn notes_are_persisted() {
+    let file = std::env::temp_dir()
+      .join(format!("pgrep-notes-{}", std::process::id()))
+      .join(Notes::FILE_NAME);
+    let mut notes = Notes::load(&file).unwrap();
+    assert_eq!(notes.set("/dev/alpha", "first"), None);
+    assert_eq!(
+      notes.set("/dev/alpha", "migrating to v2").as_deref(),
+      Some("first")
+    );
+    notes.set("/dev/beta", "archived\nkept for reference");
+    notes.save().unwrap();
+    let mut notes = Notes::load(&file).unwrap();
+    assert_eq!(notes.iter().count(), 2);
+    assert_eq!(
+      notes.get("/dev/beta").unwrap(),
+      "archived\nkept for reference"
+    );
+    assert_eq!(
+      notes.remove("/dev/alpha").as_deref(),
+      Some("migrating to v2")
+    );
+    assert_eq!(notes.get("/dev/alpha"), None);
+    std::fs::remove_dir_all(file.parent().unwrap()).unwrap();
+  }
+}
@@ -0,0 +1,142 @@
+use std::{ops::Deref, path::Path, sync::Arc};
+
+use log::{debug, trace, warn};
+
+use crate::{Error, Project};
+
+/// Told about the progress of a [`crate::FolderScan`] and about the projects detected from it,
+/// so that the caller reports scans as it sees fit: progress lines, logs, or a file watcher
+/// reusing the same pipeline.
+///
+/// Every method does nothing by default. A pair of observers tells both of them, and an
+/// [`Arc`] tells the observer it shares, which the caller keeps to read what it collected.
+///
+/// # Examples
+///
+/// ```
+/// use std::{path::Path, sync::atomic::{AtomicUsize, Ordering}, sync::Arc};
+/// use pgrep_core::{FolderScan, LogObserver, ScanObserver, ScanOptions};
+///
+/// #[derive(Default)]
+/// struct DirCounter(AtomicUsize);
+///
+/// impl ScanObserver for DirCounter {
+///   fn on_dir_entered(&self, _dir: &Path, _found: usize) {
+///     self.0.fetch_add(1, Ordering::SeqCst);
+///   }
+/// }
+///
+/// let dirs = Arc::new(DirCounter::default());
+/// let options = ScanOptions::builder().observer((dirs.clone(), LogObserver)).build();
+/// FolderScan::with_options(std::env::temp_dir(), &options).ok();
+/// assert!(dirs.0.load(Ordering::SeqCst) > 0);
+/// ```
+pub trait ScanObserver: Send + Sync {
+  /// The directory `dir` is entered, `found` files being recorded so far
+  fn on_dir_entered(&self, _dir: &Path, _found: usize) {}
+
+  /// The file at `file` is recorded
+  fn on_file(&self, _file: &Path) {}
+
+  /// The `project` is detected from a scan
+  fn on_project_detected(&self, _project: &Project) {}
+
+  /// The directory at `path` cannot be scanned because of `error`, and is skipped
+  fn on_error(&self, _path: &Path, _error: &Error) {}
+}
+
+impl<O: ScanObserver + ?Sized> ScanObserver for Arc<O> {
+  fn on_dir_entered(&self, dir: &Path, found: usize) {
+    self.as_ref().on_dir_entered(dir, found);
+  }
+
+  fn on_file(&self, file: &Path) {
+    self.as_ref().on_file(file);
+  }
+
+  fn on_project_detected(&self, project: &Project) {
+    self.as_ref().on_project_detected(project);
+  }
+
+  fn on_error(&self, path: &Path, error: &Error) {
+    self.as_ref().on_error(path, error);
+  }
+}
+
+impl<A: ScanObserver, B: ScanObserver> ScanObserver for (A, B) {
+  fn on_dir_entered(&self, dir: &Path, found: usize) {
+    self.0.on_dir_entered(dir, found);
+    self.1.on_dir_entered(dir, found);
+  }
+
+  fn on_file(&self, file: &Path) {
+    self.0.on_file(file);
+    self.1.on_file(file);
+  }
+
+  fn on_project_detected(&self, project: &Project) {
+    self.0.on_project_detected(project);
+    self.1.on_project_detected(project);
+  }
+
+  fn on_error(&self, path: &Path, error: &Error) {
+    self.0.on_error(path, error);
+    self.1.on_error(path, error);
+  }
+}
+
+/// A [`ScanObserver`] shared by every clone of the [`crate::ScanOptions`] holding it
+#[derive(Clone)]
+pub struct SharedObserver(Arc<dyn ScanObserver>);
+
+impl SharedObserver {
+  /// Share `observer`
+  pub fn new<O: ScanObserver + 'static>(observer: O) -> Self {
+    Self(Arc::new(observer))
+  }
+}
+
+impl Deref for SharedObserver {
+  type Target = dyn ScanObserver;
+
+  fn deref(&self) -> &Self::Target {
+    self.0.as_ref()
+  }
+}
+
+impl std::fmt::Debug for SharedObserver {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str("SharedObserver")
+  }
+}
+
+impl PartialEq for SharedObserver {
+  fn eq(&self, other: &Self) -> bool {
+    Arc::ptr_eq(&self.0, &other.0)
+  }
+}
+
+impl Eq for SharedObserver {}
+
+/// A [`ScanObserver`] logging the scanned directories and files at the trace level, the
+/// detected projects at the debug level, and the skipped directories as warnings
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LogObserver;
+
+impl ScanObserver for LogObserver {
+  fn on_dir_entered(&self, dir: &Path, _found: usize) {
+    trace!("scanning '{}'", dir.display());
+  }
+
+  fn on_file(&self, file: &Path) {
+    trace!("found '{}'", file.display());
+  }
+
+  fn on_project_detected(&self, project: &Project) {
+    debug!("detected '{}'", project.path().display());
+  }
+
+  fn on_error(&self, path: &Path, error: &Error) {
+    warn!("skipping '{}': {}", path.display(), error);
+  }
+}
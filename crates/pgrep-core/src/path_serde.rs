@@ -0,0 +1,168 @@
+//! Serialize paths whatever their encoding, for `#[serde(with = "...")]` attributes.
+//!
+//! Human-readable formats, such as the json output, write paths as
+//! [`crate::config::normalize_path`] does, lossily when they aren't valid UTF-8. Binary ones
+//! such as the cache write them as is, as strings when they are valid UTF-8 like serde's own
+//! implementation, and as their raw bytes otherwise so that they are read back as the same
+//! path.
+
+use std::{
+  collections::HashMap,
+  fmt::Formatter,
+  path::{Path, PathBuf},
+};
+
+use serde::{
+  de::{Error, Visitor},
+  Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::normalize_path;
+
+/// A path serialized as described in [`self`]
+struct Lossless<'a>(&'a Path);
+
+impl Serialize for Lossless<'_> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+      return serializer.serialize_str(&normalize_path(self.0));
+    }
+    match self.0.to_str() {
+      Some(path) => serializer.serialize_str(path),
+      None => serializer.serialize_bytes(self.0.as_os_str().as_encoded_bytes()),
+    }
+  }
+}
+
+/// A path deserialized as described in [`self`]
+#[derive(PartialEq, Eq, Hash)]
+struct LosslessBuf(PathBuf);
+
+impl<'de> Deserialize<'de> for LosslessBuf {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_any(LosslessVisitor)
+  }
+}
+
+struct LosslessVisitor;
+
+impl Visitor<'_> for LosslessVisitor {
+  type Value = LosslessBuf;
+
+  fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+    f.write_str("a path")
+  }
+
+  fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+    Ok(LosslessBuf(PathBuf::from(v)))
+  }
+
+  #[cfg(unix)]
+  fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+    use std::os::unix::ffi::OsStrExt;
+    Ok(LosslessBuf(
+      Path::new(std::ffi::OsStr::from_bytes(v)).to_path_buf(),
+    ))
+  }
+
+  #[cfg(not(unix))]
+  fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+    Ok(LosslessBuf(PathBuf::from(
+      String::from_utf8_lossy(v).into_owned(),
+    )))
+  }
+}
+
+pub fn serialize<S: Serializer>(path: &Path, serializer: S) -> Result<S::Ok, S::Error> {
+  Lossless(path).serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PathBuf, D::Error> {
+  LosslessBuf::deserialize(deserializer).map(|path| path.0)
+}
+
+/// Serialize a list of paths as described in [`self`]
+pub mod vec {
+  use super::*;
+
+  pub fn serialize<S: Serializer>(paths: &[PathBuf], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(paths.iter().map(|path| Lossless(path)))
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<PathBuf>, D::Error> {
+    let paths = Vec::<LosslessBuf>::deserialize(deserializer)?;
+    Ok(paths.into_iter().map(|path| path.0).collect())
+  }
+}
+
+/// Serialize an optional path as described in [`self`]
+pub mod option {
+  use super::*;
+
+  pub fn serialize<S: Serializer>(
+    path: &Option<PathBuf>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    path.as_deref().map(Lossless).serialize(serializer)
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Option<PathBuf>, D::Error> {
+    Ok(Option::<LosslessBuf>::deserialize(deserializer)?.map(|path| path.0))
+  }
+}
+
+/// Serialize a list of paths along with values as described in [`self`]
+pub mod pairs {
+  use super::*;
+
+  pub fn serialize<S: Serializer, T: Serialize>(
+    pairs: &[(PathBuf, T)],
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(pairs.iter().map(|(path, value)| (Lossless(path), value)))
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>, T: Deserialize<'de>>(
+    deserializer: D,
+  ) -> Result<Vec<(PathBuf, T)>, D::Error> {
+    let pairs = Vec::<(LosslessBuf, T)>::deserialize(deserializer)?;
+    Ok(
+      pairs
+        .into_iter()
+        .map(|(path, value)| (path.0, value))
+        .collect(),
+    )
+  }
+}
+
+/// Serialize a map keyed by paths as described in [`self`]
+pub mod map {
+  use super::*;
+
+  pub fn serialize<'a, S: Serializer, M, T: Serialize + 'a>(
+    map: &'a M,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error>
+  where
+    &'a M: IntoIterator<Item = (&'a PathBuf, &'a T)>,
+  {
+    serializer.collect_map(map.into_iter().map(|(path, value)| (Lossless(path), value)))
+  }
+
+  pub fn deserialize<'de, D, M, T>(deserializer: D) -> Result<M, D::Error>
+  where
+    D: Deserializer<'de>,
+    M: FromIterator<(PathBuf, T)>,
+    T: Deserialize<'de>,
+  {
+    let map = HashMap::<LosslessBuf, T>::deserialize(deserializer)?;
+    Ok(
+      map
+        .into_iter()
+        .map(|(path, value)| (path.0, value))
+        .collect(),
+    )
+  }
+}
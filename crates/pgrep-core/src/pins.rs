@@ -0,0 +1,166 @@
+use std::{
+  collections::BTreeSet,
+  path::{Path, PathBuf},
+};
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::{get_data_dir, Error, Project};
+
+/// The pinned project folders, reported before the other matches.
+///
+/// They are stored as a TOML file in the user data folder, apart from the cache so that
+/// cleaning it keeps them. The folders are compared by their canonical paths, so that a project
+/// found under a symlinked code folder is the one pinned by its real path.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pins {
+  /// The file the pins are stored in
+  #[serde(skip)]
+  file: PathBuf,
+  /// The pinned project folders
+  pinned: BTreeSet<PathBuf>,
+}
+
+impl Pins {
+  /// The pins file name
+  pub const FILE_NAME: &'static str = "pins.toml";
+
+  /// Load the pins stored in `file`, none if it doesn't exist yet
+  pub fn load<P: AsRef<Path>>(file: P) -> crate::Result<Self> {
+    let file = file.as_ref().to_path_buf();
+    let mut ret = match file.exists() {
+      true => {
+        let content = std::fs::read_to_string(&file)
+          .map_err(|e| Error::io(format!("cannot read pins '{}'", file.display()), e))?;
+        toml::from_str(&content)?
+      }
+      false => Self::default(),
+    };
+    ret.file = file;
+    Ok(ret)
+  }
+
+  /// Load the pins stored in the user data folder, or in a temporary folder when the
+  /// platform has none
+  pub fn open() -> crate::Result<Self> {
+    Self::load(get_data_dir().join(Self::FILE_NAME))
+  }
+
+  /// Write the pins to their file, creating its folder if needed
+  pub fn save(&self) -> crate::Result<()> {
+    if let Some(dir) = self.file.parent() {
+      std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&self.file, toml::to_string_pretty(self)?)
+      .map_err(|e| Error::io(format!("cannot write pins '{}'", self.file.display()), e))?;
+    debug!(
+      "saved {} pins to '{}'",
+      self.pinned.len(),
+      self.file.display()
+    );
+    Ok(())
+  }
+
+  /// The path a folder is pinned as: its canonical path, or the path itself when it doesn't
+  /// exist
+  fn key(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+  }
+
+  /// Pin a project folder, returning whether it wasn't already
+  pub fn pin<P: AsRef<Path>>(&mut self, path: P) -> bool {
+    !self.contains(&path) && self.pinned.insert(Self::key(path.as_ref()))
+  }
+
+  /// Unpin a project folder, returning whether it was pinned
+  pub fn unpin<P: AsRef<Path>>(&mut self, path: P) -> bool {
+    let removed = self.pinned.remove(path.as_ref());
+    self.pinned.remove(&Self::key(path.as_ref())) || removed
+  }
+
+  /// Pin a project folder if it isn't, unpin it otherwise. Returns whether it is now pinned.
+  pub fn toggle<P: AsRef<Path>>(&mut self, path: P) -> bool {
+    match self.unpin(&path) {
+      true => false,
+      false => self.pin(path),
+    }
+  }
+
+  /// Check whether a project folder is pinned
+  pub fn contains<P: AsRef<Path>>(&self, path: P) -> bool {
+    self.pinned.contains(path.as_ref()) || self.pinned.contains(&Self::key(path.as_ref()))
+  }
+
+  /// Iterate over the pinned folders, in path order
+  pub fn iter(&self) -> impl Iterator<Item = &PathBuf> {
+    self.pinned.iter()
+  }
+
+  /// Move the pinned projects first, keeping the order of the others
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::{Pins, Project};
+  ///
+  /// let mut pins = Pins::default();
+  /// pins.pin("/dev/gamma");
+  /// let mut projects = ["/dev/alpha", "/dev/beta", "/dev/gamma"]
+  ///   .map(|path| Project::new(path, vec![], vec![], vec![]));
+  /// pins.sort_first(&mut projects);
+  /// let paths = projects.iter().map(|p| p.path().to_str().unwrap()).collect::<Vec<_>>();
+  /// assert_eq!(paths, ["/dev/gamma", "/dev/alpha", "/dev/beta"]);
+  /// ```
+  pub fn sort_first(&self, projects: &mut [Project]) {
+    projects.sort_by_cached_key(|project| !self.contains(project.path()));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Pins;
+  use crate::Project;
+
+  #[test]
+  fn pins_are_persisted() {
+    let file = std::env::temp_dir()
+      .join(format!("pgrep-pins-{}", std::process::id()))
+      .join(Pins::FILE_NAME);
+    let mut pins = Pins::load(&file).unwrap();
+    assert!(pins.pin("/dev/alpha"));
+    assert!(!pins.pin("/dev/alpha"));
+    assert!(pins.toggle("/dev/beta"));
+    pins.save().unwrap();
+    let mut pins = Pins::load(&file).unwrap();
+    assert_eq!(pins.iter().count(), 2);
+    assert!(!pins.toggle("/dev/beta"));
+    assert!(pins.unpin("/dev/alpha"));
+    assert!(!pins.contains("/dev/alpha"));
+    std::fs::remove_dir_all(file.parent().unwrap()).unwrap();
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn pins_follow_symlinked_folders() {
+    let root = std::env::temp_dir().join(format!("pgrep-pins-links-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("data").join("dev").join("api")).unwrap();
+    std::os::unix::fs::symlink(root.join("data").join("dev"), root.join("dev")).unwrap();
+    let (real, linked) = (
+      root.join("data").join("dev").join("api"),
+      root.join("dev").join("api"),
+    );
+    let mut pins = Pins::default();
+    assert!(pins.pin(real.canonicalize().unwrap()));
+    assert!(pins.contains(&linked));
+    assert!(!pins.pin(&linked));
+    let mut projects = [root.join("dev").join("other"), linked.clone()]
+      .map(|path| Project::new(path, vec![], vec![], vec![]));
+    pins.sort_first(&mut projects);
+    assert_eq!(projects[0].path(), &linked);
+    assert!(pins.toggle(root.join("dev").join("other")) && !pins.toggle(&linked));
+    assert!(!pins.contains(&real));
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+}
@@ -0,0 +1,1977 @@
+use std::{
+  collections::{hash_map::DefaultHasher, BTreeMap, HashMap, HashSet},
+  ffi::{OsStr, OsString},
+  fmt::Display,
+  hash::{Hash, Hasher},
+  path::{Path, PathBuf},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+};
+
+use chrono::{DateTime, Local};
+use log::{debug, trace, warn};
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::{
+  i18n::{tr, tr_fmt},
+  is_remote_path, spotlight_scan, ContainerFolder, Error, FacetValue, FolderConfig, GeneralConfig,
+  IgnoreFile, ScanObserver, SharedObserver, SshFolder,
+};
+
+/// Which files a [`FolderScan`] records
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanDetail {
+  /// Only the project files and source files of the known [`ProjectKind`]s
+  Light,
+  /// Every file
+  #[default]
+  Full,
+}
+
+/// How a [`FolderScan`] finds the files of a folder
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Discovery {
+  /// Walk the folder tree
+  #[default]
+  Walk,
+  /// Ask the Spotlight index of macOS for the project files, walking the folders it doesn't
+  /// index, see [`crate::spotlight_scan`]
+  Spotlight,
+  /// Ask the plocate database for the project files, walking the folders it doesn't hold.
+  /// Requires the `locate` feature
+  Locate,
+}
+
+impl Discovery {
+  /// Fail with the feature to enable, if this discovery was left out of the build
+  pub fn ensure_available(&self) -> crate::Result<()> {
+    match self {
+      Self::Locate if !cfg!(feature = "locate") => Err(Error::config(tr_fmt(
+        "config.discovery-unavailable",
+        &[&"locate", &"locate"],
+      ))),
+      _ => Ok(()),
+    }
+  }
+}
+
+/// A flag shared between threads, asking running scans to stop
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::CancelToken;
+///
+/// let token = CancelToken::default();
+/// let handle = token.clone();
+/// handle.cancel();
+/// assert!(token.is_cancelled());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+  /// Ask every scan holding this token, or a clone of it, to stop
+  pub fn cancel(&self) {
+    self.0.store(true, Ordering::SeqCst);
+  }
+
+  /// Check whether [`CancelToken::cancel`] was called
+  pub fn is_cancelled(&self) -> bool {
+    self.0.load(Ordering::SeqCst)
+  }
+}
+
+impl PartialEq for CancelToken {
+  fn eq(&self, other: &Self) -> bool {
+    Arc::ptr_eq(&self.0, &other.0)
+  }
+}
+
+impl Eq for CancelToken {}
+
+/// Decides whether a scan stops at the project found in a directory, see
+/// [`ScanOptions::stop_at`]
+#[derive(Clone)]
+pub struct StopPredicate(Arc<dyn Fn(&Project) -> bool + Send + Sync>);
+
+impl StopPredicate {
+  /// Stop the scans at the first project accepted by `predicate`
+  pub fn new<F: Fn(&Project) -> bool + Send + Sync + 'static>(predicate: F) -> Self {
+    Self(Arc::new(predicate))
+  }
+
+  /// Check whether the scan stops at `project`
+  pub fn stops_at(&self, project: &Project) -> bool {
+    (self.0)(project)
+  }
+}
+
+impl std::fmt::Debug for StopPredicate {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str("StopPredicate")
+  }
+}
+
+impl PartialEq for StopPredicate {
+  fn eq(&self, other: &Self) -> bool {
+    Arc::ptr_eq(&self.0, &other.0)
+  }
+}
+
+impl Eq for StopPredicate {}
+
+/// The options controlling a [`FolderScan`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanOptions {
+  /// Marker file names which exclude the directory holding them, and its subtree.
+  pub stop_markers: Vec<String>,
+  /// Directories holding more entries are skipped, `None` for no limit
+  pub max_files_per_dir: Option<usize>,
+  /// The scan stops after recording this many files, `None` for no limit
+  pub max_scan_files: Option<usize>,
+  /// Which files are recorded
+  pub detail: ScanDetail,
+  /// How the files are found
+  pub discovery: Discovery,
+  /// The custom kinds whose files are recorded in [`ScanDetail::Light`] mode
+  pub custom_kinds: Vec<ProjectKind>,
+  /// The names of the kinds of the projects kept by detection, every kind when empty, see
+  /// [`ScanOptions::detects`]
+  pub kinds: Vec<String>,
+  /// The number of directory levels scanned below the folder, `None` for no limit
+  pub max_depth: Option<usize>,
+  /// Whether the directories symbolic links point to are scanned, each one only once
+  pub follow_symlinks: bool,
+  /// Whether the directories whose name starts with a `.` are scanned
+  pub include_hidden: bool,
+  /// The names of the directories never scanned, [`FolderScan::DIR_EXCLUSIONS`] by default
+  pub exclusions: Vec<String>,
+  /// Patterns of the entries skipped in the whole folder, with the syntax of an [`IgnoreFile`]
+  pub ignore_patterns: Vec<String>,
+  /// Cancelling it stops the scan with [`Error::Interrupted`]
+  pub cancel: CancelToken,
+  /// Told about the progress of the scan
+  pub observer: Option<SharedObserver>,
+  /// Stops the walk once a directory holds a project it accepts, the project being built from
+  /// the project files of the directory only. The projects nested in another are never given to
+  /// it, see [`FolderScan::is_stopped`]
+  pub stop_at: Option<StopPredicate>,
+}
+
+impl ScanOptions {
+  /// The marker file name always honored, in addition to the configured ones
+  pub const DEFAULT_STOP_MARKER: &'static str = ".pgrep-stop";
+  /// The default [`ScanOptions::max_files_per_dir`]
+  pub const DEFAULT_MAX_FILES_PER_DIR: usize = 10_000;
+  /// The default [`ScanOptions::max_scan_files`]
+  pub const DEFAULT_MAX_SCAN_FILES: usize = 1_000_000;
+
+  /// Create scan options from the user configuration, see [`ScanOptionsBuilder::config`]
+  pub fn from_config(config: &GeneralConfig) -> Self {
+    Self::builder().config(config).build()
+  }
+
+  /// Start building scan options from the default ones, see [`ScanOptionsBuilder`]
+  pub fn builder() -> ScanOptionsBuilder {
+    ScanOptionsBuilder::default()
+  }
+
+  /// Hash the options changing what a [`FolderScan`] records, so that cached scans made with
+  /// other options can be told apart
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::ScanOptions;
+  ///
+  /// let mut options = ScanOptions::default();
+  /// let fingerprint = options.fingerprint();
+  /// options.cancel.cancel();
+  /// assert_eq!(options.fingerprint(), fingerprint);
+  /// options.stop_markers.push(".nopgrep".to_string());
+  /// assert_ne!(options.fingerprint(), fingerprint);
+  /// ```
+  pub fn fingerprint(&self) -> String {
+    let mut hasher = DefaultHasher::new();
+    self.stop_markers.hash(&mut hasher);
+    self.max_files_per_dir.hash(&mut hasher);
+    self.max_scan_files.hash(&mut hasher);
+    self.detail.hash(&mut hasher);
+    self.max_depth.hash(&mut hasher);
+    self.follow_symlinks.hash(&mut hasher);
+    self.include_hidden.hash(&mut hasher);
+    self.exclusions.hash(&mut hasher);
+    self.ignore_patterns.hash(&mut hasher);
+    // the custom kinds only choose the recorded files in light mode
+    if self.detail == ScanDetail::Light {
+      self.custom_kinds.hash(&mut hasher);
+    }
+    // keep the fingerprints of the scans made before the discovery could be chosen
+    if self.discovery != Discovery::Walk {
+      self.discovery.hash(&mut hasher);
+      self.custom_kinds.hash(&mut hasher);
+    }
+    format!("{:016x}", hasher.finish())
+  }
+
+  /// Hash the options changing the projects detected by [`detect_projects`] from a scan: the
+  /// ones of [`ScanOptions::fingerprint`], the custom kinds and the kinds kept
+  pub fn detection_fingerprint(&self) -> String {
+    let mut hasher = DefaultHasher::new();
+    self.fingerprint().hash(&mut hasher);
+    self.custom_kinds.hash(&mut hasher);
+    // keep the fingerprints of the detections made before kinds could be filtered
+    if !self.kinds.is_empty() {
+      self.kinds.hash(&mut hasher);
+    }
+    format!("{:016x}", hasher.finish())
+  }
+
+  /// Retrieve these options with the ones of a code folder entry applied, overriding them, see
+  /// [`FolderConfig`]
+  pub fn for_folder(&self, folder: &FolderConfig) -> Self {
+    let mut ret = self.clone();
+    for exclusion in &folder.exclude {
+      if !ret.exclusions.contains(exclusion) {
+        ret.exclusions.push(exclusion.clone());
+      }
+    }
+    ret.max_depth = folder.max_depth.or(ret.max_depth);
+    ret.kinds = folder.kinds.clone();
+    ret
+  }
+
+  /// Check whether detection keeps `project`, having one of the [`ScanOptions::kinds`] when
+  /// they are set
+  pub fn detects(&self, project: &Project) -> bool {
+    self.kinds.is_empty()
+      || project.kinds().iter().any(|kind| {
+        self
+          .kinds
+          .iter()
+          .any(|name| kind.name().eq_ignore_ascii_case(name))
+      })
+  }
+
+  /// Build the filter deciding which files are recorded, `None` when every file is
+  fn file_filter(&self) -> Option<FileFilter> {
+    match self.detail {
+      ScanDetail::Full => None,
+      ScanDetail::Light => {
+        let mut ret = FileFilter::default();
+        let kinds = ProjectKind::iter()
+          .filter(|kind| !matches!(kind, ProjectKind::Custom { .. }))
+          .chain(self.custom_kinds.iter().cloned());
+        for kind in kinds {
+          ret
+            .names
+            .extend(kind.project_files().iter().map(|f| f.to_lowercase()));
+          ret
+            .extensions
+            .extend(kind.language_extensions().iter().map(|e| e.to_lowercase()));
+        }
+        Some(ret)
+      }
+    }
+  }
+}
+
+/// The project file names and source extensions recorded in [`ScanDetail::Light`] mode,
+/// lowercased
+#[derive(Debug, Default)]
+struct FileFilter {
+  names: HashSet<String>,
+  extensions: HashSet<String>,
+}
+
+impl FileFilter {
+  /// Check whether the file `name` must be recorded
+  fn keeps(&self, name: &OsStr) -> bool {
+    let name = name.to_string_lossy().to_lowercase();
+    self.names.contains(&name)
+      || Path::new(&name)
+        .extension()
+        .is_some_and(|ext| self.extensions.contains(ext.to_string_lossy().as_ref()))
+  }
+}
+
+impl Default for ScanOptions {
+  fn default() -> Self {
+    Self {
+      stop_markers: vec![Self::DEFAULT_STOP_MARKER.to_string()],
+      max_files_per_dir: Some(Self::DEFAULT_MAX_FILES_PER_DIR),
+      max_scan_files: Some(Self::DEFAULT_MAX_SCAN_FILES),
+      detail: ScanDetail::default(),
+      discovery: Discovery::default(),
+      custom_kinds: vec![],
+      kinds: vec![],
+      max_depth: None,
+      follow_symlinks: false,
+      include_hidden: false,
+      exclusions: FolderScan::DIR_EXCLUSIONS.map(String::from).to_vec(),
+      ignore_patterns: vec![],
+      cancel: CancelToken::default(),
+      observer: None,
+      stop_at: None,
+    }
+  }
+}
+
+/// Builds [`ScanOptions`] from the default ones
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::{ScanDetail, ScanOptions};
+///
+/// let options = ScanOptions::builder()
+///   .max_depth(Some(3))
+///   .include_hidden(true)
+///   .exclusion("dist")
+///   .ignore_pattern("*.min.js")
+///   .detail(ScanDetail::Light)
+///   .build();
+/// assert_eq!(options.max_depth, Some(3));
+/// assert!(options.exclusions.contains(&"node_modules".to_string()));
+/// assert!(options.exclusions.contains(&"dist".to_string()));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanOptionsBuilder {
+  options: ScanOptions,
+}
+
+impl ScanOptionsBuilder {
+  /// Apply the user configuration, where a limit of `0` disables it, and whose stop markers
+  /// and exclusions add to the current ones
+  pub fn config(mut self, config: &GeneralConfig) -> Self {
+    for marker in &config.stop_markers {
+      self = self.stop_marker(marker);
+    }
+    for exclusion in &config.exclusions {
+      self = self.exclusion(exclusion);
+    }
+    let limit = |value: Option<usize>, default| match value {
+      Some(0) => None,
+      Some(value) => Some(value),
+      None => default,
+    };
+    let options = &mut self.options;
+    options.max_files_per_dir = limit(config.max_files_per_dir, options.max_files_per_dir);
+    options.max_scan_files = limit(config.max_scan_files, options.max_scan_files);
+    options.max_depth = config.max_depth.or(options.max_depth);
+    options.follow_symlinks |= config.follow_symlinks;
+    options.include_hidden |= config.include_hidden;
+    options.detail = config.scan_detail;
+    options.discovery = config.discovery;
+    options.custom_kinds = config.project_kinds.clone();
+    options
+      .ignore_patterns
+      .extend(config.ignore_patterns.iter().cloned());
+    self
+  }
+
+  /// Add a marker file name excluding the directory holding it, see
+  /// [`ScanOptions::stop_markers`]
+  pub fn stop_marker<S: Into<String>>(mut self, marker: S) -> Self {
+    let marker = marker.into();
+    if !self.options.stop_markers.contains(&marker) {
+      self.options.stop_markers.push(marker);
+    }
+    self
+  }
+
+  /// Set [`ScanOptions::max_files_per_dir`]
+  pub fn max_files_per_dir(mut self, max: Option<usize>) -> Self {
+    self.options.max_files_per_dir = max;
+    self
+  }
+
+  /// Set [`ScanOptions::max_scan_files`]
+  pub fn max_scan_files(mut self, max: Option<usize>) -> Self {
+    self.options.max_scan_files = max;
+    self
+  }
+
+  /// Set [`ScanOptions::detail`]
+  pub fn detail(mut self, detail: ScanDetail) -> Self {
+    self.options.detail = detail;
+    self
+  }
+
+  /// Set [`ScanOptions::discovery`]
+  pub fn discovery(mut self, discovery: Discovery) -> Self {
+    self.options.discovery = discovery;
+    self
+  }
+
+  /// Set [`ScanOptions::custom_kinds`]
+  pub fn custom_kinds(mut self, kinds: Vec<ProjectKind>) -> Self {
+    self.options.custom_kinds = kinds;
+    self
+  }
+
+  /// Set [`ScanOptions::max_depth`]
+  pub fn max_depth(mut self, max: Option<usize>) -> Self {
+    self.options.max_depth = max;
+    self
+  }
+
+  /// Set [`ScanOptions::follow_symlinks`]
+  pub fn follow_symlinks(mut self, state: bool) -> Self {
+    self.options.follow_symlinks = state;
+    self
+  }
+
+  /// Set [`ScanOptions::include_hidden`]
+  pub fn include_hidden(mut self, state: bool) -> Self {
+    self.options.include_hidden = state;
+    self
+  }
+
+  /// Add a directory name never scanned, see [`ScanOptions::exclusions`]
+  pub fn exclusion<S: Into<String>>(mut self, name: S) -> Self {
+    let name = name.into();
+    if !self.options.exclusions.contains(&name) {
+      self.options.exclusions.push(name);
+    }
+    self
+  }
+
+  /// Add a pattern of entries skipped, see [`ScanOptions::ignore_patterns`]
+  pub fn ignore_pattern<S: Into<String>>(mut self, pattern: S) -> Self {
+    self.options.ignore_patterns.push(pattern.into());
+    self
+  }
+
+  /// Set the token cancelling the scan, see [`ScanOptions::cancel`]
+  pub fn cancel(mut self, token: CancelToken) -> Self {
+    self.options.cancel = token;
+    self
+  }
+
+  /// Set the observer told about the progress of the scan, see [`ScanOptions::observer`]
+  pub fn observer<O: ScanObserver + 'static>(mut self, observer: O) -> Self {
+    self.options.observer = Some(SharedObserver::new(observer));
+    self
+  }
+
+  /// Stop the walk at the first project accepted by `predicate`, see [`ScanOptions::stop_at`]
+  pub fn stop_at<F: Fn(&Project) -> bool + Send + Sync + 'static>(mut self, predicate: F) -> Self {
+    self.options.stop_at = Some(StopPredicate::new(predicate));
+    self
+  }
+
+  /// Build the scan options
+  pub fn build(self) -> ScanOptions {
+    self.options
+  }
+}
+
+/// A [`ScanOptions`] limit, which made a [`FolderScan`] skip part of a tree
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum ScanLimit {
+  /// The directory held more than [`ScanOptions::max_files_per_dir`] entries
+  MaxFilesPerDir,
+  /// The scan recorded [`ScanOptions::max_scan_files`] files while in this directory
+  MaxScanFiles,
+}
+
+impl Display for ScanLimit {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::MaxFilesPerDir => write!(f, "max_files_per_dir"),
+      Self::MaxScanFiles => write!(f, "max_scan_files"),
+    }
+  }
+}
+
+/// The files found in a directory by a [`FolderScan`]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ScannedDir {
+  /// The directory path, relative to the scanned folder
+  #[serde(with = "crate::path_serde")]
+  path: PathBuf,
+  /// The file names
+  files: Vec<OsString>,
+}
+
+impl ScannedDir {
+  /// Retrieve the directory path, relative to the scanned folder
+  pub fn path(&self) -> &PathBuf {
+    &self.path
+  }
+
+  /// Retrieve the names of the files found in this directory
+  pub fn files(&self) -> &Vec<OsString> {
+    &self.files
+  }
+}
+
+/// Simple recursive folder scanning.
+///
+/// Files are stored per directory, relative to the scanned folder, so that long path prefixes
+/// aren't repeated for every file. Full paths are rebuilt on demand by [`FolderScan::files`].
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct FolderScan {
+  #[serde(with = "crate::path_serde")]
+  path: PathBuf,
+  dirs: Vec<ScannedDir>,
+  last_scanned: DateTime<Local>,
+  #[serde(default, with = "crate::path_serde::pairs")]
+  skipped: Vec<(PathBuf, ScanLimit)>,
+  /// Whether the walk stopped at a project accepted by [`ScanOptions::stop_at`]
+  #[serde(skip)]
+  stopped: bool,
+}
+
+impl FolderScan {
+  /// The common dirs to be excluded
+  pub const DIR_EXCLUSIONS: [&'static str; 4] = [".git", "node_modules", "target", "vendor"];
+
+  /// Create a new folder scanner
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::project::FolderScan;
+  /// use chrono::Local;
+  /// use std::path::PathBuf;
+  ///
+  /// let now = Local::now();
+  /// let scan = FolderScan::new(".").unwrap();
+  /// for file in scan.files() {
+  ///   println!("{}", file.display());
+  /// }
+  /// ```
+  pub fn new<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+    Self::with_options(path, &ScanOptions::default())
+  }
+
+  /// Create a new folder scanner using custom [`ScanOptions`]
+  #[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "scan", skip_all, fields(folder = %path.as_ref().display()))
+  )]
+  pub fn with_options<P: AsRef<Path>>(path: P, options: &ScanOptions) -> crate::Result<Self> {
+    if let Some(remote) = SshFolder::parse(path.as_ref()) {
+      return remote.scan(path.as_ref(), options);
+    }
+    if let Some(container) = ContainerFolder::parse(path.as_ref()) {
+      return container.scan(path.as_ref(), options);
+    }
+    let indexed = match options.discovery {
+      Discovery::Walk => None,
+      Discovery::Spotlight => spotlight_scan(path.as_ref(), options),
+      #[cfg(feature = "locate")]
+      Discovery::Locate => crate::locate_scan(path.as_ref(), options),
+      #[cfg(not(feature = "locate"))]
+      Discovery::Locate => None,
+    };
+    if let Some(scan) = indexed {
+      return Ok(scan);
+    }
+    let mut ret = Self {
+      path: path.as_ref().to_path_buf(),
+      dirs: vec![],
+      last_scanned: Local::now(),
+      skipped: vec![],
+      stopped: false,
+    };
+    let filter = options.file_filter();
+    let mut ignores = vec![];
+    if !options.ignore_patterns.is_empty() {
+      ignores.push(IgnoreFile::parse(
+        path.as_ref(),
+        &options.ignore_patterns.join("\n"),
+      ));
+    }
+    ret.scan_folder(
+      path.as_ref(),
+      options,
+      filter.as_ref(),
+      &mut ignores,
+      &mut 0,
+      &mut HashSet::new(),
+      false,
+    )?;
+    ret.dirs.retain(|dir| !dir.files.is_empty());
+    Ok(ret)
+  }
+
+  /// Build the scan of the folder at `path` from the paths of its files, relative to it, such
+  /// as listed by a command on another machine, see [`SshFolder`], or found in a file index.
+  ///
+  /// The files are selected as by a regular scan, except that symbolic links are never
+  /// followed, and that the stop markers and the ignore files of the directories leading to
+  /// them are only honored in local folders.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::{FolderScan, ScanOptions};
+  ///
+  /// let files = ["api/Cargo.toml", "api/src/main.rs", "api/target/debug/api", ".cache/x"];
+  /// let scan = FolderScan::from_files("/dev", files.map(Into::into), &ScanOptions::default());
+  /// assert_eq!(scan.files().count(), 2);
+  /// assert_eq!(scan.dirs()[0].path().to_str(), Some("api"));
+  /// ```
+  pub fn from_files<P: AsRef<Path>, I: IntoIterator<Item = PathBuf>>(
+    path: P,
+    files: I,
+    options: &ScanOptions,
+  ) -> Self {
+    let mut ret = Self {
+      path: path.as_ref().to_path_buf(),
+      dirs: vec![],
+      last_scanned: Local::now(),
+      skipped: vec![],
+      stopped: false,
+    };
+    let filter = options.file_filter();
+    let mut ignores = HashMap::new();
+    let mut dirs: BTreeMap<PathBuf, Vec<OsString>> = BTreeMap::new();
+    let mut found = 0;
+    for file in files {
+      let file = file.strip_prefix(".").unwrap_or(&file);
+      let (Some(dir), Some(name)) = (file.parent(), file.file_name()) else {
+        continue;
+      };
+      let mut ancestors = dir.ancestors().filter(|dir| !dir.as_os_str().is_empty());
+      let excluded = ancestors.any(|dir| {
+        let name = dir.file_name().unwrap_or_default();
+        options
+          .exclusions
+          .iter()
+          .any(|excluded| name == excluded.as_str())
+          || (!options.include_hidden && name.as_encoded_bytes().starts_with(b"."))
+      });
+      if excluded
+        || options
+          .max_depth
+          .is_some_and(|max| dir.components().count() > max)
+        || filter.as_ref().is_some_and(|filter| !filter.keeps(name))
+      {
+        continue;
+      }
+      match ret.listed_dir_ignores(dir, options, &mut ignores) {
+        Some(ignores) if !IgnoreFile::is_ignored_by(&ignores, ret.path.join(file), false) => {}
+        _ => continue,
+      }
+      if options.max_scan_files.is_some_and(|max| found >= max) {
+        debug!(
+          "stopping in '{}', {} files found",
+          ret.path.display(),
+          found
+        );
+        ret.skip(&ret.path.join(dir), ScanLimit::MaxScanFiles);
+        break;
+      }
+      if let Some(observer) = &options.observer {
+        observer.on_file(&ret.path.join(file));
+      }
+      dirs
+        .entry(dir.to_path_buf())
+        .or_default()
+        .push(name.to_os_string());
+      found += 1;
+    }
+    ret.dirs = dirs
+      .into_iter()
+      .map(|(path, files)| ScannedDir { path, files })
+      .collect();
+    ret
+  }
+
+  /// Retrieve the ignore files applying to the entries of `dir`, relative to the scanned folder,
+  /// for [`FolderScan::from_files`]. `None` when `dir` is ignored, or when it or one of its
+  /// parents holds a stop marker. The ignore files and stop markers are only looked up in
+  /// local folders, `cache` holding the ignore files of the directories already seen.
+  fn listed_dir_ignores(
+    &self,
+    dir: &Path,
+    options: &ScanOptions,
+    cache: &mut HashMap<PathBuf, Option<Vec<IgnoreFile>>>,
+  ) -> Option<Vec<IgnoreFile>> {
+    if let Some(ignores) = cache.get(dir) {
+      return ignores.clone();
+    }
+    let path = self.path.join(dir);
+    let local = !is_remote_path(&self.path);
+    let mut ret = match dir.parent() {
+      Some(parent) => self
+        .listed_dir_ignores(parent, options, cache)
+        .filter(|ignores| !IgnoreFile::is_ignored_by(ignores, &path, true)),
+      None => Some(vec![IgnoreFile::parse(
+        &self.path,
+        &options.ignore_patterns.join("\n"),
+      )]),
+    };
+    if local && ret.is_some() {
+      if let Some(marker) = options
+        .stop_markers
+        .iter()
+        .find(|marker| path.join(marker).exists())
+      {
+        debug!("skipping '{}', found '{}'", path.display(), marker);
+        ret = None;
+      }
+    }
+    if let Some(ignores) = ret.as_mut().filter(|_| local) {
+      ignores.extend(IgnoreFile::load(&path).ok().flatten());
+    }
+    cache.insert(dir.to_path_buf(), ret.clone());
+    ret
+  }
+
+  /// Sample the modification times of the folder at `path` and of its direct subdirectories,
+  /// which change whenever an entry is added, removed or renamed in them.
+  ///
+  /// Comparing samples tells cheaply that a scan is outdated, such as when a project was cloned
+  /// or deleted, but misses the changes made deeper in the tree.
+  pub fn mtime_sample<P: AsRef<Path>>(path: P) -> String {
+    let mtime = |path: &Path| {
+      std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+    };
+    let mut hasher = DefaultHasher::new();
+    mtime(path.as_ref()).hash(&mut hasher);
+    if let Ok(dir) = std::fs::read_dir(path.as_ref()) {
+      let mut dirs = dir
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_ok_and(|kind| kind.is_dir()))
+        .filter(|e| {
+          let name = e.file_name();
+          !Self::DIR_EXCLUSIONS
+            .iter()
+            .any(|excluded| name == *excluded)
+            && !name.as_encoded_bytes().starts_with(b".")
+        })
+        .map(|e| (e.file_name(), mtime(&e.path())))
+        .collect::<Vec<_>>();
+      dirs.sort();
+      dirs.hash(&mut hasher);
+    }
+    format!("{:016x}", hasher.finish())
+  }
+
+  /// Recursively list files, honoring the [`IgnoreFile`]s found along the way and
+  /// the [`ScanOptions`] limits.
+  ///
+  /// `filter` selects the recorded files, `ignores` holds the ignore files of every parent
+  /// directory, `found` the number of files found so far, and `visited` the canonical paths
+  /// of the directories scanned when following symbolic links. `in_project` tells that a parent
+  /// directory holds a project, the nested ones never being given to [`ScanOptions::stop_at`].
+  #[allow(clippy::too_many_arguments)]
+  fn scan_folder<P: AsRef<Path>>(
+    &mut self,
+    path: P,
+    options: &ScanOptions,
+    filter: Option<&FileFilter>,
+    ignores: &mut Vec<IgnoreFile>,
+    found: &mut usize,
+    visited: &mut HashSet<PathBuf>,
+    in_project: bool,
+  ) -> crate::Result<()> {
+    if options.cancel.is_cancelled() {
+      return Err(Error::Interrupted(tr_fmt(
+        "error.interrupted",
+        &[&self.path.display()],
+      )));
+    }
+    let unreadable = |path: &Path, e: std::io::Error| Error::Scan {
+      path: path.to_path_buf(),
+      message: tr("error.unreadable").to_string(),
+      source: Some(Box::new(e)),
+    };
+    // links may point to a directory already scanned, or to one of its parents
+    let canonical = match options.follow_symlinks {
+      true => Some(std::fs::canonicalize(path.as_ref()).map_err(|e| unreadable(path.as_ref(), e))?),
+      false => None,
+    };
+    if canonical.is_some_and(|canonical| !visited.insert(canonical)) {
+      debug!("skipping '{}', already scanned", path.as_ref().display());
+      return Ok(());
+    }
+    if let Some(marker) = options
+      .stop_markers
+      .iter()
+      .find(|marker| path.as_ref().join(marker).exists())
+    {
+      debug!("skipping '{}', found '{}'", path.as_ref().display(), marker);
+      return Ok(());
+    }
+    let dir = std::fs::read_dir(path.as_ref())
+      .and_then(|dir| dir.collect::<Result<Vec<_>, _>>())
+      .map_err(|e| unreadable(path.as_ref(), e))?;
+    if options.max_files_per_dir.is_some_and(|max| dir.len() > max) {
+      debug!(
+        "skipping '{}', it holds {} entries",
+        path.as_ref().display(),
+        dir.len()
+      );
+      self.skip(path.as_ref(), ScanLimit::MaxFilesPerDir);
+      return Ok(());
+    }
+    trace!("scanning '{}'", path.as_ref().display());
+    if let Some(observer) = &options.observer {
+      observer.on_dir_entered(path.as_ref(), *found);
+    }
+    let ignore = IgnoreFile::load(path.as_ref())?;
+    let has_ignore = ignore.is_some();
+    ignores.extend(ignore);
+    let dir_id = self.dirs.len();
+    self.dirs.push(ScannedDir {
+      path: path
+        .as_ref()
+        .strip_prefix(&self.path)
+        .unwrap_or(path.as_ref())
+        .to_path_buf(),
+      files: vec![],
+    });
+    // the files are recorded before descending, so that a project is known before the ones
+    // nested in it, which belong to it
+    let mut subdirs = vec![];
+    for e in dir {
+      if self.is_truncated() {
+        break;
+      }
+      let entry_path = e.path();
+      let file_type = e.file_type().map_err(|e| unreadable(&entry_path, e))?;
+      let is_dir = match options.follow_symlinks && file_type.is_symlink() {
+        true => entry_path.is_dir(),
+        false => file_type.is_dir(),
+      };
+      if IgnoreFile::is_ignored_by(ignores, &entry_path, is_dir) {
+        trace!("ignoring '{}'", entry_path.display());
+        continue;
+      }
+      if is_dir {
+        let fname = e.file_name();
+        if options
+          .exclusions
+          .iter()
+          .any(|excluded| fname == excluded.as_str())
+          || (!options.include_hidden && fname.as_encoded_bytes().starts_with(b"."))
+        {
+          continue;
+        }
+        let depth = entry_path
+          .strip_prefix(&self.path)
+          .map_or(0, |rel| rel.components().count());
+        if options.max_depth.is_some_and(|max| depth > max) {
+          trace!("skipping '{}', too deep", entry_path.display());
+          continue;
+        }
+        subdirs.push(entry_path);
+      } else if filter.is_some_and(|filter| !filter.keeps(&e.file_name())) {
+        continue;
+      } else if options.max_scan_files.is_some_and(|max| *found >= max) {
+        debug!(
+          "stopping in '{}', {} files found",
+          path.as_ref().display(),
+          found
+        );
+        self.skip(path.as_ref(), ScanLimit::MaxScanFiles);
+        break;
+      } else {
+        if let Some(observer) = &options.observer {
+          observer.on_file(&entry_path);
+        }
+        self.dirs[dir_id].files.push(e.file_name());
+        *found += 1;
+      }
+    }
+    let mut in_project = in_project;
+    if let Some(stop_at) = options.stop_at.as_ref().filter(|_| !in_project) {
+      let kinds = ProjectKind::of_project_files(&self.dirs[dir_id].files, &options.custom_kinds);
+      in_project = !kinds.is_empty();
+      if in_project && stop_at.stops_at(&Project::new(path.as_ref(), kinds, vec![], vec![])) {
+        debug!(
+          "stopping at '{}', its project is the one looked for",
+          path.as_ref().display()
+        );
+        self.stopped = true;
+      }
+    }
+    for subdir in subdirs {
+      if self.is_truncated() || self.stopped {
+        break;
+      }
+      match self.scan_folder(
+        &subdir, options, filter, ignores, found, visited, in_project,
+      ) {
+        // an unreadable subdirectory is skipped rather than failing the whole folder
+        Err(e @ (Error::IO { .. } | Error::Scan { .. })) => match &options.observer {
+          Some(observer) => observer.on_error(&subdir, &e),
+          None => warn!("skipping '{}': {}", subdir.display(), e),
+        },
+        ret => ret?,
+      }
+    }
+    if has_ignore {
+      ignores.pop();
+    }
+    Ok(())
+  }
+
+  /// Record a directory left out because of `limit`
+  fn skip(&mut self, path: &Path, limit: ScanLimit) {
+    self.skipped.push((path.to_path_buf(), limit));
+  }
+
+  /// Check whether the scan stopped early, after recording [`ScanOptions::max_scan_files`] files
+  pub fn is_truncated(&self) -> bool {
+    self
+      .skipped
+      .iter()
+      .any(|(_, limit)| *limit == ScanLimit::MaxScanFiles)
+  }
+
+  /// Check whether the walk stopped at a project accepted by [`ScanOptions::stop_at`], leaving
+  /// the rest of the folder out
+  pub fn is_stopped(&self) -> bool {
+    self.stopped
+  }
+
+  /// Retrieve the directories left out of the scan, and the limit they exceeded
+  pub fn skipped(&self) -> &Vec<(PathBuf, ScanLimit)> {
+    &self.skipped
+  }
+
+  /// Retrieve the scanned folder path
+  pub fn path(&self) -> &PathBuf {
+    &self.path
+  }
+
+  /// Retrieve the full paths of the discovered files, grouped by directory
+  pub fn files(&self) -> impl Iterator<Item = PathBuf> + '_ {
+    self.dirs.iter().flat_map(|dir| {
+      let dir_path = self.path.join(&dir.path);
+      dir.files.iter().map(move |name| dir_path.join(name))
+    })
+  }
+
+  /// Retrieve the number of discovered files
+  pub fn file_count(&self) -> usize {
+    self.dirs.iter().map(|dir| dir.files.len()).sum()
+  }
+
+  /// Retrieve the directories holding the discovered files
+  pub fn dirs(&self) -> &Vec<ScannedDir> {
+    &self.dirs
+  }
+
+  /// Retrieve the last time the folder was scanned
+  pub fn last_scanned(&self) -> &DateTime<Local> {
+    &self.last_scanned
+  }
+}
+
+/// A known project kind
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, EnumIter, Clone, Hash)]
+#[serde(tag = "type")]
+pub enum ProjectKind {
+  Rust,
+  Go,
+  C,
+  Node,
+  Maven,
+  Other,
+  /// A directory bookmarked in the configuration, reported although it holds no project file,
+  /// see [`crate::BookmarksConfig`]
+  Bookmark,
+  /// The source code of a dependency, extracted by its package manager, see
+  /// [`crate::dependency_sources`]
+  Dependency,
+  Custom {
+    name: String,
+    language_exts: Vec<String>,
+    project_files: Vec<String>,
+  },
+}
+
+impl ProjectKind {
+  /// Find the built-in or `custom` kind named `name`, ignoring case
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::ProjectKind;
+  ///
+  /// assert_eq!(ProjectKind::from_name("rust", &[]), Some(ProjectKind::Rust));
+  /// assert_eq!(ProjectKind::from_name("python", &[]), None);
+  /// ```
+  pub fn from_name(name: &str, custom: &[ProjectKind]) -> Option<ProjectKind> {
+    Self::iter()
+      .filter(|kind| !matches!(kind, Self::Custom { .. }))
+      .chain(custom.iter().cloned())
+      .find(|kind| kind.name().eq_ignore_ascii_case(name))
+  }
+
+  pub fn name(&self) -> String {
+    match self {
+      ProjectKind::Rust => "Rust".to_string(),
+      ProjectKind::Go => "Go".to_string(),
+      ProjectKind::C => "C".to_string(),
+      ProjectKind::Node => "Node".to_string(),
+      ProjectKind::Maven => "Maven".to_string(),
+      ProjectKind::Other => "Other".to_string(),
+      ProjectKind::Bookmark => "Bookmark".to_string(),
+      ProjectKind::Dependency => "Dependency".to_string(),
+      ProjectKind::Custom { name, .. } => name.clone(),
+    }
+  }
+
+  /// Retrieve the known project files
+  pub fn project_files(&self) -> Vec<String> {
+    match self {
+      Self::Rust => vec!["Cargo.toml", "Cargo.lock"],
+      Self::Go => vec!["go.mod"],
+      Self::C => vec!["Makefile", "CMakeLists.txt"],
+      Self::Node => vec!["package.json", "package.lock"],
+      Self::Maven => vec!["pom.xml"],
+      Self::Other => vec!["README.md", "LICENSE.md", "CONTRIBUTING.md"],
+      Self::Bookmark | Self::Dependency => vec![],
+      Self::Custom { project_files, .. } => project_files
+        .iter()
+        .map(|ext| ext.as_str())
+        .collect::<Vec<_>>(),
+    }
+    .iter()
+    .map(|f| f.to_string())
+    .collect::<Vec<_>>()
+  }
+
+  /// Retrieve the folders holding build artifacts, relative to the project root
+  pub fn artifact_dirs(&self) -> Vec<String> {
+    match self {
+      Self::Rust | Self::Maven => vec!["target"],
+      Self::Node => vec!["node_modules"],
+      Self::C => vec!["build"],
+      Self::Go | Self::Other | Self::Bookmark | Self::Dependency | Self::Custom { .. } => {
+        vec![]
+      }
+    }
+    .iter()
+    .map(|s| s.to_string())
+    .collect::<Vec<_>>()
+  }
+
+  /// Retrieve the built-in kinds, and the `custom_kinds`, having one of the project files `names`
+  pub fn of_project_files(names: &[OsString], custom_kinds: &[ProjectKind]) -> Vec<ProjectKind> {
+    ProjectKind::iter()
+      .filter(|kind| !matches!(kind, ProjectKind::Custom { .. }))
+      .chain(custom_kinds.iter().cloned())
+      .filter(|kind| {
+        kind
+          .project_files()
+          .iter()
+          .any(|file| names.iter().any(|name| name == file.as_str()))
+      })
+      .collect()
+  }
+
+  /// Retrieve the common source code extensions
+  pub fn language_extensions(&self) -> Vec<String> {
+    match self {
+      Self::Rust => vec!["rs"],
+      Self::Go => vec!["go"],
+      Self::C => vec!["c", "h", "cc", "cpp", "cxx", "hh", "hxx", "hpp"],
+      Self::Node => vec!["js", "ts"],
+      Self::Maven => vec!["java"],
+      Self::Other | Self::Bookmark | Self::Dependency => vec![],
+      Self::Custom { language_exts, .. } => language_exts
+        .iter()
+        .map(|ext| ext.as_str())
+        .collect::<Vec<_>>(),
+    }
+    .iter()
+    .map(|s| s.to_string())
+    .collect::<Vec<_>>()
+  }
+}
+
+/// Sum the on-disk size of the files below `path`, skipping the `skipped` folders
+fn disk_usage(path: &Path, skipped: &[PathBuf]) -> u64 {
+  let Ok(entries) = std::fs::read_dir(path) else {
+    return 0;
+  };
+  let mut ret = 0;
+  for entry in entries.flatten() {
+    let Ok(metadata) = entry.metadata() else {
+      continue;
+    };
+    if metadata.is_dir() {
+      let dir = entry.path();
+      if !skipped.contains(&dir) {
+        ret += disk_usage(&dir, skipped);
+      }
+      continue;
+    }
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::MetadataExt;
+      // the allocated blocks, sparse and small files included
+      ret += metadata.blocks() * 512;
+    }
+    #[cfg(not(unix))]
+    {
+      ret += metadata.len();
+    }
+  }
+  ret
+}
+
+impl Display for ProjectKind {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
+
+/// A stable identifier of a project, computed from its path by [`Project::id`], so that the
+/// layers adding metadata to projects can refer to them cheaply
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::ProjectId;
+///
+/// assert_eq!(ProjectId::of("/dev/api"), ProjectId::of("/dev//api/"));
+/// assert_ne!(ProjectId::of("/dev/api"), ProjectId::of("/dev/api2"));
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ProjectId(u64);
+
+impl ProjectId {
+  /// Compute the identifier of the project in `path`, from its components so that equivalent
+  /// spellings such as `/dev/api/` and `/dev/./api` agree
+  pub fn of<P: AsRef<Path>>(path: P) -> Self {
+    // FNV-1a, as the output of `DefaultHasher` may change across Rust releases
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for component in path.as_ref().components() {
+      for byte in component.as_os_str().as_encoded_bytes().iter().chain(b"/") {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+      }
+    }
+    Self(hash)
+  }
+}
+
+impl Display for ProjectId {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:016x}", self.0)
+  }
+}
+
+/// Represent a discovered project.
+///
+/// Projects are equal when they share their [`Project::id`], whatever their files and metadata.
+/// Fields are only added along with their getters and [`ProjectBuilder`] methods, so that
+/// adding one doesn't break the library users.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Project {
+  #[serde(with = "crate::path_serde")]
+  path: PathBuf,
+  kinds: Vec<ProjectKind>,
+  #[serde(with = "crate::path_serde::vec")]
+  source_files: Vec<PathBuf>,
+  #[serde(with = "crate::path_serde::vec")]
+  project_files: Vec<PathBuf>,
+  /// A hash of the project file names and contents, recognizing the project once moved.
+  /// Kept before `moved_from` which is only set along with it, as skipped trailing fields
+  /// keep the cached array representation readable.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  identity: Option<String>,
+  /// The path the project was found at by the previous scan, if it moved since
+  #[serde(
+    default,
+    skip_serializing_if = "Option::is_none",
+    with = "crate::path_serde::option"
+  )]
+  moved_from: Option<PathBuf>,
+  /// A hash of the primary manifest contents, only computed for reports with `--hash`
+  /// so that it's never cached: it would shift the cached array when `moved_from` is skipped.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  manifest_hash: Option<String>,
+  /// The note attached by the user, only set for reports so that it's never cached either
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  note: Option<String>,
+  /// The size of the project folder in bytes, only computed for reports with `--du`
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  disk_usage: Option<u64>,
+  /// The metadata extracted by [`crate::ProjectFacet`]s by facet name, only for reports with
+  /// `--facets` or `--facet`
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  facets: BTreeMap<String, FacetValue>,
+}
+
+impl PartialEq for Project {
+  fn eq(&self, other: &Self) -> bool {
+    self.id() == other.id()
+  }
+}
+
+impl Eq for Project {}
+
+impl Hash for Project {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.id().hash(state);
+  }
+}
+
+impl Project {
+  /// Create a new [`Project`] from its kinds and files, see [`ProjectBuilder::build`] for their
+  /// order.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use std::path::PathBuf;
+  /// use pgrep_core::{Project, ProjectKind};
+  ///
+  /// let project = Project::new(
+  ///   "/dev/api",
+  ///   vec![ProjectKind::Node, ProjectKind::Rust, ProjectKind::Node],
+  ///   vec![],
+  ///   vec![PathBuf::from("/dev/api/package.json"), PathBuf::from("/dev/api/Cargo.toml")],
+  /// );
+  /// assert_eq!(project.kinds(), &vec![ProjectKind::Rust, ProjectKind::Node]);
+  /// assert_eq!(project.project_files()[0], PathBuf::from("/dev/api/Cargo.toml"));
+  /// ```
+  pub fn new<P: AsRef<Path>>(
+    path: P,
+    kinds: Vec<ProjectKind>,
+    source_files: Vec<PathBuf>,
+    project_files: Vec<PathBuf>,
+  ) -> Self {
+    Self::builder(path)
+      .kinds(kinds)
+      .source_files(source_files)
+      .project_files(project_files)
+      .build()
+  }
+
+  /// Start building a [`Project`] in the folder at `path`, see [`ProjectBuilder`]
+  pub fn builder<P: AsRef<Path>>(path: P) -> ProjectBuilder {
+    ProjectBuilder::new(path)
+  }
+
+  /// Retrieve the identifier of the project, derived from its path
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::{Project, ProjectId, ProjectKind};
+  ///
+  /// let api = Project::new("/dev/api", vec![ProjectKind::Rust], vec![], vec![]);
+  /// assert_eq!(api.id(), ProjectId::of("/dev/api"));
+  /// assert_eq!(api, Project::new("/dev/api/", vec![], vec![], vec![]));
+  /// ```
+  pub fn id(&self) -> ProjectId {
+    ProjectId::of(&self.path)
+  }
+
+  /// Hash the names and contents of the given project files, `None` if one cannot be read.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::Project;
+  ///
+  /// let manifest = std::env::temp_dir().join("pgrep-identity").join("Cargo.toml");
+  /// std::fs::create_dir_all(manifest.parent().unwrap()).unwrap();
+  /// std::fs::write(&manifest, "[package]").unwrap();
+  /// let identity = Project::compute_identity(&[manifest.clone()]);
+  /// assert!(identity.is_some());
+  /// assert_eq!(identity, Project::compute_identity(&[manifest]));
+  /// assert_eq!(Project::compute_identity(&[]), None);
+  /// ```
+  pub fn compute_identity(project_files: &[PathBuf]) -> Option<String> {
+    if project_files.is_empty() {
+      return None;
+    }
+    let mut files = project_files.iter().collect::<Vec<_>>();
+    files.sort_by_key(|file| file.file_name());
+    let mut hasher = DefaultHasher::new();
+    for file in files {
+      file.file_name().hash(&mut hasher);
+      std::fs::read(file).ok()?.hash(&mut hasher);
+    }
+    Some(format!("{:016x}", hasher.finish()))
+  }
+
+  /// Retrieve the hash identifying the project across moves, see [`Project::compute_identity`]
+  pub fn identity(&self) -> Option<&String> {
+    self.identity.as_ref()
+  }
+
+  /// Set the hash identifying the project across moves
+  pub fn set_identity(&mut self, identity: Option<String>) {
+    self.identity = identity;
+  }
+
+  /// Retrieve the manifest of the first kind of the project, such as its `Cargo.toml`
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use std::path::PathBuf;
+  /// use pgrep_core::{Project, ProjectKind};
+  ///
+  /// let project = Project::new(
+  ///   "/dev/alpha",
+  ///   vec![ProjectKind::Rust, ProjectKind::Node],
+  ///   vec![],
+  ///   vec![PathBuf::from("/dev/alpha/package.json"), PathBuf::from("/dev/alpha/Cargo.toml")],
+  /// );
+  /// assert_eq!(project.primary_manifest(), Some(&PathBuf::from("/dev/alpha/Cargo.toml")));
+  /// ```
+  pub fn primary_manifest(&self) -> Option<&PathBuf> {
+    let names = self
+      .kinds
+      .first()
+      .map(|kind| kind.project_files())
+      .unwrap_or_default();
+    self
+      .project_files
+      .iter()
+      .find(|file| {
+        file
+          .file_name()
+          .is_some_and(|fname| names.iter().any(|name| fname.eq_ignore_ascii_case(name)))
+      })
+      .or_else(|| self.project_files.first())
+  }
+
+  /// Hash the contents of the primary manifest, `None` if it cannot be read.
+  /// Identical checkouts have the same hash, whatever their names.
+  pub fn compute_manifest_hash(&self) -> Option<String> {
+    let content = std::fs::read(self.primary_manifest()?).ok()?;
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    Some(format!("{:016x}", hasher.finish()))
+  }
+
+  /// Retrieve the hash of the primary manifest, see [`Project::compute_manifest_hash`]
+  pub fn manifest_hash(&self) -> Option<&String> {
+    self.manifest_hash.as_ref()
+  }
+
+  /// Set the hash of the primary manifest
+  pub fn set_manifest_hash(&mut self, hash: Option<String>) {
+    self.manifest_hash = hash;
+  }
+
+  /// Compute the on-disk size of the project folder in bytes, skipping the build artifacts
+  /// of its kinds unless `artifacts` is set, see [`ProjectKind::artifact_dirs`].
+  ///
+  /// Symbolic links aren't followed, and entries which cannot be read are ignored.
+  pub fn compute_disk_usage(&self, artifacts: bool) -> u64 {
+    let skipped = match artifacts {
+      true => vec![],
+      false => self.artifact_dirs(),
+    };
+    disk_usage(&self.path, &skipped)
+  }
+
+  /// Retrieve the build artifact folders of the project kinds, such as `target` for Rust,
+  /// whether they exist or not
+  pub fn artifact_dirs(&self) -> Vec<PathBuf> {
+    let mut ret = vec![];
+    for dir in self.kinds.iter().flat_map(|kind| kind.artifact_dirs()) {
+      let dir = self.path.join(dir);
+      if !ret.contains(&dir) {
+        ret.push(dir);
+      }
+    }
+    ret
+  }
+
+  /// Retrieve the size of the project folder, see [`Project::compute_disk_usage`]
+  pub fn disk_usage(&self) -> Option<u64> {
+    self.disk_usage
+  }
+
+  /// Set the size of the project folder
+  pub fn set_disk_usage(&mut self, size: Option<u64>) {
+    self.disk_usage = size;
+  }
+
+  /// Retrieve the value of the facet named `name`, `None` when it wasn't extracted or
+  /// doesn't apply, see [`crate::FacetRegistry::extract`]
+  pub fn facet(&self, name: &str) -> Option<&FacetValue> {
+    self.facets.get(name)
+  }
+
+  /// Set the value of the facet named `name`, removing it when `None`
+  pub fn set_facet(&mut self, name: &str, value: Option<FacetValue>) {
+    match value {
+      Some(value) => self.facets.insert(name.to_string(), value),
+      None => self.facets.remove(name),
+    };
+  }
+
+  /// Retrieve the extracted facet values, by facet name
+  pub fn facets(&self) -> &BTreeMap<String, FacetValue> {
+    &self.facets
+  }
+
+  /// Retrieve the note attached by the user, see [`crate::Notes`]
+  pub fn note(&self) -> Option<&String> {
+    self.note.as_ref()
+  }
+
+  /// Set the note attached by the user
+  pub fn set_note(&mut self, note: Option<String>) {
+    self.note = note;
+  }
+
+  /// Retrieve the path the project was found at by the previous scan, if it moved since
+  pub fn moved_from(&self) -> Option<&PathBuf> {
+    self.moved_from.as_ref()
+  }
+
+  /// Record the path the project was found at by the previous scan
+  pub fn set_moved_from(&mut self, path: Option<PathBuf>) {
+    self.moved_from = path;
+  }
+
+  /// Retrieve the project name from it's path, lossily converted when it isn't valid UTF-8
+  pub fn name(&self) -> Option<String> {
+    let name = self.path.file_name()?;
+    Some(name.to_string_lossy().into_owned())
+  }
+
+  /// Retrieve the project path (folder)
+  pub fn path(&self) -> &PathBuf {
+    &self.path
+  }
+
+  /// Retrieve the project path (folder) as a mutable reference
+  pub fn path_mut(&mut self) -> &mut PathBuf {
+    &mut self.path
+  }
+
+  /// Retrieve the project kinds that were discovered using [`ProjectKind::project_files`]
+  pub fn kinds(&self) -> &Vec<ProjectKind> {
+    &self.kinds
+  }
+  /// Retrieve the kind best describing the project, to display it alone: the one most of the
+  /// project files belong to, the first one on ties, and [`ProjectKind::Other`] only when no
+  /// other kind was detected.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use std::path::PathBuf;
+  /// use pgrep_core::{Project, ProjectKind};
+  ///
+  /// let project = Project::new(
+  ///   "/dev/api",
+  ///   vec![ProjectKind::Rust, ProjectKind::Node, ProjectKind::Other],
+  ///   vec![],
+  ///   ["README.md", "package.json", "package.lock"]
+  ///     .map(|file| PathBuf::from("/dev/api").join(file))
+  ///     .to_vec(),
+  /// );
+  /// assert_eq!(project.primary_kind(), Some(&ProjectKind::Node));
+  /// ```
+  pub fn primary_kind(&self) -> Option<&ProjectKind> {
+    let confidence = |kind: &ProjectKind| {
+      let names = kind.project_files();
+      let files = self
+        .project_files
+        .iter()
+        .filter_map(|file| file.file_name())
+        .filter(|file| names.iter().any(|name| file.eq_ignore_ascii_case(name)))
+        .count();
+      (*kind != ProjectKind::Other, files)
+    };
+    // `max_by_key` keeps the last maximum
+    self.kinds.iter().rev().max_by_key(|kind| confidence(kind))
+  }
+
+  /// Retrieve the project kinds that were discovered using [`ProjectKind::project_files`]
+  /// as a mutable reference
+  pub fn kinds_mut(&mut self) -> &mut Vec<ProjectKind> {
+    &mut self.kinds
+  }
+
+  /// Retrieve the source code files that were discovered using [`ProjectKind::language_extensions`]
+  pub fn source_files(&self) -> &Vec<PathBuf> {
+    &self.source_files
+  }
+  /// Retrieve the source code files that were discovered using [`ProjectKind::language_extensions`]
+  /// as a mutable reference
+  pub fn source_files_mut(&mut self) -> &mut Vec<PathBuf> {
+    &mut self.source_files
+  }
+
+  /// Retrieve the project files files that were discovered using [`ProjectKind::project_files`]
+  pub fn project_files(&self) -> &Vec<PathBuf> {
+    &self.project_files
+  }
+  /// Retrieve the project files files that were discovered using [`ProjectKind::project_files`]
+  /// as a mutable reference
+  pub fn project_files_mut(&mut self) -> &mut Vec<PathBuf> {
+    &mut self.project_files
+  }
+
+  /// Estimate the bytes this project holds in memory, mostly its file paths
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::Project;
+  ///
+  /// let mut project = Project::builder("/dev/api")
+  ///   .source_files((0..100).map(|id| format!("/dev/api/src/file_{}.rs", id).into()))
+  ///   .build();
+  /// let usage = project.memory_usage();
+  /// project.drop_source_files();
+  /// assert!(project.memory_usage() < usage / 10);
+  /// ```
+  pub fn memory_usage(&self) -> usize {
+    let path = |path: &PathBuf| std::mem::size_of::<PathBuf>() + path.capacity();
+    let string = |s: &String| std::mem::size_of::<String>() + s.capacity();
+    std::mem::size_of::<Self>()
+      + path(&self.path)
+      + self.kinds.capacity() * std::mem::size_of::<ProjectKind>()
+      + self.source_files.iter().map(path).sum::<usize>()
+      + self.project_files.iter().map(path).sum::<usize>()
+      + self.moved_from.as_ref().map_or(0, path)
+      + [&self.identity, &self.manifest_hash, &self.note]
+        .into_iter()
+        .flatten()
+        .map(string)
+        .sum::<usize>()
+      + self
+        .facets
+        .iter()
+        .map(|(name, value)| string(name) + value.to_string().len())
+        .sum::<usize>()
+  }
+
+  /// Drop the source files, the bulk of a project in memory, keeping a light representation
+  /// for long-running processes that don't need them, see [`Self::memory_usage`]
+  pub fn drop_source_files(&mut self) {
+    self.source_files = vec![];
+  }
+}
+
+/// Builds a [`Project`] field by field, the other ones keeping their default value
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::{FacetValue, Project, ProjectKind};
+///
+/// let project = Project::builder("/dev/api")
+///   .kind(ProjectKind::Rust)
+///   .project_file("/dev/api/Cargo.toml")
+///   .source_file("/dev/api/src/main.rs")
+///   .facet("loc", FacetValue::Count(42))
+///   .build();
+/// assert_eq!(project.kinds(), &vec![ProjectKind::Rust]);
+/// assert_eq!(project.facet("loc"), Some(&FacetValue::Count(42)));
+/// assert_eq!(project.note(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProjectBuilder {
+  project: Project,
+}
+
+impl ProjectBuilder {
+  /// Start building a project in the folder at `path`
+  pub fn new<P: AsRef<Path>>(path: P) -> Self {
+    Self {
+      project: Project {
+        path: path.as_ref().to_path_buf(),
+        kinds: vec![],
+        source_files: vec![],
+        project_files: vec![],
+        identity: None,
+        moved_from: None,
+        manifest_hash: None,
+        note: None,
+        disk_usage: None,
+        facets: BTreeMap::new(),
+      },
+    }
+  }
+
+  /// Add a kind, see [`Project::kinds`]
+  pub fn kind(mut self, kind: ProjectKind) -> Self {
+    self.project.kinds.push(kind);
+    self
+  }
+
+  /// Add several kinds, see [`Project::kinds`]
+  pub fn kinds<I: IntoIterator<Item = ProjectKind>>(mut self, kinds: I) -> Self {
+    self.project.kinds.extend(kinds);
+    self
+  }
+
+  /// Add a source file, see [`Project::source_files`]
+  pub fn source_file<P: AsRef<Path>>(mut self, file: P) -> Self {
+    self.project.source_files.push(file.as_ref().to_path_buf());
+    self
+  }
+
+  /// Add several source files, see [`Project::source_files`]
+  pub fn source_files<I: IntoIterator<Item = PathBuf>>(mut self, files: I) -> Self {
+    self.project.source_files.extend(files);
+    self
+  }
+
+  /// Add a project file, see [`Project::project_files`]
+  pub fn project_file<P: AsRef<Path>>(mut self, file: P) -> Self {
+    self.project.project_files.push(file.as_ref().to_path_buf());
+    self
+  }
+
+  /// Add several project files, see [`Project::project_files`]
+  pub fn project_files<I: IntoIterator<Item = PathBuf>>(mut self, files: I) -> Self {
+    self.project.project_files.extend(files);
+    self
+  }
+
+  /// Set the hash recognizing the project once moved, see [`Project::identity`]
+  pub fn identity(mut self, identity: Option<String>) -> Self {
+    self.project.identity = identity;
+    self
+  }
+
+  /// Set the path the project was found at before moving, see [`Project::moved_from`]
+  pub fn moved_from(mut self, path: Option<PathBuf>) -> Self {
+    self.project.moved_from = path;
+    self
+  }
+
+  /// Set the hash of the primary manifest, see [`Project::manifest_hash`]
+  pub fn manifest_hash(mut self, hash: Option<String>) -> Self {
+    self.project.manifest_hash = hash;
+    self
+  }
+
+  /// Set the note attached by the user, see [`Project::note`]
+  pub fn note(mut self, note: Option<String>) -> Self {
+    self.project.note = note;
+    self
+  }
+
+  /// Set the size of the project folder in bytes, see [`Project::disk_usage`]
+  pub fn disk_usage(mut self, size: Option<u64>) -> Self {
+    self.project.disk_usage = size;
+    self
+  }
+
+  /// Set the value of the facet named `name`, see [`Project::facet`]
+  pub fn facet(mut self, name: &str, value: FacetValue) -> Self {
+    self.project.facets.insert(name.to_string(), value);
+    self
+  }
+
+  /// Build the project.
+  ///
+  /// The kinds are sorted in declaration order without duplicates, and the files by path, so
+  /// that the same project always looks the same whatever order its files were found in.
+  pub fn build(mut self) -> Project {
+    let project = &mut self.project;
+    project.kinds.sort();
+    project.kinds.dedup();
+    project.source_files.sort();
+    project.project_files.sort();
+    self.project
+  }
+}
+
+/// Run `f` over `items` split in one chunk per available core, returning the result of every
+/// chunk in order
+fn par_chunks<T: Sync, R: Send, F: Fn(&[T]) -> R + Sync>(items: &[T], f: F) -> Vec<R> {
+  let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+  let chunk = items.len().div_ceil(threads).max(1);
+  std::thread::scope(|scope| {
+    let handles = items
+      .chunks(chunk)
+      .map(|chunk| scope.spawn(|| f(chunk)))
+      .collect::<Vec<_>>();
+    handles
+      .into_iter()
+      .map(|handle| handle.join().unwrap())
+      .collect()
+  })
+}
+
+/// A project root found by [`detect_projects`], before its source files are attributed
+struct DetectedRoot {
+  path: PathBuf,
+  /// The path relative to the scanned folder
+  relative: PathBuf,
+  kinds: Vec<ProjectKind>,
+  project_files: Vec<PathBuf>,
+  /// The lowercased [`ProjectKind::language_extensions`] of its kinds
+  extensions: HashSet<String>,
+}
+
+/// The project roots found by [`detect_projects`], as a trie keyed on their path components
+#[derive(Debug, Default)]
+struct RootTrie {
+  /// The index of the root ending at this node
+  root: Option<usize>,
+  children: HashMap<OsString, RootTrie>,
+}
+
+impl RootTrie {
+  /// Add the root `id` at `path`
+  fn insert(&mut self, path: &Path, id: usize) {
+    let node = path.components().fold(self, |node, component| {
+      node
+        .children
+        .entry(component.as_os_str().to_os_string())
+        .or_default()
+    });
+    node.root = Some(id);
+  }
+
+  /// Find the outermost root holding `path`, walking one node per component
+  fn find(&self, path: &Path) -> Option<usize> {
+    let mut node = self;
+    for component in path.components() {
+      if node.root.is_some() {
+        break;
+      }
+      node = node.children.get(component.as_os_str())?;
+    }
+    node.root
+  }
+}
+
+/// Detect all the discovered [`Project`] roots from a given folder scan.
+///
+/// Detection runs in two phases, each spread over the available cores:
+/// - the scanned directories holding [`ProjectKind::project_files`] become project roots, the
+///   outermost one winning when they are nested;
+/// - the files of every directory are attributed to the root holding it, found by walking a
+///   trie of the root path components, as source files when their extension is one of the
+///   [`ProjectKind::language_extensions`] of the project.
+///
+/// The projects are sorted by path, see [`Project::new`] for the order of their kinds and files.
+#[cfg_attr(
+  feature = "tracing",
+  tracing::instrument(name = "detect", skip_all, fields(folder = %scan.path().display()))
+)]
+pub fn detect_projects(scan: &FolderScan, custom_kinds: Vec<ProjectKind>) -> Vec<Project> {
+  let mut manifests: HashMap<String, Vec<ProjectKind>> = HashMap::new();
+  for kind in ProjectKind::iter().chain(custom_kinds) {
+    for project_file in kind.project_files() {
+      manifests
+        .entry(project_file.to_lowercase())
+        .or_default()
+        .push(kind.clone());
+    }
+  }
+  let mut roots = par_chunks(scan.dirs(), |dirs| {
+    dirs
+      .iter()
+      .filter_map(|dir| {
+        let mut root = DetectedRoot {
+          path: scan.path().join(&dir.path),
+          relative: dir.path.clone(),
+          kinds: vec![],
+          project_files: vec![],
+          extensions: HashSet::new(),
+        };
+        for file in &dir.files {
+          let Some(kinds) = manifests.get(&file.to_string_lossy().to_lowercase()) else {
+            continue;
+          };
+          // several project files of a kind, such as `Cargo.toml` and `Cargo.lock`, are common
+          for kind in kinds {
+            if !root.kinds.contains(kind) {
+              root.kinds.push(kind.clone());
+            }
+          }
+          root.project_files.push(root.path.join(file));
+        }
+        root.extensions = root
+          .kinds
+          .iter()
+          .flat_map(|kind| kind.language_extensions())
+          .map(|ext| ext.to_lowercase())
+          .collect();
+        (!root.kinds.is_empty()).then_some(root)
+      })
+      .collect::<Vec<_>>()
+  })
+  .into_iter()
+  .flatten()
+  .collect::<Vec<_>>();
+  // outermost roots first, so that nested ones are found inside them and left out
+  roots.sort_by(|a, b| a.path.cmp(&b.path));
+  let mut trie = RootTrie::default();
+  let mut outermost = vec![];
+  for root in roots {
+    if trie.find(&root.relative).is_none() {
+      trie.insert(&root.relative, outermost.len());
+      outermost.push(root);
+    }
+  }
+  let roots = outermost;
+  let attributed = par_chunks(scan.dirs(), |dirs| {
+    let mut ret = vec![vec![]; roots.len()];
+    for dir in dirs {
+      let Some(id) = trie.find(&dir.path) else {
+        continue;
+      };
+      let path = scan.path().join(&dir.path);
+      let extensions = &roots[id].extensions;
+      ret[id].extend(
+        dir
+          .files
+          .iter()
+          .filter(|file| {
+            Path::new(file)
+              .extension()
+              .is_some_and(|ext| extensions.contains(&ext.to_string_lossy().to_lowercase()))
+          })
+          .map(|file| path.join(file)),
+      );
+    }
+    ret
+  });
+  let mut source_files = vec![vec![]; roots.len()];
+  for chunk in attributed {
+    for (files, found) in source_files.iter_mut().zip(chunk) {
+      files.extend(found);
+    }
+  }
+  roots
+    .into_iter()
+    .zip(source_files)
+    .map(|(root, source_files)| {
+      let identity = Project::compute_identity(&root.project_files);
+      Project::builder(&root.path)
+        .kinds(root.kinds)
+        .source_files(source_files)
+        .project_files(root.project_files)
+        .identity(identity)
+        .build()
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{detect_projects, FolderScan, ScanOptions};
+  use crate::test_support::temp_tree;
+
+  /// The files of `scan`, relative to the scanned folder and sorted
+  fn files(scan: &FolderScan) -> Vec<String> {
+    let mut ret = scan
+      .files()
+      .filter(|file| file.file_name().is_some_and(|name| name != ".pgrepignore"))
+      .map(|file| {
+        let file = file.strip_prefix(scan.path()).unwrap();
+        file.to_string_lossy().replace('\\', "/")
+      })
+      .collect::<Vec<_>>();
+    ret.sort();
+    ret
+  }
+
+  #[test]
+  fn nested_ignore_files_are_honored() {
+    let root = temp_tree(
+      "scan-ignore",
+      &[
+        (".pgrepignore", "fixtures/\n*.gen.rs\n!keep.gen.rs"),
+        ("api/Cargo.toml", ""),
+        ("api/schema.gen.rs", ""),
+        ("api/keep.gen.rs", ""),
+        ("api/fixtures/demo/Cargo.toml", ""),
+        ("web/.pgrepignore", "!fixtures/\nlegacy"),
+        ("web/fixtures/demo/package.json", ""),
+        ("web/legacy/package.json", ""),
+        ("legacy/package.json", ""),
+      ],
+    );
+    let scan = FolderScan::new(&root).unwrap();
+    assert_eq!(
+      files(&scan),
+      [
+        "api/Cargo.toml",
+        "api/keep.gen.rs",
+        "legacy/package.json",
+        "web/fixtures/demo/package.json",
+      ]
+    );
+    std::fs::remove_dir_all(root).unwrap();
+  }
+
+  #[test]
+  fn stop_markers_prevent_descending_and_detection() {
+    let root = temp_tree(
+      "scan-stop",
+      &[
+        ("api/Cargo.toml", ""),
+        ("templates/.pgrep-stop", ""),
+        ("templates/Cargo.toml", ""),
+        ("templates/node/package.json", ""),
+      ],
+    );
+    let scan = FolderScan::new(&root).unwrap();
+    assert_eq!(files(&scan), ["api/Cargo.toml"]);
+    let projects = detect_projects(&scan, vec![]);
+    let paths = projects
+      .iter()
+      .map(|p| p.path().as_path())
+      .collect::<Vec<_>>();
+    assert_eq!(paths, [root.join("api").as_path()]);
+    assert!(!paths
+      .iter()
+      .any(|path| path.starts_with(root.join("templates"))));
+    std::fs::remove_dir_all(root).unwrap();
+  }
+
+  #[test]
+  fn listed_files_honor_ignore_files_and_stop_markers() {
+    let root = temp_tree(
+      "scan-listed",
+      &[
+        (".pgrepignore", "fixtures/"),
+        ("api/Cargo.toml", ""),
+        ("api/fixtures/demo/Cargo.toml", ""),
+        ("web/.pgrepignore", "!fixtures/"),
+        ("web/fixtures/demo/package.json", ""),
+        ("templates/.pgrep-stop", ""),
+        ("templates/node/package.json", ""),
+      ],
+    );
+    let listed = [
+      "api/Cargo.toml",
+      "api/fixtures/demo/Cargo.toml",
+      "web/fixtures/demo/package.json",
+      "templates/node/package.json",
+    ];
+    let scan = FolderScan::from_files(&root, listed.map(Into::into), &ScanOptions::default());
+    assert_eq!(
+      files(&scan),
+      ["api/Cargo.toml", "web/fixtures/demo/package.json"]
+    );
+    std::fs::remove_dir_all(root).unwrap();
+  }
+}
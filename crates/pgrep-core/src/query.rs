@@ -0,0 +1,1525 @@
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use strum::IntoEnumIterator;
+
+use crate::{normalize_path, CiSystem, FacetValue, Project};
+
+/// Some part of a [`Query`] expression
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Part {
+  /// An optional character. Corresponds to `?`
+  OptionalChar,
+  /// A required character. Corresponds to `_`
+  RequiredChar,
+  /// Any string. Corresponds to `*`
+  AnyStr,
+  /// A contiguous stream of integers. Corresponds to `#`
+  Integer,
+  /// A fixed-length string
+  Fixed(String),
+  /// A single character among a set of inclusive ranges. Corresponds to `[a-z_]` or `[!0-9]`
+  Class {
+    /// Whether the class matches any character *outside* of the ranges
+    negated: bool,
+    /// The inclusive character ranges, a single character being a `(c, c)` range
+    ranges: Vec<(char, char)>,
+  },
+}
+
+/// A filter on a project property, written `name:value` in a [`Query`] expression
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Selector {
+  /// `ci:<system>` keeps the projects configured for a CI system, `ci:none` the ones without
+  Ci(Option<CiSystem>),
+  /// `kind:<name>` keeps the projects of a built-in or custom kind, ignoring case
+  Kind(String),
+  /// `name:<pattern>` keeps the projects whose folder name matches the pattern
+  Name(Box<Query>),
+  /// `path:<pattern>` keeps the projects whose full path matches the pattern, a leading `~`
+  /// standing for the home folder
+  Path(Box<Query>),
+}
+
+impl Selector {
+  /// The selector names, written before the ':' of a selector word
+  pub const NAMES: [&'static str; 4] = ["ci", "kind", "name", "path"];
+
+  /// Retrieve the selector name, written before the ':' of its word
+  pub fn name(&self) -> &'static str {
+    match self {
+      Self::Ci(..) => "ci",
+      Self::Kind(..) => "kind",
+      Self::Name(..) => "name",
+      Self::Path(..) => "path",
+    }
+  }
+
+  /// Parse the query word starting at byte `offset` of `expr`, `None` when it isn't a selector
+  fn parse(expr: &str, word: &str, offset: usize) -> Result<Option<Self>, QueryParseError> {
+    let Some((name, value)) = word.split_once(':') else {
+      return Ok(None);
+    };
+    match name.to_ascii_lowercase().as_str() {
+      "ci" if value.eq_ignore_ascii_case("none") => Ok(Some(Self::Ci(None))),
+      "ci" => match CiSystem::from_name(value) {
+        Some(ci) => Ok(Some(Self::Ci(Some(ci)))),
+        None => {
+          let known = CiSystem::iter()
+            .map(|ci| ci.name())
+            .collect::<Vec<_>>()
+            .join(", ");
+          let message = format!("unknown CI system '{}', expected none or {}", value, known);
+          Err(QueryParseError::new(expr, offset + name.len() + 1, message))
+        }
+      },
+      "kind" | "name" | "path" if value.is_empty() => Err(QueryParseError::new(
+        expr,
+        offset + name.len() + 1,
+        format!("missing value after '{}:'", name),
+      )),
+      "kind" => Ok(Some(Self::Kind(value.to_string()))),
+      "name" | "path" => {
+        // a leading '~' stands for the home folder in path patterns
+        let in_home = value == "~" || value.starts_with("~/");
+        let home = match name.eq_ignore_ascii_case("path") && in_home {
+          true => crate::config::expand_path("~")
+            .ok()
+            .map(|home| format!("{}{}", home.display(), &value[1..])),
+          false => None,
+        };
+        let pattern = Query::parse_pattern(home.as_deref().unwrap_or(value)).map_err(|e| {
+          let position = match home {
+            Some(_) => 0,
+            None => e.position(),
+          };
+          QueryParseError::new(expr, offset + name.len() + 1 + position, e.message())
+        })?;
+        Ok(Some(match name.to_ascii_lowercase().as_str() {
+          "name" => Self::Name(Box::new(pattern)),
+          _ => Self::Path(Box::new(pattern)),
+        }))
+      }
+      _ => Ok(None),
+    }
+  }
+
+  /// Check whether `project` passes this filter
+  pub fn selects(&self, project: &Project) -> bool {
+    match self {
+      Self::Ci(system) => {
+        let ci = match project.facet("ci") {
+          Some(FacetValue::List(names)) => names.clone(),
+          _ => CiSystem::detect(project.path())
+            .iter()
+            .map(|ci| ci.to_string())
+            .collect(),
+        };
+        match system {
+          Some(system) => ci.iter().any(|name| name == system.name()),
+          None => ci.is_empty(),
+        }
+      }
+      Self::Kind(name) => project
+        .kinds()
+        .iter()
+        .any(|kind| kind.name().eq_ignore_ascii_case(name)),
+      Self::Name(pattern) => project.name().is_some_and(|name| pattern.matches(name)),
+      Self::Path(pattern) => pattern.matches(normalize_path(project.path())),
+    }
+  }
+}
+
+impl Display for Selector {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Ci(None) => write!(f, "ci:none"),
+      Self::Ci(Some(system)) => write!(f, "ci:{}", system.name()),
+      Self::Kind(kind) => write!(f, "kind:{}", kind),
+      Self::Name(pattern) | Self::Path(pattern) => write!(f, "{}:{}", self.name(), pattern),
+    }
+  }
+}
+
+/// Writes the part as it is written in an expression, escaping the wildcards of fixed strings
+impl Display for Part {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::OptionalChar => write!(f, "?"),
+      Self::RequiredChar => write!(f, "_"),
+      Self::AnyStr => write!(f, "*"),
+      Self::Integer => write!(f, "#"),
+      Self::Fixed(s) => {
+        let mut word = String::new();
+        let last = s.chars().count().saturating_sub(1);
+        for (i, ch) in s.chars().enumerate() {
+          // a leading '@' would name a saved query, a ':' after a selector name a selector, and
+          // the whitespace around the pattern is trimmed
+          let escaped = match ch {
+            '?' | '_' | '*' | '#' | '[' | ']' | '\\' | '^' | '$' => true,
+            '@' => word.is_empty(),
+            ch if ch.is_whitespace() => i == 0 || i == last,
+            ':' => Selector::NAMES.contains(&word.to_ascii_lowercase().as_str()),
+            _ => false,
+          };
+          if escaped {
+            write!(f, "\\")?;
+          }
+          write!(f, "{}", ch)?;
+          match ch {
+            ' ' => word.clear(),
+            ch => word.push(ch),
+          }
+        }
+        Ok(())
+      }
+      Self::Class { negated, ranges } => {
+        write!(f, "[")?;
+        if *negated {
+          write!(f, "!")?;
+        }
+        let escape = |ch: char| match ch {
+          ']' | '\\' | '-' | '!' | '^' => format!("\\{}", ch),
+          ch => ch.to_string(),
+        };
+        for (from, to) in ranges {
+          match from == to {
+            true => write!(f, "{}", escape(*from))?,
+            false => write!(f, "{}-{}", escape(*from), escape(*to))?,
+          }
+        }
+        write!(f, "]")
+      }
+    }
+  }
+}
+
+/// An anchor of a [`Query`] name pattern, see [`QueryVisitor::visit_anchor`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Anchor {
+  /// A leading '^'
+  Start,
+  /// A trailing '$'
+  End,
+}
+
+/// Walks the syntax tree of a parsed [`Query`], to inspect or validate it without matching
+/// projects. Every method does nothing by default, except [`QueryVisitor::visit_query`] and
+/// [`QueryVisitor::visit_selector`] which walk their children, see [`walk_query`] and
+/// [`walk_selector`].
+///
+/// The name pattern and the selectors are all required to match, so the tree has no operators.
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::{Part, Query, QueryVisitor, Selector};
+///
+/// /// Collect the fixed strings of the name patterns
+/// #[derive(Default)]
+/// struct Literals(Vec<String>);
+///
+/// impl QueryVisitor for Literals {
+///   fn visit_part(&mut self, part: &Part) {
+///     if let Part::Fixed(s) = part {
+///       self.0.push(s.clone());
+///     }
+///   }
+/// }
+///
+/// let query = "api-# kind:rust name:web*".parse::<Query>().unwrap();
+/// let mut literals = Literals::default();
+/// literals.visit_query(&query);
+/// assert_eq!(literals.0, ["api-", "web"]);
+/// ```
+pub trait QueryVisitor {
+  /// Visit a query, or the pattern of a `name:` or `path:` selector
+  fn visit_query(&mut self, query: &Query) {
+    walk_query(self, query);
+  }
+
+  /// Visit an anchor of a name pattern, before its first part or after its last one
+  fn visit_anchor(&mut self, _anchor: Anchor) {}
+
+  /// Visit a part of a name pattern
+  fn visit_part(&mut self, _part: &Part) {}
+
+  /// Visit a project property filter
+  fn visit_selector(&mut self, selector: &Selector) {
+    walk_selector(self, selector);
+  }
+}
+
+/// Visit the anchors and parts of the `query` name pattern in order, then its selectors
+pub fn walk_query<V: QueryVisitor + ?Sized>(visitor: &mut V, query: &Query) {
+  if query.is_anchored_start() {
+    visitor.visit_anchor(Anchor::Start);
+  }
+  for part in query.parts() {
+    visitor.visit_part(part);
+  }
+  if query.is_anchored_end() {
+    visitor.visit_anchor(Anchor::End);
+  }
+  for selector in query.selectors() {
+    visitor.visit_selector(selector);
+  }
+}
+
+/// Visit the pattern of a `name:` or `path:` selector, other selectors having no children
+pub fn walk_selector<V: QueryVisitor + ?Sized>(visitor: &mut V, selector: &Selector) {
+  match selector {
+    Selector::Name(pattern) | Selector::Path(pattern) => visitor.visit_query(pattern),
+    Selector::Ci(..) | Selector::Kind(..) => {}
+  }
+}
+
+/// Represents a match against a string and a [`Query`]. This is an [`Option`] equivalent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PartMatch {
+  /// The part was matched successfully, and the cursor must be advanced by as much chars
+  Success(usize),
+  /// The part failed to match
+  Failure,
+}
+
+impl PartMatch {
+  /// Helper to find if the part matched successfully
+  pub fn is_success(&self) -> bool {
+    match self {
+      Self::Success(..) => true,
+      Self::Failure => false,
+    }
+  }
+
+  /// Helper to find if the part did NOT match
+  pub fn is_failure(&self) -> bool {
+    match self {
+      Self::Success(..) => false,
+      Self::Failure => true,
+    }
+  }
+}
+
+/// How a [`Query`] is applied to a subject
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchMode {
+  /// The query must match the whole subject
+  #[default]
+  Anchored,
+  /// The query may match any substring of the subject, unless anchored with `^` and/or `$`
+  Partial,
+}
+
+/// How well a [`Query`] matched a project, the greater the better
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, strum::Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum MatchScore {
+  /// The project path, or one of its components, matched but not its name
+  PathComponent,
+  /// The name contains the query
+  NameContains,
+  /// The name starts with the query
+  NamePrefix,
+  /// The name is exactly the query
+  Exact,
+}
+
+/// The order in which matching projects are reported
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+  /// Best matches first, see [`MatchScore`]
+  #[default]
+  Score,
+  /// By project name
+  Name,
+  /// By project path
+  Path,
+  /// Largest projects first, see --du
+  Size,
+}
+
+/// The diagnostic produced when a [`Query`] expression cannot be parsed
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::{Query, QueryParseError};
+///
+/// let err = "my[a-".parse::<Query>().unwrap_err();
+/// assert_eq!(err.position(), 2);
+/// assert_eq!(err.render(), "unterminated character class at position 2\n  my[a-\n    ^");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QueryParseError {
+  /// The parsed expression
+  expr: String,
+  /// The byte offset of the offending character in `expr`
+  position: usize,
+  /// What went wrong
+  message: String,
+}
+
+impl QueryParseError {
+  /// Create a new parse error
+  pub fn new<E: Into<String>, M: Into<String>>(expr: E, position: usize, message: M) -> Self {
+    Self {
+      expr: expr.into(),
+      position,
+      message: message.into(),
+    }
+  }
+
+  /// Retrieve the expression that failed to parse
+  pub fn expr(&self) -> &String {
+    &self.expr
+  }
+
+  /// Retrieve the byte offset of the offending character
+  pub fn position(&self) -> usize {
+    self.position
+  }
+
+  /// Retrieve the error message
+  pub fn message(&self) -> &String {
+    &self.message
+  }
+
+  /// Render the error with the expression and a caret pointing at the offending character
+  pub fn render(&self) -> String {
+    let column = self
+      .expr
+      .char_indices()
+      .take_while(|(offset, _)| *offset < self.position)
+      .count();
+    format!(
+      "{} at position {}\n  {}\n  {}^",
+      self.message,
+      self.position,
+      self.expr,
+      " ".repeat(column)
+    )
+  }
+}
+
+impl Display for QueryParseError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{} at position {} in '{}'",
+      self.message, self.position, self.expr
+    )
+  }
+}
+
+impl std::error::Error for QueryParseError {}
+
+/// A glob-like pattern for filtering [`crate::project::Project`]s
+///
+/// It supports the following wildcards:
+///   - '?': an optional character
+///   - '_': a required character
+///   - '#': a required digit
+///   - '*': any string
+///   - '[abc]', '[a-z]': one character of the set, '[!abc]' negates it
+///   - '\\': escapes the next character, e.g. '\\_' for a literal underscore
+///
+/// In [`MatchMode::Partial`], a leading '^' and/or trailing '$' anchor the query
+/// to the start and/or end of the subject.
+///
+/// A query containing '/' is meant to be matched against full project paths,
+/// see [`Query::matches_path`].
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::Query;
+///
+/// let q = "abc*".parse::<Query>().unwrap(); // accepts 'abcdefg' and 'abc' but not 'zabc'
+/// let q = "*abc".parse::<Query>().unwrap(); // accepts '123abc' and 'abc' but not 'abcz'
+/// let q = "abc#".parse::<Query>().unwrap(); // accepts 'abc1' and 'abc2345' but not 'abcz' or 'abc'
+/// let q = "abc?".parse::<Query>().unwrap(); // accepts 'abc' and 'abca' but not 'abczd'
+/// let q = "abc_".parse::<Query>().unwrap(); // accepts 'abc1' and 'abcz' but not 'abc' or 'abczz'
+/// let q = "v[0-9]".parse::<Query>().unwrap(); // accepts 'v1' but not 'vx' or 'v'
+/// let q = "my\\_app".parse::<Query>().unwrap(); // accepts 'my_app' but not 'myxapp'
+/// ```
+///
+/// ```
+/// use pgrep_core::{MatchMode, Query};
+///
+/// let q = "api".parse::<Query>().unwrap().with_mode(MatchMode::Partial);
+/// assert!(q.matches("my-api-server"));
+/// let q = "^api".parse::<Query>().unwrap().with_mode(MatchMode::Partial);
+/// assert!(!q.matches("my-api-server"));
+/// assert!(q.matches("api-server"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Query {
+  /// The original expression
+  expr: String,
+  /// The parsed parts
+  parts: Vec<Part>,
+  /// Whether the expression starts with '^'
+  anchored_start: bool,
+  /// Whether the expression ends with '$'
+  anchored_end: bool,
+  /// How the query is applied
+  mode: MatchMode,
+  /// Whether the query targets full paths rather than path components
+  match_path: bool,
+  /// The project property filters, see [`Selector`]
+  selectors: Vec<Selector>,
+}
+
+impl Query {
+  /// Match a single fixed-width part at the given character position.
+  ///
+  /// # Arguments
+  ///
+  /// `p` - The part to match, variable-width parts always fail
+  /// `subject` - The characters to match against
+  /// `ch_id` - The current subject character being matched against
+  fn match_part(p: &Part, subject: &[char], ch_id: usize) -> PartMatch {
+    match p {
+      Part::RequiredChar if ch_id < subject.len() => PartMatch::Success(ch_id + 1),
+      Part::Fixed(s) => {
+        let mut ch_id = ch_id;
+        for s_ch in s.chars() {
+          match subject.get(ch_id) {
+            Some(e_ch) if Self::same_char(s_ch, *e_ch) => ch_id += 1,
+            _ => return PartMatch::Failure,
+          }
+        }
+        PartMatch::Success(ch_id)
+      }
+      Part::Class { negated, ranges } => match subject.get(ch_id) {
+        Some(ch) => {
+          let lower = ch.to_lowercase().next().unwrap_or(*ch);
+          let upper = ch.to_uppercase().next().unwrap_or(*ch);
+          let found = ranges.iter().any(|(from, to)| {
+            [*ch, lower, upper]
+              .iter()
+              .any(|candidate| from <= candidate && candidate <= to)
+          });
+          match found != *negated {
+            true => PartMatch::Success(ch_id + 1),
+            false => PartMatch::Failure,
+          }
+        }
+        None => PartMatch::Failure,
+      },
+      _ => PartMatch::Failure,
+    }
+  }
+
+  /// Case-insensitive character comparison
+  fn same_char(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+  }
+
+  /// The positions where `segment`, which holds no [`Part::AnyStr`], can end when matched
+  /// from the character position `ch_id`, in ascending order
+  fn segment_ends(segment: &[Part], subject: &[char], ch_id: usize) -> Vec<usize> {
+    let mut ends = vec![ch_id];
+    for part in segment {
+      let mut next = vec![];
+      for &ch_id in &ends {
+        match part {
+          Part::OptionalChar => {
+            next.push(ch_id);
+            if ch_id < subject.len() {
+              next.push(ch_id + 1);
+            }
+          }
+          Part::Integer => {
+            let digits = subject[ch_id.min(subject.len())..]
+              .iter()
+              .take_while(|ch| ch.is_numeric())
+              .count();
+            next.extend((1..=digits).map(|len| ch_id + len));
+          }
+          part => {
+            if let PartMatch::Success(end) = Self::match_part(part, subject, ch_id) {
+              next.push(end);
+            }
+          }
+        }
+      }
+      next.sort_unstable();
+      next.dedup();
+      ends = next;
+      if ends.is_empty() {
+        break;
+      }
+    }
+    ends
+  }
+
+  /// Internal matcher: check if `parts` match the `subject` starting at the character position
+  /// `ch_id`.
+  ///
+  /// When `end_free` is set, the parts may match a prefix of the remaining subject.
+  ///
+  /// The subject and the segments of parts between '*' are walked with two cursors, without
+  /// ever going back: as a '*' can absorb anything, each segment is matched at the earliest
+  /// position after the previous one and ends as early as it can. Only the last segment,
+  /// unless `end_free` is set, must end with the subject.
+  fn match_parts(parts: &[Part], subject: &[char], ch_id: usize, end_free: bool) -> bool {
+    let mut segments = parts.split(|part| *part == Part::AnyStr);
+    let first = segments.next().unwrap_or_default();
+    let ends = Self::segment_ends(first, subject, ch_id);
+    let mut ch_id = match parts.contains(&Part::AnyStr) {
+      true => match ends.first() {
+        Some(end) => *end,
+        None => return false,
+      },
+      false => return (end_free && !ends.is_empty()) || ends.contains(&subject.len()),
+    };
+    let mut segments = segments.peekable();
+    while let Some(segment) = segments.next() {
+      let last = segments.peek().is_none();
+      if last && !end_free {
+        return (ch_id..=subject.len())
+          .any(|start| Self::segment_ends(segment, subject, start).contains(&subject.len()));
+      }
+      let found = (ch_id..=subject.len())
+        .find_map(|start| Self::segment_ends(segment, subject, start).first().copied());
+      match found {
+        Some(end) => ch_id = end,
+        None => return false,
+      }
+    }
+    true
+  }
+
+  /// Change how this query is applied
+  pub fn with_mode(mut self, mode: MatchMode) -> Self {
+    self.mode = mode;
+    self
+  }
+
+  /// Retrieve how this query is applied
+  pub fn mode(&self) -> MatchMode {
+    self.mode
+  }
+
+  /// Force matching against full project paths, even without '/' in the expression
+  pub fn with_match_path(mut self, match_path: bool) -> Self {
+    self.match_path |= match_path;
+    self
+  }
+
+  /// Check whether this query targets full, '/'-separated, project paths
+  /// instead of project names and path components.
+  ///
+  /// This is implied by a '/' in the expression.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::Query;
+  ///
+  /// assert!("*/work/api*".parse::<Query>().unwrap().matches_path());
+  /// assert!(!"api*".parse::<Query>().unwrap().matches_path());
+  /// assert!("api*".parse::<Query>().unwrap().with_match_path(true).matches_path());
+  /// ```
+  pub fn matches_path(&self) -> bool {
+    self.match_path
+  }
+
+  /// Compute how well this [`Query`] matches the given name, if it matches at all.
+  ///
+  /// Leading and trailing '*' are ignored when ranking, so that `*api*` finds `api` as an
+  /// exact match, `api-server` as a prefix match and `my-api` as a substring match.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::{MatchScore, Query};
+  ///
+  /// let q = "*api*".parse::<Query>().unwrap();
+  /// assert_eq!(q.score("api"), Some(MatchScore::Exact));
+  /// assert_eq!(q.score("api-server"), Some(MatchScore::NamePrefix));
+  /// assert_eq!(q.score("my-api"), Some(MatchScore::NameContains));
+  /// assert_eq!(q.score("web"), None);
+  /// ```
+  pub fn score<S: AsRef<str>>(&self, name: S) -> Option<MatchScore> {
+    if !self.matches(name.as_ref()) {
+      return None;
+    }
+    let subject = name.as_ref().chars().collect::<Vec<_>>();
+    let leading = self
+      .parts
+      .iter()
+      .take_while(|p| **p == Part::AnyStr)
+      .count();
+    let trailing = self.parts[leading..]
+      .iter()
+      .rev()
+      .take_while(|p| **p == Part::AnyStr)
+      .count();
+    let core = &self.parts[leading..self.parts.len() - trailing];
+    if Self::match_parts(core, &subject, 0, false) {
+      Some(MatchScore::Exact)
+    } else if Self::match_parts(core, &subject, 0, true) {
+      Some(MatchScore::NamePrefix)
+    } else {
+      Some(MatchScore::NameContains)
+    }
+  }
+
+  /// Retrieve the project property filters of the expression
+  pub fn selectors(&self) -> &Vec<Selector> {
+    &self.selectors
+  }
+
+  /// Retrieve the parsed parts of the name pattern, see [`crate::QueryVisitor`] to walk them
+  /// along with the anchors and selectors
+  pub fn parts(&self) -> &Vec<Part> {
+    &self.parts
+  }
+
+  /// Check whether the name pattern is anchored to the start of the subject with '^'
+  pub fn is_anchored_start(&self) -> bool {
+    self.anchored_start
+  }
+
+  /// Check whether the name pattern is anchored to the end of the subject with '$'
+  pub fn is_anchored_end(&self) -> bool {
+    self.anchored_end
+  }
+
+  /// Replace the name pattern parts, rewriting the expression accordingly
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::{Part, Query};
+  ///
+  /// let query = "api* ci:github".parse::<Query>().unwrap();
+  /// let mut parts = query.parts().clone();
+  /// parts.insert(0, Part::AnyStr);
+  /// let query = query.with_parts(parts);
+  /// assert_eq!(query.to_string(), "*api* ci:github");
+  /// assert!(query.matches("my-api"));
+  /// ```
+  pub fn with_parts(mut self, parts: Vec<Part>) -> Self {
+    self.match_path |= parts
+      .iter()
+      .any(|part| matches!(part, Part::Fixed(s) if s.contains('/')));
+    self.parts = parts;
+    self.expr = self.render();
+    self
+  }
+
+  /// Replace the project property filters, rewriting the expression accordingly
+  pub fn with_selectors(mut self, selectors: Vec<Selector>) -> Self {
+    self.selectors = selectors;
+    self.expr = self.render();
+    self
+  }
+
+  /// Write the expression of the parsed pattern and selectors
+  fn render(&self) -> String {
+    let mut words = vec![];
+    // a lone '*' is implied by the selectors
+    let implied = !self.anchored_start && !self.anchored_end && self.parts == [Part::AnyStr];
+    if !implied || self.selectors.is_empty() {
+      let mut pattern = match self.anchored_start {
+        true => "^".to_string(),
+        false => String::new(),
+      };
+      pattern.extend(self.parts.iter().map(|part| part.to_string()));
+      if self.anchored_end {
+        pattern.push('$');
+      }
+      words.push(pattern);
+    }
+    words.extend(self.selectors.iter().map(|selector| selector.to_string()));
+    words.join(" ")
+  }
+
+  /// Check whether `project` passes every [`Selector`] of the expression. Its name or path must
+  /// match too, see [`Query::score`].
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::{FacetValue, Project, Query};
+  ///
+  /// let query = "api* ci:github".parse::<Query>().unwrap();
+  /// assert!(query.matches("api-server"));
+  /// let mut project = Project::new("/dev/api-server", vec![], vec![], vec![]);
+  /// project.set_facet("ci", Some(FacetValue::List(vec!["gitlab".to_string()])));
+  /// assert!(!query.selects(&project));
+  /// assert!("ci:gitlab".parse::<Query>().unwrap().selects(&project));
+  /// assert!("ci:unknown".parse::<Query>().is_err());
+  /// ```
+  pub fn selects(&self, project: &Project) -> bool {
+    self
+      .selectors
+      .iter()
+      .all(|selector| selector.selects(project))
+  }
+
+  /// Check if this [`Query`] matches the given expression
+  ///
+  /// # Arguments
+  ///
+  /// `expr` - Anything that can be considered a string ref. In the form `*abc?`
+  pub fn matches<S: AsRef<str>>(&self, expr: S) -> bool {
+    let subject = expr.as_ref().chars().collect::<Vec<_>>();
+    match self.mode {
+      MatchMode::Anchored => Self::match_parts(&self.parts, &subject, 0, false),
+      MatchMode::Partial => {
+        let starts = match self.anchored_start {
+          true => 0..=0,
+          false => 0..=subject.len(),
+        };
+        starts
+          .into_iter()
+          .any(|start| Self::match_parts(&self.parts, &subject, start, !self.anchored_end))
+      }
+    }
+  }
+
+  /// Check if this [`Query`] matches the `/`-separated project `path`. A pattern containing a
+  /// `/` may also match any suffix of the path starting at a component, unless anchored with
+  /// '^', so that `work/api*` matches `/dev/work/api-server` but not `/dev/homework/api`.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::Query;
+  ///
+  /// let q = "work/api*".parse::<Query>().unwrap();
+  /// assert!(q.matches_full_path("/dev/work/api-server"));
+  /// assert!(!q.matches_full_path("/dev/homework/api-server"));
+  /// let q = "api*".parse::<Query>().unwrap().with_match_path(true);
+  /// assert!(!q.matches_full_path("/dev/work/api-server"));
+  /// ```
+  pub fn matches_full_path<S: AsRef<str>>(&self, path: S) -> bool {
+    let path = path.as_ref();
+    if self.matches(path) {
+      return true;
+    }
+    let has_separator = self
+      .parts
+      .iter()
+      .any(|part| matches!(part, Part::Fixed(s) if s.contains('/')));
+    has_separator
+      && !self.anchored_start
+      && path
+        .match_indices('/')
+        .any(|(pos, _)| self.matches(&path[pos + 1..]))
+  }
+
+  /// Parse a character class, `start` being the byte offset of its opening `[`.
+  ///
+  /// Returns the parsed class and the byte offset following the closing `]`.
+  fn parse_class(expr: &str, start: usize) -> Result<(Part, usize), QueryParseError> {
+    let mut chars = expr[start + 1..]
+      .char_indices()
+      .map(|(offset, ch)| (offset + start + 1, ch))
+      .peekable();
+    let negated = matches!(chars.peek(), Some((_, '!' | '^')));
+    if negated {
+      chars.next();
+    }
+    let mut ranges = vec![];
+    while let Some((offset, ch)) = chars.next() {
+      let from = match ch {
+        ']' if ranges.is_empty() => {
+          return Err(QueryParseError::new(expr, start, "empty character class"))
+        }
+        ']' => return Ok((Part::Class { negated, ranges }, offset + 1)),
+        '\\' => match chars.next() {
+          Some((_, escaped)) => escaped,
+          None => return Err(QueryParseError::new(expr, offset, "dangling escape")),
+        },
+        ch => ch,
+      };
+      // a '-' right before the closing ']' is a literal, handled by the next iteration
+      let mut lookahead = chars.clone();
+      let is_range = matches!(lookahead.next(), Some((_, '-')))
+        && !matches!(lookahead.next(), Some((_, ']')) | None);
+      let to = match is_range {
+        true => {
+          chars.next();
+          match chars.next() {
+            Some((_, '\\')) => match chars.next() {
+              Some((_, escaped)) => escaped,
+              None => return Err(QueryParseError::new(expr, offset, "dangling escape")),
+            },
+            Some((_, to)) => to,
+            None => unreachable!("checked by lookahead"),
+          }
+        }
+        false => from,
+      };
+      if to < from {
+        return Err(QueryParseError::new(
+          expr,
+          offset,
+          format!("invalid range '{}-{}'", from, to),
+        ));
+      }
+      ranges.push((from, to));
+    }
+    Err(QueryParseError::new(
+      expr,
+      start,
+      "unterminated character class",
+    ))
+  }
+}
+
+impl Query {
+  /// Replace the `@<name>` words of `expr` by the saved query `name`, keeping the other words
+  /// so that they further filter it. Saved queries may refer to one another.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use std::collections::BTreeMap;
+  /// use pgrep_core::Query;
+  ///
+  /// let saved = BTreeMap::from([("apis".to_string(), "kind:rust name:*api*".to_string())]);
+  /// let expr = Query::expand_saved("@apis ci:github", &saved).unwrap();
+  /// assert_eq!(expr, "kind:rust name:*api* ci:github");
+  /// assert!(Query::expand_saved("@web", &saved).is_err());
+  /// ```
+  pub fn expand_saved(
+    expr: &str,
+    saved: &BTreeMap<String, String>,
+  ) -> Result<String, QueryParseError> {
+    Self::expand_saved_from(expr, saved, &mut vec![])
+  }
+
+  /// Replace the words of `expr` named after an alias by its expansion. The expansions aren't
+  /// expanded again.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use std::collections::BTreeMap;
+  /// use pgrep_core::Query;
+  ///
+  /// let aliases = BTreeMap::from([
+  ///   ("js".to_string(), "kind:node".to_string()),
+  ///   ("work".to_string(), "path:~/work/*".to_string()),
+  /// ]);
+  /// assert_eq!(Query::expand_aliases("api* js work", &aliases), "api* kind:node path:~/work/*");
+  /// ```
+  pub fn expand_aliases(expr: &str, aliases: &BTreeMap<String, String>) -> String {
+    expr
+      .trim()
+      .split(' ')
+      .map(|word| aliases.get(word).map_or(word, String::as_str))
+      .collect::<Vec<_>>()
+      .join(" ")
+  }
+
+  /// Expand the saved queries of `expr`, `expanding` holding the saved queries being expanded
+  fn expand_saved_from<'a>(
+    expr: &str,
+    saved: &'a BTreeMap<String, String>,
+    expanding: &mut Vec<&'a str>,
+  ) -> Result<String, QueryParseError> {
+    let mut words = vec![];
+    let mut offset = 0;
+    for word in expr.trim().split(' ') {
+      match word.strip_prefix('@').filter(|name| !name.is_empty()) {
+        Some(name) => {
+          let Some((name, query)) = saved.get_key_value(name) else {
+            let message = format!("unknown saved query '{}'", word);
+            return Err(QueryParseError::new(expr, offset, message));
+          };
+          if expanding.contains(&name.as_str()) {
+            let message = format!("saved query '{}' refers to itself", word);
+            return Err(QueryParseError::new(expr, offset, message));
+          }
+          expanding.push(name);
+          let expanded = Self::expand_saved_from(query, saved, expanding).map_err(|e| {
+            let message = format!("in saved query '{}': {}", word, e.message());
+            QueryParseError::new(expr, offset, message)
+          })?;
+          expanding.pop();
+          words.push(expanded);
+        }
+        None => words.push(word.to_string()),
+      }
+      offset += word.len() + 1;
+    }
+    Ok(words.join(" "))
+  }
+}
+
+/// Trim the whitespace around an expression, except a trailing one escaped by an odd number of
+/// backslashes
+fn trim(expr: &str) -> &str {
+  let expr = expr.trim_start();
+  let trimmed = expr.trim_end();
+  let escapes = trimmed.chars().rev().take_while(|ch| *ch == '\\').count();
+  match (escapes % 2, expr[trimmed.len()..].chars().next()) {
+    (1, Some(escaped)) => &expr[..trimmed.len() + escaped.len_utf8()],
+    _ => trimmed,
+  }
+}
+
+impl FromStr for Query {
+  type Err = QueryParseError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let expr = trim(s).to_string();
+    if expr.is_empty() {
+      return Err(QueryParseError::new(expr, 0, "cannot parse empty query"));
+    }
+    // selectors are separate words, the others make the name pattern
+    let mut selectors = vec![];
+    let mut words = vec![];
+    let mut offset = 0;
+    for word in expr.split(' ') {
+      match Selector::parse(&expr, word, offset)? {
+        Some(selector) => selectors.push(selector),
+        None => words.push(word),
+      }
+      offset += word.len() + 1;
+    }
+    let pattern = words.join(" ");
+    let mut query = match trim(&pattern) {
+      "" => Self::default(),
+      pattern => Self::parse_pattern(pattern)?,
+    };
+    query.expr = expr;
+    query.selectors = selectors;
+    Ok(query)
+  }
+}
+
+impl Query {
+  /// Parse the name pattern of an expression, its selectors left out
+  fn parse_pattern(expr: &str) -> Result<Self, QueryParseError> {
+    let expr = expr.to_string();
+    let mut parts = vec![];
+    let anchored_start = expr.starts_with('^');
+    // a trailing '$' is an anchor, unless escaped by an odd number of backslashes
+    let anchored_end = expr.len() > anchored_start as usize
+      && expr.ends_with('$')
+      && expr[..expr.len() - 1]
+        .chars()
+        .rev()
+        .take_while(|ch| *ch == '\\')
+        .count()
+        % 2
+        == 0;
+    let body_end = expr.len() - anchored_end as usize;
+    let push_char = |parts: &mut Vec<Part>, ch: char| {
+      if let Some(Part::Fixed(s)) = parts.last_mut() {
+        s.push(ch);
+      } else {
+        parts.push(Part::Fixed(ch.to_string()));
+      }
+    };
+    // byte offset of the current character
+    let mut offset = anchored_start as usize;
+    while let Some(ch) = expr[offset..body_end].chars().next() {
+      let mut next = offset + ch.len_utf8();
+      match ch {
+        '?' => parts.push(Part::OptionalChar),
+        '_' => parts.push(Part::RequiredChar),
+        '*' => parts.push(Part::AnyStr),
+        '#' => parts.push(Part::Integer),
+        '\\' => match expr[next..].chars().next() {
+          Some(escaped) => {
+            push_char(&mut parts, escaped);
+            next += escaped.len_utf8();
+          }
+          None => return Err(QueryParseError::new(&expr, offset, "dangling escape")),
+        },
+        '[' => {
+          let (class, class_end) = Self::parse_class(&expr, offset)?;
+          parts.push(class);
+          next = class_end;
+        }
+        ']' => {
+          return Err(QueryParseError::new(
+            &expr,
+            offset,
+            "unexpected ']', escape it with '\\]'",
+          ))
+        }
+        ch => push_char(&mut parts, ch),
+      }
+      offset = next;
+    }
+    let match_path = parts.iter().any(|part| match part {
+      Part::Fixed(s) => s.contains('/'),
+      _ => false,
+    });
+    Ok(Self {
+      expr,
+      parts,
+      anchored_start,
+      anchored_end,
+      mode: MatchMode::default(),
+      match_path,
+      selectors: vec![],
+    })
+  }
+}
+
+impl Display for Query {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.expr)
+  }
+}
+
+/// Queries are serialized as their expression, such as `api* ci:github`
+impl Serialize for Query {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&self.expr)
+  }
+}
+
+impl<'de> Deserialize<'de> for Query {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let expr = String::deserialize(deserializer)?;
+    expr.parse().map_err(serde::de::Error::custom)
+  }
+}
+
+/// Queries are ordered by expression, then by how they are applied
+impl PartialOrd for Query {
+  fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for Query {
+  fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    (&self.expr, self.mode, self.match_path).cmp(&(&other.expr, other.mode, other.match_path))
+  }
+}
+
+impl Default for Query {
+  fn default() -> Self {
+    Self {
+      expr: "*".to_string(),
+      parts: vec![Part::AnyStr],
+      anchored_start: false,
+      anchored_end: false,
+      mode: MatchMode::default(),
+      match_path: false,
+      selectors: vec![],
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{MatchMode, Query};
+
+  fn run_cases(cases: &[(&str, &str, bool)]) {
+    run_mode_cases(MatchMode::Anchored, cases)
+  }
+
+  fn run_mode_cases(mode: MatchMode, cases: &[(&str, &str, bool)]) {
+    for (query, subject, expected) in cases {
+      let query = query.parse::<Query>().unwrap().with_mode(mode);
+      assert_eq!(
+        query.matches(subject),
+        *expected,
+        "\nquery = {}, subject = {}",
+        query.expr,
+        subject
+      );
+    }
+  }
+
+  #[test]
+  fn fixed() {
+    run_cases(&[("myTest", "MYTEST", true)])
+  }
+
+  #[test]
+  fn star() {
+    run_cases(&[
+      ("*test*", "mytestproject", true),
+      ("*test*", "project", false),
+      ("mytest*", "mytestproject", true),
+      ("nytest*", "mytestproject", false),
+    ]);
+  }
+
+  #[test]
+  fn optional_char() {
+    run_cases(&[
+      ("test?", "test", true),
+      ("test?", "test2", true),
+      ("test", "test2", false),
+    ]);
+  }
+
+  #[test]
+  fn required_char() {
+    run_cases(&[
+      ("test_", "test", false),
+      ("test_", "test2", true),
+      ("test", "test2", false),
+    ]);
+  }
+
+  #[test]
+  fn digit() {
+    run_cases(&[("test#", "test", false), ("test#", "test2", true)]);
+  }
+
+  #[test]
+  fn backtracking() {
+    run_cases(&[
+      ("*ab", "abab", true),
+      ("*a*b", "xaxxb", true),
+      ("a#1", "a11", true),
+      ("*test*", "test", true),
+    ]);
+  }
+
+  #[test]
+  fn pathological() {
+    let subject = "a".repeat(200);
+    let matched = format!("{}b", subject);
+    for pattern in ["*a*a*a*a*b", "*a*a*a*a*a*a*a*a*a*a*a*a*b", "*a?*a?*a?*a?*b"] {
+      for mode in [MatchMode::Anchored, MatchMode::Partial] {
+        run_mode_cases(
+          mode,
+          &[(pattern, &subject, false), (pattern, &matched, true)],
+        );
+      }
+    }
+    run_cases(&[
+      ("*a?b*c", "xaxbyc", true),
+      ("*#*#", "a1b2", true),
+      ("*a?", "ba", true),
+      ("*1#", "x1", false),
+    ]);
+  }
+
+  #[test]
+  fn class() {
+    run_cases(&[
+      ("v[0-9]", "v1", true),
+      ("v[0-9]", "vx", false),
+      ("v[0-9]", "v", false),
+      ("[abc]x", "Bx", true),
+      ("[!abc]x", "dx", true),
+      ("[!abc]x", "ax", false),
+      ("a[\\]-]b", "a]b", true),
+      ("a[b-]", "a-", true),
+    ]);
+  }
+
+  #[test]
+  fn escape() {
+    run_cases(&[
+      ("my\\_app", "my_app", true),
+      ("my\\_app", "myxapp", false),
+      ("\\*", "*", true),
+      ("\\*", "a", false),
+    ]);
+  }
+
+  #[test]
+  fn anchored() {
+    run_cases(&[
+      ("api", "my-api", false),
+      ("^api$", "api", true),
+      ("^api", "api-server", false),
+      ("\\^api", "^api", true),
+      ("api\\$", "api$", true),
+    ]);
+  }
+
+  #[test]
+  fn partial() {
+    run_mode_cases(
+      MatchMode::Partial,
+      &[
+        ("api", "my-api-server", true),
+        ("api", "my-app", false),
+        ("^api", "api-server", true),
+        ("^api", "my-api", false),
+        ("api$", "my-api", true),
+        ("api$", "my-api-server", false),
+        ("^api$", "api", true),
+        ("^api$", "api2", false),
+        ("a#b", "xa12bx", true),
+        ("v[0-9]", "crate-v2-rs", true),
+        ("^", "anything", true),
+        ("^$", "", true),
+        ("^$", "x", false),
+        ("api\\$", "my-api$-x", true),
+      ],
+    );
+  }
+
+  #[test]
+  fn score() {
+    use crate::MatchScore::*;
+    for (mode, query, subject, expected) in [
+      (MatchMode::Anchored, "api", "API", Some(Exact)),
+      (MatchMode::Anchored, "api*", "api", Some(Exact)),
+      (MatchMode::Anchored, "api*", "api-server", Some(NamePrefix)),
+      (MatchMode::Anchored, "*api", "my-api", Some(NameContains)),
+      (MatchMode::Anchored, "api", "api-server", None),
+      (MatchMode::Anchored, "a#", "a12", Some(Exact)),
+      (MatchMode::Partial, "api", "api", Some(Exact)),
+      (MatchMode::Partial, "api", "api-server", Some(NamePrefix)),
+      (
+        MatchMode::Partial,
+        "api",
+        "my-api-server",
+        Some(NameContains),
+      ),
+      (MatchMode::Partial, "api$", "my-api", Some(NameContains)),
+      (MatchMode::Partial, "api", "web", None),
+    ] {
+      let query = query.parse::<Query>().unwrap().with_mode(mode);
+      assert_eq!(
+        query.score(subject),
+        expected,
+        "\nquery = {}, subject = {}",
+        query,
+        subject
+      );
+    }
+  }
+
+  #[test]
+  fn parse_errors() {
+    for (expr, position, message) in [
+      ("", 0, "cannot parse empty query"),
+      ("abc\\", 3, "dangling escape"),
+      ("ab[cd", 2, "unterminated character class"),
+      ("ab[]", 2, "empty character class"),
+      ("a[z-a]", 2, "invalid range 'z-a'"),
+      ("ab]", 2, "unexpected ']', escape it with '\\]'"),
+      ("é[", 2, "unterminated character class"),
+      (
+        "api ci:travis",
+        7,
+        "unknown CI system 'travis', expected none or github, gitlab, jenkins",
+      ),
+    ] {
+      let err = expr.parse::<Query>().unwrap_err();
+      assert_eq!(err.position(), position, "\nquery = {}", expr);
+      assert_eq!(err.message(), message, "\nquery = {}", expr);
+    }
+  }
+
+  #[test]
+  fn render_caret() {
+    let err = "éa]".parse::<Query>().unwrap_err();
+    assert_eq!(
+      err.render(),
+      "unexpected ']', escape it with '\\]' at position 3\n  éa]\n    ^"
+    );
+  }
+
+  #[test]
+  fn selectors() {
+    use crate::{CiSystem, Selector};
+
+    let query = "my app ci:github".parse::<Query>().unwrap();
+    assert_eq!(query.to_string(), "my app ci:github");
+    assert_eq!(
+      query.selectors(),
+      &vec![Selector::Ci(Some(CiSystem::Github))]
+    );
+    assert!(query.matches("my app"));
+    let query = "CI:None".parse::<Query>().unwrap();
+    assert_eq!(query.selectors(), &vec![Selector::Ci(None)]);
+    assert!(query.matches("anything"));
+    // other words holding ':' stay in the pattern
+    assert!("C:/dev/*".parse::<Query>().unwrap().selectors().is_empty());
+    let query = "kind:Rust name:*api* path:*/work/*"
+      .parse::<Query>()
+      .unwrap();
+    assert_eq!(query.selectors().len(), 3);
+    assert!(query.matches("anything"));
+    let err = "name:a[".parse::<Query>().unwrap_err();
+    assert_eq!(err.position(), 6);
+    assert!("kind:".parse::<Query>().is_err());
+  }
+
+  #[test]
+  fn saved_queries() {
+    use std::collections::BTreeMap;
+
+    let saved = BTreeMap::from([
+      ("rust".to_string(), "kind:rust".to_string()),
+      ("apis".to_string(), "@rust name:*api*".to_string()),
+      ("loop".to_string(), "@loop web".to_string()),
+    ]);
+    assert_eq!(
+      Query::expand_saved("@apis path:*work*", &saved).unwrap(),
+      "kind:rust name:*api* path:*work*"
+    );
+    assert_eq!(Query::expand_saved("my app", &saved).unwrap(), "my app");
+    let err = Query::expand_saved("web @loop", &saved).unwrap_err();
+    assert_eq!(err.position(), 4);
+    assert!(err.message().contains("refers to itself"));
+    let err = Query::expand_saved("web @nope", &saved).unwrap_err();
+    assert_eq!(err.message(), "unknown saved query '@nope'");
+  }
+
+  #[test]
+  fn aliases() {
+    use std::collections::BTreeMap;
+
+    let aliases = BTreeMap::from([
+      ("js".to_string(), "kind:node".to_string()),
+      ("node".to_string(), "js".to_string()),
+    ]);
+    assert_eq!(Query::expand_aliases("js  web", &aliases), "kind:node  web");
+    // expansions aren't expanded again, and only whole words are
+    assert_eq!(Query::expand_aliases("node jsx", &aliases), "js jsx");
+    let home = crate::config::expand_path("~").unwrap();
+    let query = "path:~/work/*".parse::<Query>().unwrap();
+    let expected = format!("{}/work/*", home.display())
+      .parse::<Query>()
+      .unwrap();
+    assert_eq!(
+      query.selectors(),
+      &vec![crate::Selector::Path(Box::new(expected))]
+    );
+  }
+
+  #[test]
+  fn rendered_ast_parses_back() {
+    use crate::{walk_query, Anchor, QueryVisitor};
+
+    for expr in [
+      "^my\\_app#$",
+      "v[0-9a] ci:none",
+      "[!\\]\\-x] kind:Rust",
+      "C:/dev/* name:*api* path:*/work/*",
+      "ci\\:github \\@home",
+      "kind:node",
+      "\\ #\\aa? ",
+      "a#\\ ",
+      "\\\tx",
+    ] {
+      let query = expr.parse::<Query>().unwrap();
+      let rendered = query.clone().with_parts(query.parts().clone());
+      let parsed = rendered.to_string().parse::<Query>().unwrap();
+      assert_eq!(parsed.parts(), query.parts(), "\nquery = {}", expr);
+      assert_eq!(parsed.selectors(), query.selectors(), "\nquery = {}", expr);
+      assert_eq!(parsed.is_anchored_start(), query.is_anchored_start());
+      assert_eq!(parsed.is_anchored_end(), query.is_anchored_end());
+    }
+
+    /// Count the anchors, including the nested ones, and the selectors
+    #[derive(Default)]
+    struct Counter {
+      anchors: Vec<Anchor>,
+      selectors: usize,
+    }
+
+    impl QueryVisitor for Counter {
+      fn visit_anchor(&mut self, anchor: Anchor) {
+        self.anchors.push(anchor);
+      }
+
+      fn visit_query(&mut self, query: &Query) {
+        self.selectors += query.selectors().len();
+        walk_query(self, query);
+      }
+    }
+
+    let mut counter = Counter::default();
+    counter.visit_query(&"^api ci:none name:web$".parse::<Query>().unwrap());
+    assert_eq!(counter.anchors, [Anchor::Start, Anchor::End]);
+    assert_eq!(counter.selectors, 2);
+  }
+
+  /// Generate a pattern in the syntax shared with [`glob::Pattern`], written for both
+  fn shared_pattern(rng: &mut impl rand::Rng) -> (String, String) {
+    let (mut query, mut glob) = (String::new(), String::new());
+    for _ in 0..rng.gen_range(1..6) {
+      let (q, g) = match rng.gen_range(0..6) {
+        // glob reads '**' as recursive wildcards
+        0 if glob.ends_with('*') => continue,
+        0 => ("*".to_string(), "*".to_string()),
+        // pgrep's '?' is optional, its '_' matches exactly one character like glob's '?'
+        1 => ("_".to_string(), "?".to_string()),
+        2 => {
+          let class = ["[a-b]", "[!a0]", "[0-1b]", "[!a-b]"][rng.gen_range(0..4)];
+          (class.to_string(), class.to_string())
+        }
+        3 => {
+          let ch = ['*', '_', '?', '['][rng.gen_range(0..4)];
+          (format!("\\{}", ch), format!("[{}]", ch))
+        }
+        _ => {
+          let ch = ['a', 'b', 'A', 'B', '0', '1', '-', '.', '/'][rng.gen_range(0..9)];
+          (ch.to_string(), ch.to_string())
+        }
+      };
+      query.push_str(&q);
+      glob.push_str(&g);
+    }
+    (query, glob)
+  }
+
+  /// Generate a subject made of the characters [`shared_pattern`] may match
+  fn subject(rng: &mut impl rand::Rng) -> String {
+    const CHARS: &[char] = &[
+      'a', 'b', 'A', 'B', '0', '1', '-', '.', '/', '*', '_', '?', '[',
+    ];
+    let len = rng.gen_range(0..8);
+    (0..len)
+      .map(|_| CHARS[rng.gen_range(0..CHARS.len())])
+      .collect()
+  }
+
+  /// Compare the matcher against the glob crate for the syntax they share, anchored and with
+  /// the partial matches glob writes with surrounding stars
+  #[test]
+  fn matches_like_glob() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let options = glob::MatchOptions {
+      case_sensitive: false,
+      require_literal_separator: false,
+      require_literal_leading_dot: false,
+    };
+    let mut rng = StdRng::seed_from_u64(0x9e3779b97f4a7c15);
+    for _ in 0..2000 {
+      let (expr, pattern) = shared_pattern(&mut rng);
+      let query = expr.parse::<Query>().unwrap();
+      let anchored = glob::Pattern::new(&pattern).unwrap();
+      let partial = format!("*{}*", pattern.trim_matches('*'));
+      let partial = glob::Pattern::new(&partial).unwrap();
+      for _ in 0..20 {
+        let subject = subject(&mut rng);
+        let context = format!(
+          "\nquery = {}, glob = {}, subject = {}",
+          expr, pattern, subject
+        );
+        let expected = anchored.matches_with(&subject, options);
+        assert_eq!(query.matches(&subject), expected, "{}", context);
+        let expected = partial.matches_with(&subject, options);
+        let query = query.clone().with_mode(MatchMode::Partial);
+        assert_eq!(query.matches(&subject), expected, "{} (partial)", context);
+      }
+    }
+  }
+}
@@ -0,0 +1,153 @@
+use std::path::{Path, PathBuf};
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::{get_data_dir, Error, Table};
+
+/// The latest queries searched for, recalled with `--recall` instead of retyping them.
+///
+/// They are stored as a TOML file in the user data folder, oldest first.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryHistory {
+  /// The file the queries are stored in
+  #[serde(skip)]
+  file: PathBuf,
+  /// The queries, oldest first
+  queries: Vec<String>,
+}
+
+impl QueryHistory {
+  /// The query history file name
+  pub const FILE_NAME: &'static str = "query-history.toml";
+  /// The number of queries kept
+  pub const CAPACITY: usize = 100;
+
+  /// Load the queries stored in `file`, none if it doesn't exist yet
+  pub fn load<P: AsRef<Path>>(file: P) -> crate::Result<Self> {
+    let file = file.as_ref().to_path_buf();
+    let mut ret = match file.exists() {
+      true => {
+        let content = std::fs::read_to_string(&file)
+          .map_err(|e| Error::io(format!("cannot read query history '{}'", file.display()), e))?;
+        toml::from_str(&content)?
+      }
+      false => Self::default(),
+    };
+    ret.file = file;
+    Ok(ret)
+  }
+
+  /// Load the queries stored in the user data folder, or in a temporary folder when the
+  /// platform has none
+  pub fn open() -> crate::Result<Self> {
+    Self::load(get_data_dir().join(Self::FILE_NAME))
+  }
+
+  /// Write the queries to their file, creating its folder if needed
+  pub fn save(&self) -> crate::Result<()> {
+    if let Some(dir) = self.file.parent() {
+      std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&self.file, toml::to_string_pretty(self)?).map_err(|e| {
+      Error::io(
+        format!("cannot write query history '{}'", self.file.display()),
+        e,
+      )
+    })?;
+    debug!(
+      "saved {} queries to '{}'",
+      self.queries.len(),
+      self.file.display()
+    );
+    Ok(())
+  }
+
+  /// Record a query as the most recent one, moving it if it was already recorded and dropping
+  /// the oldest ones beyond [`Self::CAPACITY`]. Blank queries and `*` aren't recorded.
+  /// Returns whether the history changed.
+  pub fn record<S: AsRef<str>>(&mut self, query: S) -> bool {
+    let query = query.as_ref().trim();
+    if query.is_empty() || query == "*" || self.queries.last().is_some_and(|last| last == query) {
+      return false;
+    }
+    self.queries.retain(|known| known != query);
+    self.queries.push(query.to_string());
+    let excess = self.queries.len().saturating_sub(Self::CAPACITY);
+    self.queries.drain(..excess);
+    true
+  }
+
+  /// Iterate over the queries, most recent first
+  pub fn recent(&self) -> impl Iterator<Item = &String> {
+    self.queries.iter().rev()
+  }
+
+  /// Retrieve the `n`-th most recent query, `1` being the latest one
+  pub fn get(&self, n: usize) -> Option<&String> {
+    self.recent().nth(n.checked_sub(1)?)
+  }
+
+  /// Recall the `n`-th most recent query, as given to `--recall`, failing when the history
+  /// holds fewer queries
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::QueryHistory;
+  ///
+  /// let mut history = QueryHistory::default();
+  /// history.record("api*");
+  /// history.record("web ci:github");
+  /// assert_eq!(history.recall(1), Ok("web ci:github"));
+  /// assert_eq!(history.recall(2), Ok("api*"));
+  /// assert!(history.recall(3).is_err());
+  /// assert!(history.recall(0).is_err());
+  /// ```
+  pub fn recall(&self, n: usize) -> Result<&str, String> {
+    self.get(n).map(String::as_str).ok_or_else(|| {
+      format!(
+        "no query #{} in the history of {} queries",
+        n,
+        self.queries.len()
+      )
+    })
+  }
+
+  /// Describe the queries as a table, most recent first along with their recall number
+  pub fn table(&self) -> Table {
+    let mut table = Table::new("Query history", &["Number", "Query"]);
+    for (id, query) in self.recent().enumerate() {
+      table.push_row(vec![(id + 1).to_string().into(), query.clone().into()]);
+    }
+    table
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::QueryHistory;
+
+  #[test]
+  fn queries_are_recorded_most_recent_first() {
+    let file = std::env::temp_dir()
+      .join(format!("pgrep-query-history-{}", std::process::id()))
+      .join(QueryHistory::FILE_NAME);
+    let mut history = QueryHistory::load(&file).unwrap();
+    assert!(history.record("api*"));
+    assert!(history.record("web"));
+    assert!(!history.record("web"));
+    assert!(!history.record("*"));
+    // recalling an older query moves it first
+    assert!(history.record("api*"));
+    history.save().unwrap();
+    let mut history = QueryHistory::load(&file).unwrap();
+    assert_eq!(history.recent().collect::<Vec<_>>(), ["api*", "web"]);
+    for id in 0..QueryHistory::CAPACITY {
+      history.record(format!("project-{}", id));
+    }
+    assert_eq!(history.recent().count(), QueryHistory::CAPACITY);
+    assert_eq!(history.get(QueryHistory::CAPACITY).unwrap(), "project-0");
+    std::fs::remove_dir_all(file.parent().unwrap()).unwrap();
+  }
+}
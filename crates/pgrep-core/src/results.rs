@@ -0,0 +1,148 @@
+use std::{
+  collections::hash_map::DefaultHasher,
+  hash::{Hash, Hasher},
+  path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Duration, Local};
+use log::debug;
+use rmp_serde::{Deserializer, Serializer};
+use serde::{Deserialize, Serialize};
+
+use crate::{Config, Error, Project, Query};
+
+/// The matches of a query, see [`ResultCache`]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct CachedResult {
+  /// The hash of the query and configuration, see [`ResultCache::key`]
+  key: String,
+  /// The generation of the cache index the matches were found at, see
+  /// [`crate::Cache::generation`]
+  generation: u64,
+  /// When the matches were found
+  written_at: DateTime<Local>,
+  /// The matching projects, best first
+  matches: Vec<Project>,
+  /// The number of projects the query was matched against
+  total: usize,
+}
+
+/// The matches of the latest queries, reused when a query is repeated shortly after, such as by
+/// a shell prompt widget polling it, to skip loading the code folders and matching.
+///
+/// Matches are only reused for [`Self::TTL`], and as long as the cache index didn't change,
+/// which happens whenever a code folder is scanned again.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResultCache {
+  /// The file the matches are stored in
+  file: PathBuf,
+  /// The matches, oldest first
+  entries: Vec<CachedResult>,
+}
+
+impl ResultCache {
+  /// The result cache file name
+  pub const FILE_NAME: &'static str = "results.bin";
+  /// How long matches are reused
+  pub const TTL: Duration = Duration::seconds(30);
+  /// The number of queries whose matches are kept
+  pub const CAPACITY: usize = 16;
+
+  /// Load the matches stored in `file`, none if it doesn't exist yet or was written by an
+  /// incompatible version
+  pub fn load<P: AsRef<Path>>(file: P) -> Self {
+    let file = file.as_ref().to_path_buf();
+    let entries = match std::fs::read(&file) {
+      Ok(content) => Deserialize::deserialize(&mut Deserializer::new(content.as_slice()))
+        .unwrap_or_else(|e| {
+          debug!("ignoring the result cache '{}': {}", file.display(), e);
+          vec![]
+        }),
+      Err(_) => vec![],
+    };
+    Self { file, entries }
+  }
+
+  /// Write the matches to their file, creating its folder if needed
+  pub fn save(&self) -> crate::Result<()> {
+    if let Some(dir) = self.file.parent() {
+      std::fs::create_dir_all(dir)?;
+    }
+    let mut buf = vec![];
+    self
+      .entries
+      .serialize(&mut Serializer::new(&mut buf))
+      .map_err(|e| Error::IO {
+        message: "cannot serialize result cache".to_string(),
+        source: Some(Box::new(e)),
+      })?;
+    std::fs::write(&self.file, buf).map_err(|e| {
+      Error::io(
+        format!("cannot write result cache '{}'", self.file.display()),
+        e,
+      )
+    })?;
+    debug!("saved result cache '{}'", self.file.display());
+    Ok(())
+  }
+
+  /// Compute the key the matches of `query` are cached under, which changes along with the
+  /// configuration
+  pub fn key(query: &Query, config: &Config) -> String {
+    let mut hasher = DefaultHasher::new();
+    query.hash(&mut hasher);
+    config.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+  }
+
+  /// Retrieve the matches cached under `key` along with the number of projects they were
+  /// matched against, unless they expired or were found at another cache index `generation`
+  pub fn get(&self, key: &str, generation: u64) -> Option<(&Vec<Project>, usize)> {
+    let entry = self.entries.iter().find(|entry| entry.key == key)?;
+    let fresh = entry.generation == generation && Local::now() < entry.written_at + Self::TTL;
+    fresh.then_some((&entry.matches, entry.total))
+  }
+
+  /// Cache the matches found under `key` at the cache index `generation`, dropping the ones
+  /// found at other generations and the oldest ones beyond [`Self::CAPACITY`]
+  pub fn insert(&mut self, key: String, generation: u64, matches: Vec<Project>, total: usize) {
+    self
+      .entries
+      .retain(|entry| entry.key != key && entry.generation == generation);
+    self.entries.push(CachedResult {
+      key,
+      generation,
+      written_at: Local::now(),
+      matches,
+      total,
+    });
+    let excess = self.entries.len().saturating_sub(Self::CAPACITY);
+    self.entries.drain(..excess);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{Config, Project, Query, ResultCache};
+
+  #[test]
+  fn results_are_reused_at_the_same_generation() {
+    let file = std::env::temp_dir()
+      .join(format!("pgrep-results-{}", std::process::id()))
+      .join(ResultCache::FILE_NAME);
+    let query = "api*".parse::<Query>().unwrap();
+    let key = ResultCache::key(&query, &Config::default());
+    assert_ne!(
+      key,
+      ResultCache::key(&"web".parse().unwrap(), &Config::default())
+    );
+    let mut results = ResultCache::load(&file);
+    let project = Project::new("/dev/api", vec![], vec![], vec![]);
+    results.insert(key.clone(), 3, vec![project.clone()], 10);
+    results.save().unwrap();
+    let results = ResultCache::load(&file);
+    assert_eq!(results.get(&key, 3), Some((&vec![project], 10)));
+    assert_eq!(results.get(&key, 4), None);
+    std::fs::remove_dir_all(file.parent().unwrap()).unwrap();
+  }
+}
@@ -0,0 +1,226 @@
+use std::{io::Read, time::Duration};
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use log::debug;
+use sha2::{Digest, Sha256};
+
+use crate::{resolve_secret, Error, S3Config};
+
+/// The maximum size of a downloaded cache entry
+const MAX_OBJECT_SIZE: u64 = 256 * 1024 * 1024;
+
+/// The headers covered by request signatures, in canonical order
+const SIGNED_HEADERS: &str = "host;x-amz-content-sha256;x-amz-date";
+
+/// A minimal client for an S3-compatible bucket, storing cache files as objects
+pub struct S3Store {
+  endpoint: String,
+  host: String,
+  bucket: String,
+  prefix: String,
+  region: String,
+  access_key_id: String,
+  secret_access_key: String,
+  session_token: Option<String>,
+  agent: ureq::Agent,
+}
+
+impl S3Store {
+  /// The default region, used by most S3-compatible servers
+  pub const DEFAULT_REGION: &'static str = "us-east-1";
+
+  /// Create a client from the `[cache.s3]` settings, expanding their `${VAR}` references and
+  /// resolving their secrets, see [`crate::secrets`].
+  /// Credentials default to the `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and
+  /// `AWS_SESSION_TOKEN` environment variables.
+  pub fn new(config: &S3Config) -> crate::Result<Self> {
+    let setting = |value: &Option<String>, var: &str| -> crate::Result<Option<String>> {
+      match value {
+        Some(value) => resolve_secret(value).map(Some),
+        None => Ok(std::env::var(var).ok()),
+      }
+    };
+    let missing = |name: &str| Error::config(format!("[cache.s3] {} is missing", name));
+    let endpoint = resolve_secret(&config.endpoint)?
+      .trim_end_matches('/')
+      .to_string();
+    let host = endpoint
+      .split_once("://")
+      .map(|(_, rest)| rest)
+      .unwrap_or(&endpoint)
+      .split('/')
+      .next()
+      .unwrap_or_default()
+      .to_string();
+    let prefix = match &config.prefix {
+      Some(prefix) => format!("{}/", resolve_secret(prefix)?.trim_matches('/')),
+      None => String::new(),
+    };
+    Ok(Self {
+      bucket: resolve_secret(&config.bucket)?,
+      region: setting(&config.region, "AWS_REGION")?
+        .unwrap_or_else(|| Self::DEFAULT_REGION.to_string()),
+      access_key_id: setting(&config.access_key_id, "AWS_ACCESS_KEY_ID")?
+        .ok_or_else(|| missing("access_key_id"))?,
+      secret_access_key: setting(&config.secret_access_key, "AWS_SECRET_ACCESS_KEY")?
+        .ok_or_else(|| missing("secret_access_key"))?,
+      session_token: setting(&None, "AWS_SESSION_TOKEN")?,
+      agent: ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(30))
+        .build(),
+      endpoint,
+      host,
+      prefix,
+    })
+  }
+
+  /// Retrieve the path of an object, relative to the endpoint
+  fn object_path(&self, name: &str) -> String {
+    format!("/{}/{}{}", self.bucket, self.prefix, name)
+  }
+
+  /// Download an object, if it exists
+  pub fn get(&self, name: &str) -> crate::Result<Option<Vec<u8>>> {
+    let response = match self.request("GET", name, &[]) {
+      Ok(response) => response,
+      Err(e) if matches!(*e, ureq::Error::Status(404, _)) => return Ok(None),
+      Err(e) => return Err(self.error("download", name, e)),
+    };
+    let mut content = vec![];
+    response
+      .into_reader()
+      .take(MAX_OBJECT_SIZE)
+      .read_to_end(&mut content)
+      .map_err(|e| {
+        Error::io(
+          format!("failed to download '{}'", self.object_path(name)),
+          e,
+        )
+      })?;
+    Ok(Some(content))
+  }
+
+  /// Upload an object, replacing it if it exists
+  pub fn put(&self, name: &str, content: &[u8]) -> crate::Result<()> {
+    self
+      .request("PUT", name, content)
+      .map(|_| ())
+      .map_err(|e| self.error("upload", name, e))
+  }
+
+  /// Build the error reported when a request about `name` failed
+  fn error(&self, action: &str, name: &str, e: Box<ureq::Error>) -> Error {
+    Error::IO {
+      message: format!(
+        "failed to {} '{}' from '{}'",
+        action,
+        self.object_path(name),
+        self.endpoint
+      ),
+      source: Some(e),
+    }
+  }
+
+  /// Send a request signed with AWS signature version 4
+  fn request(
+    &self,
+    method: &str,
+    name: &str,
+    payload: &[u8],
+  ) -> Result<ureq::Response, Box<ureq::Error>> {
+    let path = self.object_path(name);
+    let now = Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let payload_hash = sha256_hex(payload);
+    let mut signed_headers = SIGNED_HEADERS.to_string();
+    let mut canonical_headers = format!(
+      "host:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n",
+      self.host, payload_hash, amz_date
+    );
+    if let Some(token) = &self.session_token {
+      signed_headers.push_str(";x-amz-security-token");
+      canonical_headers.push_str(&format!("x-amz-security-token:{}\n", token));
+    }
+    let canonical_request = format!(
+      "{}\n{}\n\n{}\n{}\n{}",
+      method, path, canonical_headers, signed_headers, payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+    let string_to_sign = format!(
+      "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+      amz_date,
+      scope,
+      sha256_hex(canonical_request.as_bytes())
+    );
+    let key = signing_key(&self.secret_access_key, &date, &self.region, "s3");
+    let signature = to_hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+    debug!("{} {}{}", method, self.endpoint, path);
+    let mut request = self
+      .agent
+      .request(method, &format!("{}{}", self.endpoint, path))
+      .set("x-amz-content-sha256", &payload_hash)
+      .set("x-amz-date", &amz_date)
+      .set(
+        "Authorization",
+        &format!(
+          "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+          self.access_key_id, scope, signed_headers, signature
+        ),
+      );
+    if let Some(token) = &self.session_token {
+      request = request.set("x-amz-security-token", token);
+    }
+    match method {
+      "GET" => request.call(),
+      _ => request.send_bytes(payload),
+    }
+    .map_err(Box::new)
+  }
+}
+
+/// Derive the key signing the requests of a day, region and service
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+  let key = hmac_sha256(format!("AWS4{}", secret).as_bytes(), date.as_bytes());
+  let key = hmac_sha256(&key, region.as_bytes());
+  let key = hmac_sha256(&key, service.as_bytes());
+  hmac_sha256(&key, b"aws4_request")
+}
+
+/// Compute the HMAC-SHA256 of `data`
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+  let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+  mac.update(data);
+  mac.finalize().into_bytes().to_vec()
+}
+
+/// Compute the hex-encoded SHA256 of `data`
+fn sha256_hex(data: &[u8]) -> String {
+  to_hex(&Sha256::digest(data))
+}
+
+/// Encode bytes as lowercase hex
+fn to_hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{signing_key, to_hex};
+
+  #[test]
+  fn signing_key_matches_the_aws_example() {
+    // https://docs.aws.amazon.com/IAM/latest/UserGuide/signing-elements.html
+    let key = signing_key(
+      "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+      "20120215",
+      "us-east-1",
+      "iam",
+    );
+    assert_eq!(
+      to_hex(&key),
+      "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+    );
+  }
+}
@@ -0,0 +1,116 @@
+use std::{
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+use log::debug;
+
+use crate::{i18n::tr_fmt, Error, ProjectKind};
+
+/// Create the skeleton of a new `kind` project in the `path` folder, which must not exist.
+///
+/// The `template` folder is copied when given, otherwise the skeleton is created by the kind's
+/// own tool: `cargo new` for Rust, `npm init` for Node and `go mod init` for Go. Other kinds
+/// require a template.
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::{scaffold, ProjectKind};
+///
+/// let root = std::env::temp_dir().join(format!("pgrep-scaffold-doc-{}", std::process::id()));
+/// let template = root.join("template");
+/// std::fs::create_dir_all(template.join("src")).unwrap();
+/// std::fs::write(template.join("Makefile"), "all:\n").unwrap();
+/// std::fs::write(template.join("src").join("main.c"), "int main() {}\n").unwrap();
+///
+/// scaffold(&ProjectKind::C, &root.join("hello"), Some(&template)).unwrap();
+/// assert!(root.join("hello").join("src").join("main.c").exists());
+/// // the folder must be new
+/// assert!(scaffold(&ProjectKind::C, &root.join("hello"), Some(&template)).is_err());
+/// # std::fs::remove_dir_all(&root).unwrap();
+/// ```
+pub fn scaffold(kind: &ProjectKind, path: &Path, template: Option<&Path>) -> crate::Result<()> {
+  if path.exists() {
+    return Err(Error::config(tr_fmt("scaffold.exists", &[&path.display()])));
+  }
+  if let Some(template) = template {
+    if !template.is_dir() {
+      return Err(Error::config(tr_fmt(
+        "scaffold.no-template-dir",
+        &[&template.display()],
+      )));
+    }
+    debug!(
+      "copying template '{}' to '{}'",
+      template.display(),
+      path.display()
+    );
+    return copy_dir(template, path);
+  }
+  let name = path
+    .file_name()
+    .map(|name| name.to_string_lossy().into_owned())
+    .unwrap_or_default();
+  let (program, args): (&str, Vec<&str>) = match kind {
+    ProjectKind::Rust => ("cargo", vec!["init", "--name", &name]),
+    ProjectKind::Node => ("npm", vec!["init", "-y"]),
+    ProjectKind::Go => ("go", vec!["mod", "init", &name]),
+    kind => return Err(Error::config(tr_fmt("scaffold.no-template", &[kind]))),
+  };
+  std::fs::create_dir_all(path)
+    .map_err(|e| Error::io(format!("cannot create '{}'", path.display()), e))?;
+  debug!(
+    "running `{} {}` in '{}'",
+    program,
+    args.join(" "),
+    path.display()
+  );
+  let output = Command::new(program).args(&args).current_dir(path).output();
+  let failure = match output {
+    Ok(output) if output.status.success() => return Ok(()),
+    Ok(output) => String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    Err(e) => e.to_string(),
+  };
+  // leave no half-created project behind
+  let _ = std::fs::remove_dir_all(path);
+  Err(Error::Exec(tr_fmt(
+    "scaffold.failed",
+    &[&program, &failure],
+  )))
+}
+
+/// Copy the `from` folder to `to` recursively, recreating symbolic links as links rather than
+/// copying what they point to
+pub(crate) fn copy_dir(from: &Path, to: &Path) -> crate::Result<()> {
+  std::fs::create_dir_all(to)
+    .map_err(|e| Error::io(format!("cannot create '{}'", to.display()), e))?;
+  for entry in std::fs::read_dir(from)? {
+    let entry = entry?;
+    let target: PathBuf = to.join(entry.file_name());
+    let file_type = entry.file_type()?;
+    if file_type.is_symlink() {
+      copy_link(&entry.path(), &target)?;
+    } else if file_type.is_dir() {
+      copy_dir(&entry.path(), &target)?;
+    } else {
+      std::fs::copy(entry.path(), &target)
+        .map_err(|e| Error::io(format!("cannot copy '{}'", entry.path().display()), e))?;
+    }
+  }
+  Ok(())
+}
+
+/// Create the `to` symbolic link pointing where the `from` one does, dangling or not
+fn copy_link(from: &Path, to: &Path) -> crate::Result<()> {
+  let link = std::fs::read_link(from)
+    .map_err(|e| Error::io(format!("cannot read link '{}'", from.display()), e))?;
+  #[cfg(unix)]
+  let created = std::os::unix::fs::symlink(&link, to);
+  #[cfg(windows)]
+  let created = match from.is_dir() {
+    true => std::os::windows::fs::symlink_dir(&link, to),
+    false => std::os::windows::fs::symlink_file(&link, to),
+  };
+  created.map_err(|e| Error::io(format!("cannot create link '{}'", to.display()), e))
+}
@@ -0,0 +1,90 @@
+//! Secrets referenced by configuration values rather than written in plain text, such as the
+//! `Authorization` header of reports or the credentials of the S3 cache.
+//!
+//! A value is resolved by [`resolve_secret`] only when it is used, so that a command-line which
+//! doesn't need it never asks a password manager for it:
+//!
+//! - `env:NAME` reads the `NAME` environment variable
+//! - `cmd:COMMAND` runs `COMMAND` through the platform shell and reads its output, such as
+//!   `cmd:pass show pgrep/token`
+//! - any other value is used as is, its `${VAR}` references expanded, see [`expand_env`]
+
+use log::debug;
+
+use crate::{expand_env, hooks::platform_shell, Error};
+
+/// The prefix of a value read from an environment variable
+pub const ENV_SECRET_PREFIX: &str = "env:";
+
+/// The prefix of a value read from the output of a command
+pub const CMD_SECRET_PREFIX: &str = "cmd:";
+
+/// Check whether resolving `value` runs a command, in which case the configuration holding it
+/// must be trusted first, see [`crate::Config::ensure_trusted`]
+pub fn is_secret_command<S: AsRef<str>>(value: S) -> bool {
+  value.as_ref().starts_with(CMD_SECRET_PREFIX)
+}
+
+/// Resolve a configuration value which may reference a secret, see the [module](self)
+/// documentation. The trailing newline of a command output is removed.
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::resolve_secret;
+///
+/// std::env::set_var("MY_SECRET", "hunter2");
+///
+/// assert_eq!(resolve_secret("env:MY_SECRET").unwrap(), "hunter2");
+/// assert_eq!(resolve_secret("cmd:echo hunter2").unwrap(), "hunter2");
+/// assert_eq!(resolve_secret("Bearer ${MY_SECRET}").unwrap(), "Bearer hunter2");
+/// assert!(resolve_secret("env:MY_UNDEFINED_SECRET").is_err());
+/// ```
+pub fn resolve_secret<S: AsRef<str>>(value: S) -> crate::Result<String> {
+  let value = value.as_ref();
+  if let Some(name) = value.strip_prefix(ENV_SECRET_PREFIX) {
+    return std::env::var(name.trim()).map_err(|_| {
+      Error::config(format!(
+        "{}: invalid configuration value, environment variable '{}' is undefined",
+        value,
+        name.trim()
+      ))
+    });
+  }
+  let Some(command) = value.strip_prefix(CMD_SECRET_PREFIX) else {
+    return expand_env(value);
+  };
+  debug!("running secret command `{}`", command);
+  let output = platform_shell(command)
+    .output()
+    .map_err(|e| Error::Config {
+      message: format!("{}: cannot run the secret command", value),
+      source: Some(Box::new(e)),
+    })?;
+  if !output.status.success() {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    return Err(Error::config(format!(
+      "{}: the secret command failed with {}, {}",
+      value,
+      output.status,
+      stderr.trim()
+    )));
+  }
+  let stdout = String::from_utf8(output.stdout)?;
+  Ok(stdout.trim_end_matches(['\r', '\n']).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn failing_commands_are_errors() {
+    assert!(resolve_secret("cmd:exit 3").is_err());
+    assert!(is_secret_command("cmd:pass show pgrep/token"));
+    assert!(!is_secret_command("env:TOKEN"));
+    // only the line ending is removed, a secret may end with spaces
+    #[cfg(unix)]
+    assert_eq!(resolve_secret("cmd:echo 'a b '").unwrap(), "a b ");
+  }
+}
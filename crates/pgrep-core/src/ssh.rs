@@ -0,0 +1,256 @@
+//! Code folders on other machines, configured as `ssh://<host>:<path>` such as
+//! `ssh://devbox:~/src`.
+//!
+//! The host is given as to the `ssh` command-line, so that the aliases, users, ports, keys and
+//! jump hosts of `~/.ssh/config` apply. The files are listed by `find` on the host and scanned
+//! by [`FolderScan::from_files`], and the connection is multiplexed unless the configuration
+//! of the host already sets a `ControlPath`.
+
+use std::{
+  path::{Path, PathBuf},
+  process::{Command, Output},
+};
+
+use directories::UserDirs;
+use log::debug;
+
+use crate::{config::closest, i18n::tr_fmt, Error, FolderScan, ScanOptions};
+
+/// A code folder on another machine, reached through `ssh`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshFolder {
+  /// The host name or alias, such as `devbox` or `me@devbox`
+  pub host: String,
+  /// The folder path on the host, `~/` being its home folder
+  pub path: String,
+}
+
+impl SshFolder {
+  /// The prefix of the remote folders
+  pub const SCHEME: &'static str = "ssh://";
+
+  /// Parse a `ssh://<host>:<path>` folder, `None` if `path` is a local one or if the host starts
+  /// with `-`, which `ssh` would take for an option. The path defaults to the home folder.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use pgrep_core::SshFolder;
+  ///
+  /// let folder = SshFolder::parse("ssh://devbox:~/src").unwrap();
+  /// assert_eq!(folder.host, "devbox");
+  /// assert_eq!(folder.path, "~/src");
+  /// assert_eq!(SshFolder::parse("ssh://devbox").unwrap().path, "~");
+  /// assert_eq!(SshFolder::parse("/home/me/src"), None);
+  /// assert_eq!(SshFolder::parse("ssh://-oProxyCommand=sh:~"), None);
+  /// ```
+  pub fn parse<P: AsRef<Path>>(path: P) -> Option<Self> {
+    let remote = path.as_ref().to_str()?.strip_prefix(Self::SCHEME)?;
+    let (host, path) = remote.split_once(':').unwrap_or((remote, ""));
+    if host.is_empty() || host.starts_with('-') {
+      return None;
+    }
+    Some(Self {
+      host: host.to_string(),
+      path: match path.trim_end_matches('/') {
+        "" => "~".to_string(),
+        path => path.to_string(),
+      },
+    })
+  }
+
+  /// Quote the folder path for the shell of the host
+  pub(crate) fn quoted_path(&self) -> String {
+    // the home folder is left unquoted for the remote shell to expand it
+    match self.path.strip_prefix("~") {
+      Some("") => "~".to_string(),
+      Some(rest) if rest.starts_with('/') => format!("~/{}", shell_quote(&rest[1..])),
+      _ => shell_quote(&self.path),
+    }
+  }
+
+  /// Build the shell command listing the files of the folder on the host
+  fn find_command(&self, options: &ScanOptions) -> String {
+    find_command(&self.quoted_path(), options)
+  }
+
+  /// Build the `ssh` command running `command` on the host, the host following `--` so that it
+  /// is never taken for an option
+  fn ssh_command(&self, command: &str) -> Command {
+    let mut ret = Command::new("ssh");
+    ret
+      .args(["-o", "BatchMode=yes"])
+      .args(multiplexing_options(&self.host))
+      .arg("--")
+      .arg(&self.host)
+      .arg(command);
+    ret
+  }
+
+  /// Run the shell `command` on the host
+  pub(crate) fn run(&self, command: &str) -> std::io::Result<Output> {
+    self.ssh_command(command).output()
+  }
+
+  /// Scan the folder, reported as `path`
+  pub fn scan<P: AsRef<Path>>(&self, path: P, options: &ScanOptions) -> crate::Result<FolderScan> {
+    let command = self.find_command(options);
+    debug!(
+      "listing the files of '{}' with `{}`",
+      path.as_ref().display(),
+      command
+    );
+    let output = self.run(&command).map_err(|e| Error::Scan {
+      path: path.as_ref().to_path_buf(),
+      message: tr_fmt("ssh.unavailable", &[]),
+      source: Some(Box::new(e)),
+    })?;
+    // find fails on unreadable directories, the files it could list are still scanned
+    if !output.status.success() && output.stdout.is_empty() {
+      let mut reason = String::from_utf8_lossy(&output.stderr).trim().to_string();
+      if let Some(host) = closest(&self.host, known_ssh_hosts().iter().map(String::as_str))
+        .filter(|host| *host != self.host)
+      {
+        reason = tr_fmt("ssh.unknown-host", &[&reason, &host]);
+      }
+      return Err(Error::Scan {
+        path: path.as_ref().to_path_buf(),
+        message: tr_fmt("ssh.failed", &[&output.status, &reason]),
+        source: None,
+      });
+    }
+    let files = String::from_utf8_lossy(&output.stdout)
+      .lines()
+      .map(PathBuf::from)
+      .collect::<Vec<_>>();
+    Ok(FolderScan::from_files(path, files, options))
+  }
+}
+
+/// Quote `value` for a POSIX shell
+pub(crate) fn shell_quote(value: &str) -> String {
+  format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Build the POSIX shell command listing the files of the folder `dir`, as written in the
+/// command, relative to it and pruning the directories a scan with `options` would never enter
+pub(crate) fn find_command(dir: &str, options: &ScanOptions) -> String {
+  let mut ret = format!("cd {} && find .", dir);
+  if let Some(max_depth) = options.max_depth {
+    ret.push_str(&format!(" -maxdepth {}", max_depth + 1));
+  }
+  let mut pruned = options
+    .exclusions
+    .iter()
+    .map(|name| format!("-name {}", shell_quote(name)))
+    .collect::<Vec<_>>();
+  if !options.include_hidden {
+    pruned.push("-name '.?*'".to_string());
+  }
+  if !pruned.is_empty() {
+    ret.push_str(&format!(
+      " -type d \\( {} \\) -prune -o",
+      pruned.join(" -o ")
+    ));
+  }
+  ret.push_str(" -type f -print");
+  ret
+}
+
+/// The options sharing a connection between the scans of a host, unless its configuration
+/// already decides how connections are shared
+fn multiplexing_options(host: &str) -> Vec<String> {
+  if cfg!(windows) {
+    // the Windows build of OpenSSH has no connection multiplexing
+    return vec![];
+  }
+  let configured = Command::new("ssh")
+    .args(["-G", "--", host])
+    .output()
+    .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+    .unwrap_or_default();
+  let has_control_path = configured.lines().any(|line| {
+    line
+      .strip_prefix("controlpath ")
+      .is_some_and(|path| path.trim() != "none")
+  });
+  if has_control_path {
+    return vec![];
+  }
+  let control_path = std::env::temp_dir().join("pgrep-ssh-%C");
+  vec![
+    "-o".to_string(),
+    "ControlMaster=auto".to_string(),
+    "-o".to_string(),
+    format!("ControlPath={}", control_path.display()),
+    "-o".to_string(),
+    "ControlPersist=60".to_string(),
+  ]
+}
+
+/// List the hosts declared by the `Host` lines of a ssh configuration, leaving out the
+/// patterns such as `*.internal`
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::ssh_config_hosts;
+///
+/// let config = "Host devbox build\n  User me\nhost *.internal !bastion\n";
+/// assert_eq!(ssh_config_hosts(config), ["devbox", "build"]);
+/// ```
+pub fn ssh_config_hosts(content: &str) -> Vec<String> {
+  content
+    .lines()
+    .filter_map(|line| {
+      let line = line.trim();
+      let (keyword, hosts) = line.split_once(|c: char| c.is_whitespace() || c == '=')?;
+      keyword.eq_ignore_ascii_case("host").then_some(hosts)
+    })
+    .flat_map(|hosts| hosts.split_whitespace())
+    .filter(|host| !host.contains(['*', '?', '!']))
+    .map(|host| host.trim_matches('"').to_string())
+    .collect()
+}
+
+/// List the hosts of the user's `~/.ssh/config`
+pub fn known_ssh_hosts() -> Vec<String> {
+  UserDirs::new()
+    .and_then(|dirs| std::fs::read_to_string(dirs.home_dir().join(".ssh").join("config")).ok())
+    .map(|content| ssh_config_hosts(&content))
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::SshFolder;
+  use crate::ScanOptions;
+
+  #[test]
+  fn find_prunes_the_excluded_directories() {
+    let options = ScanOptions::builder().max_depth(Some(2)).build();
+    let command = SshFolder::parse("ssh://devbox:~/my src")
+      .unwrap()
+      .find_command(&options);
+    assert!(command.starts_with("cd ~/'my src' && find . -maxdepth 3 -type d \\( -name "));
+    assert!(command.contains(" -o -name 'target' -o "));
+    assert!(command.ends_with(" -o -name '.?*' \\) -prune -o -type f -print"));
+    let command = SshFolder::parse("ssh://devbox:/srv/it's")
+      .unwrap()
+      .find_command(&options);
+    assert!(command.starts_with("cd '/srv/it'\\''s' && "));
+  }
+
+  #[test]
+  fn hosts_are_never_options() {
+    assert_eq!(SshFolder::parse("ssh://-oProxyCommand=touch pwned:x"), None);
+    assert_eq!(SshFolder::parse("ssh://-v"), None);
+    let folder = SshFolder::parse("ssh://devbox:~/src").unwrap();
+    let command = folder.ssh_command("true");
+    let args = command
+      .get_args()
+      .map(|arg| arg.to_str().unwrap())
+      .collect::<Vec<_>>();
+    assert_eq!(args[args.len() - 3..], ["--", "devbox", "true"]);
+  }
+}
@@ -0,0 +1,151 @@
+use std::{
+  collections::HashSet,
+  ffi::OsStr,
+  fmt::Display,
+  path::{Path, PathBuf},
+};
+
+use strum::IntoEnumIterator;
+
+use crate::{FolderScan, Project, ProjectKind, Table};
+
+/// The detection outcome of a single [`ProjectKind`] in a code folder
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KindStats {
+  /// The project kind
+  pub kind: ProjectKind,
+  /// The number of detected projects having this kind
+  pub projects: usize,
+  /// The number of files named like a project file of this kind which didn't make a project
+  /// root, usually because they are nested in another project
+  pub rejected: usize,
+}
+
+/// The detection outcome of a code folder, to help tuning custom kinds and exclusions
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::{DetectionStats, Project, ProjectKind};
+///
+/// let projects = vec![Project::new("/dev/alpha", vec![ProjectKind::Rust], vec![], vec![])];
+/// let stats = DetectionStats::from_projects("/dev", &projects, &[]);
+/// assert_eq!(stats.projects, 1);
+/// assert_eq!(stats.kinds[0].kind, ProjectKind::Rust);
+/// assert_eq!(stats.kinds[0].projects, 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DetectionStats {
+  /// The configured code folder
+  pub folder: PathBuf,
+  /// The number of detected projects
+  pub projects: usize,
+  /// The outcome of each kind found in the folder, in [`ProjectKind`] order
+  pub kinds: Vec<KindStats>,
+}
+
+impl DetectionStats {
+  /// Compute the statistics of the `projects` detected in `folder`, without rejected candidates
+  pub fn from_projects<P: AsRef<Path>>(
+    folder: P,
+    projects: &[Project],
+    custom_kinds: &[ProjectKind],
+  ) -> Self {
+    Self::compute(folder.as_ref(), projects, custom_kinds, &[])
+  }
+
+  /// Compute the statistics of the `projects` detected from `scan`, counting the project files
+  /// which didn't make a project root
+  pub fn from_scan(scan: &FolderScan, projects: &[Project], custom_kinds: &[ProjectKind]) -> Self {
+    let accepted = projects
+      .iter()
+      .flat_map(|p| p.project_files())
+      .collect::<HashSet<_>>();
+    let rejected = scan
+      .dirs()
+      .iter()
+      .flat_map(|dir| {
+        let dir_path = scan.path().join(dir.path());
+        let accepted = &accepted;
+        dir
+          .files()
+          .iter()
+          .filter(move |name| !accepted.contains(&dir_path.join(name)))
+          .map(|name| name.as_os_str())
+      })
+      .collect::<Vec<_>>();
+    Self::compute(scan.path(), projects, custom_kinds, &rejected)
+  }
+
+  /// Count the projects of every kind, and the `rejected` file names matching its project files
+  fn compute(
+    folder: &Path,
+    projects: &[Project],
+    custom_kinds: &[ProjectKind],
+    rejected: &[&OsStr],
+  ) -> Self {
+    let kinds = ProjectKind::iter()
+      .filter(|kind| !matches!(kind, ProjectKind::Custom { .. }))
+      .chain(custom_kinds.iter().cloned())
+      .map(|kind| {
+        let names = kind.project_files();
+        KindStats {
+          projects: projects
+            .iter()
+            .filter(|p| p.kinds().contains(&kind))
+            .count(),
+          rejected: rejected
+            .iter()
+            .filter(|fname| names.iter().any(|name| fname.eq_ignore_ascii_case(name)))
+            .count(),
+          kind,
+        }
+      })
+      .filter(|k| k.projects > 0 || k.rejected > 0)
+      .collect();
+    Self {
+      folder: folder.to_path_buf(),
+      projects: projects.len(),
+      kinds,
+    }
+  }
+
+  /// Retrieve the number of rejected project files, all kinds included
+  pub fn rejected(&self) -> usize {
+    self.kinds.iter().map(|k| k.rejected).sum()
+  }
+
+  /// Describe the statistics of several folders, one row per folder and kind
+  pub fn table(stats: &[Self]) -> Table {
+    let mut table = Table::new("Statistics", &["Folder", "Kind", "Projects", "Rejected"]);
+    for folder in stats {
+      for kind in &folder.kinds {
+        table.push_row(vec![
+          folder.folder.display().to_string().into(),
+          kind.kind.name().into(),
+          kind.projects.to_string().into(),
+          kind.rejected.to_string().into(),
+        ]);
+      }
+    }
+    table
+  }
+}
+
+impl Display for DetectionStats {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let kinds = self
+      .kinds
+      .iter()
+      .map(|k| format!("{}: {}", k.kind, k.projects))
+      .collect::<Vec<_>>();
+    write!(
+      f,
+      "'{}': {} projects ({}), {} project files rejected",
+      self.folder.display(),
+      self.projects,
+      kinds.join(", "),
+      self.rejected()
+    )
+  }
+}
@@ -0,0 +1,245 @@
+use std::{
+  fmt::Display,
+  io::IsTerminal,
+  sync::atomic::{AtomicU8, Ordering},
+};
+
+/// When to emit colors and other ANSI styles
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum ColorChoice {
+  /// Only when writing to a terminal and `NO_COLOR` is unset
+  #[default]
+  Auto,
+  /// Always, even when writing to pipes or files
+  Always,
+  /// Never
+  Never,
+}
+
+/// The output streams a style can be written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+  Stdout,
+  Stderr,
+  /// A file, such as the one given to `--output`, only styled with [`ColorChoice::Always`]
+  File,
+}
+
+/// The ANSI styles used across the crate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+  Bold,
+  Dim,
+  Red,
+  Green,
+  Yellow,
+  Cyan,
+}
+
+impl Style {
+  /// Retrieve the ANSI SGR parameter of this style
+  fn code(&self) -> &'static str {
+    match self {
+      Self::Bold => "1",
+      Self::Dim => "2",
+      Self::Red => "31",
+      Self::Green => "32",
+      Self::Yellow => "33",
+      Self::Cyan => "36",
+    }
+  }
+}
+
+/// The selected [`ColorChoice`], stored as its discriminant
+static COLOR_CHOICE: AtomicU8 = AtomicU8::new(ColorChoice::Auto as u8);
+
+/// Select when colors are emitted, for the whole process
+pub fn set_color_choice(choice: ColorChoice) {
+  COLOR_CHOICE.store(choice as u8, Ordering::Relaxed);
+}
+
+/// Retrieve the selected [`ColorChoice`]
+pub fn color_choice() -> ColorChoice {
+  match COLOR_CHOICE.load(Ordering::Relaxed) {
+    x if x == ColorChoice::Always as u8 => ColorChoice::Always,
+    x if x == ColorChoice::Never as u8 => ColorChoice::Never,
+    _ => ColorChoice::Auto,
+  }
+}
+
+/// Check whether styles must be emitted on the given stream.
+///
+/// In [`ColorChoice::Auto`] mode, styles are disabled when the `NO_COLOR` environment
+/// variable is set to a non-empty value, or when the stream is redirected to a pipe or file.
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::style::{enabled, set_color_choice, ColorChoice, Stream};
+///
+/// set_color_choice(ColorChoice::Auto);
+/// assert!(!enabled(Stream::File));
+/// set_color_choice(ColorChoice::Always);
+/// assert!(enabled(Stream::File));
+/// ```
+pub fn enabled(stream: Stream) -> bool {
+  match color_choice() {
+    ColorChoice::Always => true,
+    ColorChoice::Never => false,
+    ColorChoice::Auto => {
+      let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+      let is_terminal = match stream {
+        Stream::Stdout => std::io::stdout().is_terminal(),
+        Stream::Stderr => std::io::stderr().is_terminal(),
+        Stream::File => false,
+      };
+      !no_color && is_terminal
+    }
+  }
+}
+
+/// Apply `style` to `text` if styles are [`enabled`] on `stream`
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::style::{paint, set_color_choice, ColorChoice, Stream, Style};
+///
+/// set_color_choice(ColorChoice::Always);
+/// assert_eq!(paint(Stream::Stdout, Style::Bold, "fatal"), "\x1b[1mfatal\x1b[0m");
+/// set_color_choice(ColorChoice::Never);
+/// assert_eq!(paint(Stream::Stdout, Style::Bold, "fatal"), "fatal");
+/// ```
+pub fn paint<T: Display>(stream: Stream, style: Style, text: T) -> String {
+  match enabled(stream) {
+    true => format!("\x1b[{}m{}\x1b[0m", style.code(), text),
+    false => text.to_string(),
+  }
+}
+
+/// Apply `style` to `text` unless [`ColorChoice::Never`] is selected, for the writers which
+/// don't know where they write: the [`styled_writer`] they write through removes the styles
+/// its destination doesn't support.
+pub fn paint_styled<T: Display>(style: Style, text: T) -> String {
+  match color_choice() {
+    ColorChoice::Never => text.to_string(),
+    _ => format!("\x1b[{}m{}\x1b[0m", style.code(), text),
+  }
+}
+
+/// Wrap `to`, written to `stream`, in a [`StripAnsi`] writer unless styles are [`enabled`] on
+/// `stream`
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::style::{paint_styled, set_color_choice, styled_writer, ColorChoice};
+/// use pgrep_core::style::{Stream, Style};
+/// use std::io::Write;
+///
+/// set_color_choice(ColorChoice::Auto);
+/// let mut out = vec![];
+/// let mut writer = styled_writer(Stream::File, &mut out);
+/// write!(writer, "{}", paint_styled(Style::Bold, "api")).unwrap();
+/// drop(writer);
+/// assert_eq!(out, b"api");
+/// ```
+pub fn styled_writer<'a, W: std::io::Write + 'a>(
+  stream: Stream,
+  to: W,
+) -> Box<dyn std::io::Write + 'a> {
+  match enabled(stream) {
+    true => Box::new(to),
+    false => Box::new(StripAnsi::new(to)),
+  }
+}
+
+/// Remove the ANSI escape sequences from the given text
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::style::strip_ansi;
+///
+/// assert_eq!(strip_ansi("\x1b[1;31mfatal\x1b[0m: oops"), "fatal: oops");
+/// ```
+pub fn strip_ansi(text: &str) -> String {
+  let mut ret = String::with_capacity(text.len());
+  let mut chars = text.chars().peekable();
+  while let Some(ch) = chars.next() {
+    if ch != '\x1b' {
+      ret.push(ch);
+      continue;
+    }
+    // CSI sequences are terminated by a byte in the '@'..='~' range
+    if chars.next_if_eq(&'[').is_some() {
+      for ch in chars.by_ref() {
+        if ('@'..='~').contains(&ch) {
+          break;
+        }
+      }
+    }
+  }
+  ret
+}
+
+/// A writer removing ANSI escape sequences from everything written through it,
+/// used when styles are disabled on the underlying stream.
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::style::StripAnsi;
+/// use std::io::Write;
+///
+/// let mut out = vec![];
+/// let mut writer = StripAnsi::new(&mut out);
+/// write!(writer, "\x1b[1mname\x1b[0m\n\x1b[36m").unwrap();
+/// write!(writer, "kind\x1b[0m").unwrap();
+/// drop(writer);
+/// assert_eq!(String::from_utf8(out).unwrap(), "name\nkind");
+/// ```
+pub struct StripAnsi<W: std::io::Write> {
+  inner: W,
+  /// The bytes of an incomplete UTF-8 sequence or escape sequence, kept for the next write
+  pending: Vec<u8>,
+}
+
+impl<W: std::io::Write> StripAnsi<W> {
+  /// Create a new writer wrapping `inner`
+  pub fn new(inner: W) -> Self {
+    Self {
+      inner,
+      pending: vec![],
+    }
+  }
+}
+
+impl<W: std::io::Write> std::io::Write for StripAnsi<W> {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    self.pending.extend_from_slice(buf);
+    // only process complete lines, escape sequences never span them
+    if let Some(end) = self.pending.iter().rposition(|b| *b == b'\n') {
+      let line = self.pending.drain(..=end).collect::<Vec<_>>();
+      self
+        .inner
+        .write_all(strip_ansi(&String::from_utf8_lossy(&line)).as_bytes())?;
+    }
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    let rest = std::mem::take(&mut self.pending);
+    self
+      .inner
+      .write_all(strip_ansi(&String::from_utf8_lossy(&rest)).as_bytes())?;
+    self.inner.flush()
+  }
+}
+
+impl<W: std::io::Write> Drop for StripAnsi<W> {
+  fn drop(&mut self) {
+    let _ = std::io::Write::flush(self);
+  }
+}
@@ -0,0 +1,16 @@
+//! Fixtures shared by the unit tests.
+
+use std::path::PathBuf;
+
+/// Create a temporary folder named after `test`, emptied first, holding the `files` with their
+/// content
+pub(crate) fn temp_tree(test: &str, files: &[(&str, &str)]) -> PathBuf {
+  let root = std::env::temp_dir().join(format!("pgrep-{}-{}", test, std::process::id()));
+  let _ = std::fs::remove_dir_all(&root);
+  std::fs::create_dir_all(&root).unwrap();
+  for (file, content) in files {
+    std::fs::create_dir_all(root.join(file).parent().unwrap()).unwrap();
+    std::fs::write(root.join(file), content).unwrap();
+  }
+  root
+}
@@ -0,0 +1,279 @@
+use std::{
+  path::{Path, PathBuf},
+  time::Duration,
+};
+
+use chrono::{Datelike, NaiveDate};
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::{get_data_dir, Error, Table};
+
+/// The counters of a run, or of every run of a day, see [`UsageStats`]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Usage {
+  /// The number of searches
+  pub queries: u64,
+  /// The number of searches answered by the cached matches, see [`crate::ResultCache`]
+  pub result_hits: u64,
+  /// The number of code folders loaded from the cache
+  pub folder_hits: u64,
+  /// The number of code folders scanned
+  pub scans: u64,
+  /// The time spent scanning code folders, in milliseconds
+  pub scan_millis: u64,
+}
+
+impl Usage {
+  /// Record a code folder, loaded from the cache or scanned in `scan_time`
+  pub fn record_folder(&mut self, scan_time: Option<Duration>) {
+    match scan_time {
+      Some(scan_time) => {
+        self.scans += 1;
+        self.scan_millis += scan_time.as_millis() as u64;
+      }
+      None => self.folder_hits += 1,
+    }
+  }
+
+  /// Add the counters of `other` to these ones
+  pub fn add(&mut self, other: &Usage) {
+    self.queries += other.queries;
+    self.result_hits += other.result_hits;
+    self.folder_hits += other.folder_hits;
+    self.scans += other.scans;
+    self.scan_millis += other.scan_millis;
+  }
+
+  /// The ratio of code folders loaded from the cache rather than scanned, `None` when no
+  /// folder was loaded
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use std::time::Duration;
+  /// use pgrep_core::Usage;
+  ///
+  /// let mut usage = Usage::default();
+  /// assert_eq!(usage.hit_rate(), None);
+  /// usage.record_folder(None);
+  /// usage.record_folder(None);
+  /// usage.record_folder(None);
+  /// usage.record_folder(Some(Duration::from_millis(250)));
+  /// assert_eq!(usage.hit_rate(), Some(0.75));
+  /// assert_eq!(usage.mean_scan_time(), Some(Duration::from_millis(250)));
+  /// ```
+  pub fn hit_rate(&self) -> Option<f64> {
+    let folders = self.folder_hits + self.scans;
+    (folders > 0).then(|| self.folder_hits as f64 / folders as f64)
+  }
+
+  /// The mean duration of a folder scan, `None` when no folder was scanned
+  pub fn mean_scan_time(&self) -> Option<Duration> {
+    (self.scans > 0).then(|| Duration::from_millis(self.scan_millis / self.scans))
+  }
+
+  /// Check whether nothing was recorded
+  pub fn is_empty(&self) -> bool {
+    *self == Self::default()
+  }
+}
+
+/// The [`Usage`] of a day
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UsageDay {
+  /// The day, in the local time zone
+  pub date: NaiveDate,
+  /// The counters of every run of the day
+  #[serde(flatten)]
+  pub usage: Usage,
+}
+
+/// How [`UsageStats::table`] groups the days
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum UsagePeriod {
+  /// A row per day, such as `2024-01-31`
+  Day,
+  /// A row per ISO week, such as `2024-W05`
+  #[default]
+  Week,
+  /// A row per month, such as `2024-01`
+  Month,
+}
+
+impl UsagePeriod {
+  /// The name of the period holding `date`
+  fn name(&self, date: NaiveDate) -> String {
+    match self {
+      Self::Day => date.format("%Y-%m-%d").to_string(),
+      Self::Week => {
+        let week = date.iso_week();
+        format!("{}-W{:02}", week.year(), week.week())
+      }
+      Self::Month => date.format("%Y-%m").to_string(),
+    }
+  }
+}
+
+/// The searches, scan durations and cache hits of the latest days, recorded when the
+/// `usage_stats` setting is enabled to help tuning the cache. They never leave the machine.
+///
+/// They are stored as a TOML file in the user data folder, oldest day first.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct UsageStats {
+  /// The file the statistics are stored in
+  #[serde(skip)]
+  file: PathBuf,
+  /// The recorded days, oldest first
+  days: Vec<UsageDay>,
+}
+
+impl UsageStats {
+  /// The usage statistics file name
+  pub const FILE_NAME: &'static str = "usage-stats.toml";
+  /// The number of days kept
+  pub const CAPACITY: usize = 400;
+
+  /// Load the statistics stored in `file`, none if it doesn't exist yet
+  pub fn load<P: AsRef<Path>>(file: P) -> crate::Result<Self> {
+    let file = file.as_ref().to_path_buf();
+    let mut ret = match file.exists() {
+      true => {
+        let content = std::fs::read_to_string(&file)
+          .map_err(|e| Error::io(format!("cannot read usage stats '{}'", file.display()), e))?;
+        toml::from_str(&content)?
+      }
+      false => Self::default(),
+    };
+    ret.file = file;
+    Ok(ret)
+  }
+
+  /// Load the statistics stored in the user data folder, or in a temporary folder when the
+  /// platform has none
+  pub fn open() -> crate::Result<Self> {
+    Self::load(get_data_dir().join(Self::FILE_NAME))
+  }
+
+  /// Write the statistics to their file, creating its folder if needed
+  pub fn save(&self) -> crate::Result<()> {
+    if let Some(dir) = self.file.parent() {
+      std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&self.file, toml::to_string_pretty(self)?).map_err(|e| {
+      Error::io(
+        format!("cannot write usage stats '{}'", self.file.display()),
+        e,
+      )
+    })?;
+    debug!(
+      "saved {} days of usage to '{}'",
+      self.days.len(),
+      self.file.display()
+    );
+    Ok(())
+  }
+
+  /// Add the counters of a run to those of `date`, dropping the oldest days beyond
+  /// [`Self::CAPACITY`]
+  pub fn record(&mut self, date: NaiveDate, usage: &Usage) {
+    match self.days.iter_mut().find(|day| day.date == date) {
+      Some(day) => day.usage.add(usage),
+      None => {
+        self.days.push(UsageDay {
+          date,
+          usage: *usage,
+        });
+        self.days.sort_by_key(|day| day.date);
+      }
+    }
+    let excess = self.days.len().saturating_sub(Self::CAPACITY);
+    self.days.drain(..excess);
+  }
+
+  /// Iterate over the recorded days, oldest first
+  pub fn days(&self) -> impl Iterator<Item = &UsageDay> {
+    self.days.iter()
+  }
+
+  /// Describe the statistics as a table, a row per `period`, oldest first so that trends read
+  /// from top to bottom
+  pub fn table(&self, period: UsagePeriod) -> Table {
+    let mut rows: Vec<(String, Usage)> = vec![];
+    for day in &self.days {
+      let name = period.name(day.date);
+      match rows.last_mut().filter(|(last, _)| *last == name) {
+        Some((_, usage)) => usage.add(&day.usage),
+        None => rows.push((name, day.usage)),
+      }
+    }
+    let mut table = Table::new(
+      "Usage statistics",
+      &[
+        "Period",
+        "Queries",
+        "Cached matches",
+        "Scans",
+        "Mean scan time",
+        "Cache hit rate",
+      ],
+    );
+    for (name, usage) in rows {
+      let mean_scan_time = usage
+        .mean_scan_time()
+        .map(|time| format!("{}ms", time.as_millis()));
+      let hit_rate = usage.hit_rate().map(|rate| format!("{:.0}%", rate * 100.0));
+      table.push_row(vec![
+        name.into(),
+        usage.queries.to_string().into(),
+        usage.result_hits.to_string().into(),
+        usage.scans.to_string().into(),
+        mean_scan_time.unwrap_or_default().into(),
+        hit_rate.unwrap_or_default().into(),
+      ]);
+    }
+    table
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::NaiveDate;
+
+  use super::{Usage, UsagePeriod, UsageStats};
+
+  #[test]
+  fn days_are_aggregated_by_period() {
+    let file = std::env::temp_dir()
+      .join(format!("pgrep-usage-stats-{}", std::process::id()))
+      .join(UsageStats::FILE_NAME);
+    let mut stats = UsageStats::load(&file).unwrap();
+    let run = Usage {
+      queries: 1,
+      result_hits: 0,
+      folder_hits: 1,
+      scans: 1,
+      scan_millis: 100,
+    };
+    let day = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+    stats.record(day(31), &run);
+    stats.record(day(29), &run);
+    stats.record(day(29), &run);
+    stats.save().unwrap();
+    let stats = UsageStats::load(&file).unwrap();
+    std::fs::remove_dir_all(file.parent().unwrap()).unwrap();
+    let dates = stats
+      .days()
+      .map(|day| (day.date, day.usage.queries))
+      .collect::<Vec<_>>();
+    assert_eq!(dates, [(day(29), 2), (day(31), 1)]);
+    let weeks = stats.table(UsagePeriod::Week);
+    assert_eq!(weeks.rows().len(), 1);
+    assert_eq!(weeks.rows()[0][0].to_string(), "2024-W05");
+    assert_eq!(weeks.rows()[0][1].to_string(), "3");
+    assert_eq!(weeks.rows()[0][5].to_string(), "50%");
+    assert_eq!(stats.table(UsagePeriod::Day).rows().len(), 2);
+  }
+}
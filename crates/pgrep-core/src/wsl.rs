@@ -0,0 +1,147 @@
+//! Interoperability with the Windows Subsystem for Linux.
+//!
+//! On Windows, the files of a distro are shared as `\\wsl$\<distro>\...`, or
+//! `\\wsl.localhost\<distro>\...` on recent versions. Such folders are scanned like local
+//! ones, and reports write their paths `wsl://<distro>/...` as [`crate::normalize_path`] does.
+//! `--wsl` scans the home folders of every installed distro.
+
+use std::{
+  ffi::OsString,
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+use log::debug;
+
+use crate::FolderConfig;
+
+/// The prefix of the paths in a WSL distro, as written in reports
+pub const WSL_SCHEME: &str = "wsl://";
+
+/// The names of the Windows shares holding the WSL distros
+const WSL_SHARES: [&str; 2] = ["wsl$", "wsl.localhost"];
+
+/// List the installed WSL distros, none when not on Windows or when WSL isn't installed
+pub fn wsl_distros() -> Vec<String> {
+  if !cfg!(windows) {
+    return vec![];
+  }
+  let output = match Command::new("wsl.exe").args(["--list", "--quiet"]).output() {
+    Ok(output) if output.status.success() => output.stdout,
+    Ok(output) => {
+      debug!(
+        "cannot list the WSL distros, wsl.exe failed with {}",
+        output.status
+      );
+      return vec![];
+    }
+    Err(e) => {
+      debug!("cannot list the WSL distros, {}", e);
+      return vec![];
+    }
+  };
+  // wsl.exe writes UTF-16
+  let units = output
+    .chunks_exact(2)
+    .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+    .collect::<Vec<_>>();
+  String::from_utf16_lossy(&units)
+    .lines()
+    .map(|line| line.trim_matches(|c: char| c.is_whitespace() || c == '\0'))
+    .filter(|distro| !distro.is_empty())
+    .map(String::from)
+    .collect()
+}
+
+/// The home folders of the installed WSL distros, scanned with `--wsl`
+pub fn wsl_folders() -> Vec<FolderConfig> {
+  wsl_distros()
+    .into_iter()
+    .map(|distro| PathBuf::from(format!(r"\\{}\{}\home", WSL_SHARES[0], distro)))
+    .filter(|home| home.is_dir())
+    .map(FolderConfig::from)
+    .collect()
+}
+
+/// Split a Windows path into a WSL distro into the distro name and the path in it
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::split_wsl_path;
+///
+/// let (distro, path) = split_wsl_path(r"\\wsl$\Ubuntu\home\me\api").unwrap();
+/// assert_eq!((distro.as_str(), path.as_str()), ("Ubuntu", "/home/me/api"));
+/// let (distro, path) = split_wsl_path(r"\\?\UNC\wsl.localhost\Debian").unwrap();
+/// assert_eq!((distro.as_str(), path.as_str()), ("Debian", "/"));
+/// assert_eq!(split_wsl_path(r"\\server\share\api"), None);
+/// ```
+pub fn split_wsl_path<P: AsRef<Path>>(path: P) -> Option<(String, String)> {
+  let path = path.as_ref().to_str()?.replace('\\', "/");
+  let share = path
+    .strip_prefix("//?/UNC/")
+    .or_else(|| path.strip_prefix("//"))?;
+  let (host, rest) = share.split_once('/')?;
+  if !WSL_SHARES
+    .iter()
+    .any(|name| host.eq_ignore_ascii_case(name))
+  {
+    return None;
+  }
+  let (distro, rest) = rest.split_once('/').unwrap_or((rest, ""));
+  if distro.is_empty() {
+    return None;
+  }
+  Some((
+    distro.to_string(),
+    format!("/{}", rest.trim_end_matches('/')),
+  ))
+}
+
+/// Translate a `wsl://<distro>/<path>` folder, as written in reports, into the Windows path of
+/// the share holding it, `None` when `path` isn't such a folder
+///
+/// # Examples
+///
+/// ```
+/// use pgrep_core::wsl_share_path;
+///
+/// let path = wsl_share_path("wsl://Ubuntu/home/me").unwrap();
+/// assert_eq!(path.to_str(), Some(r"\\wsl$\Ubuntu\home\me"));
+/// assert_eq!(wsl_share_path("/home/me"), None);
+/// ```
+pub fn wsl_share_path<P: AsRef<Path>>(path: P) -> Option<PathBuf> {
+  let rest = path.as_ref().to_str()?.strip_prefix(WSL_SCHEME)?;
+  let rest = rest.trim_end_matches('/').replace('/', "\\");
+  Some(PathBuf::from(format!(r"\\{}\{}", WSL_SHARES[0], rest)))
+}
+
+/// The form of the project `path` an editor understands: the path in the distro for the Linux
+/// editors run by `wsl` on Windows, and the Windows one, as given by `wslpath -w`, for the
+/// Windows editors run from a distro.
+pub fn wsl_editor_path<P: AsRef<Path>>(editor: &Path, path: P) -> OsString {
+  let path = path.as_ref();
+  let is_named = |name: &str| {
+    editor
+      .file_stem()
+      .is_some_and(|stem| stem.to_string_lossy().eq_ignore_ascii_case(name))
+  };
+  if cfg!(windows) && is_named("wsl") {
+    if let Some((_, linux_path)) = split_wsl_path(path) {
+      return linux_path.into();
+    }
+  }
+  let windows_editor = editor
+    .extension()
+    .is_some_and(|ext| ext.eq_ignore_ascii_case("exe"));
+  if cfg!(unix) && windows_editor && std::env::var_os("WSL_DISTRO_NAME").is_some() {
+    match Command::new("wslpath").arg("-w").arg(path).output() {
+      Ok(output) if output.status.success() => {
+        let windows_path = String::from_utf8_lossy(&output.stdout);
+        return windows_path.trim_end_matches(['\r', '\n']).into();
+      }
+      _ => debug!("cannot translate '{}' with wslpath", path.display()),
+    }
+  }
+  path.into()
+}
@@ -0,0 +1,20 @@
+//! Match arbitrary subjects against arbitrary expressions in every mode, so that the index
+//! arithmetic of the matcher is exercised at every boundary, and its slow paths show up as
+//! timeouts
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pgrep_core::{MatchMode, Query};
+
+fuzz_target!(|input: (&str, &str)| {
+  let (expr, subject) = input;
+  let Ok(query) = expr.parse::<Query>() else {
+    return;
+  };
+  for mode in [MatchMode::Anchored, MatchMode::Partial] {
+    let query = query.clone().with_mode(mode);
+    let matches = query.matches(subject);
+    assert_eq!(query.score(subject).is_some(), matches, "'{}' ~ '{}'", expr, subject);
+  }
+});
@@ -0,0 +1,19 @@
+//! Parse arbitrary expressions, checking that the parts of the valid ones render back to an
+//! expression parsing to the same query
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pgrep_core::Query;
+
+fuzz_target!(|expr: &str| {
+  let Ok(query) = expr.parse::<Query>() else {
+    return;
+  };
+  let rendered = query.clone().with_parts(query.parts().clone()).to_string();
+  let parsed = rendered
+    .parse::<Query>()
+    .unwrap_or_else(|e| panic!("'{}' rendered as '{}': {}", expr, rendered, e));
+  assert_eq!(parsed.parts(), query.parts(), "'{}' rendered as '{}'", expr, rendered);
+  assert_eq!(parsed.selectors(), query.selectors(), "'{}' rendered as '{}'", expr, rendered);
+});
@@ -1,16 +1,35 @@
 use std::process::ExitCode;
 
 use log::error;
-use pgrep::App;
+use pgrep::{
+  i18n::tr,
+  style::{paint, Stream, Style},
+  App,
+};
 
-fn run() -> pgrep::Result<()> {
-  App::new()?.run()
+fn run(json_errors: &mut bool) -> pgrep::Result<()> {
+  let (options, matches) = App::options()?;
+  // the configuration may fail to load, so its output format is only known afterwards
+  *json_errors = options.reports_json();
+  let app = App::with_options(options, &matches)?;
+  *json_errors = app.reports_json();
+  app.run()
 }
 
 fn main() -> ExitCode {
-  if let Err(e) = run() {
-    error!("\x1b[1mfatal\x1b[0m: {}", e);
-    return ExitCode::FAILURE;
+  let mut json_errors = false;
+  if let Err(e) = run(&mut json_errors) {
+    #[cfg(feature = "json")]
+    if json_errors {
+      println!("{}", e.to_json());
+      return ExitCode::from(e.code());
+    }
+    let fatal = paint(Stream::Stderr, Style::Bold, tr("error.fatal"));
+    error!("{}: {}: {}", fatal, e.localized_kind(), e);
+    if let Some(cause) = e.cause() {
+      error!("{}: {}", tr("error.caused-by"), cause);
+    }
+    return ExitCode::from(e.code());
   }
   ExitCode::SUCCESS
 }
@@ -1,28 +1,53 @@
 use std::{
-  collections::HashMap,
-  io::stdout,
-  path::PathBuf,
-  sync::{Arc, Mutex},
+  collections::{BTreeMap, HashMap, HashSet},
+  ffi::OsString,
+  io::{stderr, stdout, IsTerminal, Write},
+  path::{Path, PathBuf},
+  str::FromStr,
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+  },
+  time::{Duration, Instant},
 };
 
+#[cfg(feature = "json")]
+use crate::BuildInfo;
 use crate::{
-  cache, detect_projects, AppOptions, BoxedProjectMatchesFormatter, BoxedUI, Cache, Config, Error,
-  FolderScan, Project, Query,
+  archive, clean, clean_actions, clone_remote, dependency_sources, detect_projects, exec_all,
+  expand_path, human_size, is_remote_path, normalize_path, scaffold, wsl_editor_path, wsl_folders,
+  AppCommand, AppOptions, ArchiveMode, ArchivedProject, BoxedProjectMatchesFormatter, BoxedUI,
+  Cache, CacheCommand, CancelToken, Change, CleanResult, CloneRemote, Config, ConfigCommand,
+  DetectionStats, Error, ExecResult, FacetCache, FacetRegistry, FolderScan, GenTarget, History,
+  LogObserver, MatchMode, MatchScore, Notes, OutputFormat, Pins, Project, ProjectKind,
+  QueriesCommand, Query, QueryHistory, ResultCache, ScanObserver, ScanOptions, Snapshot, SortOrder,
+  StopPredicate, Table, Usage, UsageStats, WidgetShell, WorkspaceConfig,
 };
-use clap::Parser;
-use directories::ProjectDirs;
-use log::{debug, warn};
-
-/// The qualifier for windows and macOS config folders
-pub const APP_QUALIFIER: &'static str = "com";
-/// The organization for windows and macOS config folders
-pub const APP_ORGANIZATION: &'static str = "darksofts";
-/// The application for windows and macOS config folders
-pub const APP_APPLICATION: &'static str = env!("CARGO_PKG_NAME");
-
-/// Retrieve the platform-dependent project directories.
-pub fn get_project_dirs() -> Option<ProjectDirs> {
-  ProjectDirs::from(APP_QUALIFIER, APP_ORGANIZATION, APP_APPLICATION)
+#[cfg(feature = "audit")]
+use crate::{audit_outdated, AuditCommand, AuditResult};
+use crate::{
+  i18n::{self, tr, tr_fmt, Lang},
+  style::{self, Stream},
+};
+use clap::{parser::ValueSource, ArgMatches, CommandFactory, FromArgMatches};
+use lazy_static::lazy_static;
+use log::{debug, info, log_enabled, warn, Level};
+use pretty_env_logger::env_logger::WriteStyle;
+use serde::{de::DeserializeOwned, Serialize};
+use strum::IntoEnumIterator;
+
+/// The cache key of the projects detected in a folder, relative to the folder
+const PROJECTS_KEY: &str = ".projects";
+
+lazy_static! {
+  /// The global cache instance as a mutexed [`std::sync::Arc`]
+  static ref _INST: Arc<Mutex<Cache>> =
+    Arc::new(Mutex::new(Cache::new()));
+}
+
+/// Retrieve the global cache instance, shared by the whole app
+pub fn cache() -> &'static Arc<Mutex<Cache>> {
+  &_INST
 }
 
 /// The application structure
@@ -35,8 +60,18 @@ pub struct App {
   cache: Arc<Mutex<Cache>>,
   /// The parsed query
   query: Query,
+  /// The selected output format
+  #[cfg_attr(not(any(feature = "json", feature = "http")), allow(dead_code))]
+  format: OutputFormat,
   /// The project formatter to use
   formatter: BoxedProjectMatchesFormatter,
+  /// Cancelled by Ctrl-C, stopping the running scans
+  cancel: CancelToken,
+  /// The usage of this run, recorded in the [`UsageStats`] when enabled
+  usage: Mutex<Usage>,
+  /// The folders holding the source code of dependencies, scanned with
+  /// `--include-deps-sources`
+  dependency_folders: Vec<PathBuf>,
 }
 
 impl App {
@@ -47,34 +82,340 @@ impl App {
   ///   - load the user configuration
   ///   - parse the query string
   pub fn new() -> crate::Result<Self> {
-    pretty_env_logger::try_init()?;
-    let options = AppOptions::parse();
-    let config = Config::load(options.config.as_ref(), options.folders.clone())?;
-    if config.general.folders.is_empty() {
-      return Err(Error::Init(format!(
-        "No source code folders configured. use -F/--folder to specify one or more."
-      )));
+    let (options, matches) = Self::options()?;
+    Self::with_options(options, &matches)
+  }
+
+  /// Parse the command-line options, once the language of the messages is selected. The
+  /// matches tell which options were left to their default.
+  pub fn options() -> crate::Result<(AppOptions, ArgMatches)> {
+    i18n::set_lang(Lang::detect(std::env::args()));
+    let matches = Self::parse_args(std::env::args_os())?;
+    Ok((AppOptions::from_arg_matches(&matches)?, matches))
+  }
+
+  /// Create a new application instance from the parsed command-line `options` and their
+  /// `matches`, see [`App::new`]
+  pub fn with_options(mut options: AppOptions, matches: &ArgMatches) -> crate::Result<Self> {
+    style::set_color_choice(options.color);
+    Self::init_logger()?;
+    #[cfg(feature = "tracing")]
+    if let Some(path) = &options.trace_file {
+      crate::trace::ChromeTrace::install(path)?;
     }
+    let cancel = CancelToken::default();
+    Self::init_interrupt_handler(cancel.clone());
+    // subcommands don't search projects, and must not need nor write a configuration
+    let mut config = match options.needs_config() {
+      true => Config::load(options.config.as_ref(), options.folders.clone())?,
+      false => Config::default(),
+    };
+    // the dependency sources are scanned like the code folders, without being saved
+    let mut dependency_folders = vec![];
+    if options.include_deps_sources && options.needs_config() {
+      for folder in dependency_sources() {
+        dependency_folders.push(folder.path.clone());
+        if config.general.folder(&folder.path).is_none() {
+          config.general.folders.push(folder);
+        }
+      }
+    }
+    // so are the container folders and the WSL homes
+    if options.needs_config() {
+      let extra_folders = options
+        .in_container
+        .iter()
+        .map(|container| container.to_path().into());
+      let wsl_folders = match options.wsl {
+        true => wsl_folders(),
+        false => vec![],
+      };
+      for folder in extra_folders.chain(wsl_folders) {
+        if config.general.folder(&folder.path).is_none() {
+          config.general.folders.push(folder);
+        }
+      }
+    }
+    if options.searches_projects() && config.general.folders.is_empty() {
+      return Err(Error::config(tr("app.no-folders")));
+    }
+    Self::apply_config_defaults(&mut options, matches, &config)?;
     let cache = cache().clone();
+    if let Some(dir) = &options.cache_dir {
+      cache.lock().unwrap().set_base_dir(dir)?;
+    } else if let Some(dir) = Self::shared_cache_dir(&config)? {
+      if let Err(e) = cache.lock().unwrap().attach_shared(&dir) {
+        warn!(
+          "{}",
+          e.with_context(format!("ignoring the shared cache '{}'", dir.display()))
+        );
+      }
+    }
     if options.no_cache {
       cache.lock().unwrap().disable();
+    } else if options.cache_readonly {
+      cache.lock().unwrap().set_read_only(true);
+    } else if let Some(s3) = &config.cache.s3 {
+      #[cfg(feature = "s3")]
+      if let Err(e) = config
+        .ensure_trusted(options.trust || !s3.runs_commands())
+        .and_then(|_| crate::S3Store::new(s3))
+        .and_then(|store| cache.lock().unwrap().attach_remote(store))
+      {
+        warn!("{}", tr_fmt("cache.remote-failed", &[&e]));
+      }
+      #[cfg(not(feature = "s3"))]
+      warn!("{}", tr_fmt("cache.s3-unavailable", &[&s3.bucket]));
     }
-    let query = options.query.clone();
+    let mode = match options.partial {
+      true => MatchMode::Partial,
+      false => config.general.match_mode,
+    };
+    let query = Self::expand_query(&options.query, &config)?
+      .with_mode(mode)
+      .with_match_path(options.match_path);
+    let default_format = config.output.format.unwrap_or_else(OutputFormat::preferred);
+    let format = options.output_format(default_format);
     Ok(Self {
-      formatter: options.format.formatter()?,
+      formatter: format.formatter()?,
+      format,
       options,
       config,
       cache,
       query,
+      cancel,
+      usage: Mutex::default(),
+      dependency_folders,
     })
   }
 
+  /// Fill the options left out of the command-line with the configured defaults: the query,
+  /// unless one is recalled with --recall, the sort order and the facets
+  fn apply_config_defaults(
+    options: &mut AppOptions,
+    matches: &ArgMatches,
+    config: &Config,
+  ) -> crate::Result<()> {
+    let defaulted = |id: &str| matches.value_source(id) != Some(ValueSource::CommandLine);
+    if let Some(n) = options.recall {
+      let history = QueryHistory::open()?;
+      let query = history.recall(n).map_err(Error::config)?;
+      info!("{}", tr_fmt("app.recalled-query", &[&query]));
+      options.query = query.parse::<Query>()?;
+    } else if options.needs_query() && defaulted("query") {
+      match &config.general.query {
+        Some(query) => options.query = query.clone(),
+        None => return Err(Error::config(tr("app.missing-query"))),
+      }
+    }
+    if let Some(sort) = config.output.sort.filter(|_| defaulted("sort")) {
+      options.sort = sort;
+    }
+    if options.facets.is_empty() {
+      options.facets = config
+        .output
+        .facets
+        .iter()
+        .map(|name| crate::options::parse_facet(name))
+        .collect::<Result<_, _>>()
+        .map_err(Error::config)?;
+    }
+    Ok(())
+  }
+
+  /// Replace the `@<name>` words of `query` with the configured saved queries, then its aliases
+  /// with their expression, see [`Query::expand_saved`] and [`Query::expand_aliases`]
+  fn expand_query(query: &Query, config: &Config) -> crate::Result<Query> {
+    let expr = query.to_string();
+    let expanded = match expr.contains('@') {
+      true => Query::expand_saved(&expr, &config.queries)?,
+      false => expr.clone(),
+    };
+    let expanded = Query::expand_aliases(&expanded, &config.aliases);
+    if expanded == expr {
+      return Ok(query.clone());
+    }
+    debug!("expanded '{}' to '{}'", expr, expanded);
+    Ok(expanded.parse::<Query>()?)
+  }
+
+  /// Retrieve the shared cache folder: the configured one, else the default one if it exists
+  fn shared_cache_dir(config: &Config) -> crate::Result<Option<PathBuf>> {
+    match &config.cache.shared {
+      Some(dir) => Ok(Some(crate::config::expand_path(dir)?)),
+      None => Ok(
+        Cache::DEFAULT_SHARED_DIR
+          .map(PathBuf::from)
+          .filter(|dir| dir.exists()),
+      ),
+    }
+  }
+
+  /// Handle Ctrl-C: the first interrupt cancels the running scans, which keep the cache entries
+  /// stored so far and exit with [`Error::Interrupted`], the second one exits at once. The
+  /// terminal interface, which never scans, is restored before exiting at once.
+  fn init_interrupt_handler(cancel: CancelToken) {
+    let result = ctrlc::set_handler(move || {
+      #[cfg(feature = "tui")]
+      if crate::Terminal::is_active() {
+        let _ = crate::Terminal::restore_tui();
+        std::process::exit(130);
+      }
+      if cancel.is_cancelled() {
+        std::process::exit(130);
+      }
+      cancel.cancel();
+    });
+    if let Err(e) = result {
+      warn!("{}", tr_fmt("app.no-interrupt-handler", &[&e]));
+    }
+  }
+
+  /// Configure the logger from `RUST_LOG`, with colors if [`style::enabled`] on stderr
+  fn init_logger() -> crate::Result<()> {
+    let mut builder = pretty_env_logger::formatted_builder();
+    if let Ok(filters) = std::env::var("RUST_LOG") {
+      builder.parse_filters(&filters);
+    }
+    builder.write_style(match style::enabled(Stream::Stderr) {
+      true => WriteStyle::Always,
+      false => WriteStyle::Never,
+    });
+    builder.try_init()?;
+    Ok(())
+  }
+
+  /// Check whether reports, and errors, are written as json
+  pub fn reports_json(&self) -> bool {
+    #[cfg(feature = "json")]
+    return self.format == OutputFormat::Json;
+    #[cfg(not(feature = "json"))]
+    return false;
+  }
+
   /// Run the application, scanning the code folders and filtering projects.
   pub fn run(self) -> crate::Result<()> {
+    #[cfg(feature = "json")]
+    if self.options.build_info {
+      println!("{}", serde_json::to_string_pretty(&BuildInfo::current())?);
+      return Ok(());
+    }
+    if self.options.history {
+      return self.write_table(&QueryHistory::open()?.table());
+    }
+    for path in &self.options.rescan {
+      let folder = self.configured_folder(path)?;
+      debug!("rescanning '{}'", folder.display());
+      self.invalidate_folder(&folder);
+    }
+    match self.options.command {
+      Some(AppCommand::Gen(target)) => return Self::generate(target, &mut stdout()),
+      Some(AppCommand::ListFormats) => {
+        return self.write_table(&Self::formats_table());
+      }
+      Some(AppCommand::ListKinds) => {
+        return self.write_table(&Self::kinds_table(&self.config.general.project_kinds));
+      }
+      Some(AppCommand::Stats { period }) => {
+        return self.write_table(&UsageStats::open()?.table(period));
+      }
+      Some(AppCommand::Diff { since }) => {
+        let previous = History::open().at(since)?;
+        let current = Snapshot::new(self.list_projects()?.values().flatten());
+        self.shutdown_cache()?;
+        let changes = match previous {
+          Some(previous) => previous.diff(&current),
+          None => {
+            warn!("{}", tr("app.no-history"));
+            vec![]
+          }
+        };
+        return self.write_table(&Change::table(&changes));
+      }
+      Some(AppCommand::Exec {
+        ref command,
+        ref query,
+        ref workspace,
+        parallel,
+      }) => {
+        let projects = match workspace {
+          Some(name) => self.workspace_projects(name)?,
+          None => self.select_projects(query.as_ref())?,
+        };
+        return self.exec(command, &projects, parallel);
+      }
+      Some(AppCommand::Archive {
+        ref query,
+        ref to,
+        mode,
+        apply,
+      }) => return self.archive(query, to, mode, apply),
+      Some(AppCommand::CleanArtifacts { ref query, yes }) => {
+        return self.clean_artifacts(query, yes)
+      }
+      Some(AppCommand::New {
+        ref kind,
+        ref name,
+        ref parent,
+        open,
+      }) => return self.new_project(kind, name, parent.as_deref(), open),
+      Some(AppCommand::Ws { name: None }) => return self.write_table(&self.workspaces_table()),
+      Some(AppCommand::Ws {
+        name: Some(ref name),
+      }) => {
+        let projects = self.workspace_projects(name)?;
+        if projects.is_empty() {
+          return Err(Error::NoMatch(tr_fmt("app.workspace-empty", &[name])));
+        }
+        return self.write_report(&projects);
+      }
+      Some(AppCommand::Pin { ref path }) => return self.pin(path.as_deref()),
+      Some(AppCommand::Unpin { ref path }) => return self.unpin(path),
+      Some(AppCommand::Note {
+        ref path,
+        ref text,
+        delete,
+      }) => return self.note(path.as_deref(), text.as_deref(), delete),
+      #[cfg(feature = "git")]
+      Some(AppCommand::Status { ref query }) => return self.git_status(query.as_ref()),
+      #[cfg(feature = "audit")]
+      Some(AppCommand::Audit(AuditCommand::Outdated {
+        ref query,
+        parallel,
+        timeout,
+      })) => return self.audit_outdated(query.as_ref(), parallel, timeout),
+      Some(AppCommand::Watch {
+        interval,
+        memory_cap,
+        ..
+      }) => {
+        let memory_cap = match memory_cap {
+          Some(cap) => cap,
+          None => self.config.watch.memory_cap()?,
+        };
+        return self.watch(std::time::Duration::from_secs(interval), memory_cap);
+      }
+      Some(AppCommand::Queries(QueriesCommand::List)) => {
+        return self.write_table(&self.saved_queries_table());
+      }
+      Some(AppCommand::Config(ConfigCommand::Schema)) => {
+        print!("{}", Config::SCHEMA);
+        return Ok(());
+      }
+      Some(AppCommand::Cache(CacheCommand::Gc)) => {
+        let removed = self.collect_cache()?;
+        self.cache.lock().unwrap().shutdown()?;
+        println!("{}", tr_fmt("app.cache-gc", &[&removed.len()]));
+        return Ok(());
+      }
+      #[cfg(feature = "self-update")]
+      Some(AppCommand::SelfUpdate { check, force }) => {
+        return crate::update::self_update(check, force)
+      }
+      None => {}
+    }
     if self.options.list && self.options.query != Default::default() {
-      return Err(Error::Init(format!(
-        "Query given with --list but the two options are mutually exclusive!"
-      )));
+      return Err(Error::config(tr("app.list-with-query")));
     } else if self.options.dump_config {
       println!("{:#?}", self.config);
       return Ok(());
@@ -83,123 +424,1526 @@ impl App {
       warn!("removed '{}'", path.display());
       return Ok(());
     }
+    if self.options.any {
+      let found = self.any_match()?;
+      self.shutdown_cache()?;
+      return match found {
+        true => Ok(()),
+        false => Err(Error::NoMatch(tr_fmt("app.no-match-any", &[&self.query]))),
+      };
+    }
     if !self.options.list {
       debug!(
         "Looking for '{}' in the following paths: {:?}",
         self.options.query, self.config.general.folders
       );
+      self.record_query();
+    }
+    let searched = self.search_projects();
+    self.record_usage();
+    #[cfg_attr(not(feature = "tui"), allow(unused_variables))]
+    let (mut matches, total) = searched?;
+    let pins = Pins::open()?;
+    if self.options.pinned {
+      let count = matches.len();
+      matches.retain(|proj| pins.contains(proj.path()));
+      if matches.is_empty() {
+        return Err(Error::NoMatch(tr_fmt("app.no-pinned-match", &[&count])));
+      }
+    }
+    #[cfg(feature = "tui")]
+    let has_tui = self.options.tui || self.options.tui_inline;
+    #[cfg(not(feature = "tui"))]
+    let has_tui = false;
+    // the TUI extracts the expensive facets of the projects once selected, unless they filter
+    let registry = FacetRegistry::default();
+    #[cfg_attr(not(feature = "tui"), allow(unused_variables))]
+    let (lazy_facets, mut facets): (Vec<_>, Vec<_>) =
+      self.options.facets.iter().cloned().partition(|name| {
+        has_tui
+          && !self.options.facet.contains(name)
+          && registry.get(name).is_some_and(|facet| facet.expensive())
+      });
+    for name in &self.options.facet {
+      if !facets.contains(name) {
+        facets.push(name.clone());
+      }
+    }
+    if !facets.is_empty() {
+      self.extract_facets(&facets, &mut matches)?;
+    }
+    for name in &self.options.facet {
+      let count = matches.len();
+      matches.retain(|proj| proj.facet(name).is_some_and(|value| value.is_set()));
+      if matches.is_empty() {
+        return Err(Error::NoMatch(tr_fmt(
+          "app.no-facet-match",
+          &[&count, name],
+        )));
+      }
+    }
+    if self.options.du || self.options.sort == SortOrder::Size {
+      Self::compute_disk_usages(&mut matches, !self.options.du_skip_artifacts);
+    }
+    match self.options.sort {
+      SortOrder::Score => {}
+      SortOrder::Name => matches.sort_by_key(|proj| proj.name()),
+      SortOrder::Path => matches.sort_by(|a, b| a.path().cmp(b.path())),
+      SortOrder::Size => matches.sort_by_key(|proj| std::cmp::Reverse(proj.disk_usage())),
+    }
+    pins.sort_first(&mut matches);
+    Notes::open()?.attach(&mut matches);
+    if self.options.hash || self.options.duplicates {
+      for project in &mut matches {
+        project.set_manifest_hash(project.compute_manifest_hash());
+      }
+    }
+    if self.options.duplicates {
+      matches = Self::duplicate_projects(matches);
+      if matches.is_empty() {
+        return Err(Error::NoMatch(tr("app.no-duplicates").to_string()));
+      }
+    }
+
+    if self.options.emit_candidates {
+      self.write_candidates(&matches)?;
+      return self.shutdown_cache();
+    }
+
+    #[cfg(feature = "http")]
+    if let Some(url) = &self.options.post {
+      let mut body = vec![];
+      self.formatter.write(&mut body, &mut matches.iter())?;
+      if self.config.post.runs_commands() {
+        self.config.ensure_trusted(self.options.trust)?;
+      }
+      crate::post::post_report(url, self.format, &body, &self.config.post)?;
+      return self.shutdown_cache();
+    }
+
+    #[cfg(feature = "gui")]
+    let has_gui = self.options.gui;
+    #[cfg(not(feature = "gui"))]
+    let has_gui = false;
+    let mut ui: BoxedUI = match (has_gui, has_tui) {
+      (true, _) => {
+        #[cfg(not(feature = "gui"))]
+        panic!("Feature 'gui' not available");
+        #[cfg(feature = "gui")]
+        {
+          let mut gui = crate::Gui::new(self.options.editor.clone());
+          gui.set_clone_remote(self.clone_action());
+          Box::new(gui)
+        }
+      }
+      (false, true) => {
+        #[cfg(not(feature = "tui"))]
+        panic!("Feature 'tui' not available");
+        #[cfg(feature = "tui")]
+        {
+          use crate::{Status, Terminal};
+          let editor = self.options.editor.clone();
+          let mut terminal = match self.options.tui_inline {
+            true => Terminal::inline(editor)?,
+            false => Terminal::new(editor)?,
+          };
+          if !lazy_facets.is_empty() {
+            let (facet_cache, writable) = self.open_facet_cache();
+            terminal.set_lazy_facets(lazy_facets, facet_cache, writable);
+          }
+          let cache = self.cache.lock().unwrap();
+          terminal.set_status(Status {
+            query: self.options.query.to_string(),
+            total,
+            sort: self.options.sort,
+            folders: self
+              .config
+              .general
+              .folders
+              .iter()
+              .map(|folder| {
+                let cached = cache.written_at(&folder.path);
+                (
+                  folder.path.clone(),
+                  cached.map(|at| at..at + cache.max_age(&folder.path)),
+                )
+              })
+              .collect(),
+          });
+          terminal.set_pins(pins);
+          terminal.set_query_history(QueryHistory::open()?);
+          terminal.set_clone_remote(self.clone_action());
+          Box::new(terminal)
+        }
+      }
+      (false, false) => {
+        #[cfg(not(feature = "console"))]
+        panic!("Feature 'console' not available");
+        #[cfg(feature = "console")]
+        {
+          use crate::Console;
+          match self.options.output {
+            Some(ref path) => Box::new(Console::with_output(path)),
+            None => Box::new(Console::new()),
+          }
+        }
+      }
+    };
+    {
+      #[cfg(feature = "tracing")]
+      let _span = tracing::info_span!("format", matches = matches.len()).entered();
+      ui.write_matches(&matches, &self.formatter)?;
+    }
+    ui.render_loop()?;
+    self.shutdown_cache()?;
+    Ok(())
+  }
+
+  /// Match the projects of every code folder against the query, or list them all with
+  /// `--list`. Returns the matches along with the number of projects found.
+  ///
+  /// The matches of a query are cached for a short while, so that repeating it skips loading
+  /// the code folders and matching as long as the cache index doesn't change, see
+  /// [`ResultCache`].
+  fn search_projects(&self) -> crate::Result<(Vec<Project>, usize)> {
+    self.usage.lock().unwrap().queries += 1;
+    let key = ResultCache::key(&self.query, &self.config);
+    let mut results = match self.options.list || self.options.stats || self.options.no_cache {
+      true => None,
+      false => Some(self.open_result_cache()),
+    };
+    if let Some(hit) = results
+      .as_ref()
+      .and_then(|results| self.cached_results(results, &key))
+    {
+      debug!(
+        "reusing the {} cached matches of '{}'",
+        hit.0.len(),
+        self.query
+      );
+      self.usage.lock().unwrap().result_hits += 1;
+      return Ok(hit);
     }
-    // get list of projects
     let projects = self.list_projects()?;
     if projects.is_empty() {
-      return Err(Error::Unknown(format!(
-        "no project root discovered for {} dirs:\n{:#?}",
-        self.config.general.folders.len(),
-        self.config.general.folders
+      let folders = self.folder_paths().collect::<Vec<_>>();
+      return Err(Error::NoMatch(tr_fmt(
+        "app.no-project",
+        &[&folders.len(), &format!("{:#?}", folders)],
       )));
-    } else {
-      // match discovered projects with user query
-      let projects = projects
+    }
+    // match discovered projects with user query
+    let projects = projects
+      .iter()
+      .flat_map(|(_, projects)| projects)
+      .collect::<Vec<_>>();
+    let total = projects.len();
+    debug!("found {} projects", total);
+    let matches = match self.options.list {
+      false => {
+        let matches = Self::match_projects(&self.query, &projects);
+        if matches.is_empty() {
+          return Err(Error::NoMatch(tr_fmt(
+            "app.no-match",
+            &[&self.query, &projects.len()],
+          )));
+        }
+        matches
+      }
+      true => projects,
+    }
+    .iter()
+    .map(|proj| (*proj).clone())
+    .collect::<Vec<_>>();
+    let cache = self.cache.lock().unwrap();
+    if let Some(results) = results.as_mut().filter(|_| !cache.is_read_only()) {
+      results.insert(key, cache.generation(), matches.clone(), total);
+      if let Err(e) = results.save() {
+        warn!(
+          "{}",
+          e.with_context("failed to cache the matches".to_string())
+        );
+      }
+    }
+    Ok((matches, total))
+  }
+
+  /// Load the matches cached in the cache folder, see [`ResultCache`]
+  fn open_result_cache(&self) -> ResultCache {
+    let cache = self.cache.lock().unwrap();
+    ResultCache::load(cache.base_dir().join(ResultCache::FILE_NAME))
+  }
+
+  /// Retrieve the cached matches of the query under `key`, unless the cache index changed since,
+  /// a code folder needs rescanning or a matching project vanished
+  fn cached_results(&self, results: &ResultCache, key: &str) -> Option<(Vec<Project>, usize)> {
+    let mut cache = self.cache.lock().unwrap();
+    let scan_options = self.scan_options();
+    for folder in self.folder_paths() {
+      let scan_options = self.folder_scan_options(folder, &scan_options);
+      Self::check_folder_entries(&mut cache, folder, &scan_options);
+    }
+    let (matches, total) = results.get(key, cache.generation())?;
+    let fresh = self
+      .folder_paths()
+      .all(|folder| cache.contains(&folder) && cache.contains(&folder.join(PROJECTS_KEY)));
+    let exist = matches
+      .iter()
+      .all(|project| project.path().exists() || is_remote_path(project.path()));
+    (fresh && exist).then(|| (matches.clone(), total))
+  }
+
+  /// Run `command` in the folder of every project, `parallel` at a time, writing each output to
+  /// stderr then the summary table.
+  ///
+  /// Fails with [`Error::Exec`] when any command failed.
+  fn exec(&self, command: &str, projects: &[Project], parallel: usize) -> crate::Result<()> {
+    if projects.is_empty() {
+      return Err(Error::NoMatch(tr("app.exec-no-match").to_string()));
+    }
+    let results = exec_all(command, projects, parallel, &self.cancel);
+    for result in &results {
+      eprintln!("==> {} ({})", result.path.display(), result.command);
+      eprint!("{}", result.output);
+    }
+    self.write_table(&ExecResult::table(&results))?;
+    if self.cancel.is_cancelled() {
+      return Err(Error::Interrupted(tr("app.interrupted").to_string()));
+    }
+    match ExecResult::failures(&results) {
+      0 => Ok(()),
+      failed => Err(Error::Exec(tr_fmt(
+        "app.exec-failed",
+        &[&failed, &results.len()],
+      ))),
+    }
+  }
+
+  /// Pin the project folder at `path`, or list the pinned folders when omitted
+  fn pin(&self, path: Option<&Path>) -> crate::Result<()> {
+    let mut pins = Pins::open()?;
+    let Some(path) = path else {
+      let mut table = Table::new("Pins", &["Path"]);
+      for pinned in pins.iter() {
+        table.push_row(vec![pinned.display().to_string().into()]);
+      }
+      return self.write_table(&table);
+    };
+    let path = std::fs::canonicalize(path)
+      .map_err(|e| Error::io(format!("cannot find '{}'", path.display()), e))?;
+    pins.pin(&path);
+    pins.save()?;
+    println!("{}", tr_fmt("app.pinned", &[&path.display()]));
+    Ok(())
+  }
+
+  /// Unpin the project folder at `path`, which may no longer exist
+  fn unpin(&self, path: &Path) -> crate::Result<()> {
+    let mut pins = Pins::open()?;
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    match pins.unpin(&path) {
+      true => {
+        pins.save()?;
+        println!("{}", tr_fmt("app.unpinned", &[&path.display()]));
+      }
+      false => warn!("{}", tr_fmt("app.not-pinned", &[&path.display()])),
+    }
+    Ok(())
+  }
+
+  /// Set the note of the project folder at `path` to `text`, remove it if `delete` is set or
+  /// print it when `text` is omitted. Every note is listed when `path` is omitted too.
+  fn note(&self, path: Option<&Path>, text: Option<&str>, delete: bool) -> crate::Result<()> {
+    let mut notes = Notes::open()?;
+    let Some(path) = path else {
+      let mut table = Table::new("Notes", &["Path", "Note"]);
+      for (path, note) in notes.iter() {
+        table.push_row(vec![
+          path.display().to_string().into(),
+          note.as_str().into(),
+        ]);
+      }
+      return self.write_table(&table);
+    };
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    match (text, delete) {
+      (_, true) => match notes.remove(&path) {
+        Some(_) => {
+          notes.save()?;
+          println!("{}", tr_fmt("app.note-removed", &[&path.display()]));
+        }
+        None => warn!("{}", tr_fmt("app.no-note", &[&path.display()])),
+      },
+      (Some(text), false) => {
+        if !path.is_dir() {
+          return Err(Error::config(tr_fmt(
+            "app.note-not-a-folder",
+            &[&path.display()],
+          )));
+        }
+        notes.set(&path, text);
+        notes.save()?;
+      }
+      (None, false) => match notes.get(&path) {
+        Some(note) => println!("{}", note),
+        None => return Err(Error::NoMatch(tr_fmt("app.no-note", &[&path.display()]))),
+      },
+    }
+    Ok(())
+  }
+
+  /// Write the git status of every project matching `query` that is in a git work tree.
+  ///
+  /// Fails with [`Error::NoMatch`] when none is.
+  #[cfg(feature = "git")]
+  fn git_status(&self, query: Option<&Query>) -> crate::Result<()> {
+    let mut statuses = vec![];
+    for project in self.select_projects(query)? {
+      if self.cancel.is_cancelled() {
+        return Err(Error::Interrupted(tr("app.interrupted").to_string()));
+      }
+      match crate::GitStatus::read(project.path())? {
+        Some(status) => statuses.push(status),
+        None => debug!("'{}' isn't in a git work tree", project.path().display()),
+      }
+    }
+    if statuses.is_empty() {
+      return Err(Error::NoMatch(tr("app.git-no-repository").to_string()));
+    }
+    self.write_table(&crate::GitStatus::table(&statuses))
+  }
+
+  /// List the outdated direct dependencies of the projects matching `query`, running `parallel`
+  /// tools at a time and killing them after `timeout` seconds
+  #[cfg(feature = "audit")]
+  fn audit_outdated(
+    &self,
+    query: Option<&Query>,
+    parallel: usize,
+    timeout: u64,
+  ) -> crate::Result<()> {
+    let projects = self.select_projects(query)?;
+    let timeout = std::time::Duration::from_secs(timeout);
+    let results = audit_outdated(&projects, parallel, timeout, &self.cancel);
+    if results.is_empty() && !self.cancel.is_cancelled() {
+      return Err(Error::NoMatch(tr("app.audit-no-match").to_string()));
+    }
+    let failed = results
+      .iter()
+      .filter(|result| result.error.is_some())
+      .collect::<Vec<_>>();
+    for result in &failed {
+      eprintln!(
+        "{}: {}",
+        result.path.display(),
+        result.error.as_deref().unwrap_or_default()
+      );
+    }
+    self.write_table(&AuditResult::table(&results))?;
+    if self.cancel.is_cancelled() {
+      return Err(Error::Interrupted(tr("app.interrupted").to_string()));
+    }
+    let outdated = results
+      .iter()
+      .filter(|result| !result.outdated.is_empty())
+      .count();
+    eprintln!(
+      "{}",
+      tr_fmt("app.audit-summary", &[&outdated, &results.len()])
+    );
+    match failed.is_empty() {
+      true => Ok(()),
+      false => Err(Error::Exec(tr_fmt(
+        "app.audit-failed",
+        &[&failed.len(), &results.len()],
+      ))),
+    }
+  }
+
+  /// Move the projects matching `query` to the `to` archive folder, or only list them unless
+  /// `apply` is set. The code folders which held them are rescanned.
+  fn archive(&self, query: &Query, to: &Path, mode: ArchiveMode, apply: bool) -> crate::Result<()> {
+    let matches = self.select_projects(Some(query))?;
+    if matches.is_empty() {
+      return Err(Error::NoMatch(tr_fmt("app.archive-no-match", &[&query])));
+    }
+    let to = expand_path(to)?;
+    if !apply {
+      let planned = matches
         .iter()
-        .flat_map(|(_, projects)| projects)
+        .map(|project| ArchivedProject::new(project, &to, mode))
         .collect::<Vec<_>>();
-      debug!("found {} projects", projects.len());
-      let matches = match self.options.list {
-        false => {
-          let matches = Self::match_projects(&self.query, &projects);
-          if matches.is_empty() {
-            return Err(Error::Unknown(format!(
-              "no match found for query '{}' in {} projects",
-              self.query,
-              projects.len()
-            )));
-          }
-          matches
-        }
-        true => projects,
+      self.write_table(&ArchivedProject::table(&planned, false))?;
+      eprintln!("{}", tr_fmt("app.archive-dry-run", &[&planned.len()]));
+      return Ok(());
+    }
+    let archived = archive(&matches, &to, mode);
+    // rescan even after a failure, some projects may have been moved
+    let scan_options = self.scan_options();
+    for folder in self.folder_paths() {
+      if matches
+        .iter()
+        .any(|project| project.path().starts_with(folder))
+      {
+        self.rescan_folder(folder, &scan_options)?;
+      }
+    }
+    self.shutdown_cache()?;
+    self.write_table(&ArchivedProject::table(&archived?, true))
+  }
+
+  /// Remove the build artifacts of the projects matching `query` after listing them and asking
+  /// for confirmation, unless `yes` is set, then report the space freed
+  fn clean_artifacts(&self, query: &Query, yes: bool) -> crate::Result<()> {
+    let matches = self.select_projects(Some(query))?;
+    let planned = matches
+      .into_iter()
+      .filter(|project| !clean_actions(project).is_empty())
+      .collect::<Vec<_>>();
+    if planned.is_empty() {
+      return Err(Error::NoMatch(tr_fmt("app.clean-no-match", &[&query])));
+    }
+    if !yes {
+      for project in &planned {
+        let actions = clean_actions(project)
+          .iter()
+          .map(|a| a.to_string())
+          .collect::<Vec<_>>();
+        eprintln!("{}: {}", project.path().display(), actions.join(", "));
       }
+      eprint!("{} ", tr_fmt("app.clean-confirm", &[&planned.len()]));
+      stderr().flush()?;
+      let mut answer = String::new();
+      std::io::stdin().read_line(&mut answer)?;
+      if !matches!(
+        answer.trim().to_lowercase().as_str(),
+        "y" | "yes" | "o" | "oui"
+      ) {
+        eprintln!("{}", tr("app.clean-cancelled"));
+        return Ok(());
+      }
+    }
+    let results = planned.iter().map(clean).collect::<Vec<_>>();
+    let freed = results.iter().map(|result| result.freed).sum::<u64>();
+    let failed = results
       .iter()
-      .map(|proj| (*proj).clone())
+      .filter(|result| result.error.is_some())
       .collect::<Vec<_>>();
+    for result in &failed {
+      eprintln!(
+        "{}: {}",
+        result.path.display(),
+        result.error.as_deref().unwrap_or_default()
+      );
+    }
+    self.write_table(&CleanResult::table(&results))?;
+    eprintln!(
+      "{}",
+      tr_fmt("app.clean-freed", &[&human_size(freed), &results.len()])
+    );
+    match failed.is_empty() {
+      true => Ok(()),
+      false => Err(Error::Exec(tr_fmt(
+        "app.clean-failed",
+        &[&failed.len(), &results.len()],
+      ))),
+    }
+  }
 
-      #[cfg(feature = "tui")]
-      let has_tui = self.options.tui;
-      #[cfg(not(feature = "tui"))]
-      let has_tui = false;
-      let mut ui: BoxedUI = match has_tui {
-        true => {
-          #[cfg(not(feature = "tui"))]
-          panic!("Feature 'tui' not available");
-          #[cfg(feature = "tui")]
-          {
-            use crate::Terminal;
-            Box::new(Terminal::new(self.options.editor)?)
-          }
+  /// Create the skeleton of a new `kind` project named `name` in `parent`, then rescan the code
+  /// folder holding it so that it's found right away. The project is opened in the editor when
+  /// `open` is set.
+  fn new_project(
+    &self,
+    kind: &str,
+    name: &str,
+    parent: Option<&Path>,
+    open: bool,
+  ) -> crate::Result<()> {
+    let custom = &self.config.general.project_kinds;
+    let Some(kind) = ProjectKind::from_name(kind, custom) else {
+      return Err(Error::config(tr_fmt("app.unknown-kind", &[&kind])));
+    };
+    let parent = match parent {
+      Some(parent) => expand_path(parent)?,
+      None => std::env::current_dir()?,
+    };
+    let path = parent.join(name);
+    let template = self
+      .config
+      .templates
+      .iter()
+      .find(|(name, _)| name.eq_ignore_ascii_case(&kind.name()))
+      .map(|(_, template)| expand_path(template))
+      .transpose()?;
+    scaffold(&kind, &path, template.as_deref())?;
+    println!(
+      "{}",
+      tr_fmt("app.project-created", &[&kind, &path.display()])
+    );
+    let path = std::fs::canonicalize(&path)?;
+    match self.configured_folder(&path).ok() {
+      Some(folder) => {
+        self.rescan_folder(&folder, &self.scan_options())?;
+        self.shutdown_cache()?;
+      }
+      None => warn!(
+        "{}",
+        tr_fmt("app.project-outside-folders", &[&path.display()])
+      ),
+    }
+    if open {
+      match &self.options.editor {
+        Some(editor) => {
+          std::process::Command::new(editor)
+            .arg(wsl_editor_path(editor, &path))
+            .status()
+            .map_err(|e| Error::io(format!("failed to run '{}'", editor.display()), e))?;
         }
-        false => {
-          #[cfg(not(feature = "console"))]
-          panic!("Feature 'console' not available");
-          #[cfg(feature = "console")]
-          {
-            use crate::Console;
-            Box::new(Console::new())
-          }
+        None => warn!("{}", tr("app.no-editor")),
+      }
+    }
+    Ok(())
+  }
+
+  /// List the member projects of the `name` workspace, in the order of its members and
+  /// without duplicates
+  fn workspace_projects(&self, name: &str) -> crate::Result<Vec<Project>> {
+    let Some(workspace) = self.config.workspace.get(name) else {
+      let names = self.config.workspace.keys().cloned().collect::<Vec<_>>();
+      return Err(Error::config(tr_fmt(
+        "app.unknown-workspace",
+        &[&name, &names.join(", ")],
+      )));
+    };
+    let projects = self.list_projects()?;
+    self.shutdown_cache()?;
+    let projects = projects.values().flatten().collect::<Vec<_>>();
+    let mut ret: Vec<Project> = vec![];
+    for member in &workspace.members {
+      let members: Vec<Project> = match WorkspaceConfig::member_folder(member)? {
+        Some(folder) => projects
+          .iter()
+          .filter(|project| project.path().starts_with(&folder))
+          .map(|project| (*project).clone())
+          .collect(),
+        None => {
+          let query = Query::from_str(member)
+            .map_err(|e| {
+              Error::from(e).with_context(tr_fmt("app.workspace-member", &[&member, &name]))
+            })?
+            .with_mode(self.config.general.match_mode);
+          Self::match_projects(&query, &projects)
+            .into_iter()
+            .cloned()
+            .collect()
         }
       };
-      ui.write_matches(&matches, &self.formatter)?;
-      ui.render_loop()?;
+      if members.is_empty() {
+        warn!("{}", tr_fmt("app.workspace-no-member", &[&member, &name]));
+      }
+      for project in members {
+        if !ret.contains(&project) {
+          ret.push(project);
+        }
+      }
+    }
+    Ok(ret)
+  }
+
+  /// Describe the saved queries, by the name they're run with
+  fn saved_queries_table(&self) -> Table {
+    let mut table = Table::new("Saved queries", &["Name", "Query"]);
+    for (name, query) in &self.config.queries {
+      table.push_row(vec![format!("@{}", name).into(), query.as_str().into()]);
+    }
+    table
+  }
+
+  /// Describe the configured workspaces and their members
+  fn workspaces_table(&self) -> Table {
+    let mut table = Table::new("Workspaces", &["Name", "Members"]);
+    for (name, workspace) in &self.config.workspace {
+      table.push_row(vec![name.as_str().into(), workspace.members.clone().into()]);
+    }
+    table
+  }
+
+  /// List the projects matching `query` with the configured match mode, or every project
+  /// when it is omitted
+  fn select_projects(&self, query: Option<&Query>) -> crate::Result<Vec<Project>> {
+    let projects = self.list_projects()?;
+    self.shutdown_cache()?;
+    let projects = projects.values().flatten().collect::<Vec<_>>();
+    Ok(match query {
+      Some(query) => {
+        let query =
+          Self::expand_query(query, &self.config)?.with_mode(self.config.general.match_mode);
+        Self::match_projects(&query, &projects)
+          .into_iter()
+          .cloned()
+          .collect()
+      }
+      None => projects.into_iter().cloned().collect(),
+    })
+  }
+
+  /// Rescan the code folders every `interval`, writing the changes since the previous scan and
+  /// running the configured hooks for them. Never returns unless a scan fails.
+  fn watch(&self, interval: std::time::Duration, memory_cap: u64) -> crate::Result<()> {
+    if !self.config.hooks.is_empty() {
+      self.config.ensure_trusted(self.options.trust)?;
+    }
+    let mut projects = self.fresh_projects()?;
+    let mut previous = Snapshot::new(&projects);
+    Self::cap_memory(&mut projects, &previous, memory_cap);
+    #[cfg(feature = "web")]
+    let served = self.serve_web(&projects)?;
+    loop {
+      self.sleep(interval)?;
+      let mut projects = self.fresh_projects()?;
+      let current = Snapshot::new(&projects);
+      Self::cap_memory(&mut projects, &current, memory_cap);
+      #[cfg(feature = "web")]
+      if let Some(served) = &served {
+        *served.write().unwrap() = projects;
+      }
+      let changes = previous.diff(&current);
+      if !changes.is_empty() {
+        self.write_table(&Change::table(&changes))?;
+        self.config.hooks.run(&changes);
+      }
+      previous = current;
+    }
+  }
+
+  /// Keep the `projects` and `snapshot` held until the next scan under `memory_cap` bytes,
+  /// falling back to projects without their source files when they exceed it
+  fn cap_memory(projects: &mut [Project], snapshot: &Snapshot, memory_cap: u64) {
+    let usage = |projects: &[Project]| {
+      let usage = projects.iter().map(Project::memory_usage).sum::<usize>();
+      (usage + snapshot.memory_usage()) as u64
+    };
+    let full = usage(projects);
+    if full <= memory_cap {
+      debug!("holding {} of projects", human_size(full));
+      return;
+    }
+    projects.iter_mut().for_each(Project::drop_source_files);
+    let light = usage(projects);
+    debug!(
+      "holding {} of projects without their source files, instead of {}",
+      human_size(light),
+      human_size(full)
+    );
+    if light > memory_cap {
+      warn!(
+        "{}",
+        tr_fmt(
+          "app.memory-cap",
+          &[&human_size(light), &human_size(memory_cap)]
+        )
+      );
+    }
+  }
+
+  /// Wait for `duration`, failing with [`Error::Interrupted`] as soon as Ctrl-C is pressed
+  fn sleep(&self, duration: std::time::Duration) -> crate::Result<()> {
+    let deadline = std::time::Instant::now() + duration;
+    while let Some(left) = deadline.checked_duration_since(std::time::Instant::now()) {
+      if self.cancel.is_cancelled() {
+        return Err(Error::Interrupted(tr("app.interrupted").to_string()));
+      }
+      std::thread::sleep(left.min(std::time::Duration::from_millis(100)));
     }
-    self.cache.lock().unwrap().shutdown()?;
     Ok(())
   }
 
-  /// Scan code folders and extract project roots
-  pub fn list_projects(&self) -> crate::Result<HashMap<PathBuf, Vec<Project>>> {
-    let mut projects = HashMap::new();
-    for folder in &self.config.general.folders {
-      let mut cache = self.cache.lock().unwrap();
-      let scan = cache.load_store(folder, || FolderScan::new(folder))?;
-      projects.insert(
-        folder.clone(),
-        cache.load_store(&folder.join(".projects"), || {
-          Ok(detect_projects(
-            &scan,
-            self.config.general.project_kinds.clone(),
-          ))
-        })?,
+  /// Serve the web interface when `watch --serve` is given, returning the projects it shows
+  #[cfg(feature = "web")]
+  fn serve_web(&self, projects: &[Project]) -> crate::Result<Option<crate::web::SharedProjects>> {
+    let Some(AppCommand::Watch {
+      serve: Some(port),
+      bind,
+      ..
+    }) = self.options.command
+    else {
+      return Ok(None);
+    };
+    let served = Arc::new(std::sync::RwLock::new(projects.to_vec()));
+    crate::web::serve((bind, port).into(), served.clone())?;
+    Ok(Some(served))
+  }
+
+  /// Scan every code folder bypassing the cache
+  fn fresh_projects(&self) -> crate::Result<Vec<Project>> {
+    for folder in self.folder_paths() {
+      self.invalidate_folder(folder);
+    }
+    let projects = self.list_projects()?.into_values().flatten().collect();
+    self.shutdown_cache()?;
+    Ok(projects)
+  }
+
+  /// Find the configured code folder holding `path`, the innermost one when they are nested
+  fn configured_folder(&self, path: &Path) -> crate::Result<PathBuf> {
+    let path = expand_path(path)?;
+    let path = match path.is_absolute() || is_remote_path(&path) {
+      true => path,
+      false => std::env::current_dir()?.join(path),
+    };
+    let path = path.canonicalize().unwrap_or(path);
+    self
+      .folder_paths()
+      .filter(|folder| {
+        let canonical = folder
+          .canonicalize()
+          .unwrap_or_else(|_| folder.to_path_buf());
+        path.starts_with(folder) || path.starts_with(canonical)
+      })
+      .max_by_key(|folder| folder.components().count())
+      .map(Path::to_path_buf)
+      .ok_or_else(|| Error::config(tr_fmt("app.rescan-unknown", &[&path.display()])))
+  }
+
+  /// Build the action cloning the projects of remote folders into `clone_dir` when they are
+  /// opened, then dropping the cached scan of the code folder holding the clones so that the
+  /// next search finds them
+  #[cfg_attr(not(any(feature = "tui", feature = "gui")), allow(dead_code))]
+  fn clone_action(&self) -> CloneRemote {
+    let dir = self
+      .config
+      .general
+      .clone_dir
+      .as_ref()
+      .map(|dir| expand_path(dir).unwrap_or_else(|_| dir.clone()));
+    let folder = dir
+      .as_ref()
+      .and_then(|dir| self.configured_folder(dir).ok());
+    let cache = self.cache.clone();
+    Arc::new(move |path| {
+      let Some(dir) = &dir else {
+        return Err(Error::config(tr_fmt(
+          "app.no-clone-dir",
+          &[&path.display()],
+        )));
+      };
+      eprintln!(
+        "{}",
+        tr_fmt("app.cloning", &[&path.display(), &dir.display()])
+      );
+      let clone = clone_remote(path, dir)?;
+      match &folder {
+        Some(folder) => {
+          let mut cache = cache.lock().unwrap();
+          cache.invalidate(folder);
+          cache.invalidate(&folder.join(PROJECTS_KEY));
+        }
+        None => warn!(
+          "{}",
+          tr_fmt("app.project-outside-folders", &[&clone.display()])
+        ),
+      }
+      Ok(clone)
+    })
+  }
+
+  /// Drop the cached scan and projects of a code folder
+  fn invalidate_folder(&self, folder: &Path) {
+    let mut cache = self.cache.lock().unwrap();
+    cache.invalidate(&folder);
+    cache.invalidate(&folder.join(PROJECTS_KEY));
+  }
+
+  /// Drop the stale cache entries then save the cache index
+  fn shutdown_cache(&self) -> crate::Result<()> {
+    self.collect_cache()?;
+    self.cache.lock().unwrap().shutdown()
+  }
+
+  /// Drop the cache entries of folders which were deleted or are no longer under a configured
+  /// folder, returning their keys
+  pub fn collect_cache(&self) -> crate::Result<Vec<PathBuf>> {
+    let folders = self.folder_paths().collect::<Vec<_>>();
+    self.cache.lock().unwrap().gc(|key| {
+      let path = match key.file_name() == Some(PROJECTS_KEY.as_ref()) {
+        true => key.parent().unwrap_or(key),
+        false => key,
+      };
+      (path.exists() || is_remote_path(path))
+        && folders.iter().any(|folder| path.starts_with(folder))
+    })
+  }
+
+  /// Scan code folders and extract project roots.
+  ///
+  /// The [`DetectionStats`] of each folder are logged, and written to stderr with `--stats`.
+  /// The projects are recorded in the [`History`] when they changed since the latest scan.
+  ///
+  /// The folders are sorted by path, and their projects as by [`detect_projects`]. The
+  /// bookmarked directories are added as projects of their own, see [`crate::BookmarksConfig`].
+  #[cfg_attr(feature = "tracing", tracing::instrument(name = "list", skip_all))]
+  pub fn list_projects(&self) -> crate::Result<BTreeMap<PathBuf, Vec<Project>>> {
+    let mut projects = BTreeMap::new();
+    let mut stats = vec![];
+    let scan_options = self.scan_options();
+    let mut scans = self.scan_folders(&scan_options)?;
+    self.detect_moves(&mut scans, &scan_options)?;
+    for (folder, scan, folder_projects) in scans {
+      if self.options.stats || log_enabled!(Level::Debug) {
+        let kinds = &self.config.general.project_kinds;
+        let folder_stats = DetectionStats::from_scan(&scan, &folder_projects, kinds);
+        debug!("{}", folder_stats);
+        stats.push(folder_stats);
+      }
+      projects.insert(folder.clone(), folder_projects);
+    }
+    // bookmarks are listed under their own path, unless already a code folder or a project
+    for bookmark in self.config.bookmarks.projects() {
+      let known = projects.contains_key(bookmark.path())
+        || projects
+          .values()
+          .flatten()
+          .any(|project| project.path() == bookmark.path());
+      if !known {
+        projects.insert(bookmark.path().clone(), vec![bookmark]);
+      }
+    }
+    if self.options.stats {
+      self
+        .formatter
+        .write_table(&mut stderr(), &DetectionStats::table(&stats))?;
+    }
+    if let Err(e) = History::open().record(&Snapshot::new(projects.values().flatten())) {
+      warn!(
+        "{}",
+        e.with_context("failed to record the scan history".to_string())
       );
     }
     Ok(projects)
   }
 
-  /// Filter discovered project using the command-line query
+  /// Scan the code folders `--jobs` at a time, each one on its own thread and with its own
+  /// cache entries, keeping their configured order.
+  ///
+  /// Every folder is loaded even when another one fails, the error of the first failing
+  /// folder being returned.
+  fn scan_folders(
+    &self,
+    scan_options: &ScanOptions,
+  ) -> crate::Result<Vec<(PathBuf, FolderScan, Vec<Project>)>> {
+    let folders = self.folder_paths().collect::<Vec<_>>();
+    let jobs = self
+      .options
+      .jobs
+      .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()));
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(folders.iter().map(|_| None).collect::<Vec<_>>());
+    std::thread::scope(|scope| {
+      for _ in 0..jobs.clamp(1, folders.len().max(1)) {
+        scope.spawn(|| loop {
+          let id = next.fetch_add(1, Ordering::SeqCst);
+          let Some(folder) = folders.get(id) else {
+            return;
+          };
+          let result = self.folder_projects(folder, scan_options);
+          results.lock().unwrap()[id] = Some(result);
+        });
+      }
+    });
+    folders
+      .iter()
+      .zip(results.into_inner().unwrap())
+      .map(|(folder, result)| {
+        let (scan, projects) = result.expect("every folder is loaded")?;
+        Ok((folder.to_path_buf(), scan, projects))
+      })
+      .collect()
+  }
+
+  /// Rescan the folders whose cached projects vanished, and annotate the new projects holding
+  /// the same project files as a vanished one with [`Project::moved_from`].
+  ///
+  /// When a vanished project isn't found in its own folder, the other folders are rescanned
+  /// too, as it may have moved to one of them.
+  fn detect_moves(
+    &self,
+    scans: &mut [(PathBuf, FolderScan, Vec<Project>)],
+    scan_options: &ScanOptions,
+  ) -> crate::Result<()> {
+    let mut vanished = HashMap::new();
+    // the projects known before rescanning, which cannot have moved
+    let mut known = HashSet::new();
+    let mut rescanned = vec![false; scans.len()];
+    for (id, (folder, scan, projects)) in scans.iter_mut().enumerate() {
+      let gone = projects
+        .iter()
+        .filter(|p| !p.path().exists() && !is_remote_path(p.path()))
+        .filter_map(|p| Some((p.identity()?.clone(), p.path().clone())))
+        .collect::<Vec<_>>();
+      if gone.is_empty() {
+        continue;
+      }
+      debug!(
+        "{} projects vanished from '{}'",
+        gone.len(),
+        folder.display()
+      );
+      vanished.extend(gone);
+      known.extend(projects.iter().map(|p| p.path().clone()));
+      (*scan, *projects) = self.rescan_folder(folder, scan_options)?;
+      rescanned[id] = true;
+    }
+    if vanished.is_empty() {
+      return Ok(());
+    }
+    let found = scans
+      .iter()
+      .flat_map(|(_, _, projects)| projects.iter().filter_map(|p| p.identity()))
+      .collect::<HashSet<_>>();
+    if vanished.keys().any(|id| !found.contains(id)) {
+      for (id, (folder, scan, projects)) in scans.iter_mut().enumerate() {
+        if !rescanned[id] {
+          known.extend(projects.iter().map(|p| p.path().clone()));
+          (*scan, *projects) = self.rescan_folder(folder, scan_options)?;
+        }
+      }
+    }
+    for (folder, _, projects) in scans.iter_mut() {
+      let mut moved = false;
+      for project in projects.iter_mut() {
+        if known.contains(project.path()) {
+          continue;
+        }
+        if let Some(from) = project.identity().and_then(|id| vanished.get(id)) {
+          debug!(
+            "'{}' moved to '{}'",
+            from.display(),
+            project.path().display()
+          );
+          project.set_moved_from(Some(from.clone()));
+          moved = true;
+        }
+      }
+      if moved {
+        self
+          .cache
+          .lock()
+          .unwrap()
+          .store(&folder.join(PROJECTS_KEY), projects)?;
+      }
+    }
+    Ok(())
+  }
+
+  /// Scan a single code folder and extract its project roots, bypassing the cache
+  fn rescan_folder(
+    &self,
+    folder: &Path,
+    scan_options: &ScanOptions,
+  ) -> crate::Result<(FolderScan, Vec<Project>)> {
+    self.invalidate_folder(folder);
+    self.folder_projects(folder, scan_options)
+  }
+
+  /// Retrieve the options of folder scans, from the configuration and the command-line, which
+  /// stop on Ctrl-C
+  fn scan_options(&self) -> ScanOptions {
+    let general = &self.config.general;
+    ScanOptions::builder()
+      .config(general)
+      .max_depth(self.options.max_depth.or(general.max_depth))
+      .follow_symlinks(self.options.follow_symlinks || general.follow_symlinks)
+      .include_hidden(self.options.hidden || general.include_hidden)
+      .cancel(self.cancel.clone())
+      .observer((LogObserver, ProgressLine::new()))
+      .build()
+  }
+
+  /// Retrieve the options of the scans of `folder`: `scan_options` along with the ones of its
+  /// configuration entry, `--max-depth` still taking precedence
+  fn folder_scan_options(&self, folder: &Path, scan_options: &ScanOptions) -> ScanOptions {
+    let Some(entry) = self.config.general.folder(folder) else {
+      return scan_options.clone();
+    };
+    let mut ret = scan_options.for_folder(entry);
+    if let Some(max_depth) = self.options.max_depth {
+      ret.max_depth = Some(max_depth);
+    }
+    ret
+  }
+
+  /// Retrieve the paths of the configured code folders
+  fn folder_paths(&self) -> impl Iterator<Item = &Path> {
+    self
+      .config
+      .general
+      .folders
+      .iter()
+      .map(|folder| folder.path.as_path())
+  }
+
+  /// Scan a single code folder and extract its project roots.
+  ///
+  /// When interrupted, the cache index is saved so that the folders scanned so far are kept.
+  /// The projects of the dependency folders are given the [`ProjectKind::Dependency`] kind.
+  fn folder_projects(
+    &self,
+    folder: &Path,
+    scan_options: &ScanOptions,
+  ) -> crate::Result<(FolderScan, Vec<Project>)> {
+    let ret = match self.cancel.is_cancelled() {
+      true => Err(Error::Interrupted(tr_fmt(
+        "error.interrupted",
+        &[&folder.display()],
+      ))),
+      false => self.cached_folder_projects(folder, scan_options),
+    };
+    if let Err(Error::Interrupted(..)) = &ret {
+      self.cache.lock().unwrap().save_index()?;
+    }
+    let (scan, mut projects) = ret?;
+    if self
+      .dependency_folders
+      .iter()
+      .any(|dependencies| dependencies == folder)
+    {
+      for project in &mut projects {
+        project.kinds_mut().push(ProjectKind::Dependency);
+      }
+    }
+    Ok((scan, projects))
+  }
+
+  /// Load the scan and projects of a code folder from the cache, scanning it if needed
+  /// Invalidate the cached scan and projects of `folder` when they were made with other
+  /// settings, a detection change keeping the scan, or when its top-level directories changed
+  fn check_folder_entries(cache: &mut Cache, folder: &Path, scan_options: &ScanOptions) {
+    let projects_key = folder.join(PROJECTS_KEY);
+    cache.check_fingerprint(&folder, &scan_options.fingerprint());
+    cache.check_fingerprint(&projects_key, &scan_options.detection_fingerprint());
+    let sample = FolderScan::mtime_sample(folder);
+    cache.check_sample(&folder, &sample);
+    cache.check_sample(&projects_key, &sample);
+  }
+
+  #[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "folder", skip_all, fields(folder = %folder.display()))
+  )]
+  fn cached_folder_projects(
+    &self,
+    folder: &Path,
+    scan_options: &ScanOptions,
+  ) -> crate::Result<(FolderScan, Vec<Project>)> {
+    let scan_options = &self.folder_scan_options(folder, scan_options);
+    Self::check_folder_entries(&mut self.cache.lock().unwrap(), folder, scan_options);
+    let mut scan_time = None;
+    let scan = self.load_store(&folder, || {
+      let started = Instant::now();
+      let scan = FolderScan::with_options(folder, scan_options);
+      scan_time = Some(started.elapsed());
+      scan
+    })?;
+    self.usage.lock().unwrap().record_folder(scan_time);
+    for (path, limit) in scan.skipped() {
+      warn!("{}", tr_fmt("app.scan-limit", &[&path.display(), limit]));
+    }
+    let projects = self.load_store(&folder.join(PROJECTS_KEY), || {
+      let mut projects = detect_projects(&scan, self.config.general.project_kinds.clone());
+      projects.retain(|project| scan_options.detects(project));
+      if let Some(observer) = &scan_options.observer {
+        projects
+          .iter()
+          .for_each(|project| observer.on_project_detected(project));
+      }
+      Ok(projects)
+    })?;
+    // the walk stopped early, the scan and projects are partial
+    if scan.is_stopped() {
+      let mut cache = self.cache.lock().unwrap();
+      cache.invalidate(&folder);
+      cache.invalidate(&folder.join(PROJECTS_KEY));
+    }
+    Ok((scan, projects))
+  }
+
+  /// Same as [`Cache::load_store`], except that the cache stays unlocked while `action` runs,
+  /// so that the other folders are loaded meanwhile
+  fn load_store<
+    K: AsRef<Path>,
+    E: DeserializeOwned + Serialize,
+    F: FnOnce() -> crate::Result<E>,
+  >(
+    &self,
+    key: &K,
+    action: F,
+  ) -> crate::Result<E> {
+    // entries written by an older version may not deserialize anymore, refresh them
+    match self.cache.lock().unwrap().load::<_, E>(key) {
+      Ok(Some(entity)) => return Ok(entity),
+      Ok(None) => {}
+      Err(e) => debug!("{}, refreshing it", e),
+    }
+    let entity = action()?;
+    self.cache.lock().unwrap().store(key, &entity)?;
+    Ok(entity)
+  }
+
+  /// Check whether any project matches the command-line query.
+  ///
+  /// Code folders are scanned one after the other, stopping at the first one holding a match.
+  /// Unless the query has selectors, which may need the facets extracted after detection, the
+  /// walk of a folder stops at the first matching project too.
+  pub fn any_match(&self) -> crate::Result<bool> {
+    let mut scan_options = self.scan_options();
+    if self.query.selectors().is_empty() {
+      let (query, detected) = (self.query.clone(), scan_options.clone());
+      scan_options.stop_at = Some(StopPredicate::new(move |project| {
+        detected.detects(project) && Self::score_project(&query, project).is_some()
+      }));
+    }
+    for folder in self.folder_paths() {
+      let (_, projects) = self.folder_projects(folder, &scan_options)?;
+      if let Some(project) = projects
+        .iter()
+        .find(|project| Self::score_project(&self.query, project).is_some())
+      {
+        debug!("'{}' matches '{}'", project.path().display(), self.query);
+        return Ok(true);
+      }
+    }
+    Ok(false)
+  }
+
+  /// Filter discovered project using the command-line query, best matches first
+  #[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "match", skip_all, fields(projects = projects.len()))
+  )]
   pub fn match_projects<'a>(query: &'a Query, projects: &'a Vec<&'a Project>) -> Vec<&'a Project> {
-    projects
+    let mut matches = projects
       .iter()
-      .filter(|project| {
-        if let Some(name) = project.name() {
-          if query.matches(&name) {
-            return true;
+      .filter_map(|project| Self::score_project(query, project).map(|score| (score, *project)))
+      .collect::<Vec<_>>();
+    matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    matches.into_iter().map(|(_, project)| project).collect()
+  }
+
+  /// Compute the disk usage of every project, spreading them over the available cores.
+  /// See [`Project::compute_disk_usage`].
+  pub fn compute_disk_usages(projects: &mut [Project], artifacts: bool) {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk = projects.len().div_ceil(threads).max(1);
+    std::thread::scope(|scope| {
+      for chunk in projects.chunks_mut(chunk) {
+        scope.spawn(move || {
+          for project in chunk {
+            project.set_disk_usage(Some(project.compute_disk_usage(artifacts)));
           }
-        }
-        project
-          .path()
-          .components()
-          .find(|part| {
-            if let Some(part_str) = part.as_os_str().to_str() {
-              return query.matches(part_str);
-            }
-            return false;
-          })
-          .is_some()
-      })
-      .map(|proj| *proj)
-      .collect::<Vec<_>>()
+        });
+      }
+    });
+  }
+
+  /// Record the command-line query in the [`QueryHistory`], warning when it cannot be saved
+  fn record_query(&self) {
+    let recorded = QueryHistory::open().and_then(|mut history| {
+      match history.record(self.options.query.to_string()) {
+        true => history.save(),
+        false => Ok(()),
+      }
+    });
+    if let Err(e) = recorded {
+      warn!(
+        "{}",
+        e.with_context("failed to record the query history".to_string())
+      );
+    }
+  }
+
+  /// Add the usage of this run to the [`UsageStats`] when enabled by `usage_stats`, warning
+  /// when they cannot be saved
+  fn record_usage(&self) {
+    let usage = *self.usage.lock().unwrap();
+    if !self.config.general.usage_stats || usage.is_empty() {
+      return;
+    }
+    let recorded = UsageStats::open().and_then(|mut stats| {
+      stats.record(chrono::Local::now().date_naive(), &usage);
+      stats.save()
+    });
+    if let Err(e) = recorded {
+      warn!(
+        "{}",
+        e.with_context("failed to record the usage statistics".to_string())
+      );
+    }
+  }
+
+  /// Load the facet values cached in the cache folder, none when the cache is disabled. Also
+  /// returns whether they may be saved, the cache being neither disabled nor read-only.
+  fn open_facet_cache(&self) -> (FacetCache, bool) {
+    let cache = self.cache.lock().unwrap();
+    let writable = !self.options.no_cache && !cache.is_read_only();
+    match self.options.no_cache {
+      true => (FacetCache::default(), false),
+      false => (
+        FacetCache::load(cache.base_dir().join(FacetCache::FILE_NAME)),
+        writable,
+      ),
+    }
+  }
+
+  /// Extract the facets named `names` from the projects, reusing the values cached in the
+  /// cache folder unless the cache is disabled or read-only
+  fn extract_facets(&self, names: &[String], projects: &mut [Project]) -> crate::Result<()> {
+    let (mut cache, writable) = self.open_facet_cache();
+    FacetRegistry::default().extract(names, projects, &mut cache);
+    if writable {
+      cache.save()?;
+    }
+    Ok(())
+  }
+
+  /// Keep the projects sharing their manifest hash with another one, grouped by hash.
+  /// The order of the projects is kept within each group.
+  pub fn duplicate_projects(projects: Vec<Project>) -> Vec<Project> {
+    let mut counts = HashMap::new();
+    for hash in projects.iter().filter_map(|p| p.manifest_hash()) {
+      *counts.entry(hash.clone()).or_insert(0) += 1;
+    }
+    let mut ret = projects
+      .into_iter()
+      .filter(|p| p.manifest_hash().is_some_and(|hash| counts[hash] > 1))
+      .collect::<Vec<_>>();
+    ret.sort_by(|a, b| a.manifest_hash().cmp(&b.manifest_hash()));
+    ret
+  }
+
+  /// Compute how well a project matches the command-line query, if it matches at all
+  pub fn score_project(query: &Query, project: &Project) -> Option<MatchScore> {
+    if !query.selects(project) {
+      return None;
+    }
+    if query.matches_path() {
+      return query
+        .matches_full_path(normalize_path(project.path()))
+        .then_some(MatchScore::PathComponent);
+    }
+    if let Some(score) = project.name().and_then(|name| query.score(name)) {
+      return Some(score);
+    }
+    project
+      .path()
+      .components()
+      .any(|part| query.matches(part.as_os_str().to_string_lossy()))
+      .then_some(MatchScore::PathComponent)
+  }
+
+  /// Parse the command-line `args`, exiting with the usage on errors. A query named after a
+  /// subcommand, such as `pgrep config`, runs the subcommand and fails with an error pointing
+  /// to `pgrep -- config` when its arguments are missing, rather than printing its usage.
+  pub fn parse_args<I, T>(args: I) -> crate::Result<ArgMatches>
+  where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+  {
+    let mut args = args.into_iter().map(Into::into).collect::<Vec<OsString>>();
+    let cmd = i18n::localize_command(AppOptions::command());
+    let e = match cmd.clone().try_get_matches_from(&args) {
+      Ok(matches) => return Ok(matches),
+      Err(e) => e,
+    };
+    let subcommand = args
+      .iter()
+      .skip(1)
+      .take_while(|arg| *arg != "--")
+      .position(|arg| cmd.find_subcommand(arg).is_some());
+    if let Some(pos) = subcommand.filter(|_| e.use_stderr()) {
+      args.insert(pos + 1, "--".into());
+      if cmd.try_get_matches_from(&args).is_ok() {
+        let name = args[pos + 2].to_string_lossy().into_owned();
+        return Err(Error::config(tr_fmt(
+          "app.subcommand-query",
+          &[&name, &name, &name, &name],
+        )));
+      }
+    }
+    e.exit()
+  }
+
+  /// Generate documentation from the command-line definition, in the selected language
+  pub fn generate(target: GenTarget, to: &mut dyn Write) -> crate::Result<()> {
+    let cmd = i18n::localize_command(AppOptions::command());
+    match target {
+      GenTarget::Man => clap_mangen::Man::new(cmd)
+        .render(to)
+        .map_err(|e| Error::io("failed to render man page", e)),
+      GenTarget::MarkdownHelp => to
+        .write_all(clap_markdown::help_markdown_command(&cmd).as_bytes())
+        .map_err(|e| Error::io("failed to write markdown help", e)),
+      GenTarget::FzfWidget { shell } => {
+        let snippet = match shell {
+          WidgetShell::Bash => include_str!("widgets/fzf.bash"),
+          WidgetShell::Zsh => include_str!("widgets/fzf.zsh"),
+          WidgetShell::Fish => include_str!("widgets/fzf.fish"),
+        };
+        to.write_all(snippet.as_bytes())
+          .map_err(|e| Error::io("failed to write the fzf widget", e))
+      }
+    }
+  }
+
+  /// Describe the known output formats, and whether they were compiled in
+  pub fn formats_table() -> Table {
+    let mut table = Table::new("Formats", &["Name", "Default", "Available", "Feature"]);
+    let preferred = OutputFormat::preferred();
+    for format in OutputFormat::iter() {
+      table.push_row(vec![
+        format.to_string().into(),
+        (format.is_available() && format == preferred)
+          .to_string()
+          .into(),
+        format.is_available().to_string().into(),
+        format.feature().into(),
+      ]);
+    }
+    table
+  }
+
+  /// Describe the built-in project kinds, followed by the `custom` ones
+  pub fn kinds_table(custom: &[ProjectKind]) -> Table {
+    let mut table = Table::new("Kinds", &["Name", "Project files", "Extensions", "Source"]);
+    let builtins = ProjectKind::iter().filter(|kind| !matches!(kind, ProjectKind::Custom { .. }));
+    let kinds = builtins
+      .map(|kind| (kind, "built-in"))
+      .chain(custom.iter().map(|kind| (kind.clone(), "config")));
+    for (kind, source) in kinds {
+      table.push_row(vec![
+        kind.name().into(),
+        kind.project_files().into(),
+        kind.language_extensions().into(),
+        source.into(),
+      ]);
+    }
+    table
   }
 
   /// Write the report to the configured writer
-  pub fn write_report(&self, matches: &Vec<Project>) -> crate::Result<()> {
-    self.formatter.write(&mut stdout(), matches)?;
+  pub fn write_report(&self, matches: &[Project]) -> crate::Result<()> {
+    let mut to = self.output()?;
+    self.formatter.write(&mut to, &mut matches.iter())?;
+    to.flush()?;
+    Ok(())
+  }
+
+  /// Write a table to the configured writer
+  /// Write the matches for fuzzy finders, see [`AppOptions::emit_candidates`].
+  ///
+  /// Fields are sanitized so that tabs and line breaks don't break the lines apart, the score
+  /// is empty when listing projects.
+  pub fn write_candidates(&self, matches: &[Project]) -> crate::Result<()> {
+    let field = |value: String| value.replace(['\t', '\n', '\r'], " ");
+    let mut out = self.output()?;
+    for project in matches {
+      let kinds = project.kinds().iter().map(|k| k.name()).collect::<Vec<_>>();
+      let score = match self.options.list {
+        true => None,
+        false => Self::score_project(&self.query, project),
+      };
+      writeln!(
+        out,
+        "{}\t{}\t{}\t{}",
+        field(project.name().unwrap_or_default()),
+        field(format!("{}", project.path().display())),
+        field(kinds.join(",")),
+        score.map(|score| score.to_string()).unwrap_or_default()
+      )?;
+    }
+    out.flush()?;
+    Ok(())
+  }
+
+  fn write_table(&self, table: &Table) -> crate::Result<()> {
+    let mut to = self.output()?;
+    self.formatter.write_table(&mut to, table)?;
+    to.flush()?;
     Ok(())
   }
+
+  /// Open the configured writer: the --output file if any, stdout otherwise, removing the
+  /// styles its destination doesn't support
+  fn output(&self) -> crate::Result<Box<dyn Write>> {
+    match &self.options.output {
+      Some(path) => Ok(style::styled_writer(Stream::File, create_output(path)?)),
+      None => Ok(style::styled_writer(Stream::Stdout, stdout())),
+    }
+  }
+}
+
+/// Create the file reports are written to, truncating it
+pub fn create_output(path: &Path) -> crate::Result<std::io::BufWriter<std::fs::File>> {
+  std::fs::File::create(path)
+    .map(std::io::BufWriter::new)
+    .map_err(|e| Error::io(format!("failed to create '{}'", path.display()), e))
+}
+
+/// A [`ScanObserver`] rewriting a line of stderr with the number of files found in every folder,
+/// when stderr is a terminal and no debug logs would be interleaved with it.
+///
+/// The line only shows up once scanning lasts long enough to be noticed, and is erased when the
+/// scan options holding it are dropped.
+struct ProgressLine {
+  /// Whether the line is written at all
+  enabled: bool,
+  /// When the scans started
+  started: Instant,
+  /// The number of files found by the scans so far
+  found: AtomicUsize,
+  /// When the line was last written, `None` if it never was
+  written: Mutex<Option<Instant>>,
+}
+
+impl ProgressLine {
+  /// How long scans last before the line shows up
+  const DELAY: Duration = Duration::from_millis(300);
+  /// How long the line stays unchanged at least
+  const INTERVAL: Duration = Duration::from_millis(100);
+
+  fn new() -> Self {
+    Self {
+      enabled: stderr().is_terminal() && !log_enabled!(Level::Debug),
+      started: Instant::now(),
+      found: AtomicUsize::new(0),
+      written: Mutex::new(None),
+    }
+  }
+}
+
+impl ScanObserver for ProgressLine {
+  fn on_file(&self, _file: &Path) {
+    self.found.fetch_add(1, Ordering::Relaxed);
+  }
+
+  fn on_dir_entered(&self, _dir: &Path, _found: usize) {
+    if !self.enabled || self.started.elapsed() < Self::DELAY {
+      return;
+    }
+    let mut written = self.written.lock().unwrap();
+    if written.is_some_and(|at| at.elapsed() < Self::INTERVAL) {
+      return;
+    }
+    *written = Some(Instant::now());
+    let found = self.found.load(Ordering::Relaxed);
+    eprint!("\r\x1b[2K{}", tr_fmt("app.scanning", &[&found]));
+  }
+}
+
+impl Drop for ProgressLine {
+  fn drop(&mut self) {
+    if self.written.get_mut().unwrap().is_some() {
+      eprint!("\r\x1b[2K");
+    }
+  }
 }
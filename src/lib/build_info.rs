@@ -0,0 +1,81 @@
+use serde::Serialize;
+use strum::IntoEnumIterator;
+
+use crate::{OutputFormat, ProjectKind};
+
+/// The cargo features this crate may be built with, and whether they are enabled
+const FEATURES: &[(&str, bool)] = &[
+  ("text", cfg!(feature = "text")),
+  ("json", cfg!(feature = "json")),
+  ("csv", cfg!(feature = "csv")),
+  ("xml", cfg!(feature = "xml")),
+  ("html", cfg!(feature = "html")),
+  ("markdown", cfg!(feature = "markdown")),
+  ("console", cfg!(feature = "console")),
+  ("tui", cfg!(feature = "tui")),
+  ("gui", cfg!(feature = "gui")),
+  ("bench", cfg!(feature = "bench")),
+  ("self-update", cfg!(feature = "self-update")),
+  ("http", cfg!(feature = "http")),
+  ("s3", cfg!(feature = "s3")),
+  ("tokio", cfg!(feature = "tokio")),
+  ("web", cfg!(feature = "web")),
+  ("git", cfg!(feature = "git")),
+  ("audit", cfg!(feature = "audit")),
+  ("tracing", cfg!(feature = "tracing")),
+  ("locate", cfg!(feature = "locate")),
+];
+
+/// The exact configuration of the running binary, printed by `--build-info`
+///
+/// # Examples
+///
+/// ```
+/// use pgrep::BuildInfo;
+///
+/// let info = BuildInfo::current();
+/// assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+/// assert!(info.kinds.contains(&"Rust".to_string()));
+/// ```
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct BuildInfo {
+  /// The package version
+  pub version: &'static str,
+  /// The abbreviated git commit the binary was built from, `unknown` outside of a git checkout
+  pub git_commit: &'static str,
+  /// The RFC 3339 build date, honoring `SOURCE_DATE_EPOCH`
+  pub build_date: &'static str,
+  /// The target triple
+  pub target: &'static str,
+  /// The enabled cargo features
+  pub features: Vec<&'static str>,
+  /// The compiled-in output formats
+  pub formats: Vec<String>,
+  /// The built-in project kinds
+  pub kinds: Vec<String>,
+}
+
+impl BuildInfo {
+  /// Retrieve the build information of the running binary
+  pub fn current() -> Self {
+    Self {
+      version: env!("CARGO_PKG_VERSION"),
+      git_commit: env!("PGREP_GIT_COMMIT"),
+      build_date: env!("PGREP_BUILD_DATE"),
+      target: env!("PGREP_TARGET"),
+      features: FEATURES
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| *name)
+        .collect(),
+      formats: OutputFormat::available()
+        .iter()
+        .map(|f| f.to_string())
+        .collect(),
+      kinds: ProjectKind::iter()
+        .filter(|kind| !matches!(kind, ProjectKind::Custom { .. }))
+        .map(|kind| kind.name())
+        .collect(),
+    }
+  }
+}
@@ -0,0 +1,77 @@
+pub use pgrep_core::i18n::*;
+
+/// Replace the help of every argument and subcommand of `cmd` by its translation, when there
+/// is one.
+///
+/// Arguments are looked up as `help.<id>` and subcommands as `help.<name>`, nested subcommands
+/// as `help.<parent>.<name>`. A subcommand named like an argument, such as `config`, is looked
+/// up as `help.<name>.about` first. The english help being the doc comments.
+pub fn localize_command(cmd: clap::Command) -> clap::Command {
+  let cmd = match lookup("help.about") {
+    Some(about) => cmd.about(about),
+    None => cmd,
+  };
+  localize_subcommands(localize_args(cmd, "help"), "help")
+}
+
+/// Translate the arguments of `cmd`, looked up as `<prefix>.<id>`
+fn localize_args(cmd: clap::Command, prefix: &str) -> clap::Command {
+  cmd.mut_args(
+    |arg| match lookup(&format!("{}.{}", prefix, arg.get_id())) {
+      Some(help) => arg.help(help),
+      None => arg,
+    },
+  )
+}
+
+/// Translate the subcommands of `cmd`, looked up as `<prefix>.<name>`
+fn localize_subcommands(cmd: clap::Command, prefix: &str) -> clap::Command {
+  let names = cmd
+    .get_subcommands()
+    .map(|sub| sub.get_name().to_string())
+    .collect::<Vec<_>>();
+  names.iter().fold(cmd, |cmd, name| {
+    let key = format!("{}.{}", prefix, name);
+    cmd.mut_subcommand(name, |sub| {
+      let sub = match lookup(&format!("{}.about", key)).or_else(|| lookup(&key)) {
+        Some(about) => sub.about(about),
+        None => sub,
+      };
+      localize_subcommands(localize_args(sub, &key), &key)
+    })
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use clap::CommandFactory;
+
+  use super::{lookup_in, Lang};
+  use crate::AppOptions;
+
+  #[test]
+  fn help_is_translated() {
+    let cmd = AppOptions::command();
+    let mut keys = cmd
+      .get_arguments()
+      .filter(|arg| arg.get_id() != "command")
+      .map(|arg| format!("help.{}", arg.get_id()))
+      .collect::<Vec<_>>();
+    for sub in cmd.get_subcommands() {
+      match lookup_in(Lang::Fr, &format!("help.{}.about", sub.get_name())) {
+        Some(_) => keys.push(format!("help.{}.about", sub.get_name())),
+        None => keys.push(format!("help.{}", sub.get_name())),
+      }
+      for nested in sub.get_subcommands() {
+        keys.push(format!("help.{}.{}", sub.get_name(), nested.get_name()));
+      }
+    }
+    for key in keys {
+      assert!(
+        lookup_in(Lang::Fr, &key).is_some(),
+        "missing french help '{}'",
+        key
+      );
+    }
+  }
+}
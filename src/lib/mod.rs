@@ -5,36 +5,48 @@
 //! [general]
 //! folders = ['/home/<user>/development']
 //! ```
-//! 
+//!
 //! Then run `pgrep '*test*' --config 'code.toml'` to find projects containing `test` either in the path or name.
-//! 
+//!
+//! Project discovery, matching, caching and formatting live in the `pgrep-core` crate, which
+//! is re-exported here: tools that only need them can depend on it without pulling the
+//! command-line and terminal dependencies.
+//!
 //! # Supported project kinds
-//! 
+//!
 //! For now only [`crate::ProjectKind`] are supported but over time, this list will grow.
-//! 
+//!
 //! # Caching
-//! 
-//! Scanned folders and discovered projects are cached every [`Cache::CACHE_BUST_THRESHOLD`].
-//! 
+//!
+//! Scanned folders and discovered projects are cached, and rescanned as soon as
+//! [`FolderScan::mtime_sample`] tells their top-level directories changed, or at the latest
+//! after [`Cache::SAMPLED_BUST_THRESHOLD`].
+//!
 //! You can specify the `--no-cache` comande-line options to disable cache.
 //! Or manually bust it using the exclusive `--clean-cache`
+//!
+//! # Ignoring sub-trees
+//!
+//! Any scanned directory may contain a [`IgnoreFile::FILE_NAME`] file listing glob patterns
+//! of entries to skip while scanning it and its children (fixtures, generated code, ...).
 
 pub mod app;
-pub mod cache;
-pub mod config;
-pub mod error;
-pub mod project;
-pub mod query;
+pub mod build_info;
+pub mod i18n;
 pub mod options;
-pub mod fmt;
+#[cfg(feature = "http")]
+pub mod post;
+#[cfg(feature = "tracing")]
+pub mod trace;
 pub mod ui;
+#[cfg(feature = "self-update")]
+pub mod update;
+#[cfg(feature = "web")]
+pub mod web;
+
+pub use pgrep_core::*;
 
 pub use app::*;
-pub use cache::*;
-pub use config::*;
-pub use error::*;
-pub use project::*;
-pub use query::*;
+pub use build_info::*;
 pub use options::*;
-pub use fmt::*;
 pub use ui::*;
@@ -1,27 +1,273 @@
 use std::{path::PathBuf, str::FromStr};
 
-use clap::{ArgAction, Parser};
-use strum::VariantNames;
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
 
-use crate::{OutputFormat, Query};
+use chrono::{DateTime, Local};
+
+use crate::{
+  i18n::{tr_fmt, Lang},
+  parse_since, parse_size, ArchiveMode, ColorChoice, ContainerFolder, FacetRegistry, OutputFormat,
+  Query, SortOrder, UsagePeriod,
+};
 
 /// The query format description for command-line use
-pub const QUERY_FORMAT: &'static str = "The query used to find the project. It supports the following wildcards:\n\
+pub const QUERY_FORMAT: &'static str =
+  "The query used to find the project. It supports the following wildcards:\n\
 \t- '?': an optional character\n\
 \t- '_': a required character\n\
 \t- '#': a required digit\n\
-\t- '*': any string\n";
+\t- '*': any string\n\
+\t- '[abc]', '[a-z]': one character of the set, '[!abc]' negates it\n\
+\t- '\\': escapes the next character, e.g. '\\_' for a literal underscore\n\
+The whole name must match, unless --partial is given: then the query may match anywhere\n\
+in the name, and a leading '^' or trailing '$' anchors it to the start or end.\n\
+A query containing '/' is matched against the end of full project paths, see --match-path.\n\
+A 'ci:<system>' word only keeps the projects configured for github, gitlab or jenkins,\n\
+'ci:none' the ones without continuous integration. 'kind:<kind>', 'name:<pattern>' and\n\
+'path:<pattern>' words keep the projects of a kind, or whose name or full path match.\n\
+An '@<name>' word is replaced by the query saved as <name> in the [queries] configuration,\n\
+and a word named after an alias of the [aliases] configuration by its expression.\n\
+When omitted, the `query` of the [general] configuration section is searched.\n\
+A query named after a subcommand, such as 'config', is given after '--': `pgrep -- config`.\n";
+
+/// The documentation generated by `pgrep gen`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Subcommand)]
+pub enum GenTarget {
+  /// Write the man page to stdout
+  Man,
+  /// Write the command-line reference to stdout, as markdown
+  MarkdownHelp,
+  /// Write a shell snippet binding Ctrl-G to pick a project with fzf and cd into it
+  FzfWidget {
+    /// The shell to write the snippet for
+    #[arg(value_enum)]
+    shell: WidgetShell,
+  },
+}
+
+/// The shells `pgrep gen fzf-widget` writes snippets for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum WidgetShell {
+  Bash,
+  Zsh,
+  Fish,
+}
+
+/// The cache maintenance commands, run by `pgrep cache`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Subcommand)]
+pub enum CacheCommand {
+  /// Drop the cache entries of folders which were deleted or are no longer configured
+  Gc,
+}
+
+/// The saved query commands, run by `pgrep queries`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Subcommand)]
+pub enum QueriesCommand {
+  /// List the queries saved in the `[queries]` configuration table, run with `pgrep @<name>`
+  List,
+}
+
+/// The configuration commands, run by `pgrep config`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Subcommand)]
+pub enum ConfigCommand {
+  /// Write the JSON Schema of pgrep.toml to stdout, for editors to complete and validate it
+  Schema,
+}
+
+/// The `audit` subcommands
+#[cfg(feature = "audit")]
+#[derive(Debug, Clone, PartialEq, Eq, Subcommand)]
+pub enum AuditCommand {
+  /// List the outdated direct dependencies of every project matching the query, through the
+  /// tool of its kind: `cargo outdated`, `npm outdated` or `go list -m -u`
+  Outdated {
+    /// The query selecting the projects, every project when omitted
+    #[arg(value_parser = parse_query)]
+    query: Option<Query>,
+    /// The number of tools running at the same time
+    #[arg(long, default_value_t = 4, value_name = "N")]
+    parallel: usize,
+    /// The seconds after which a tool still running is killed
+    #[arg(long, default_value_t = 120, value_name = "SECS")]
+    timeout: u64,
+  },
+}
+
+/// The subcommands, running instead of a project search
+#[derive(Debug, Clone, PartialEq, Eq, Subcommand)]
+pub enum AppCommand {
+  /// Generate documentation from the command-line definition
+  #[command(subcommand)]
+  Gen(GenTarget),
+  /// List the compiled-in output formats, in the selected format
+  ListFormats,
+  /// List the known project kinds and how they are detected, in the selected format
+  ListKinds,
+  /// Maintain the cache
+  #[command(subcommand)]
+  Cache(CacheCommand),
+  /// Manage the saved queries
+  #[command(subcommand)]
+  Queries(QueriesCommand),
+  /// Inspect the configuration file format
+  #[command(subcommand)]
+  Config(ConfigCommand),
+  /// Show the projects added, removed, or whose kinds changed since a previous scan
+  Diff {
+    /// The time of the scan to compare with: now, today, yesterday, a duration such as 2d,
+    /// 12h or 1w, or a date such as 2024-01-31
+    #[arg(long, default_value = "yesterday", value_parser = parse_since_now)]
+    since: DateTime<Local>,
+  },
+  /// Show the usage statistics recorded when `usage_stats` is enabled in the [general]
+  /// configuration section: the searches, scan durations and cache hit rates over time
+  Stats {
+    /// The period of each row
+    #[arg(long, value_enum, default_value_t = UsagePeriod::Week)]
+    period: UsagePeriod,
+  },
+  /// Run a shell command in every project folder matching the query, then summarize which
+  /// ones failed
+  Exec {
+    /// The command, where `{name}`, `{path}` and `{kinds}` are replaced by the project fields,
+    /// quoted for the shell
+    command: String,
+    /// The query selecting the projects, every project when omitted
+    #[arg(value_parser = parse_query)]
+    query: Option<Query>,
+    /// Select the members of a configured workspace instead of querying, see the `ws` subcommand
+    #[arg(long = "ws", value_name = "NAME", conflicts_with("query"))]
+    workspace: Option<String>,
+    /// The number of commands running at the same time
+    #[arg(long, default_value_t = 1, value_name = "N")]
+    parallel: usize,
+  },
+  /// Move the projects matching the query to an archive folder, recording where they were in
+  /// its manifest. Only a dry run without --apply
+  Archive {
+    /// The query selecting the projects
+    #[arg(value_parser = parse_query)]
+    query: Query,
+    /// The archive folder
+    #[arg(long, value_name = "DIR")]
+    to: PathBuf,
+    /// Move the folders as is, or compress them to .tar.gz files
+    #[arg(long, value_enum, default_value_t = ArchiveMode::Move)]
+    mode: ArchiveMode,
+    /// Archive the projects instead of listing them
+    #[arg(long)]
+    apply: bool,
+  },
+  /// Remove the build artifacts of the projects matching the query, with `cargo clean` for Rust
+  /// and by deleting folders such as node_modules for the other kinds, then report the space
+  /// freed
+  CleanArtifacts {
+    /// The query selecting the projects
+    #[arg(value_parser = parse_query)]
+    query: Query,
+    /// Clean without asking for confirmation
+    #[arg(long, short = 'y')]
+    yes: bool,
+  },
+  /// Create a new project skeleton, from the `[templates]` folder configured for its kind or
+  /// with its own tool, then rescan the code folder holding it
+  New {
+    /// The project kind, such as `rust`, `node`, `go` or a custom kind
+    kind: String,
+    /// The project name, which is also its folder name
+    name: String,
+    /// The folder holding the project, the current one by default
+    #[arg(long = "in", value_name = "DIR")]
+    parent: Option<PathBuf>,
+    /// Open the project in the editor once created
+    #[arg(long)]
+    open: bool,
+  },
+  /// Report the member projects of a workspace configured as `[workspace.<name>]`, or list the
+  /// workspaces when the name is omitted
+  #[command(visible_alias("workspace"))]
+  Ws {
+    /// The workspace name
+    name: Option<String>,
+  },
+  /// Pin a project folder, reporting it before the other matches, or list the pinned projects
+  Pin {
+    /// The project folder, the pinned projects are listed when omitted
+    path: Option<PathBuf>,
+  },
+  /// Unpin a project folder
+  Unpin {
+    /// The project folder
+    path: PathBuf,
+  },
+  /// Attach a note to a project folder, show it when the text is omitted, or list every note
+  /// when the folder is omitted too
+  Note {
+    /// The project folder
+    path: Option<PathBuf>,
+    /// The note, replacing the previous one
+    text: Option<String>,
+    /// Remove the note of the project folder
+    #[arg(long, requires("path"), conflicts_with("text"))]
+    delete: bool,
+  },
+  /// Summarize the git status of every project matching the query: the commits ahead and
+  /// behind the upstream branch, the dirty files and the stashes
+  #[cfg(feature = "git")]
+  Status {
+    /// The query selecting the projects, every project when omitted
+    #[arg(value_parser = parse_query)]
+    query: Option<Query>,
+  },
+  /// Audit the projects matching the query
+  #[cfg(feature = "audit")]
+  #[command(subcommand)]
+  Audit(AuditCommand),
+  /// Rescan the code folders periodically, reporting the projects added, removed, or whose kinds
+  /// changed, and running the configured hooks
+  Watch {
+    /// The number of seconds between two scans
+    #[arg(long, default_value_t = 60)]
+    interval: u64,
+    /// Serve a web interface browsing the scanned projects on this port
+    #[cfg(feature = "web")]
+    #[arg(long, value_name = "PORT")]
+    serve: Option<u16>,
+    /// The address the web interface listens on, only reachable from this machine by default
+    #[cfg(feature = "web")]
+    #[arg(
+      long,
+      value_name = "ADDRESS",
+      default_value = "127.0.0.1",
+      requires("serve")
+    )]
+    bind: std::net::IpAddr,
+    /// The memory the projects kept between two scans may use, such as 256MiB, beyond which
+    /// they are kept without their source files. Defaults to the `memory_cap` of the [watch]
+    /// configuration section
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    memory_cap: Option<u64>,
+  },
+  /// Download the latest release from GitHub and replace the current executable
+  #[cfg(feature = "self-update")]
+  SelfUpdate {
+    /// Only check whether a newer release is available
+    #[arg(long)]
+    check: bool,
+    /// Reinstall the latest release, even if it's not newer
+    #[arg(long)]
+    force: bool,
+  },
+}
 
 #[derive(Debug, Parser)]
 #[command(version)]
 #[command(author)]
 #[command(about, long_about = None)]
+#[command(subcommand_negates_reqs = true)]
 /// The AppOptions structure represents the command-line options and values
 pub struct AppOptions {
   /// The query used to filter projects
-  #[arg(required_unless_present("dump_config"))]
-  #[arg(required_unless_present("clean_cache"))]
-  #[arg(required_unless_present("list"))]
   #[arg(default_value("*"))]
   #[arg(next_line_help(true))]
   #[arg(help(QUERY_FORMAT))]
@@ -36,37 +282,323 @@ pub struct AppOptions {
   #[arg(long)]
   pub dump_config: bool,
 
+  /// Print the version, git commit, build date, enabled features, formats and kinds as json,
+  /// then exit.
+  #[cfg(feature = "json")]
+  #[arg(long, exclusive(true))]
+  pub build_info: bool,
+
   /// Clean the cache folder and exit.
   #[arg(long, exclusive(true))]
   pub clean_cache: bool,
 
+  /// List the latest queries, most recent first, then exit. `--recall <n>` searches the n-th
+  /// one again.
+  #[arg(long, exclusive(true))]
+  pub history: bool,
+
+  /// Search the n-th latest query of --history again instead of a query, the latest one when
+  /// omitted
+  #[arg(long, value_name = "N", num_args(0..=1), default_missing_value("1"))]
+  #[arg(conflicts_with("query"))]
+  pub recall: Option<usize>,
+
   /// Disable cache usage.
   #[arg(long)]
   pub no_cache: bool,
 
+  /// Scan the configured code folder holding the given path again, bypassing its cached
+  /// scan while still using the cache for the other folders, e.g. right after cloning a project
+  #[arg(long, value_name = "PATH", conflicts_with("no_cache"))]
+  pub rescan: Vec<PathBuf>,
+
+  /// Only scan this many directory levels below each code folder, defaults to the `max_depth`
+  /// of the [general] configuration section
+  #[arg(long, value_name = "N")]
+  pub max_depth: Option<usize>,
+
+  /// Scan the directories symbolic links point to, each one only once
+  #[arg(long)]
+  pub follow_symlinks: bool,
+
+  /// Scan the directories whose name starts with a '.'
+  #[arg(long)]
+  pub hidden: bool,
+
+  /// Also scan the source code of dependencies extracted by cargo, go and npm, reporting it
+  /// with the Dependency kind
+  #[arg(long)]
+  pub include_deps_sources: bool,
+
+  /// Also scan the home folders of the installed WSL distros, on Windows
+  #[arg(long)]
+  pub wsl: bool,
+
+  /// Also scan a folder of a running docker or podman container, such as 'app:/workspace',
+  /// reporting its projects with their paths in the container
+  #[arg(long, value_name = "NAME:PATH", value_parser = parse_container)]
+  pub in_container: Vec<ContainerFolder>,
+
+  /// The number of code folders scanned at the same time, defaults to the number of cores
+  #[arg(short, long, value_name = "N")]
+  pub jobs: Option<usize>,
+
+  /// Write how long the scan, detection, matching and formatting phases took to this file, in
+  /// the Chrome trace format
+  #[cfg(feature = "tracing")]
+  #[arg(long, value_name = "FILE")]
+  pub trace_file: Option<PathBuf>,
+
+  /// Store the cache in the given folder instead of the user one, e.g. to build a shared index
+  #[arg(long, value_name = "DIR")]
+  pub cache_dir: Option<PathBuf>,
+
+  /// Use the existing cache entries whatever their age, without ever writing to the cache
+  #[arg(long, conflicts_with("no_cache"))]
+  pub cache_readonly: bool,
+
+  /// Match the query anywhere in project names, use '^' and '$' to anchor it.
+  #[arg(long)]
+  pub partial: bool,
+
+  /// Match the query against full project paths instead of names and path components.
+  /// Implied when the query contains a '/'.
+  #[arg(long)]
+  pub match_path: bool,
+
+  /// Write the matches as tab-separated name, path, kinds and match score lines, best matches
+  /// first, to feed fuzzy finders such as fzf or skim
+  #[arg(long, conflicts_with("any"))]
+  pub emit_candidates: bool,
+
+  /// Only check whether a project matches, without reporting it.
+  /// Scanning stops at the first match, the exit status tells the outcome.
+  #[arg(long, conflicts_with("list"))]
+  pub any: bool,
+
+  /// Write the number of projects found per kind and folder to stderr, along with the
+  /// project files which didn't make a project root
+  #[arg(long, conflicts_with("any"))]
+  pub stats: bool,
+
+  /// Report the hash of each project primary manifest, such as its Cargo.toml
+  #[arg(long)]
+  pub hash: bool,
+
+  /// Only report the projects whose primary manifest is identical to another one's, grouped by
+  /// manifest hash, to find duplicate checkouts. Implies --hash
+  #[arg(long, conflicts_with("any"))]
+  pub duplicates: bool,
+
+  /// Only report the pinned projects, see the `pin` subcommand
+  #[arg(long)]
+  pub pinned: bool,
+
+  /// Only report the projects having every given facet, separated by commas, such as
+  /// `container` for the ones with a Dockerfile, a compose file or a devcontainer.json.
+  /// A project has a facet when its value is true, non-zero or non-empty, see --facets.
+  #[arg(long, value_delimiter = ',', value_name = "FACETS", value_parser = parse_facet)]
+  pub facet: Vec<String>,
+
+  /// Report the on-disk size of each project, build artifacts such as `target` or
+  /// `node_modules` included
+  #[arg(long)]
+  pub du: bool,
+
+  /// Leave the build artifacts out of the disk usage, see --du
+  #[arg(long, requires("du"))]
+  pub du_skip_artifacts: bool,
+
+  /// Extract project facets and add them to the report, separated by commas: `toolchain` for
+  /// the versions pinned by rust-toolchain.toml, .nvmrc, .python-version or the go.mod go
+  /// directive, `ci` for the continuous integration systems configured, `container` for whether
+  /// the project is containerized, `loc` for the lines of source code and `git` for the work
+  /// tree status with the git feature. Defaults to the `facets` of the [output] configuration
+  /// section
+  #[arg(
+    long,
+    visible_alias = "columns",
+    value_delimiter = ',',
+    value_name = "FACETS",
+    value_parser = parse_facet
+  )]
+  pub facets: Vec<String>,
+
+  /// Set the order in which matching projects are reported, defaults to the `sort` of the
+  /// [output] configuration section
+  #[arg(long, value_enum, default_value_t = SortOrder::Score)]
+  pub sort: SortOrder,
+
   /// Register a new entry to the searchable folders list
   #[arg(short = 'F', long = "folder", action = ArgAction::Append)]
   pub folders: Vec<PathBuf>,
 
-  /// Set the output format
-  #[arg(long = "format", default_value = OutputFormat::VARIANTS.get(0).unwrap_or(&"text"))]
-  pub format: OutputFormat,
+  /// Set the output format, inferred from the --output extension when omitted,
+  /// defaults to the first compiled-in one
+  #[arg(long = "format", value_enum)]
+  pub format: Option<OutputFormat>,
+
+  /// Write the report to the given file instead of stdout
+  #[arg(short = 'o', long)]
+  #[cfg_attr(feature = "tui", arg(conflicts_with("tui")))]
+  pub output: Option<PathBuf>,
+
+  /// Send the report to the given URL with a POST request instead of writing it,
+  /// see the `[post]` configuration section
+  #[cfg(feature = "http")]
+  #[arg(long, value_name = "URL", conflicts_with("output"))]
+  #[cfg_attr(feature = "tui", arg(conflicts_with("tui")))]
+  pub post: Option<String>,
+
+  /// Run the commands defined by a configuration file which isn't owned by the current user or
+  /// lies outside the user configuration folders, such as hooks
+  #[arg(long)]
+  pub trust: bool,
+
+  /// Set when to use colors, `auto` disables them for pipes, files and when NO_COLOR is set
+  #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+  pub color: ColorChoice,
+
+  /// Set the language of messages, detected from LANG by default
+  #[arg(long, value_enum)]
+  pub lang: Option<Lang>,
 
   /// Activate terminal ui
   #[cfg(feature = "tui")]
   #[arg(long)]
   pub tui: bool,
 
+  /// Activate terminal ui below the prompt instead of the alternate screen, so that it stays
+  /// in the scrollback after exiting
+  #[cfg(feature = "tui")]
+  #[arg(long)]
+  pub tui_inline: bool,
+
+  /// Show the matches in a window instead of the terminal
+  #[cfg(feature = "gui")]
+  #[arg(long)]
+  pub gui: bool,
+
   /// Define the path towards the editor to open
   #[arg(short, long, env = "EDITOR")]
   pub editor: Option<PathBuf>,
-  
+
   /// List project without filtering them
   #[arg(short = 'l', long = "list")]
-  pub list: bool
+  pub list: bool,
+
+  #[command(subcommand)]
+  pub command: Option<AppCommand>,
+}
+
+impl AppOptions {
+  /// Check whether these options search projects
+  pub fn searches_projects(&self) -> bool {
+    #[cfg(feature = "json")]
+    if self.build_info {
+      return false;
+    }
+    !self.history && self.command.is_none()
+  }
+
+  /// Resolve the output format: the one given with --format, else the one inferred from the
+  /// --output extension, else `default`. Unknown extensions fall back to `default` with a warning.
+  pub fn output_format(&self, default: OutputFormat) -> OutputFormat {
+    if let Some(format) = self.format {
+      return format;
+    }
+    let Some(output) = &self.output else {
+      return default;
+    };
+    OutputFormat::from_path(output).unwrap_or_else(|| {
+      log::warn!(
+        "{}",
+        tr_fmt("app.unknown-extension", &[&output.display(), &default])
+      );
+      default
+    })
+  }
+
+  /// Check whether the output format given on the command-line, with --format or the --output
+  /// extension, is json. Errors raised before the configuration is loaded are then reported as
+  /// json too.
+  pub fn reports_json(&self) -> bool {
+    #[cfg(feature = "json")]
+    return self
+      .format
+      .or_else(|| self.output.as_ref().and_then(OutputFormat::from_path))
+      == Some(OutputFormat::Json);
+    #[cfg(not(feature = "json"))]
+    return false;
+  }
+
+  /// Check whether these options need a query, given on the command-line or configured as
+  /// `[general] query`
+  pub fn needs_query(&self) -> bool {
+    self.searches_projects()
+      && !(self.dump_config || self.clean_cache || self.list || self.duplicates || self.pinned)
+  }
+
+  /// Check whether these options need the user configuration
+  pub fn needs_config(&self) -> bool {
+    self.searches_projects()
+      || matches!(
+        self.command,
+        Some(AppCommand::ListKinds)
+          | Some(AppCommand::Cache(_))
+          | Some(AppCommand::Queries(_))
+          | Some(AppCommand::Diff { .. })
+          | Some(AppCommand::Watch { .. })
+          | Some(AppCommand::Exec { .. })
+          | Some(AppCommand::Ws { .. })
+          | Some(AppCommand::New { .. })
+          | Some(AppCommand::Archive { .. })
+          | Some(AppCommand::CleanArtifacts { .. })
+      )
+      || self.lists_git_status()
+      || self.audits()
+  }
+
+  /// Check whether the `status` command was given
+  fn lists_git_status(&self) -> bool {
+    #[cfg(feature = "git")]
+    return matches!(self.command, Some(AppCommand::Status { .. }));
+    #[cfg(not(feature = "git"))]
+    return false;
+  }
+
+  /// Check whether the `audit` command was given
+  fn audits(&self) -> bool {
+    #[cfg(feature = "audit")]
+    return matches!(self.command, Some(AppCommand::Audit(_)));
+    #[cfg(not(feature = "audit"))]
+    return false;
+  }
+}
+
+/// ValueParser helper for [`clap`], see [`parse_since`]
+fn parse_since_now(s: &str) -> Result<DateTime<Local>, String> {
+  parse_since(s, Local::now())
+}
+
+/// ValueParser helper for [`clap`], accepting `<name>:<path>` container folders
+fn parse_container(s: &str) -> Result<ContainerFolder, String> {
+  ContainerFolder::from_spec(s).ok_or_else(|| tr_fmt("app.invalid-container", &[&s]))
+}
+
+/// ValueParser helper for [`clap`], accepting the names of the [`FacetRegistry`] facets
+pub(crate) fn parse_facet(s: &str) -> Result<String, String> {
+  let names = FacetRegistry::default().names();
+  match names.contains(&s) {
+    true => Ok(s.to_string()),
+    false => Err(format!(
+      "unknown facet `{s}`, expected one of {}",
+      names.join(", ")
+    )),
+  }
 }
 
 /// ValueParser helper for [`clap`]
 fn parse_query(s: &str) -> Result<Query, String> {
-  Query::from_str(s).map_err(|e| format!("`{s}` isn't a valid query, {}", e))
+  Query::from_str(s).map_err(|e| format!("`{s}` isn't a valid query, {}", e.render()))
 }
@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use log::{debug, warn};
+
+use crate::{i18n::tr_fmt, resolve_secret, Error, OutputFormat, PostConfig};
+
+/// The number of retries after a failed request, unless configured
+pub const DEFAULT_RETRIES: u32 = 3;
+/// The request timeout in seconds, unless configured
+pub const DEFAULT_TIMEOUT: u64 = 30;
+
+/// Retrieve the `Content-Type` of reports written in `format`
+pub fn content_type(format: OutputFormat) -> &'static str {
+  match format {
+    OutputFormat::Text => "text/plain; charset=utf-8",
+    OutputFormat::Json => "application/json",
+    OutputFormat::Csv => "text/csv; charset=utf-8",
+    OutputFormat::Xml => "application/xml",
+    OutputFormat::Html => "text/html; charset=utf-8",
+    OutputFormat::Markdown => "text/markdown; charset=utf-8",
+  }
+}
+
+/// Send a report written in `format` to `url` with a POST request.
+///
+/// Transport errors, `429` and `5xx` responses are retried with an exponential backoff, other
+/// error responses fail immediately.
+pub fn post_report(
+  url: &str,
+  format: OutputFormat,
+  body: &[u8],
+  config: &PostConfig,
+) -> crate::Result<()> {
+  let authorization = config
+    .authorization
+    .as_ref()
+    .map(resolve_secret)
+    .transpose()?;
+  let agent = ureq::AgentBuilder::new()
+    .timeout(Duration::from_secs(
+      config.timeout.unwrap_or(DEFAULT_TIMEOUT),
+    ))
+    .build();
+  let retries = config.retries.unwrap_or(DEFAULT_RETRIES);
+  let mut attempt = 0;
+  loop {
+    debug!("POST {} ({} bytes)", url, body.len());
+    let mut request = agent
+      .post(url)
+      .set("Content-Type", content_type(format))
+      .set(
+        "User-Agent",
+        concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")),
+      );
+    if let Some(authorization) = &authorization {
+      request = request.set("Authorization", authorization);
+    }
+    let e = match request.send_bytes(body) {
+      Ok(response) => {
+        debug!("POST {} answered {}", url, response.status());
+        return Ok(());
+      }
+      Err(e) => e,
+    };
+    let transient = match &e {
+      ureq::Error::Status(status, _) => *status == 429 || *status >= 500,
+      ureq::Error::Transport(_) => true,
+    };
+    if !transient || attempt >= retries {
+      return Err(Error::IO {
+        message: format!("request to '{}' failed", url),
+        source: Some(Box::new(e)),
+      });
+    }
+    let delay = Duration::from_secs(1 << attempt.min(6));
+    warn!(
+      "{}",
+      tr_fmt("app.post-retry", &[&url, &e, &delay.as_secs()])
+    );
+    std::thread::sleep(delay);
+    attempt += 1;
+  }
+}
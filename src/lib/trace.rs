@@ -0,0 +1,171 @@
+//! Profile the scan, detection, matching and formatting phases written to `--trace-file`.
+//!
+//! The phases are [`tracing`] spans, written as the events of the Chrome trace format: open
+//! the file in `chrome://tracing` or <https://ui.perfetto.dev> to see how long each one took
+//! on each thread.
+
+use std::{
+  collections::HashMap,
+  fs::File,
+  io::Write,
+  path::Path,
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Mutex,
+  },
+  time::Instant,
+};
+
+use serde_json::{json, Map, Value};
+use tracing::{
+  field::{Field, Visit},
+  span::{Attributes, Id, Record},
+  Event, Metadata, Subscriber,
+};
+
+use crate::Error;
+
+/// A span being traced
+struct TracedSpan {
+  name: &'static str,
+  /// The recorded fields, written along with the span
+  args: Map<String, Value>,
+  /// The number of handles to the span, see [`Subscriber::clone_span`]
+  refs: usize,
+}
+
+/// Collects the fields of a span
+struct Args<'a>(&'a mut Map<String, Value>);
+
+impl Visit for Args<'_> {
+  fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+    self.0.insert(
+      field.name().to_string(),
+      Value::from(format!("{:?}", value)),
+    );
+  }
+
+  fn record_str(&mut self, field: &Field, value: &str) {
+    self.0.insert(field.name().to_string(), Value::from(value));
+  }
+
+  fn record_u64(&mut self, field: &Field, value: u64) {
+    self.0.insert(field.name().to_string(), Value::from(value));
+  }
+}
+
+/// A [`Subscriber`] writing spans to a Chrome trace file as they are entered and exited
+pub struct ChromeTrace {
+  /// When tracing started, the origin of the event times
+  started: Instant,
+  /// The id of the next span
+  next_id: AtomicU64,
+  spans: Mutex<HashMap<u64, TracedSpan>>,
+  /// The trace file, a json array left open so that it stays valid if pgrep is interrupted
+  file: Mutex<File>,
+}
+
+impl ChromeTrace {
+  /// Create the trace file at `path`
+  pub fn create(path: &Path) -> crate::Result<Self> {
+    let mut file = File::create(path)
+      .map_err(|e| Error::io(format!("failed to create '{}'", path.display()), e))?;
+    writeln!(file, "[")?;
+    Ok(Self {
+      started: Instant::now(),
+      next_id: AtomicU64::new(1),
+      spans: Mutex::new(HashMap::new()),
+      file: Mutex::new(file),
+    })
+  }
+
+  /// Trace the whole process to the file at `path`
+  pub fn install(path: &Path) -> crate::Result<()> {
+    tracing::subscriber::set_global_default(Self::create(path)?)
+      .map_err(|e| Error::config(format!("cannot trace to '{}': {}", path.display(), e)))
+  }
+
+  /// Write the `phase` event of the span `id`: `B` when entered, `E` when exited
+  fn write(&self, id: &Id, phase: &str) {
+    let spans = self.spans.lock().unwrap();
+    let Some(span) = spans.get(&id.into_u64()) else {
+      return;
+    };
+    let event = json!({
+      "name": span.name,
+      "ph": phase,
+      "ts": self.started.elapsed().as_micros() as u64,
+      "pid": std::process::id(),
+      "tid": thread_id(),
+      "args": span.args,
+    });
+    // tracing must never fail the traced process
+    let _ = writeln!(self.file.lock().unwrap(), "{},", event);
+  }
+}
+
+/// Number the threads in the order they write their first event, as Chrome expects integers
+fn thread_id() -> u64 {
+  static NEXT: AtomicU64 = AtomicU64::new(1);
+  thread_local! {
+    static ID: u64 = NEXT.fetch_add(1, Ordering::Relaxed);
+  }
+  ID.with(|id| *id)
+}
+
+impl Subscriber for ChromeTrace {
+  fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+    metadata.is_span()
+  }
+
+  fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+    let mut args = Map::new();
+    attrs.record(&mut Args(&mut args));
+    let span = TracedSpan {
+      name: attrs.metadata().name(),
+      args,
+      refs: 1,
+    };
+    self.spans.lock().unwrap().insert(id, span);
+    Id::from_u64(id)
+  }
+
+  fn record(&self, id: &Id, values: &Record<'_>) {
+    if let Some(span) = self.spans.lock().unwrap().get_mut(&id.into_u64()) {
+      values.record(&mut Args(&mut span.args));
+    }
+  }
+
+  fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+  fn event(&self, _event: &Event<'_>) {}
+
+  fn enter(&self, id: &Id) {
+    self.write(id, "B");
+  }
+
+  fn exit(&self, id: &Id) {
+    self.write(id, "E");
+  }
+
+  fn clone_span(&self, id: &Id) -> Id {
+    if let Some(span) = self.spans.lock().unwrap().get_mut(&id.into_u64()) {
+      span.refs += 1;
+    }
+    id.clone()
+  }
+
+  fn try_close(&self, id: Id) -> bool {
+    let mut spans = self.spans.lock().unwrap();
+    let Some(span) = spans.get_mut(&id.into_u64()) else {
+      return false;
+    };
+    span.refs -= 1;
+    if span.refs > 0 {
+      return false;
+    }
+    spans.remove(&id.into_u64());
+    true
+  }
+}
@@ -1,21 +1,49 @@
-use crate::{BoxedProjectMatchesFormatter, Project, UI};
+use std::{
+  io::Write,
+  path::{Path, PathBuf},
+};
+
+use crate::{
+  create_output,
+  style::{styled_writer, Stream},
+  BoxedProjectMatchesFormatter, Project, UI,
+};
 
 /// Represent the raw console, where messages are just appended to each other (stdout by default)
-pub struct Console;
+pub struct Console {
+  /// The file matches are written to instead of stdout
+  output: Option<PathBuf>,
+}
 
 impl Console {
   pub fn new() -> Self {
-    Self
+    Self { output: None }
+  }
+
+  /// Create a console writing matches to the given file, without styles unless
+  /// [`ColorChoice::Always`] is selected
+  pub fn with_output<P: AsRef<Path>>(path: P) -> Self {
+    Self {
+      output: Some(path.as_ref().to_path_buf()),
+    }
   }
 }
 
 impl UI for Console {
   fn write_matches(
     &mut self,
-    matches: &Vec<Project>,
+    matches: &[Project],
     fmt: &BoxedProjectMatchesFormatter,
   ) -> crate::Result<()> {
-    fmt.write(&mut std::io::stdout(), matches)?;
+    if let Some(path) = &self.output {
+      let mut file = styled_writer(Stream::File, create_output(path)?);
+      fmt.write(&mut file, &mut matches.iter())?;
+      file.flush()?;
+      return Ok(());
+    }
+    let mut out = styled_writer(Stream::Stdout, std::io::stdout());
+    fmt.write(&mut out, &mut matches.iter())?;
+    out.flush()?;
     Ok(())
   }
 
@@ -0,0 +1,198 @@
+use std::{path::PathBuf, process::Command};
+
+use crate::{
+  i18n::{tr, tr_fmt},
+  is_remote_path, wsl_editor_path, BoxedProjectMatchesFormatter, CloneRemote, Error, Project, UI,
+};
+
+use eframe::egui;
+use log::warn;
+
+/// The window interface, showing a searchable table of the matches.
+///
+/// Activate with the `gui` feature **and** the `--gui` option.
+pub struct Gui {
+  projects: Vec<Project>,
+  editor: Option<PathBuf>,
+  clone: Option<CloneRemote>,
+}
+
+impl Gui {
+  pub fn new(editor: Option<PathBuf>) -> Self {
+    Self {
+      projects: vec![],
+      editor,
+      clone: None,
+    }
+  }
+
+  /// Set how the projects of remote folders are cloned when opened, they cannot be opened
+  /// otherwise
+  pub fn set_clone_remote(&mut self, clone: CloneRemote) {
+    self.clone = Some(clone);
+  }
+}
+
+impl UI for Gui {
+  fn write_matches(
+    &mut self,
+    matches: &[Project],
+    _fmt: &BoxedProjectMatchesFormatter,
+  ) -> crate::Result<()> {
+    self.projects.extend(matches.iter().cloned());
+    Ok(())
+  }
+
+  fn write_log(&mut self, text: &str, lvl: log::Level) -> crate::Result<()> {
+    log::log!(lvl, "{}", text);
+    Ok(())
+  }
+
+  fn render_loop(&mut self) -> crate::Result<()> {
+    let window = Window {
+      projects: std::mem::take(&mut self.projects),
+      editor: self.editor.clone(),
+      clone: self.clone.clone(),
+      search: String::new(),
+      selected: None,
+    };
+    eframe::run_native(
+      tr("gui.title"),
+      eframe::NativeOptions::default(),
+      Box::new(|_| Box::new(window)),
+    )
+    .map_err(|e| Error::Unknown(tr_fmt("gui.failed", &[&e])))
+  }
+}
+
+/// The state of the window
+struct Window {
+  projects: Vec<Project>,
+  editor: Option<PathBuf>,
+  /// Clones the remote projects before opening them
+  clone: Option<CloneRemote>,
+  /// The text filtering the projects by name or path
+  search: String,
+  /// The index of the selected project
+  selected: Option<usize>,
+}
+
+impl Window {
+  /// Check whether `project` contains the searched text in its name or path, ignoring case
+  fn is_visible(&self, project: &Project) -> bool {
+    let search = self.search.to_lowercase();
+    project
+      .name()
+      .unwrap_or_default()
+      .to_lowercase()
+      .contains(&search)
+      || format!("{}", project.path().display())
+        .to_lowercase()
+        .contains(&search)
+  }
+
+  /// Open the project folder in the editor, without waiting for it to exit. The projects of
+  /// remote folders are cloned first.
+  fn open(&self, project: &Project) {
+    let editor = self
+      .editor
+      .clone()
+      .or_else(|| std::env::var("VISUAL").ok().map(PathBuf::from));
+    let Some(editor) = editor else {
+      warn!("{}", tr("gui.no-editor"));
+      return;
+    };
+    let path = match (is_remote_path(project.path()), &self.clone) {
+      (false, _) => project.path().clone(),
+      (true, None) => {
+        warn!(
+          "{}",
+          tr_fmt("app.no-clone-dir", &[&project.path().display()])
+        );
+        return;
+      }
+      (true, Some(clone)) => match clone(project.path()) {
+        Ok(path) => path,
+        Err(e) => {
+          warn!("{}", e);
+          return;
+        }
+      },
+    };
+    if let Err(e) = Command::new(&editor)
+      .arg(wsl_editor_path(&editor, &path))
+      .spawn()
+    {
+      warn!("{}", tr_fmt("gui.open-failed", &[&editor.display(), &e]));
+    }
+  }
+
+  /// Render the details of the selected project and the button opening it
+  fn details(&self, ui: &mut egui::Ui, project: &Project) {
+    ui.heading(project.name().unwrap_or_default());
+    let kinds = project
+      .kinds()
+      .iter()
+      .map(|k| k.name())
+      .collect::<Vec<_>>()
+      .join(", ");
+    ui.label(tr_fmt("gui.kinds", &[&kinds]));
+    ui.label(tr_fmt("gui.path", &[&project.path().display()]));
+    ui.separator();
+    for file in project.project_files() {
+      let file = file.strip_prefix(project.path()).unwrap_or(file);
+      ui.monospace(format!("{}", file.display()));
+    }
+    ui.separator();
+    if ui.button(tr("gui.open")).clicked() {
+      self.open(project);
+    }
+  }
+}
+
+impl eframe::App for Window {
+  fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    let visible = (0..self.projects.len())
+      .filter(|id| self.is_visible(&self.projects[*id]))
+      .collect::<Vec<_>>();
+    egui::TopBottomPanel::top("search").show(ctx, |ui| {
+      ui.horizontal(|ui| {
+        ui.label(tr("gui.search"));
+        ui.text_edit_singleline(&mut self.search);
+        ui.label(tr_fmt(
+          "gui.projects",
+          &[&visible.len(), &self.projects.len()],
+        ));
+      });
+    });
+    if let Some(project) = self.selected.and_then(|id| self.projects.get(id)) {
+      egui::SidePanel::right("details")
+        .min_width(250.0)
+        .show(ctx, |ui| self.details(ui, project));
+    }
+    egui::CentralPanel::default().show(ctx, |ui| {
+      egui::ScrollArea::both().show(ui, |ui| {
+        egui::Grid::new("projects").striped(true).show(ui, |ui| {
+          ui.strong(tr("gui.name"));
+          ui.strong(tr("gui.kinds-column"));
+          ui.strong(tr("gui.path-column"));
+          ui.end_row();
+          for id in visible {
+            let project = &self.projects[id];
+            let name = project.name().unwrap_or_default();
+            if ui
+              .selectable_label(self.selected == Some(id), name)
+              .clicked()
+            {
+              self.selected = Some(id);
+            }
+            let kinds = project.kinds().iter().map(|k| k.name()).collect::<Vec<_>>();
+            ui.label(kinds.join(","));
+            ui.label(format!("{}", project.path().display()));
+            ui.end_row();
+          }
+        });
+      });
+    });
+  }
+}
@@ -9,4 +9,8 @@ pub use console::*;
 #[cfg(feature = "tui")]
 mod terminal;
 #[cfg(feature = "tui")]
-pub use terminal::*;
\ No newline at end of file
+pub use terminal::*;
+#[cfg(feature = "gui")]
+mod gui;
+#[cfg(feature = "gui")]
+pub use gui::*;
@@ -1,69 +1,740 @@
 use std::{
-  io::Stdout, panic::{set_hook, take_hook}, path::PathBuf, process::Command, time::Duration
+  collections::HashMap,
+  io::Stdout,
+  ops::Range,
+  panic::{set_hook, take_hook},
+  path::{Path, PathBuf},
+  process::Command,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc::{channel, Receiver, Sender},
+  },
+  thread::JoinHandle,
+  time::Duration,
 };
 
-use crate::{Error, Project, UI};
+use crate::{
+  i18n::{tr, tr_fmt},
+  is_remote_path, wsl_editor_path, App, CloneRemote, Error, FacetCache, FacetRegistry, MatchMode,
+  Pins, Project, Query, QueryHistory, SortOrder, UI,
+};
+
+use chrono::{DateTime, Local};
+use clap::ValueEnum;
 
 use crossterm::{
-  event::{self, Event, KeyCode},
+  event::{self, Event, KeyCode, KeyEvent},
   execute,
   terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 
-use log::{log, Level};
+use log::{debug, Level};
 use ratatui::{
   backend::CrosstermBackend,
-  layout::{Constraint, Layout, Rect},
+  layout::{Alignment, Constraint, Layout, Rect},
   style::{palette::tailwind, Modifier, Style},
-  terminal::{Frame, Terminal as RataTerm},
-  widgets::{Block, HighlightSpacing, List, ListState, Paragraph},
+  terminal::{Frame, Terminal as RataTerm, TerminalOptions, Viewport},
+  widgets::{Block, HighlightSpacing, List, ListState, Paragraph, Tabs, Wrap},
 };
 
 /// The `ncurses` interface, which allows having a user-friendly TUI in the terminal.
-/// 
+///
 /// Activate with the `tui` feature **and** the `--tui` option.
-pub struct Terminal<'a> {
+pub struct Terminal {
   term: RataTerm<CrosstermBackend<Stdout>>,
+  picker: Picker,
+  editor: Option<PathBuf>,
+  /// Whether the interface is rendered below the prompt, see [`Terminal::inline`]
+  inline: bool,
+  /// Clones the remote projects before opening them, see [`Terminal::set_clone_remote`]
+  clone: Option<CloneRemote>,
+}
+
+/// Whether a `Terminal` switched to the alternate screen, which must be left when restoring
+static ALTERNATE_SCREEN: AtomicBool = AtomicBool::new(false);
+
+/// The context of the matches, shown by the [`Terminal`] status bar
+#[derive(Debug, Clone, Default)]
+pub struct Status {
+  /// The command-line query
+  pub query: String,
+  /// The number of projects found in every code folder
+  pub total: usize,
+  /// The order of the matches
+  pub sort: SortOrder,
+  /// The code folders, and when their scan was last cached until it expires
+  pub folders: Vec<(PathBuf, Option<Range<DateTime<Local>>>)>,
+}
+
+impl Status {
+  /// Describe how fresh the cached scan of every code folder is
+  fn cache_freshness(&self) -> Vec<String> {
+    let now = Local::now();
+    self
+      .folders
+      .iter()
+      .map(|(folder, cached)| {
+        let name = folder
+          .file_name()
+          .unwrap_or(folder.as_os_str())
+          .to_string_lossy();
+        match cached {
+          Some(cached) if now < cached.end => tr_fmt(
+            "tui.cache-fresh",
+            &[&name, &(now - cached.start).num_seconds()],
+          ),
+          Some(_) => tr_fmt("tui.cache-stale", &[&name]),
+          None => tr_fmt("tui.cache-missing", &[&name]),
+        }
+      })
+      .collect()
+  }
+}
+
+/// The frames of the spinner shown while the facets of a project are extracted
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Extracts the facets of the selected projects in a background thread, so that expensive
+/// ones such as `loc` or `git` don't run for every project upfront
+#[derive(Debug)]
+struct FacetWorker {
+  /// The names of the facets extracted
+  names: Vec<String>,
+  /// Sends the projects to extract the facets of, by index. Dropped to stop the thread.
+  requests: Option<Sender<(usize, Project)>>,
+  /// Receives the projects once their facets are extracted
+  results: Receiver<(usize, Project)>,
+  /// The thread, saving the facet cache once stopped
+  thread: Option<JoinHandle<()>>,
+  /// The projects sent to the thread by index, and whether their facets were received
+  extracted: HashMap<usize, bool>,
+}
+
+impl FacetWorker {
+  /// Start the thread extracting the facets named `names`, saving the values to `cache` when
+  /// stopped if `save` is set
+  fn spawn(names: Vec<String>, mut cache: FacetCache, save: bool) -> Self {
+    let (requests, pending) = channel::<(usize, Project)>();
+    let (done, results) = channel();
+    let facets = names.clone();
+    let thread = std::thread::spawn(move || {
+      let registry = FacetRegistry::default();
+      for (id, mut project) in pending {
+        registry.extract(&facets, std::slice::from_mut(&mut project), &mut cache);
+        if done.send((id, project)).is_err() {
+          break;
+        }
+      }
+      if save {
+        if let Err(e) = cache.save() {
+          debug!("{}", e);
+        }
+      }
+    });
+    Self {
+      names,
+      requests: Some(requests),
+      results,
+      thread: Some(thread),
+      extracted: HashMap::new(),
+    }
+  }
+
+  /// Check whether the facets of the `id`-th project were requested but not received yet
+  fn is_pending(&self, id: usize) -> bool {
+    self.extracted.get(&id) == Some(&false)
+  }
+}
+
+impl Drop for FacetWorker {
+  fn drop(&mut self) {
+    self.requests.take();
+    if let Some(thread) = self.thread.take() {
+      let _ = thread.join();
+    }
+  }
+}
+
+/// The query typed in the search bar, opened with `/`
+#[derive(Debug, Default)]
+struct Search {
+  /// The query shown in the search bar
+  input: String,
+  /// The query typed before recalling older ones
+  typed: String,
+  /// The rank of the recalled query in the [`QueryHistory`], `0` for the typed one
+  recalled: usize,
+  /// The filter applied when the search bar was opened, restored when cancelled
+  previous: Option<Query>,
+}
+
+/// The state of the project picker, rendered by [`Terminal`]
+#[derive(Debug, Default)]
+struct Picker {
+  /// The context of the matches
+  status: Status,
+  /// The pinned projects, toggled with `p`
+  pins: Pins,
+  /// Every matching project
   projects: Vec<Project>,
-  projects_widget: List<'a>,
-  projects_state: ListState,
+  /// The kind names of the tabs following the `All` one, most common first
+  kinds: Vec<String>,
+  /// The selected tab, `0` showing every project
+  tab: usize,
+  /// The indices of the projects shown by the selected tab
+  visible: Vec<usize>,
+  /// The selected row among the visible projects
+  state: ListState,
   details_opened: bool,
-  editor: Option<PathBuf>
+  /// Whether the file browser is shown, it then receives the arrow keys
+  files_opened: bool,
+  /// The selected row among the files of the selected project
+  files_state: ListState,
+  /// Extracts the lazy facets of the projects shown in the details pane
+  facets: Option<FacetWorker>,
+  /// The number of frames rendered, animating the spinner
+  frames: usize,
+  /// The latest queries, recalled with the arrow keys in the search bar
+  history: QueryHistory,
+  /// The search bar, when opened
+  search: Option<Search>,
+  /// The query filtering the projects, typed in the search bar
+  filter: Option<Query>,
+}
+
+/// The depth below the project root up to which the file browser lists source files, so
+/// that `src/main.rs` is shown but not every module of a large project
+const FILE_BROWSER_DEPTH: usize = 2;
+
+impl Picker {
+  /// Replace the projects, resetting the tabs and the selection
+  fn set_projects(&mut self, projects: Vec<Project>) {
+    let mut counts: Vec<(String, usize)> = vec![];
+    for kind in projects.iter().flat_map(|p| p.kinds()) {
+      match counts.iter_mut().find(|(name, _)| *name == kind.name()) {
+        Some((_, count)) => *count += 1,
+        None => counts.push((kind.name(), 1)),
+      }
+    }
+    counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+    self.kinds = counts.into_iter().map(|(name, _)| name).collect();
+    self.projects = projects;
+    if let Some(worker) = &mut self.facets {
+      worker.extracted.clear();
+    }
+    self.select_tab(0);
+  }
+
+  /// Receive the extracted facets, then request the ones of the project shown in the details
+  /// pane
+  fn update_facets(&mut self) {
+    let Some(worker) = &mut self.facets else {
+      return;
+    };
+    while let Ok((id, project)) = worker.results.try_recv() {
+      if let Some(target) = self.projects.get_mut(id) {
+        for name in &worker.names {
+          target.set_facet(name, project.facet(name).cloned());
+        }
+        worker.extracted.insert(id, true);
+      }
+    }
+    if !self.details_opened {
+      return;
+    }
+    let Some(id) = self.state.selected().and_then(|row| self.visible.get(row)) else {
+      return;
+    };
+    if !worker.extracted.contains_key(id) {
+      if let Some(requests) = &worker.requests {
+        if requests.send((*id, self.projects[*id].clone())).is_ok() {
+          worker.extracted.insert(*id, false);
+        }
+      }
+    }
+  }
+
+  /// Check whether facets are being extracted, the spinner then needing frequent frames
+  fn extracting_facets(&self) -> bool {
+    self
+      .facets
+      .as_ref()
+      .is_some_and(|worker| worker.extracted.values().any(|done| !done))
+  }
+
+  /// Show the projects of the `tab`-th tab, selecting the first one
+  fn select_tab(&mut self, tab: usize) {
+    self.tab = tab.min(self.kinds.len());
+    let kind = match self.tab {
+      0 => None,
+      tab => Some(&self.kinds[tab - 1]),
+    };
+    self.visible = self
+      .projects
+      .iter()
+      .enumerate()
+      .filter(|(_, p)| kind.is_none_or(|kind| p.kinds().iter().any(|k| k.name() == *kind)))
+      .filter(|(_, p)| {
+        self
+          .filter
+          .as_ref()
+          .is_none_or(|q| App::score_project(q, p).is_some())
+      })
+      .map(|(id, _)| id)
+      .collect();
+    self.state = ListState::default().with_selected(match self.visible.is_empty() {
+      true => None,
+      false => Some(0),
+    });
+    self.reset_files();
+  }
+
+  /// Retrieve the selected project, if any is visible
+  fn selected(&self) -> Option<&Project> {
+    let row = self.state.selected()?;
+    self.visible.get(row).map(|id| &self.projects[*id])
+  }
+
+  /// List the project files and top-level source files of the selected project
+  fn files(&self) -> Vec<&PathBuf> {
+    let Some(proj) = self.selected() else {
+      return vec![];
+    };
+    let top_level = |file: &&PathBuf| {
+      file
+        .strip_prefix(proj.path())
+        .map_or(true, |rel| rel.components().count() <= FILE_BROWSER_DEPTH)
+    };
+    proj
+      .project_files()
+      .iter()
+      .chain(proj.source_files().iter().filter(top_level))
+      .collect()
+  }
+
+  /// Retrieve the file selected in the file browser, if it is shown
+  fn selected_file(&self) -> Option<PathBuf> {
+    if !self.files_opened {
+      return None;
+    }
+    let row = self.files_state.selected()?;
+    self.files().get(row).map(|file| (*file).clone())
+  }
+
+  /// Pin the selected project if it isn't, unpin it otherwise, then save the pins
+  fn toggle_pin(&mut self) -> crate::Result<()> {
+    let Some(path) = self.selected().map(|proj| proj.path().clone()) else {
+      return Ok(());
+    };
+    self.pins.toggle(path);
+    self.pins.save()
+  }
+
+  /// Select the first file of the selected project in the file browser
+  fn reset_files(&mut self) {
+    let first = match self.files().is_empty() {
+      true => None,
+      false => Some(0),
+    };
+    self.files_state = ListState::default().with_selected(first);
+  }
+
+  /// Filter the projects with the query in the search bar, keeping the current filter when it
+  /// isn't valid yet
+  fn apply_search(&mut self) {
+    let Some(search) = &self.search else {
+      return;
+    };
+    let filter = match search.input.trim() {
+      "" => None,
+      input => match input.parse::<Query>() {
+        Ok(query) => Some(query.with_mode(MatchMode::Partial)),
+        Err(_) => return,
+      },
+    };
+    self.filter = filter;
+    self.select_tab(self.tab);
+  }
+
+  /// Update the search bar from a key press: `Up` and `Down` recall the queries of the
+  /// history, `Return` keeps the filter and records its query, `Esc` restores the previous one
+  fn handle_search_key(&mut self, key: KeyEvent) {
+    let Some(search) = &mut self.search else {
+      return;
+    };
+    match key.code {
+      KeyCode::Esc => {
+        self.filter = self.search.take().and_then(|search| search.previous);
+        self.select_tab(self.tab);
+        return;
+      }
+      KeyCode::Enter => {
+        if self.history.record(&search.input) {
+          if let Err(e) = self.history.save() {
+            debug!("{}", e);
+          }
+        }
+        self.search = None;
+        return;
+      }
+      KeyCode::Up => {
+        if let Some(query) = self.history.get(search.recalled + 1) {
+          if search.recalled == 0 {
+            search.typed = search.input.clone();
+          }
+          search.recalled += 1;
+          search.input = query.clone();
+        }
+      }
+      KeyCode::Down if search.recalled > 0 => {
+        search.recalled -= 1;
+        search.input = match search.recalled {
+          0 => search.typed.clone(),
+          n => self.history.get(n).cloned().unwrap_or_default(),
+        };
+      }
+      KeyCode::Backspace => {
+        search.input.pop();
+        search.recalled = 0;
+      }
+      KeyCode::Char(c) => {
+        search.input.push(c);
+        search.recalled = 0;
+      }
+      _ => return,
+    }
+    self.apply_search();
+  }
+
+  /// Update the picker from a key press, returning whether the interface must close
+  fn handle_key(&mut self, key: KeyEvent) -> bool {
+    let tabs = self.kinds.len() + 1;
+    match key.code {
+      KeyCode::Char('q') => return true,
+      KeyCode::Char('/') => {
+        self.search = Some(Search {
+          input: self
+            .filter
+            .as_ref()
+            .map(|q| q.to_string())
+            .unwrap_or_default(),
+          previous: self.filter.clone(),
+          ..Default::default()
+        })
+      }
+      KeyCode::Char('f') => {
+        self.files_opened = !self.files_opened;
+        self.reset_files();
+      }
+      KeyCode::Up if self.files_opened => {
+        let cur_sel = self.files_state.selected().unwrap_or_default();
+        if cur_sel > 0 {
+          self.files_state.select(Some(cur_sel - 1));
+        }
+      }
+      KeyCode::Down if self.files_opened => {
+        let cur_sel = self.files_state.selected().unwrap_or_default();
+        if cur_sel + 1 < self.files().len() {
+          self.files_state.select(Some(cur_sel + 1));
+        }
+      }
+      KeyCode::Up => {
+        let cur_sel = self.state.selected().unwrap_or_default();
+        if cur_sel > 0 {
+          self.state.select(Some(cur_sel - 1));
+        }
+      }
+      KeyCode::Down => {
+        let cur_sel = self.state.selected().unwrap_or_default();
+        if cur_sel + 1 < self.visible.len() {
+          self.state.select(Some(cur_sel + 1));
+        }
+      }
+      KeyCode::Enter => self.details_opened = !self.details_opened,
+      KeyCode::Tab => self.select_tab((self.tab + 1) % tabs),
+      KeyCode::BackTab => self.select_tab((self.tab + tabs - 1) % tabs),
+      KeyCode::Char(c @ '1'..='9') => {
+        let tab = c as usize - '1' as usize;
+        if tab < tabs {
+          self.select_tab(tab);
+        }
+      }
+      _ => {}
+    }
+    false
+  }
+
+  /// Render a single terminal frame.
+  ///
+  /// This will be called in a loop.
+  fn render(&mut self, frame: &mut Frame) {
+    let [tabs_rect, main_rect, menu_rect] = *Layout::vertical([
+      Constraint::Length(1),
+      Constraint::Min(0),
+      Constraint::Length(1),
+    ])
+    .split(frame.size()) else {
+      return;
+    };
+    let titles = std::iter::once(tr_fmt("tui.all", &[&self.projects.len()]))
+      .chain(self.kinds.iter().enumerate().map(|(id, kind)| {
+        let count = self
+          .projects
+          .iter()
+          .filter(|p| p.kinds().iter().any(|k| k.name() == *kind))
+          .count();
+        format!("{}:{} ({})", id + 2, kind, count)
+      }))
+      .collect::<Vec<_>>();
+    let tabs = Tabs::new(titles).select(self.tab).highlight_style(
+      Style::default()
+        .add_modifier(Modifier::REVERSED)
+        .fg(tailwind::BLUE.c300),
+    );
+    frame.render_widget(tabs, tabs_rect);
+    let mut constraints = vec![Constraint::Min(0)];
+    if self.details_opened {
+      constraints.push(Constraint::Percentage(40));
+    }
+    if self.files_opened {
+      constraints.push(Constraint::Percentage(30));
+    }
+    let layout = Layout::horizontal(constraints).split(main_rect);
+    // the borders and the highlight symbol
+    let width = (layout[0].width as usize).saturating_sub(3);
+    let items = self.visible.iter().map(|id| {
+      let proj = &self.projects[*id];
+      // a single kind keeps the list narrow, the details show them all
+      let kinds = proj.primary_kind().map(|k| k.name()).unwrap_or_default();
+      let name = proj.name().unwrap_or_default();
+      let pin = match self.pins.contains(proj.path()) {
+        true => "★ ",
+        false => "",
+      };
+      let item = format!("{}[{}] {} - ", pin, kinds, name);
+      let path = format!("{}", proj.path().display());
+      let path = truncate_middle(&path, width.saturating_sub(item.chars().count()));
+      format!("{}{}", item, path)
+    });
+    let mut block = Block::bordered().title(tr_fmt("tui.projects", &[&self.visible.len()]));
+    if let Some(proj) = self.selected() {
+      block = block.title_bottom(format!("{}", proj.path().display()));
+    }
+    let list = List::new(items)
+      .block(block)
+      .highlight_style(
+        Style::default()
+          .add_modifier(Modifier::BOLD)
+          .add_modifier(Modifier::REVERSED)
+          .fg(tailwind::BLUE.c300),
+      )
+      .highlight_symbol(">")
+      .highlight_spacing(HighlightSpacing::Always);
+    frame.render_stateful_widget(list, layout[0], &mut self.state);
+    if self.files_opened {
+      self.render_files(frame, layout[layout.len() - 1]);
+    }
+    if let (true, Some(details_rect), Some(proj)) =
+      (self.details_opened, layout.get(1), self.selected())
+    {
+      let details_text = tr_fmt(
+        "tui.details-text",
+        &[
+          &proj
+            .kinds()
+            .iter()
+            .map(|k| k.name())
+            .collect::<Vec<_>>()
+            .join(","),
+          &proj.name().unwrap_or_default(),
+          &proj.path().display(),
+        ],
+      );
+      let mut details_text = match proj.note() {
+        Some(note) => format!(
+          "{}\n\n{}",
+          details_text,
+          tr_fmt("tui.details-note", &[note])
+        ),
+        None => details_text,
+      };
+      let mut facets = proj
+        .facets()
+        .iter()
+        .map(|(name, value)| tr_fmt("tui.details-facet", &[name, value]))
+        .collect::<Vec<_>>();
+      let id = self.visible[self.state.selected().unwrap_or_default()];
+      if let Some(worker) = self.facets.as_ref().filter(|worker| worker.is_pending(id)) {
+        let spinner = format!(
+          "{} {}",
+          SPINNER[self.frames % SPINNER.len()],
+          tr("tui.extracting")
+        );
+        for name in &worker.names {
+          facets.push(tr_fmt("tui.details-facet", &[name, &spinner]));
+        }
+      }
+      if !facets.is_empty() {
+        details_text = format!("{}\n\n{}", details_text, facets.join("\n"));
+      }
+      let details = Paragraph::new(details_text)
+        .wrap(Wrap { trim: false })
+        .block(Block::bordered().title(tr("tui.details")));
+      frame.render_widget(details, *details_rect);
+    }
+    self.render_status(frame, menu_rect);
+  }
+
+  /// Render the file browser, listing the files of the selected project relative to it
+  fn render_files(&mut self, frame: &mut Frame, rect: Rect) {
+    let root = self
+      .selected()
+      .map(|proj| proj.path().clone())
+      .unwrap_or_default();
+    // the borders and the highlight symbol
+    let width = (rect.width as usize).saturating_sub(3);
+    let items = self
+      .files()
+      .iter()
+      .map(|file| {
+        let rel = file.strip_prefix(&root).unwrap_or(file);
+        truncate_middle(&format!("{}", rel.display()), width)
+      })
+      .collect::<Vec<_>>();
+    let list = List::new(items)
+      .block(Block::bordered().title(tr("tui.files")))
+      .highlight_style(
+        Style::default()
+          .add_modifier(Modifier::BOLD)
+          .add_modifier(Modifier::REVERSED)
+          .fg(tailwind::BLUE.c300),
+      )
+      .highlight_symbol(">")
+      .highlight_spacing(HighlightSpacing::Always);
+    frame.render_stateful_widget(list, rect, &mut self.files_state);
+  }
+
+  /// Render the status bar: the query, the matches, the filter and sort order, the cache
+  /// freshness and the key bindings
+  fn render_status(&self, frame: &mut Frame, rect: Rect) {
+    let sort = self
+      .status
+      .sort
+      .to_possible_value()
+      .map(|v| v.get_name().to_string())
+      .unwrap_or_default();
+    let mut parts = vec![
+      tr_fmt("tui.status-query", &[&self.status.query]),
+      tr_fmt(
+        "tui.status-matches",
+        &[&self.visible.len(), &self.status.total],
+      ),
+    ];
+    if self.tab > 0 {
+      parts.push(tr_fmt("tui.status-filter", &[&self.kinds[self.tab - 1]]));
+    }
+    if let (Some(filter), None) = (&self.filter, &self.search) {
+      parts.push(tr_fmt("tui.status-search", &[filter]));
+    }
+    parts.push(tr_fmt("tui.status-sort", &[&sort]));
+    parts.extend(self.status.cache_freshness());
+    let mut status = parts.join(" | ");
+    let mut keys = tr("tui.keys");
+    if let Some(search) = &self.search {
+      status = format!("/{}▏", search.input);
+      keys = tr("tui.search-keys");
+    }
+    // the key bindings give way to the status on narrow terminals
+    let keys_width = match status.chars().count() + keys.chars().count() < rect.width as usize {
+      true => keys.chars().count() as u16,
+      false => 0,
+    };
+    let layout =
+      Layout::horizontal([Constraint::Min(0), Constraint::Length(keys_width)]).split(rect);
+    let status = Paragraph::new(status).style(Style::default().fg(tailwind::SLATE.c400));
+    frame.render_widget(status, layout[0]);
+    frame.render_widget(Paragraph::new(keys).alignment(Alignment::Right), layout[1]);
+  }
 }
 
-impl<'a> Terminal<'a> {
+/// Shorten `path` to `width` characters by replacing its middle with an ellipsis, keeping
+/// its last component (the project name) visible.
+fn truncate_middle(path: &str, width: usize) -> String {
+  let len = path.chars().count();
+  if len <= width {
+    return path.to_string();
+  }
+  let name_start = path
+    .trim_end_matches(std::path::is_separator)
+    .rfind(std::path::is_separator)
+    .unwrap_or(0);
+  let name = &path[name_start..];
+  let name_len = name.chars().count();
+  if name_len + 1 > width {
+    // not even the name fits, keep its end
+    let tail = path
+      .chars()
+      .skip(len + 1 - width.max(1))
+      .collect::<String>();
+    return format!("…{}", tail).chars().take(width).collect();
+  }
+  let head = path.chars().take(width - name_len - 1).collect::<String>();
+  format!("{}…{}", head, name)
+}
+
+impl Terminal {
   /// Create a `Terminal` instance.
   /// This will:
   ///   - Install panic hooks
   ///   - Setup cooked mode
   pub fn new(editor: Option<PathBuf>) -> crate::Result<Self> {
     Self::init_panic_hook();
-    let term = Self::init_tui()?;
+    let term = Self::init_tui(None)?;
     Ok(Self {
       term,
-      projects: vec![],
-      projects_widget: List::new::<Vec<String>>(vec![]),
-      projects_state: ListState::default(),
-      details_opened: false,
-      editor
+      picker: Picker::default(),
+      editor,
+      inline: false,
+      clone: None,
     })
   }
 
-  /// Setup cooked mode
-  /// 
+  /// Create a `Terminal` instance rendered in the normal screen buffer, below the prompt and
+  /// [`Self::INLINE_HEIGHT`] lines high.
+  ///
+  /// Unlike with the alternate screen, the last frame stays in the scrollback once it exits.
+  pub fn inline(editor: Option<PathBuf>) -> crate::Result<Self> {
+    Self::init_panic_hook();
+    let term = Self::init_tui(Some(Self::INLINE_HEIGHT))?;
+    Ok(Self {
+      term,
+      picker: Picker::default(),
+      editor,
+      inline: true,
+      clone: None,
+    })
+  }
+
+  /// The number of lines of the inline interface
+  pub const INLINE_HEIGHT: u16 = 15;
+
+  /// Setup cooked mode, switching to the alternate screen unless an inline `height` is given
+  ///
   /// https://www.gnu.org/software/mit-scheme/documentation/stable/mit-scheme-ref/Terminal-Mode.html
-  fn init_tui() -> crate::Result<RataTerm<CrosstermBackend<Stdout>>> {
+  fn init_tui(height: Option<u16>) -> crate::Result<RataTerm<CrosstermBackend<Stdout>>> {
     let mut stdout = std::io::stdout();
-    enable_raw_mode()
-      .map_err(|e| Error::IO(format!("failed to enable raw mode"), Some(Box::new(e))))?;
-    execute!(stdout, EnterAlternateScreen).map_err(|e| {
-      Error::IO(
-        format!("unable to enter alternate screen"),
-        Some(Box::new(e)),
-      )
-    })?;
-    RataTerm::new(CrosstermBackend::new(stdout))
-      .map_err(|e| Error::IO(format!("failed to create terminal"), Some(Box::new(e))))
+    enable_raw_mode().map_err(|e| Error::io("failed to enable raw mode", e))?;
+    let viewport = match height {
+      Some(height) => Viewport::Inline(height),
+      None => {
+        execute!(stdout, EnterAlternateScreen)
+          .map_err(|e| Error::io("unable to enter alternate screen", e))?;
+        ALTERNATE_SCREEN.store(true, Ordering::SeqCst);
+        Viewport::Fullscreen
+      }
+    };
+    RataTerm::with_options(CrosstermBackend::new(stdout), TerminalOptions { viewport })
+      .map_err(|e| Error::io("failed to create terminal", e))
   }
 
   /// Install a panic hook to restore the terminal to raw mode before printing it.
@@ -76,117 +747,134 @@ impl<'a> Terminal<'a> {
     }));
   }
 
-  /// Render a single terminal frame.
-  /// 
-  /// This will be called in a loop.
-  pub fn render_frame(
-    projects: &Vec<Project>,
-    details_opened: bool,
-    widget: &List,
-    state: &mut ListState,
-    frame: &mut Frame,
-  ) -> crate::Result<()> {
-    let constraints: &[Constraint] = match details_opened {
-      true => &[Constraint::Percentage(20), Constraint::Percentage(80)],
-      false => &[Constraint::Percentage(100)],
+  /// Retrieve the local path of `path`, a project folder or one of its files: a clone of the
+  /// project when it is in a remote folder, made with the interface suspended so that the
+  /// progress of `git` shows
+  fn local_path(&mut self, project: &Path, path: &Path) -> crate::Result<PathBuf> {
+    if !is_remote_path(project) {
+      return Ok(path.to_path_buf());
+    }
+    let Some(clone) = self.clone.clone() else {
+      return Err(Error::config(tr_fmt(
+        "app.no-clone-dir",
+        &[&project.display()],
+      )));
     };
-    let frame_size = frame.size();
-    let main_rect = Rect::new(
-      frame_size.x,
-      frame_size.y,
-      frame_size.width,
-      frame_size.height - 1,
-    );
-    let layout = Layout::horizontal(constraints).split(main_rect);
-    frame.render_stateful_widget(widget, layout[0], state);
-    if constraints.len() == 2 {
-      let proj = &projects[state.selected().unwrap_or_default()];
-      let details_text = format!(
-        "Languages: {}\nName: {}\nPath: {}",
-        proj
-          .kinds()
-          .iter()
-          .map(|k| k.name())
-          .collect::<Vec<_>>()
-          .join(","),
-        proj.name().unwrap_or_default(),
-        proj.path().display()
-      );
-      let details = Paragraph::new(details_text).block(Block::bordered().title("Details"));
-      frame.render_widget(details, layout[1]);
-    }
-    let menu_rect = Rect::new(frame_size.x, frame_size.height - 1, frame_size.width, 1);
-    let menu_layout = Layout::horizontal(&[
-      Constraint::Percentage(25),
-      Constraint::Percentage(25),
-      Constraint::Percentage(25),
-    ])
-    .split(menu_rect);
-    frame.render_widget(Paragraph::new("[Q]uit"), menu_layout[0]);
-    frame.render_widget(Paragraph::new("Toggle details (Return)"), menu_layout[1]);
-    frame.render_widget(Paragraph::new("[O]pen project"), menu_layout[2]);
+    Self::restore_tui()?;
+    let cloned = clone(project);
+    enable_raw_mode().map_err(|e| Error::io("failed to enable raw mode", e))?;
+    if !self.inline {
+      execute!(std::io::stdout(), EnterAlternateScreen)
+        .map_err(|e| Error::io("unable to enter alternate screen", e))?;
+      ALTERNATE_SCREEN.store(true, Ordering::SeqCst);
+    }
+    self.term.clear()?;
+    let relative = path.strip_prefix(project).unwrap_or(Path::new(""));
+    Ok(cloned?.join(relative))
+  }
+
+  /// Open a project folder or file in the editor
+  fn open(&mut self, path: &Path) -> crate::Result<()> {
+    let editor = self
+      .editor
+      .clone()
+      .or_else(|| std::env::var("EDITOR").ok().map(PathBuf::from))
+      .or_else(|| std::env::var("VISUAL").ok().map(PathBuf::from));
+    let editor = match editor {
+      Some(editor) => editor,
+      None => {
+        panic!(
+          "EDITOR or VISUAL environment variable missing, --editor missing please define it first."
+        )
+      }
+    };
+    let cmd = Command::new(&editor)
+      .arg(wsl_editor_path(&editor, path))
+      .spawn()?;
+    let output = cmd.wait_with_output()?;
+    let stdout = String::from_utf8(output.stdout)?;
+    let stderr = String::from_utf8(output.stderr)?;
+    if !output.status.success() {
+      self.write_log(&vec![stdout, stderr].join("\n"), Level::Error)?;
+    }
     Ok(())
   }
 
+  /// Set the context of the matches, shown by the status bar
+  pub fn set_status(&mut self, status: Status) {
+    self.picker.status = status;
+  }
+
+  /// Set the pinned projects, marked in the list and toggled with `p`
+  pub fn set_pins(&mut self, pins: Pins) {
+    self.picker.pins = pins;
+  }
+
+  /// Set how the projects of remote folders are cloned when opened, they cannot be opened
+  /// otherwise
+  pub fn set_clone_remote(&mut self, clone: CloneRemote) {
+    self.clone = Some(clone);
+  }
+
+  /// Set the latest queries, recalled with the arrow keys in the search bar
+  pub fn set_query_history(&mut self, history: QueryHistory) {
+    self.picker.history = history;
+  }
+
+  /// Extract the facets named `names` in the background, only for the projects shown in the
+  /// details pane, reusing the values of `cache`. It is saved once the interface closes if
+  /// `save` is set.
+  pub fn set_lazy_facets(&mut self, names: Vec<String>, cache: FacetCache, save: bool) {
+    self.picker.facets = Some(FacetWorker::spawn(names, cache, save));
+  }
+
+  /// Check whether a `Terminal` currently owns the terminal, in raw mode
+  pub(crate) fn is_active() -> bool {
+    crossterm::terminal::is_raw_mode_enabled().unwrap_or(false)
+  }
+
   /// Retore the terminal to it's raw mode
-  /// 
+  ///
   /// https://www.gnu.org/software/mit-scheme/documentation/stable/mit-scheme-ref/Terminal-Mode.html
-  fn restore_tui(/* term: &mut RataTerm<CrosstermBackend<Stdout>> */) -> crate::Result<()> {
+  pub(crate) fn restore_tui(/* term: &mut RataTerm<CrosstermBackend<Stdout>> */) -> crate::Result<()>
+  {
     let mut stdout = std::io::stdout();
-    disable_raw_mode()
-      .map_err(|e| Error::IO(format!("failed to disable raw mode"), Some(Box::new(e))))?;
-    execute!(stdout, LeaveAlternateScreen).map_err(|e| {
-      Error::IO(
-        format!("failed to switch to main screen"),
-        Some(Box::new(e)),
-      )
-    })?;
+    disable_raw_mode().map_err(|e| Error::io("failed to disable raw mode", e))?;
+    // leaving it from the main screen would restore a stale cursor position
+    if ALTERNATE_SCREEN.swap(false, Ordering::SeqCst) {
+      execute!(stdout, LeaveAlternateScreen)
+        .map_err(|e| Error::io("failed to switch to main screen", e))?;
+    }
     Ok(())
   }
 }
 
 /// Restore the terminal to it's raw mode when dropped
-impl<'a> Drop for Terminal<'a> {
+impl Drop for Terminal {
   fn drop(&mut self) {
     let _ = Self::restore_tui();
+    if self.inline {
+      // leave the last frame in the scrollback, with the prompt below it
+      let area = self.term.get_frame().size();
+      let _ = self.term.set_cursor(0, area.bottom().saturating_sub(1));
+      println!();
+    }
     let _ = self
       .term
       .show_cursor()
-      .map_err(|e| Error::IO(format!("unable to show cursor"), Some(Box::new(e))));
+      .map_err(|e| Error::io("unable to show cursor", e));
   }
 }
 
-impl<'a> UI for Terminal<'a> {
+impl UI for Terminal {
   fn write_matches(
     &mut self,
-    matches: &Vec<crate::Project>,
+    matches: &[crate::Project],
     _fmt: &crate::BoxedProjectMatchesFormatter,
   ) -> crate::Result<()> {
-    self.projects.append(&mut matches.clone());
-    self.projects_widget = List::new(self.projects.iter().map(|proj| {
-      let kinds = proj
-        .kinds()
-        .iter()
-        .map(|k| k.name())
-        .collect::<Vec<_>>()
-        .join(",");
-      let name = proj.name().unwrap_or_default();
-      let path = format!("{}", proj.path().display());
-      format!("[{}] {} - {}", kinds, name, path)
-    }))
-    .block(Block::bordered().title(format!("Projects ({})", self.projects.len())))
-    .highlight_style(
-      Style::default()
-        .add_modifier(Modifier::BOLD)
-        .add_modifier(Modifier::REVERSED)
-        .fg(tailwind::BLUE.c300),
-    )
-    .highlight_symbol(">")
-    .highlight_spacing(HighlightSpacing::Always);
-    self.projects_state = ListState::default().with_selected(match self.projects.is_empty() {
-      true => None,
-      false => Some(0),
-    });
+    let mut projects = std::mem::take(&mut self.picker.projects);
+    projects.extend(matches.iter().cloned());
+    self.picker.set_projects(projects);
     Ok(())
   }
 
@@ -196,56 +884,57 @@ impl<'a> UI for Terminal<'a> {
 
   fn render_loop(&mut self) -> crate::Result<()> {
     loop {
-      self.term.draw(|frame| {
-        Self::render_frame(
-          &self.projects,
-          self.details_opened,
-          &self.projects_widget,
-          &mut self.projects_state,
-          frame,
-        )
-        .unwrap()
-      })?;
-      if event::poll(Duration::from_millis(250))? {
-        if let Event::Key(key) = event::read()? {
-          if KeyCode::Char('q') == key.code {
-            break;
-          } else if KeyCode::Up == key.code {
-            let cur_sel = self.projects_state.selected().unwrap_or_default();
-            if !self.projects.is_empty() && cur_sel > 0 {
-              self.projects_state.select(Some(cur_sel - 1));
-            }
-          } else if KeyCode::Down == key.code {
-            let cur_sel = self.projects_state.selected().unwrap_or_default();
-            if !self.projects.is_empty() && cur_sel < self.projects.len() - 1 {
-              self.projects_state.select(Some(cur_sel + 1));
+      self.picker.update_facets();
+      self.term.draw(|frame| self.picker.render(frame))?;
+      self.picker.frames += 1;
+      let timeout = match self.picker.extracting_facets() {
+        true => Duration::from_millis(80),
+        false => Duration::from_millis(250),
+      };
+      if event::poll(timeout)? {
+        match event::read()? {
+          Event::Key(key) if self.picker.search.is_some() => self.picker.handle_search_key(key),
+          Event::Key(key) if KeyCode::Char('o') == key.code => {
+            if let Some(path) = self.picker.selected().map(|proj| proj.path().clone()) {
+              let path = self.local_path(&path, &path)?;
+              self.open(&path)?;
             }
-          } else if KeyCode::Enter == key.code {
-            self.details_opened = !self.details_opened;
-          } else if KeyCode::Char('o') == key.code {
-            let editor = self.editor.clone()
-                .or_else(|| std::env::var("EDITOR").ok().map(|v| PathBuf::from(v)))
-                .or_else(|| std::env::var("VISUAL").ok().map(|v| PathBuf::from(v)));
-            let editor = match editor {
-              Some(editor) => editor,
-              None => {
-                panic!("EDITOR or VISUAL environment variable missing, --editor missing please define it first.")
-              }
-            };
-            let proj = &self.projects[self.projects_state.selected().unwrap_or_default()];
-            let cmd = Command::new(editor)
-              .arg(format!("{}", proj.path().display()))
-              .spawn()?;
-            let output = cmd.wait_with_output()?;
-            let stdout = String::from_utf8(output.stdout)?;
-            let stderr = String::from_utf8(output.stderr)?;
-            if !output.status.success() {
-                self.write_log(&vec![stdout, stderr].join("\n"), Level::Error)?;
+          }
+          Event::Key(key) if KeyCode::Char('p') == key.code => self.picker.toggle_pin()?,
+          Event::Key(key) if KeyCode::Enter == key.code && self.picker.files_opened => {
+            if let Some(file) = self.picker.selected_file() {
+              let project = self.picker.selected().map(|proj| proj.path().clone());
+              let file = self.local_path(project.as_deref().unwrap_or(&file), &file)?;
+              self.open(&file)?;
             }
           }
+          Event::Key(key) if self.picker.handle_key(key) => break,
+          // the paths are truncated to the new width on the next frame
+          Event::Resize(_, _) => self.term.autoresize()?,
+          _ => {}
         }
       }
     }
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::truncate_middle;
+
+  #[test]
+  fn paths_are_truncated_in_the_middle() {
+    assert_eq!(
+      truncate_middle("/home/dev/project", 20),
+      "/home/dev/project"
+    );
+    assert_eq!(
+      truncate_middle("/home/dev/some/deep/project", 16),
+      "/home/d…/project"
+    );
+    assert_eq!(truncate_middle("/home/dev/project", 8), "…project");
+    assert_eq!(truncate_middle("/home/dev/project", 5), "…ject");
+    assert_eq!(truncate_middle("/home/dev/project", 0), "");
+  }
+}
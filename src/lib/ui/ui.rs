@@ -1,12 +1,15 @@
-
+use std::{
+  path::{Path, PathBuf},
+  sync::Arc,
+};
 
 use crate::{BoxedProjectMatchesFormatter, Project};
 
 pub trait UI {
-  /// Write a string directly to the screen
+  /// Write the matching projects to the screen
   fn write_matches(
     &mut self,
-    matches: &Vec<Project>,
+    matches: &[Project],
     fmt: &BoxedProjectMatchesFormatter,
   ) -> crate::Result<()>;
 
@@ -20,3 +23,7 @@ pub trait UI {
 }
 
 pub type BoxedUI = Box<dyn UI>;
+
+/// Clones the project of a remote folder once it is opened, returning the local clone to open
+/// instead, see [`crate::clone_remote`]
+pub type CloneRemote = Arc<dyn Fn(&Path) -> crate::Result<PathBuf> + Send + Sync>;
@@ -0,0 +1,283 @@
+use std::{
+  io::Read,
+  path::{Path, PathBuf},
+};
+
+use log::debug;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::{
+  i18n::{tr, tr_fmt},
+  Error,
+};
+
+/// The GitHub API endpoint describing the latest release
+pub const LATEST_RELEASE_URL: &str =
+  "https://api.github.com/repos/welschmorgan/pgrep/releases/latest";
+
+/// The base64 minisign public key release binaries are signed with, set at build time through
+/// the `PGREP_RELEASE_PUBKEY` environment variable. Without it, no release is installed.
+pub const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("PGREP_RELEASE_PUBKEY");
+
+/// The maximum size of a downloaded binary
+const MAX_DOWNLOAD_SIZE: u64 = 64 * 1024 * 1024;
+
+/// A release asset, as described by the GitHub API
+#[derive(Debug, Clone, Deserialize)]
+struct Asset {
+  name: String,
+  browser_download_url: String,
+}
+
+/// A published release, as described by the GitHub API
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+  tag_name: String,
+  assets: Vec<Asset>,
+}
+
+impl Release {
+  /// Retrieve the latest published release
+  pub fn latest() -> crate::Result<Self> {
+    let body = get(LATEST_RELEASE_URL)?
+      .into_string()
+      .map_err(|e| Error::io("failed to read release description", e))?;
+    Ok(serde_json::from_str(&body)?)
+  }
+
+  /// Retrieve the release version, from its `v`-prefixed tag
+  pub fn version(&self) -> crate::Result<semver::Version> {
+    semver::Version::parse(self.tag_name.trim_start_matches('v')).map_err(|e| Error::IO {
+      message: format!("invalid release tag '{}'", self.tag_name),
+      source: Some(Box::new(e)),
+    })
+  }
+
+  /// Check whether this release is newer than the running binary
+  pub fn is_newer(&self) -> crate::Result<bool> {
+    Ok(self.version()? > current_version())
+  }
+
+  /// Retrieve the name of the binary asset built for the running platform,
+  /// such as `pgrep-x86_64-linux` or `pgrep-x86_64-windows.exe`
+  pub fn asset_name() -> String {
+    format!(
+      "pgrep-{}-{}{}",
+      std::env::consts::ARCH,
+      std::env::consts::OS,
+      std::env::consts::EXE_SUFFIX
+    )
+  }
+
+  /// Retrieve the download url of the given asset
+  fn asset_url(&self, name: &str) -> Option<&str> {
+    self
+      .assets
+      .iter()
+      .find(|asset| asset.name == name)
+      .map(|asset| asset.browser_download_url.as_str())
+  }
+
+  /// Download the binary built for the running platform, and verify it against the
+  /// `.sha256` checksum asset and the `.minisig` signature one, see [`verify_release`].
+  pub fn download(&self) -> crate::Result<Vec<u8>> {
+    if RELEASE_PUBLIC_KEY.is_none() {
+      return Err(unverified("update.unsigned"));
+    }
+    let name = Self::asset_name();
+    let required = |name: &str| {
+      self.asset_url(name).ok_or_else(|| Error::IO {
+        message: format!("release {} has no asset '{}'", self.tag_name, name),
+        source: None,
+      })
+    };
+    let bin = download(required(&name)?)?;
+    let checksum = download(required(&format!("{}.sha256", name))?)?;
+    let signature = match self.asset_url(&format!("{}.minisig", name)) {
+      Some(url) => Some(String::from_utf8(download(url)?)?),
+      None => None,
+    };
+    verify_release(
+      &bin,
+      &String::from_utf8(checksum)?,
+      signature.as_deref(),
+      RELEASE_PUBLIC_KEY,
+    )?;
+    Ok(bin)
+  }
+}
+
+/// Retrieve the version of the running binary
+pub fn current_version() -> semver::Version {
+  semver::Version::parse(env!("CARGO_PKG_VERSION")).expect("invalid package version")
+}
+
+/// Send a GET request to the GitHub API or its download servers
+fn get(url: &str) -> crate::Result<ureq::Response> {
+  debug!("GET {}", url);
+  ureq::get(url)
+    .set(
+      "User-Agent",
+      concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")),
+    )
+    .call()
+    .map_err(|e| Error::IO {
+      message: format!("request to '{}' failed", url),
+      source: Some(Box::new(e)),
+    })
+}
+
+/// Download the whole content at `url`
+fn download(url: &str) -> crate::Result<Vec<u8>> {
+  let mut content = vec![];
+  get(url)?
+    .into_reader()
+    .take(MAX_DOWNLOAD_SIZE)
+    .read_to_end(&mut content)
+    .map_err(|e| Error::io(format!("failed to download '{}'", url), e))?;
+  Ok(content)
+}
+
+/// Verify `bin` against a `sha256sum`-style checksum line
+///
+/// # Examples
+///
+/// ```
+/// use pgrep::update::verify_checksum;
+///
+/// let sum = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  pgrep";
+/// assert!(verify_checksum(b"hello", sum).is_ok());
+/// assert!(verify_checksum(b"hello!", sum).is_err());
+/// ```
+pub fn verify_checksum(bin: &[u8], checksum: &str) -> crate::Result<()> {
+  let expected = checksum.split_whitespace().next().unwrap_or_default();
+  let actual = Sha256::digest(bin)
+    .iter()
+    .map(|b| format!("{:02x}", b))
+    .collect::<String>();
+  match actual.eq_ignore_ascii_case(expected) {
+    true => Ok(()),
+    false => Err(Error::IO {
+      message: format!(
+        "checksum mismatch, expected {} but got {}",
+        expected, actual
+      ),
+      source: None,
+    }),
+  }
+}
+
+/// Verify the minisign `signature` of `bin` with the base64 `public_key`
+pub fn verify_signature(bin: &[u8], signature: &str, public_key: &str) -> crate::Result<()> {
+  let invalid = |e: minisign_verify::Error| Error::IO {
+    message: "invalid release signature".to_string(),
+    source: Some(Box::new(e)),
+  };
+  let key = minisign_verify::PublicKey::from_base64(public_key).map_err(invalid)?;
+  let signature = minisign_verify::Signature::decode(signature).map_err(invalid)?;
+  key.verify(bin, &signature, false).map_err(invalid)
+}
+
+/// Verify the downloaded `bin` against its `sha256sum`-style `checksum` and its minisign
+/// `signature` with the base64 `public_key`, refusing it when the signature or the key is missing
+pub fn verify_release(
+  bin: &[u8],
+  checksum: &str,
+  signature: Option<&str>,
+  public_key: Option<&str>,
+) -> crate::Result<()> {
+  verify_checksum(bin, checksum)?;
+  let public_key = public_key.ok_or_else(|| unverified("update.unsigned"))?;
+  let signature = signature.ok_or_else(|| unverified("update.unsigned-release"))?;
+  verify_signature(bin, signature, public_key)
+}
+
+/// The error refusing to install a binary which cannot be verified
+fn unverified(key: &'static str) -> Error {
+  Error::IO {
+    message: tr(key).to_string(),
+    source: None,
+  }
+}
+
+/// Replace the running executable by `bin`, returning its path.
+///
+/// The new binary is written next to the current one then renamed over it, so that a failure
+/// never leaves a truncated executable behind. On windows, the running executable cannot be
+/// overwritten but can be renamed, so it is moved to a `.old` file first.
+pub fn replace_current_exe(bin: &[u8]) -> crate::Result<PathBuf> {
+  let current = std::env::current_exe()?.canonicalize()?;
+  let staged = sibling(&current, "new");
+  std::fs::write(&staged, bin).map_err(|e| Error::io("failed to write new executable", e))?;
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = std::fs::metadata(&current)?.permissions().mode();
+    std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(mode))?;
+  }
+  #[cfg(windows)]
+  {
+    let old = sibling(&current, "old");
+    let _ = std::fs::remove_file(&old);
+    std::fs::rename(&current, &old)
+      .map_err(|e| Error::io("failed to move the current executable", e))?;
+  }
+  std::fs::rename(&staged, &current)
+    .map_err(|e| Error::io("failed to replace the current executable", e))?;
+  Ok(current)
+}
+
+/// Build the path of a file next to `path`, suffixed with `ext`
+fn sibling(path: &Path, ext: &str) -> PathBuf {
+  let mut name = path.file_name().unwrap_or_default().to_os_string();
+  name.push(".");
+  name.push(ext);
+  path.with_file_name(name)
+}
+
+/// Check the latest release and install it if it's newer than the running binary
+pub fn self_update(check_only: bool, force: bool) -> crate::Result<()> {
+  let release = Release::latest()?;
+  let version = release.version()?;
+  if !force && !release.is_newer()? {
+    println!("{}", tr_fmt("update.up-to-date", &[&current_version()]));
+    return Ok(());
+  }
+  if check_only {
+    println!(
+      "{}",
+      tr_fmt("update.available", &[&version, &current_version()])
+    );
+    return Ok(());
+  }
+  let bin = release.download()?;
+  let path = replace_current_exe(&bin)?;
+  println!("{}", tr_fmt("update.done", &[&path.display(), &version]));
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::verify_release;
+
+  /// The key and signature of `test` published in the minisign-verify documentation
+  const PUBLIC_KEY: &str = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+  const SIGNATURE: &str = "untrusted comment: signature from minisign secret key\n\
+    RUQf6LRCGA9i559r3g7V1qNyJDApGip8MfqcadIgT9CuhV3EMhHoN1mGTkUidF/\
+    z7SrlQgXdy8ofjb7bNJJylDOocrCo8KLzZwo=\n\
+    trusted comment: timestamp:1633700835\tfile:test\tprehashed\n\
+    wLMDjy9FLAuxZ3q4NlEvkgtyhrr0gtTu6KC4KBJdITbbOeAi1zBIYo0v4iTgt8jJpIidRJnp94ABQkJAgAooBQ==";
+
+  #[test]
+  fn only_signed_releases_are_installed() {
+    let checksum = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08  pgrep";
+    assert!(verify_release(b"test", checksum, Some(SIGNATURE), Some(PUBLIC_KEY)).is_ok());
+    // unsigned releases, and builds without a public key
+    assert!(verify_release(b"test", checksum, None, Some(PUBLIC_KEY)).is_err());
+    assert!(verify_release(b"test", checksum, Some(SIGNATURE), None).is_err());
+    // tampered binaries, even with a matching checksum
+    let checksum = "db60c4e56ed9727f556307e104a2a47b33b9aaed36e87ac5090b5dfc1bf914f2  pgrep";
+    assert!(verify_release(b"tesT", checksum, Some(SIGNATURE), Some(PUBLIC_KEY)).is_err());
+  }
+}
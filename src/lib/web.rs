@@ -0,0 +1,261 @@
+//! The web interface served by `pgrep watch --serve`, browsing the projects of the latest scan.
+//!
+//! The page is the HTML report of every project, whose filter queries the index through
+//! `/api/matches?q=<query>` with the pgrep query syntax.
+
+use std::{
+  io::{BufRead, BufReader, ErrorKind, Read, Write},
+  net::{IpAddr, SocketAddr, TcpListener, TcpStream},
+  str::FromStr,
+  sync::{Arc, RwLock},
+  thread::JoinHandle,
+  time::Duration,
+};
+
+use log::{debug, info};
+
+use crate::{i18n::tr_fmt, normalize_path, App, Error, MatchMode, OutputFormat, Project, Query};
+
+/// The projects shown by the web interface, replaced after every scan
+pub type SharedProjects = Arc<RwLock<Vec<Project>>>;
+
+/// The route answering the queries of the page
+const API_ROUTE: &str = "/api/matches";
+
+/// The longest request or header line read, in bytes
+const MAX_LINE: u64 = 8 * 1024;
+
+/// The most header lines read from a request
+const MAX_HEADERS: usize = 100;
+
+/// How long a connection may take to send its request or read the response, so that a stalled
+/// client doesn't hold the connections queued behind it
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Serve the web interface on `address` from a background thread
+pub fn serve(address: SocketAddr, projects: SharedProjects) -> crate::Result<JoinHandle<()>> {
+  let listener =
+    TcpListener::bind(address).map_err(|e| Error::io(tr_fmt("web.bind-failed", &[&address]), e))?;
+  info!("{}", tr_fmt("web.serving", &[&address]));
+  Ok(std::thread::spawn(move || {
+    for stream in listener.incoming() {
+      let served = stream
+        .map_err(|e| Error::io("failed to accept a connection", e))
+        .and_then(|stream| handle(stream, &projects));
+      if let Err(e) = served {
+        debug!("{}", e);
+      }
+    }
+  }))
+}
+
+/// Answer a single request, then close the connection
+fn handle(mut stream: TcpStream, projects: &SharedProjects) -> crate::Result<()> {
+  stream.set_read_timeout(Some(TIMEOUT))?;
+  stream.set_write_timeout(Some(TIMEOUT))?;
+  let mut reader = BufReader::new(&stream);
+  let line = read_line(&mut reader)?;
+  let mut host = None;
+  for _ in 0..MAX_HEADERS {
+    let header = read_line(&mut reader)?;
+    match header.split_once(':') {
+      Some((name, value)) if name.eq_ignore_ascii_case("host") => {
+        host = Some(value.trim().to_string())
+      }
+      Some(_) => {}
+      None => break,
+    }
+  }
+  let mut parts = line.split_whitespace();
+  let (method, target) = (parts.next(), parts.next().unwrap_or("/"));
+  let (route, params) = target.split_once('?').unwrap_or((target, ""));
+  let projects = projects.read().unwrap();
+  let (status, content_type, body) = match (method, route) {
+    // pages of other sites resolving their own names to this machine are refused
+    _ if !host.as_deref().is_some_and(is_allowed_host) => (
+      "403 Forbidden",
+      "text/plain; charset=utf-8",
+      b"forbidden".to_vec(),
+    ),
+    (Some("GET"), "/") => ("200 OK", "text/html; charset=utf-8", page(&projects)?),
+    (Some("GET"), API_ROUTE) => match matches(&projects, &param(params, "q")) {
+      Ok(body) => ("200 OK", "application/json", body),
+      Err(e) => (
+        "400 Bad Request",
+        "text/plain; charset=utf-8",
+        e.to_string().into_bytes(),
+      ),
+    },
+    _ => (
+      "404 Not Found",
+      "text/plain; charset=utf-8",
+      b"not found".to_vec(),
+    ),
+  };
+  write!(
+    stream,
+    "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+    status,
+    content_type,
+    body.len()
+  )?;
+  stream.write_all(&body)?;
+  Ok(())
+}
+
+/// Read a line of the request, failing when it is longer than [`MAX_LINE`]
+fn read_line<R: BufRead>(reader: &mut R) -> std::io::Result<String> {
+  let mut line = String::new();
+  reader.take(MAX_LINE).read_line(&mut line)?;
+  if line.len() as u64 == MAX_LINE && !line.ends_with('\n') {
+    return Err(std::io::Error::new(
+      ErrorKind::InvalidData,
+      "request line too long",
+    ));
+  }
+  Ok(line)
+}
+
+/// Check the `Host` header of a request, which must be `localhost` or an IP address, with an
+/// optional port. Domain names are refused as DNS rebinding would let the pages of other sites
+/// query the interface through them.
+fn is_allowed_host(host: &str) -> bool {
+  let name = match host.strip_prefix('[') {
+    Some(rest) => rest.split_once(']').map_or(rest, |(name, _)| name),
+    None => host.split_once(':').map_or(host, |(name, _)| name),
+  };
+  name.eq_ignore_ascii_case("localhost") || name.parse::<IpAddr>().is_ok()
+}
+
+/// Render the HTML report of every project, pointing its filter to the API
+fn page(projects: &[Project]) -> crate::Result<Vec<u8>> {
+  let mut body = vec![];
+  OutputFormat::Html
+    .formatter()?
+    .write(&mut body, &mut projects.iter())?;
+  let html = String::from_utf8_lossy(&body).replacen(
+    "<body>",
+    &format!("<body data-api=\"{}\">", API_ROUTE),
+    1,
+  );
+  Ok(html.into_bytes())
+}
+
+/// List the paths of the projects matching `query` as JSON, see [`App::match_projects`]. They
+/// are written as [`normalize_path`] does, lossily when they aren't valid UTF-8.
+fn matches(projects: &[Project], query: &str) -> crate::Result<Vec<u8>> {
+  let projects = projects.iter().collect::<Vec<_>>();
+  let paths = match query.is_empty() {
+    true => projects
+      .iter()
+      .map(|project| normalize_path(project.path()))
+      .collect::<Vec<_>>(),
+    false => {
+      let query = Query::from_str(query)?.with_mode(MatchMode::Partial);
+      App::match_projects(&query, &projects)
+        .iter()
+        .map(|project| normalize_path(project.path()))
+        .collect()
+    }
+  };
+  Ok(serde_json::to_vec(&paths)?)
+}
+
+/// Find the percent-decoded value of `name` in a query string
+fn param(params: &str, name: &str) -> String {
+  params
+    .split('&')
+    .filter_map(|param| param.split_once('='))
+    .find(|(key, _)| *key == name)
+    .map(|(_, value)| decode_component(value))
+    .unwrap_or_default()
+}
+
+/// Decode a `application/x-www-form-urlencoded` component
+fn decode_component(value: &str) -> String {
+  let mut bytes = vec![];
+  let mut chars = value.bytes();
+  while let Some(byte) = chars.next() {
+    match byte {
+      b'+' => bytes.push(b' '),
+      b'%' => {
+        let hex = [chars.next(), chars.next()];
+        let decoded = match hex {
+          [Some(hi), Some(lo)] => std::str::from_utf8(&[hi, lo])
+            .ok()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+          _ => None,
+        };
+        bytes.push(decoded.unwrap_or(b'%'));
+      }
+      byte => bytes.push(byte),
+    }
+  }
+  String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{decode_component, is_allowed_host, matches, page, param, read_line, MAX_LINE};
+  use crate::Project;
+
+  #[test]
+  fn query_parameters_are_decoded() {
+    assert_eq!(decode_component("my+api%2A"), "my api*");
+    assert_eq!(decode_component("%C3%A9t%C3%A9"), "été");
+    assert_eq!(param("a=1&q=pg%5Bre%5Dp", "q"), "pg[re]p");
+    assert_eq!(param("a=1", "q"), "");
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn non_utf8_paths_are_listed() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let path = OsStr::from_bytes(b"/dev/caf\xe9");
+    let projects = [Project::new(path, vec![], vec![], vec![])];
+    let paths: Vec<String> = serde_json::from_slice(&matches(&projects, "").unwrap()).unwrap();
+    assert_eq!(paths, ["/dev/caf\u{fffd}"]);
+  }
+
+  #[test]
+  fn matches_are_the_paths_of_the_page_rows() {
+    let projects = [Project::new(r"\\?\C:\dev\api", vec![], vec![], vec![])];
+    let paths: Vec<String> = serde_json::from_slice(&matches(&projects, "api").unwrap()).unwrap();
+    let page = String::from_utf8(page(&projects).unwrap()).unwrap();
+    assert_eq!(paths.len(), 1);
+    assert!(
+      page.contains(&format!("<tr path=\"{}\"", paths[0])),
+      "{}",
+      paths[0]
+    );
+  }
+
+  #[test]
+  fn only_local_or_ip_hosts_are_allowed() {
+    for host in [
+      "localhost:8080",
+      "127.0.0.1:8080",
+      "192.168.1.20",
+      "[::1]:8080",
+    ] {
+      assert!(is_allowed_host(host), "{}", host);
+    }
+    for host in ["evil.example:8080", "localhost.evil.example", ""] {
+      assert!(!is_allowed_host(host), "{}", host);
+    }
+  }
+
+  #[test]
+  fn long_request_lines_are_refused() {
+    let request = format!(
+      "GET /{} HTTP/1.1\r\nHost: localhost\r\n",
+      "a".repeat(MAX_LINE as usize)
+    );
+    let mut reader = std::io::Cursor::new(request);
+    assert!(read_line(&mut reader).is_err());
+    let mut reader = std::io::Cursor::new("GET / HTTP/1.1\r\nHost: localhost\r\n");
+    assert_eq!(read_line(&mut reader).unwrap(), "GET / HTTP/1.1\r\n");
+    assert_eq!(read_line(&mut reader).unwrap(), "Host: localhost\r\n");
+  }
+}
@@ -0,0 +1,9 @@
+# pgrep fzf widget: Ctrl-G picks a project with fzf and changes to its folder.
+# Install with: eval "$(pgrep gen fzf-widget bash)"
+__pgrep_fzf_widget() {
+  local dir
+  dir=$(pgrep --emit-candidates --list 2>/dev/null \
+    | fzf --delimiter '\t' --with-nth 1,3,2 --tiebreak index --preview 'ls {2}' \
+    | cut -f2) && cd -- "$dir"
+}
+bind -x '"\C-g": __pgrep_fzf_widget'
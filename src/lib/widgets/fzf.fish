@@ -0,0 +1,10 @@
+# pgrep fzf widget: Ctrl-G picks a project with fzf and changes to its folder.
+# Install with: pgrep gen fzf-widget fish | source
+function __pgrep_fzf_widget
+  set -l dir (pgrep --emit-candidates --list 2>/dev/null \
+    | fzf --delimiter \t --with-nth 1,3,2 --tiebreak index --preview 'ls {2}' \
+    | cut -f2)
+  and cd -- $dir
+  commandline -f repaint
+end
+bind \cg __pgrep_fzf_widget
@@ -0,0 +1,117 @@
+//! Helpers shared by the integration tests and the benchmarks.
+#![allow(dead_code)]
+
+use std::{
+  path::{Path, PathBuf},
+  sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The shape of a [`SyntheticTree`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeSpec {
+  /// The number of sub-directories created in each non-leaf directory
+  pub dirs: usize,
+  /// The number of files created in each directory
+  pub files: usize,
+  /// The number of directory levels below the root
+  pub depth: usize,
+}
+
+impl TreeSpec {
+  /// Create a new tree specification
+  pub const fn new(dirs: usize, files: usize, depth: usize) -> Self {
+    Self { dirs, files, depth }
+  }
+}
+
+/// The project flavours created in leaf directories, in rotation:
+/// `(manifest, source extension)`
+const LEAF_KINDS: [(&str, &str); 3] = [
+  ("Cargo.toml", "rs"),
+  ("package.json", "js"),
+  ("go.mod", "go"),
+];
+
+/// A generated folder hierarchy living in the temporary directory, removed when dropped.
+///
+/// Every leaf directory is a project holding a manifest and source files,
+/// intermediate directories only hold plain text files.
+pub struct SyntheticTree {
+  root: PathBuf,
+  spec: TreeSpec,
+  projects: usize,
+  files: usize,
+}
+
+impl SyntheticTree {
+  /// Generate a new tree matching `spec`
+  pub fn generate(spec: TreeSpec) -> std::io::Result<Self> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let root = std::env::temp_dir().join(format!(
+      "pgrep-synthetic-{}-{}",
+      std::process::id(),
+      COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    if root.exists() {
+      std::fs::remove_dir_all(&root)?;
+    }
+    let mut ret = Self {
+      root,
+      spec,
+      projects: 0,
+      files: 0,
+    };
+    let root = ret.root.clone();
+    ret.populate(&root, 0)?;
+    Ok(ret)
+  }
+
+  fn populate(&mut self, dir: &Path, level: usize) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    if level == self.spec.depth {
+      let (manifest, ext) = LEAF_KINDS[self.projects % LEAF_KINDS.len()];
+      std::fs::write(dir.join(manifest), "")?;
+      for i in 0..self.spec.files {
+        std::fs::write(dir.join(format!("source_{:04}.{}", i, ext)), "")?;
+      }
+      self.projects += 1;
+      self.files += self.spec.files + 1;
+      return Ok(());
+    }
+    for i in 0..self.spec.files {
+      std::fs::write(dir.join(format!("notes_{:04}.txt", i)), "")?;
+    }
+    self.files += self.spec.files;
+    for i in 0..self.spec.dirs {
+      // fixed-width names so that no directory name is a prefix of another
+      self.populate(&dir.join(format!("dir_{:04}", i)), level + 1)?;
+    }
+    Ok(())
+  }
+
+  /// Retrieve the tree root folder
+  pub fn root(&self) -> &Path {
+    &self.root
+  }
+
+  /// Retrieve the specification used to generate this tree
+  pub fn spec(&self) -> TreeSpec {
+    self.spec
+  }
+
+  /// Retrieve the number of generated projects
+  pub fn project_count(&self) -> usize {
+    self.projects
+  }
+
+  /// Retrieve the total number of generated files
+  pub fn file_count(&self) -> usize {
+    self.files
+  }
+}
+
+impl Drop for SyntheticTree {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_dir_all(&self.root);
+  }
+}
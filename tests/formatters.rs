@@ -0,0 +1,205 @@
+//! Golden-file tests for the output formatters.
+//!
+//! Each formatter renders the same fixed set of projects and its output is compared with
+//! `tests/golden/<format>.golden`. Run with `UPDATE_GOLDEN=1` to regenerate the files after an
+//! intentional output change, then review the diff.
+
+use std::path::{Path, PathBuf};
+
+use strum::IntoEnumIterator;
+
+use pgrep::{
+  style, BoxedProjectMatchesFormatter, ColorChoice, OutputFormat, Project, ProjectKind, Table,
+};
+
+/// The projects rendered by every formatter, covering escaping edge cases
+fn fixtures() -> Vec<Project> {
+  vec![
+    Project::new(
+      "/dev/alpha",
+      vec![ProjectKind::Rust],
+      vec![PathBuf::from("/dev/alpha/src/main.rs")],
+      vec![PathBuf::from("/dev/alpha/Cargo.toml")],
+    ),
+    Project::new(
+      "/dev/web/beta-app",
+      vec![ProjectKind::Node, ProjectKind::Go],
+      vec![],
+      vec![
+        PathBuf::from("/dev/web/beta-app/package.json"),
+        PathBuf::from("/dev/web/beta-app/go.mod"),
+      ],
+    ),
+    Project::new(
+      "/dev/odd/say \"hi\", <all> & 'bye'",
+      vec![ProjectKind::Custom {
+        name: "A|B".to_string(),
+        language_exts: vec!["ab".to_string()],
+        project_files: vec!["ab.toml".to_string()],
+      }],
+      vec![],
+      vec![],
+    ),
+    Project::new(
+      "/dev/unicode/prøjekt-ü",
+      vec![ProjectKind::Other],
+      vec![],
+      vec![],
+    ),
+  ]
+}
+
+fn golden_path(name: &str) -> PathBuf {
+  Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("tests")
+    .join("golden")
+    .join(format!("{}.golden", name))
+}
+
+/// The table rendered by every formatter, covering lists, empty cells and escaping
+fn table_fixture() -> Table {
+  let mut table = Table::new("Kinds", &["Name", "Project files", "Extensions"]);
+  table.push_row(vec![
+    "Rust".into(),
+    vec!["Cargo.toml".to_string(), "Cargo.lock".to_string()].into(),
+    vec!["rs".to_string()].into(),
+  ]);
+  table.push_row(vec!["Other".into(), vec![].into(), vec![].into()]);
+  table.push_row(vec![
+    "A|B <\"x\">".into(),
+    vec!["ab.toml".to_string()].into(),
+    vec!["ab".to_string()].into(),
+  ]);
+  table
+}
+
+fn assert_golden(format: OutputFormat) {
+  assert_golden_output(format, &format.to_string(), |fmt, out| {
+    fmt.write(out, &mut fixtures().iter())
+  });
+  assert_golden_output(format, &format!("{}.table", format), |fmt, out| {
+    fmt.write_table(out, &table_fixture())
+  });
+}
+
+fn assert_golden_output<F>(format: OutputFormat, name: &str, render: F)
+where
+  F: Fn(&BoxedProjectMatchesFormatter, &mut dyn std::io::Write) -> pgrep::Result<()>,
+{
+  // golden files never hold styles, whatever the test runner's stdout is
+  style::set_color_choice(ColorChoice::Never);
+  let mut out = vec![];
+  render(&format.formatter().unwrap(), &mut out).unwrap();
+  let actual = String::from_utf8(out).unwrap();
+  let path = golden_path(name);
+  if std::env::var_os("UPDATE_GOLDEN").is_some() {
+    std::fs::write(&path, &actual).unwrap();
+    return;
+  }
+  let expected = std::fs::read_to_string(&path)
+    .unwrap_or_else(|e| panic!("cannot read '{}': {}", path.display(), e));
+  assert_eq!(
+    actual,
+    expected,
+    "\n{} output differs from '{}', rerun with UPDATE_GOLDEN=1 if this is intended",
+    name,
+    path.display()
+  );
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn text() {
+  assert_golden(OutputFormat::Text)
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json() {
+  assert_golden(OutputFormat::Json)
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn csv() {
+  assert_golden(OutputFormat::Csv)
+}
+
+#[cfg(feature = "xml")]
+#[test]
+fn xml() {
+  assert_golden(OutputFormat::Xml)
+}
+
+#[cfg(feature = "html")]
+#[test]
+fn html() {
+  assert_golden(OutputFormat::Html)
+}
+
+#[cfg(feature = "markdown")]
+#[test]
+fn markdown() {
+  assert_golden(OutputFormat::Markdown)
+}
+
+#[test]
+fn unavailable_formats_are_reported() {
+  for format in OutputFormat::iter() {
+    match format.formatter() {
+      Ok(_) => assert!(format.is_available(), "{} has a formatter", format),
+      Err(e) => {
+        assert!(!format.is_available(), "{} has no formatter", format);
+        assert!(e
+          .to_string()
+          .contains(&format!("--features {}", format.feature())));
+      }
+    }
+  }
+}
+
+#[test]
+fn extensions_map_back_to_formats() {
+  for format in OutputFormat::iter() {
+    for ext in format.extensions() {
+      assert_eq!(OutputFormat::from_extension(ext), Some(format));
+    }
+    let path = PathBuf::from(format!("report.{}", format.extensions()[0]));
+    assert_eq!(OutputFormat::from_path(path), Some(format));
+  }
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn disk_usage_is_reported_when_computed() {
+  let mut projects = fixtures();
+  projects[0].set_disk_usage(Some(1536));
+  let mut out = vec![];
+  let formatter = OutputFormat::Csv.formatter().unwrap();
+  formatter.write(&mut out, &mut projects.iter()).unwrap();
+  let out = String::from_utf8(out).unwrap();
+  let mut lines = out.lines();
+  assert!(lines.next().unwrap().ends_with(",\"Disk usage\""));
+  assert!(lines.next().unwrap().ends_with(",\"1.5 KiB\""));
+  assert!(lines.next().unwrap().ends_with(",\"\""));
+}
+
+#[cfg(feature = "markdown")]
+#[test]
+fn toolchains_are_reported_when_detected() {
+  use pgrep::FacetValue;
+
+  let mut projects = fixtures();
+  let toolchains = [("node", "v18.17.0"), ("go", "1.21")];
+  let toolchains = toolchains.map(|(tool, version)| (tool.to_string(), version.to_string()));
+  projects[1].set_facet(
+    "toolchain",
+    Some(FacetValue::Map(toolchains.into_iter().collect())),
+  );
+  let mut out = vec![];
+  let formatter = OutputFormat::Markdown.formatter().unwrap();
+  formatter.write(&mut out, &mut projects.iter()).unwrap();
+  let out = String::from_utf8(out).unwrap();
+  assert!(out.lines().nth(2).unwrap().contains("| toolchain"));
+  assert!(out.contains("go 1.21, node v18.17.0"));
+}
@@ -0,0 +1,36 @@
+//! The documentation generated by `pgrep gen`.
+
+use pgrep::{App, GenTarget, WidgetShell};
+
+fn generate(target: GenTarget) -> String {
+  let mut out = vec![];
+  App::generate(target, &mut out).unwrap();
+  String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn man_page() {
+  let man = generate(GenTarget::Man);
+  assert!(man.contains(".TH pgrep 1"));
+  assert!(man.contains("\\-\\-match\\-path"));
+}
+
+#[test]
+fn markdown_help() {
+  let help = generate(GenTarget::MarkdownHelp);
+  assert!(help.starts_with("# Command-Line Help for `pgrep`"));
+  assert!(help.contains("`pgrep gen man`"));
+  assert!(help.contains("--match-path"));
+}
+
+#[test]
+fn fzf_widgets() {
+  for shell in [WidgetShell::Bash, WidgetShell::Zsh, WidgetShell::Fish] {
+    let widget = generate(GenTarget::FzfWidget { shell });
+    assert!(
+      widget.contains("pgrep --emit-candidates --list"),
+      "{:?}",
+      shell
+    );
+  }
+}
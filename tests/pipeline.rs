@@ -0,0 +1,531 @@
+mod common;
+
+use std::{
+  path::{Path, PathBuf},
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+  },
+};
+
+use common::{SyntheticTree, TreeSpec};
+use pgrep::{
+  detect_projects, App, Cache, CancelToken, DetectionStats, Error, FolderScan, MatchMode, Project,
+  ProjectKind, Query, ScanDetail, ScanLimit, ScanObserver, ScanOptions,
+};
+
+#[test]
+fn scan_finds_every_file() {
+  let tree = SyntheticTree::generate(TreeSpec::new(3, 4, 3)).unwrap();
+  let scan = FolderScan::new(tree.root()).unwrap();
+  assert_eq!(scan.file_count(), tree.file_count());
+  assert!(scan.files().all(|file| file.is_file()));
+}
+
+#[test]
+fn scan_honors_limits() {
+  let tree = SyntheticTree::generate(TreeSpec::new(3, 4, 2)).unwrap();
+  let options = ScanOptions {
+    max_scan_files: Some(10),
+    ..Default::default()
+  };
+  let scan = FolderScan::with_options(tree.root(), &options).unwrap();
+  assert_eq!(scan.file_count(), 10);
+  assert!(scan.is_truncated());
+  // the root holds 3 directories and 4 files
+  let options = ScanOptions {
+    max_files_per_dir: Some(6),
+    ..Default::default()
+  };
+  let scan = FolderScan::with_options(tree.root(), &options).unwrap();
+  assert_eq!(scan.file_count(), 0);
+  assert_eq!(
+    scan.skipped(),
+    &vec![(tree.root().to_path_buf(), ScanLimit::MaxFilesPerDir)]
+  );
+}
+
+#[test]
+fn scan_honors_depth_and_links() {
+  // 3 files at the root, 3 in each of its 2 directories, then 2 projects of 4 files in each
+  let tree = SyntheticTree::generate(TreeSpec::new(2, 3, 2)).unwrap();
+  let options = ScanOptions::builder().max_depth(Some(1)).build();
+  let scan = FolderScan::with_options(tree.root(), &options).unwrap();
+  assert_eq!(scan.file_count(), 9);
+  assert!(detect_projects(&scan, vec![]).is_empty());
+  #[cfg(unix)]
+  {
+    std::os::unix::fs::symlink(tree.root(), tree.root().join("loop")).unwrap();
+    let options = ScanOptions::builder().follow_symlinks(true).build();
+    let scan = FolderScan::with_options(tree.root(), &options).unwrap();
+    assert_eq!(scan.file_count(), tree.file_count());
+  }
+}
+
+/// Counts the files a scan reports
+#[derive(Default)]
+struct FileCounter(AtomicUsize);
+
+impl ScanObserver for FileCounter {
+  fn on_file(&self, _file: &Path) {
+    self.0.fetch_add(1, Ordering::SeqCst);
+  }
+}
+
+#[test]
+fn scan_reports_every_file() {
+  let tree = SyntheticTree::generate(TreeSpec::new(2, 3, 2)).unwrap();
+  let files = Arc::new(FileCounter::default());
+  let options = ScanOptions::builder().observer(files.clone()).build();
+  let scan = FolderScan::with_options(tree.root(), &options).unwrap();
+  assert_eq!(files.0.load(Ordering::SeqCst), scan.file_count());
+  assert_eq!(scan.file_count(), tree.file_count());
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn scans_are_traced() {
+  let tree = SyntheticTree::generate(TreeSpec::new(2, 2, 2)).unwrap();
+  let path = tree.root().join("trace.json");
+  let trace = pgrep::trace::ChromeTrace::create(&path).unwrap();
+  tracing::subscriber::with_default(trace, || {
+    let scan = FolderScan::new(tree.root()).unwrap();
+    detect_projects(&scan, vec![]);
+  });
+  let trace = std::fs::read_to_string(&path).unwrap();
+  for event in [r#""name":"scan","ph":"B""#, r#""name":"detect","ph":"E""#] {
+    assert!(trace.contains(event), "{} not in {}", event, trace);
+  }
+}
+
+#[test]
+fn cancelled_scans_are_interrupted() {
+  let tree = SyntheticTree::generate(TreeSpec::new(2, 2, 2)).unwrap();
+  let cancel = CancelToken::default();
+  let options = ScanOptions {
+    cancel: cancel.clone(),
+    ..Default::default()
+  };
+  cancel.cancel();
+  let e = FolderScan::with_options(tree.root(), &options).unwrap_err();
+  assert!(matches!(e, Error::Interrupted(..)));
+  assert_eq!(e.code(), 130);
+}
+
+#[test]
+fn unreadable_folders_are_scan_errors() {
+  let tree = SyntheticTree::generate(TreeSpec::new(2, 1, 1)).unwrap();
+  let missing = tree.root().join("missing");
+  let e = FolderScan::new(&missing).unwrap_err();
+  assert!(
+    matches!(&e, Error::Scan { path, .. } if *path == missing),
+    "{:?}",
+    e
+  );
+  assert_eq!(e.code(), 4);
+  assert!(e.to_string().contains("missing"));
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    let locked = tree.root().join("dir_0000");
+    std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+    // root reads every folder whatever its permissions
+    if std::fs::read_dir(&locked).is_err() {
+      let scan = FolderScan::new(tree.root()).unwrap();
+      assert!(scan.files().all(|file| !file.starts_with(&locked)));
+      let e = FolderScan::new(&locked).unwrap_err();
+      assert!(
+        matches!(&e, Error::Scan { path, .. } if *path == locked),
+        "{:?}",
+        e
+      );
+    }
+    std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+  }
+}
+
+#[test]
+fn scans_stop_at_the_first_accepted_project() {
+  let tree = SyntheticTree::generate(TreeSpec::new(5, 2, 2)).unwrap();
+  let options = ScanOptions::builder()
+    .stop_at(|project| project.kinds().contains(&ProjectKind::Go))
+    .build();
+  let scan = FolderScan::with_options(tree.root(), &options).unwrap();
+  assert!(scan.is_stopped());
+  assert!(scan.file_count() < tree.file_count());
+  let projects = detect_projects(&scan, vec![]);
+  assert!(projects.len() < tree.project_count());
+  assert_eq!(
+    projects
+      .iter()
+      .filter(|p| p.kinds().contains(&ProjectKind::Go))
+      .count(),
+    1
+  );
+  assert!(!FolderScan::new(tree.root()).unwrap().is_stopped());
+  // the projects nested in another belong to it, the walk never stops at them
+  let root = std::env::temp_dir().join(format!("pgrep-stop-nested-{}", std::process::id()));
+  for file in ["api/Cargo.toml", "api/ui/package.json", "web/package.json"] {
+    let file = root.join(file);
+    std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+    std::fs::write(file, "").unwrap();
+  }
+  let stop_at = |kind: ProjectKind| {
+    let options = ScanOptions::builder()
+      .stop_at(move |project| project.kinds().contains(&kind))
+      .build();
+    FolderScan::with_options(&root, &options).unwrap()
+  };
+  let paths = |scan: &FolderScan| {
+    let mut paths = detect_projects(scan, vec![])
+      .into_iter()
+      .map(|p| p.path().strip_prefix(&root).unwrap().to_path_buf())
+      .collect::<Vec<_>>();
+    paths.sort();
+    paths
+  };
+  for (kind, stopped_at) in [(ProjectKind::Rust, "api"), (ProjectKind::Node, "web")] {
+    let scan = stop_at(kind);
+    assert!(scan.is_stopped());
+    assert!(paths(&scan).contains(&PathBuf::from(stopped_at)));
+    assert!(!paths(&scan).contains(&PathBuf::from("api/ui")));
+  }
+  std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn non_utf8_paths_keep_their_identity() {
+  use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+  let tree = SyntheticTree::generate(TreeSpec::new(0, 0, 0)).unwrap();
+  let root = &tree.root().join("code");
+  // two names only differing by their invalid bytes
+  for name in [&b"caf\xe9"[..], &b"caf\xff"[..]] {
+    let dir = root.join(OsStr::from_bytes(name));
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    std::fs::write(dir.join("Cargo.toml"), "").unwrap();
+    std::fs::write(dir.join("src").join(OsStr::from_bytes(b"m\xfe.rs")), "").unwrap();
+  }
+  let scan = FolderScan::new(root).unwrap();
+  let projects = detect_projects(&scan, vec![]);
+  assert_eq!(projects.len(), 2);
+  assert_ne!(projects[0].id(), projects[1].id());
+  assert!(projects.iter().all(|project| project.path().exists()));
+  assert!(projects
+    .iter()
+    .all(|project| project.source_files()[0].exists()));
+  assert_eq!(projects[0].name().unwrap(), "caf\u{fffd}");
+  assert!(App::score_project(&"caf*".parse().unwrap(), &projects[0]).is_some());
+  // cached paths are read back as is
+  let mut cache = Cache::open(tree.root().join("cache")).unwrap();
+  let keys = projects
+    .iter()
+    .map(|project| project.path().join(".projects"));
+  for (key, project) in keys.clone().zip(&projects) {
+    cache.store(&key, &scan).unwrap();
+    cache.store(&key.join("project"), project).unwrap();
+  }
+  for (key, project) in keys.zip(&projects) {
+    assert_eq!(
+      cache.load::<_, FolderScan>(&key).unwrap(),
+      Some(scan.clone())
+    );
+    let cached = cache
+      .load::<_, Project>(key.join("project"))
+      .unwrap()
+      .unwrap();
+    assert_eq!(cached.path(), project.path());
+    assert_eq!(cached.source_files(), project.source_files());
+  }
+  // reports show them lossily
+  #[cfg(feature = "json")]
+  {
+    let mut out = vec![];
+    let formatter = pgrep::OutputFormat::Json.formatter().unwrap();
+    formatter.write(&mut out, &mut projects.iter()).unwrap();
+    assert!(String::from_utf8(out).unwrap().contains("caf\u{fffd}"));
+  }
+}
+
+#[test]
+fn detect_finds_every_project() {
+  let tree = SyntheticTree::generate(TreeSpec::new(4, 2, 2)).unwrap();
+  let scan = FolderScan::new(tree.root()).unwrap();
+  let projects = detect_projects(&scan, vec![]);
+  assert_eq!(projects.len(), tree.project_count());
+}
+
+#[test]
+fn detect_is_deterministic() {
+  let tree = SyntheticTree::generate(TreeSpec::new(3, 3, 2)).unwrap();
+  let scan = FolderScan::new(tree.root()).unwrap();
+  let projects = detect_projects(&scan, vec![]);
+  assert!(projects.windows(2).all(|w| w[0].path() < w[1].path()));
+  assert!(projects
+    .iter()
+    .all(|project| project.source_files().windows(2).all(|w| w[0] < w[1])));
+  assert_eq!(projects, detect_projects(&scan, vec![]));
+}
+
+#[test]
+fn detect_attributes_source_files() {
+  let tree = SyntheticTree::generate(TreeSpec::new(3, 2, 2)).unwrap();
+  let scan = FolderScan::new(tree.root()).unwrap();
+  for project in detect_projects(&scan, vec![]) {
+    // the notes of the parent folders and the manifest aren't source files
+    assert_eq!(project.project_files().len(), 1);
+    assert_eq!(project.source_files().len(), tree.spec().files);
+    assert!(project
+      .source_files()
+      .iter()
+      .all(|file| file.starts_with(project.path())));
+  }
+}
+
+#[test]
+fn detect_attributes_files_by_path_component() {
+  let root = std::env::temp_dir().join(format!("pgrep-prefixes-{}", std::process::id()));
+  for file in [
+    "app/Cargo.toml",
+    "app/Cargo.lock",
+    "app/README.md",
+    "app/src/main.rs",
+    "app/crates/core/Cargo.toml",
+    "app/crates/core/lib.rs",
+    "app2/package.json",
+    "app2/main.js",
+    "app2/main.rs",
+  ] {
+    let file = root.join(file);
+    std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+    std::fs::write(file, "").unwrap();
+  }
+  let scan = FolderScan::new(&root).unwrap();
+  let mut projects = detect_projects(&scan, vec![]);
+  projects.sort_by(|a, b| a.path().cmp(b.path()));
+  std::fs::remove_dir_all(&root).unwrap();
+  // the nested crate belongs to the outermost project, `app2` isn't part of `app`
+  let names = |files: &Vec<PathBuf>| {
+    let mut names = files
+      .iter()
+      .map(|file| file.strip_prefix(&root).unwrap().to_path_buf())
+      .collect::<Vec<_>>();
+    names.sort();
+    names
+  };
+  assert_eq!(projects.len(), 2);
+  assert_eq!(
+    projects[0].kinds(),
+    &vec![ProjectKind::Rust, ProjectKind::Other]
+  );
+  assert_eq!(projects[0].primary_kind(), Some(&ProjectKind::Rust));
+  assert_eq!(
+    names(projects[0].source_files()),
+    ["app/crates/core/lib.rs", "app/src/main.rs"].map(PathBuf::from)
+  );
+  assert_eq!(
+    names(projects[1].source_files()),
+    [PathBuf::from("app2/main.js")]
+  );
+}
+
+#[test]
+fn stats_count_every_project() {
+  let tree = SyntheticTree::generate(TreeSpec::new(4, 2, 2)).unwrap();
+  let scan = FolderScan::new(tree.root()).unwrap();
+  let projects = detect_projects(&scan, vec![]);
+  let stats = DetectionStats::from_scan(&scan, &projects, &[]);
+  assert_eq!(stats.projects, tree.project_count());
+  assert!(stats.kinds.iter().all(|k| k.projects <= stats.projects));
+  assert_eq!(
+    DetectionStats::table(std::slice::from_ref(&stats))
+      .rows()
+      .len(),
+    stats.kinds.len()
+  );
+}
+
+#[test]
+fn light_scan_detects_the_same_projects() {
+  let tree = SyntheticTree::generate(TreeSpec::new(3, 2, 2)).unwrap();
+  let options = ScanOptions {
+    detail: ScanDetail::Light,
+    ..Default::default()
+  };
+  let scan = FolderScan::with_options(tree.root(), &options).unwrap();
+  assert!(scan.file_count() < tree.file_count());
+  assert_eq!(detect_projects(&scan, vec![]).len(), tree.project_count());
+}
+
+#[test]
+fn detected_projects_have_an_identity() {
+  let tree = SyntheticTree::generate(TreeSpec::new(2, 1, 2)).unwrap();
+  let scan = FolderScan::new(tree.root()).unwrap();
+  for project in detect_projects(&scan, vec![]) {
+    let identity = Project::compute_identity(project.project_files());
+    assert!(identity.is_some());
+    assert_eq!(project.identity(), identity.as_ref());
+    assert_eq!(project.moved_from(), None);
+  }
+}
+
+#[test]
+fn identical_manifests_are_duplicates() {
+  // generated manifests are all empty
+  let tree = SyntheticTree::generate(TreeSpec::new(2, 1, 2)).unwrap();
+  let scan = FolderScan::new(tree.root()).unwrap();
+  let mut projects = detect_projects(&scan, vec![]);
+  for project in &mut projects {
+    project.set_manifest_hash(project.compute_manifest_hash());
+    assert!(project.manifest_hash().is_some());
+  }
+  let duplicates = App::duplicate_projects(projects.clone());
+  assert_eq!(duplicates.len(), projects.len());
+  assert!(duplicates
+    .windows(2)
+    .all(|w| w[0].manifest_hash() == w[1].manifest_hash()));
+  // a single project has no duplicate
+  assert!(App::duplicate_projects(projects[..1].to_vec()).is_empty());
+}
+
+#[test]
+fn query_matches_generated_names() {
+  let tree = SyntheticTree::generate(TreeSpec::new(5, 0, 1)).unwrap();
+  let scan = FolderScan::new(tree.root()).unwrap();
+  let query = "dir_000#".parse::<Query>().unwrap();
+  let matches = detect_projects(&scan, vec![])
+    .iter()
+    .filter(|project| query.matches(project.name().unwrap()))
+    .count();
+  assert_eq!(matches, tree.spec().dirs);
+}
+
+#[test]
+fn queries_named_after_subcommands_are_rejected() {
+  let e = App::parse_args(["pgrep", "config"]).unwrap_err();
+  assert!(matches!(e, Error::Config { .. }));
+  assert!(e.to_string().contains("`pgrep -- config`"), "{}", e);
+  assert!(App::parse_args(["pgrep", "-F", "/dev", "config"]).is_err());
+  let matches = App::parse_args(["pgrep", "--", "config"]).unwrap();
+  assert_eq!(
+    matches.get_one::<Query>("query").unwrap().to_string(),
+    "config"
+  );
+  let matches = App::parse_args(["pgrep", "config", "schema"]).unwrap();
+  assert_eq!(matches.subcommand_name(), Some("config"));
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_errors_are_decided_from_the_command_line() {
+  use clap::FromArgMatches;
+  use pgrep::AppOptions;
+  let reports_json = |args: &[&str]| {
+    let matches = App::parse_args(args).unwrap();
+    AppOptions::from_arg_matches(&matches)
+      .unwrap()
+      .reports_json()
+  };
+  assert!(reports_json(&["pgrep", "--format", "json", "api"]));
+  assert!(reports_json(&["pgrep", "--output", "report.json", "api"]));
+  assert!(!reports_json(&["pgrep", "--output", "report.csv", "api"]));
+  assert!(!reports_json(&["pgrep", "api"]));
+}
+
+#[test]
+fn queries_are_only_recalled_with_recall() {
+  let matches = App::parse_args(["pgrep", "!!"]).unwrap();
+  assert_eq!(matches.get_one::<Query>("query").unwrap().to_string(), "!!");
+  assert_eq!(matches.get_one::<usize>("recall"), None);
+  let matches = App::parse_args(["pgrep", "--recall"]).unwrap();
+  assert_eq!(matches.get_one::<usize>("recall"), Some(&1));
+  let matches = App::parse_args(["pgrep", "--recall", "3"]).unwrap();
+  assert_eq!(matches.get_one::<usize>("recall"), Some(&3));
+}
+
+#[test]
+fn query_matches_full_paths() {
+  let projects = [
+    "/dev/work/api-server",
+    "/dev/perso/api-client",
+    "/dev/work/web",
+  ]
+  .iter()
+  .map(|path| Project::new(path, vec![], vec![], vec![]))
+  .collect::<Vec<_>>();
+  let projects = projects.iter().collect::<Vec<_>>();
+  let names = |query: Query| {
+    App::match_projects(&query, &projects)
+      .iter()
+      .map(|project| project.name().unwrap())
+      .collect::<Vec<_>>()
+  };
+  // path components are matched one by one unless the query contains a '/'
+  assert_eq!(names("work".parse().unwrap()).len(), 2);
+  assert_eq!(names("*/work/api*".parse().unwrap()), ["api-server"]);
+  assert_eq!(
+    names(
+      "work/api"
+        .parse::<Query>()
+        .unwrap()
+        .with_mode(MatchMode::Partial)
+    ),
+    ["api-server"]
+  );
+  assert_eq!(names("work/api*".parse().unwrap()), ["api-server"]);
+  assert!(names("ork/api*".parse().unwrap()).is_empty());
+  assert_eq!(
+    names("*/dev/*/api*".parse::<Query>().unwrap()),
+    ["api-server", "api-client"]
+  );
+  assert_eq!(
+    names("api*".parse::<Query>().unwrap().with_match_path(true)).len(),
+    0
+  );
+}
+
+#[test]
+fn query_matches_windows_paths() {
+  let project = Project::new(r"\\?\c:\dev\work\api", vec![], vec![], vec![]);
+  for query in ["*/dev/work/api", "C:/dev/*/api", "path:C:/dev/*"] {
+    let query = query.parse::<Query>().unwrap();
+    assert!(App::score_project(&query, &project).is_some(), "{}", query);
+  }
+}
+
+#[test]
+fn matches_rank_best_first() {
+  let projects = ["/dev/my-api", "/dev/api/web", "/dev/api-server", "/dev/api"]
+    .iter()
+    .map(|path| Project::new(path, vec![], vec![], vec![]))
+    .collect::<Vec<_>>();
+  let projects = projects.iter().collect::<Vec<_>>();
+  let query = "*api*".parse::<Query>().unwrap();
+  let names = App::match_projects(&query, &projects)
+    .iter()
+    .map(|project| project.name().unwrap())
+    .collect::<Vec<_>>();
+  assert_eq!(names, ["api", "api-server", "my-api", "web"]);
+}
+
+#[cfg(all(feature = "console", feature = "text"))]
+#[test]
+fn output_files_hold_no_styles() {
+  use pgrep::{style, ColorChoice, Console, OutputFormat, UI};
+
+  style::set_color_choice(ColorChoice::Auto);
+  let path = std::env::temp_dir().join(format!("pgrep-output-{}.txt", std::process::id()));
+  let projects = [Project::new(
+    "/dev/api",
+    vec![ProjectKind::Rust],
+    vec![],
+    vec![],
+  )];
+  let formatter = OutputFormat::Text.formatter().unwrap();
+  Console::with_output(&path)
+    .write_matches(&projects, &formatter)
+    .unwrap();
+  let written = std::fs::read_to_string(&path).unwrap();
+  std::fs::remove_file(&path).unwrap();
+  assert_eq!(written, "[Rust] api - /dev/api\n");
+}